@@ -1,17 +1,31 @@
 use std::{
+    collections::{BTreeMap, BTreeSet},
     convert::Infallible,
     fmt::Display,
+    fs,
     io::{self, stdin, stdout, Write},
     path::Path,
+    process::{Command, Stdio},
 };
 
-use time::{macros::format_description, Date, Duration};
+use time::{macros::format_description, Date, Duration, PrimitiveDateTime, UtcOffset};
 
 use repository::{
+    backup::Backup,
+    backup_retention, duplicate_window_days,
     job_application_model::{
-        HumanResponse, JobApplication, JobApplicationField, PartialJobApplication,
+        Activity, Attachment, AttachmentRole, CustomField, CustomFieldType, CustomFieldValue,
+        CustomFields, DeletedJobApplication, FullTextSearchResult, HumanResponse, JobApplication,
+        JobApplicationField, JobDescription, NewJobApplication, Offer, PartialJobApplication,
+        PayPeriod, ResumeVersion, Tag, WorkArrangement,
     },
-    job_application_repository::JobApplicationRepository,
+    job_application_repository::{
+        JobApplicationRepository, RepositoryError, SqliteRepository, JOURNAL_LENGTH,
+    },
+    job_description::read_job_description_file,
+    offer_comparison::{score_offers, CriteriaWeights},
+    sync::{SyncChanges, SyncChoice, SyncConflict},
+    validation::{check_human_response_date, check_text_field, check_website},
 };
 
 use super::shell_option::{ReadType, ShellOption, UpdateType};
@@ -74,6 +88,50 @@ pub fn main_loop<C: JobApplicationRepository>(conn: &mut C) -> Result<(), io::Er
                 ShellOption::Read(read_type) => read(conn, read_type, temp_dir.path()),
                 ShellOption::Update(update_type, id) => update(conn, update_type, id),
                 ShellOption::Delete(id) => delete(conn, id),
+                ShellOption::Trash => list_trash(conn),
+                ShellOption::Restore(id) => restore_command(conn, id),
+                ShellOption::EmptyTrash => empty_trash_command(conn),
+                ShellOption::Undo => undo_command(conn),
+                ShellOption::Redo => redo_command(conn),
+                ShellOption::History(id) => history_command(conn, id),
+                ShellOption::Merge(keep_id, merged_id) => merge_command(conn, keep_id, merged_id),
+                ShellOption::Find(query) => find(conn, &query),
+                ShellOption::Tag(id, tag) => tag_command(conn, id, &tag),
+                ShellOption::Untag(id, tag) => untag_command(conn, id, &tag),
+                ShellOption::Tags => list_tags(conn),
+                ShellOption::Fields => list_fields(conn),
+                ShellOption::AddField(name) => add_field(conn, &name),
+                ShellOption::DeleteField(name) => delete_field(conn, &name),
+                ShellOption::Offer(id) => offer_command(conn, id),
+                ShellOption::Compare(weights) => compare_command(conn, &weights),
+                ShellOption::Stats => stats_command(conn),
+                ShellOption::Description(id, file) => {
+                    description_command(conn, id, file.as_deref())
+                }
+                ShellOption::Attach(id, role, file) => attach_command(conn, id, role, &file),
+                ShellOption::Detach(id) => detach_command(conn, id),
+                ShellOption::Open(id) => open_command(conn, id, temp_dir.path()),
+                ShellOption::Resumes => list_resumes(conn),
+                ShellOption::AddResume(label) => add_resume(conn, &label),
+                ShellOption::DeleteResume(id) => delete_resume(conn, id),
+                ShellOption::SentResume(id, resume_id) => sent_resume_command(conn, id, resume_id),
+                ShellOption::Encrypt => sqlite_only(conn, encrypt_command),
+                ShellOption::Decrypt => sqlite_only(conn, decrypt_command),
+                ShellOption::BackupNow => sqlite_only(conn, backup_now),
+                ShellOption::Backups => sqlite_only(conn, list_backups),
+                ShellOption::RestoreBackup(file) => {
+                    sqlite_only(conn, |conn| restore_backup_command(conn, &file))
+                }
+                ShellOption::Sync(file) => sqlite_only(conn, |conn| sync_command(conn, &file)),
+                ShellOption::ExportSyncFile(file) => {
+                    sqlite_only(conn, |conn| export_sync_file_command(conn, &file))
+                }
+                ShellOption::ExportPlainText(dir) => {
+                    sqlite_only(conn, |conn| export_plain_text_command(conn, &dir))
+                }
+                ShellOption::ImportPlainText(dir) => {
+                    sqlite_only(conn, |conn| import_plain_text_command(conn, &dir))
+                }
                 ShellOption::Exit => unreachable!(),
             }
             .map_or_else(|e| println!("{e}"), |_| ()),
@@ -91,11 +149,83 @@ Available commands:
   help | h
   exit | quit
   create | new
-  read [all] | pending | <id> | search <search_query>
+  read [all] | pending | <id> [--description] | search <search_query>
+    ^--description also shows the saved job posting description
   search <search_query>
     ^shorthand for read search <search_query>
+    ^e.g. company:acme status:rejected applied:>=2025-01-01 \"backend engineer\" -notes:contract
+    ^filters: source, company, title, website, notes, status, applied, responded, tag, field.<name>. Prefix with - to negate
+    ^location filters: location, city, region, country, arrangement (remote, hybrid, onsite), relocation (yes, no), visa (yes, no)
+    ^date filters: applied, responded, created, updated, active (the last date anything happened), e.g. active:<2025-01-01
   (update | edit) (response | other) <id>
   delete <id>
+    ^moves a job application to the trash
+  trash [list]
+    ^list job applications in the trash. Set TRASH_RETENTION_DAYS to purge them automatically after that many days
+  trash restore <id>
+  trash empty
+    ^permanently delete everything in the trash
+  undo
+    ^undo the most recent create, edit, delete, or restore. The last {JOURNAL_LENGTH} changes are kept between sessions
+  redo
+  history <id>
+    ^show who changed a job application, when, and which fields. Only MySQL databases keep a history.
+    ^set AUDIT_USER to record a name other than DB_USER
+  merge <id> <duplicate_id>
+    ^combine a duplicate into a job application, moving its notes, response, tags, and attachments, then move the duplicate to the trash
+    ^create warns about likely duplicates sent within DUPLICATE_WINDOW_DAYS (default 30) of each other
+  find <full_text_query>
+    ^ranked search of all text fields. Use \"quotes\" for phrases and a trailing * for prefixes
+  tag <id> <tag>
+  untag <id> <tag>
+  tags
+    ^list every tag in use
+  fields
+    ^list custom fields
+  field add <name>
+    ^prompts for the type: text, number, date, bool, or select:<option>,<option>,...
+  field delete <name>
+  offer <id>
+    ^add or replace the job offer for a job application
+  compare [<criterion>=<weight> ...]
+    ^score every offer side by side by yearly amounts. Criteria: base, bonus, equity, total
+    ^e.g. compare base=2 equity=1. Defaults to total=1
+  stats
+    ^responses broken down by work arrangement and by resume version
+  description <id> [<file>]
+    ^save the job posting description, imported from a text or HTML file or typed in
+  attach <id> <role> <file>
+    ^attach a file. Roles: resume, cover-letter, offer-letter, take-home, other
+  detach <attachment_id>
+  open <attachment_id>
+    ^open an attached file. Attachment ids are shown by read <id>
+  resumes
+    ^list resume versions
+  resume add <label>
+    ^prompts for the resume file, date, and notes
+  resume delete <resume_id>
+  resume <id> (<resume_id> | none)
+    ^record which resume version was sent with a job application
+  encrypt | rekey
+    ^encrypt the database with a passphrase, or change the passphrase. Needs a build with the sqlcipher feature
+  decrypt
+    ^store the database as plain text again
+  backup now
+    ^backups are also taken automatically, as often as BACKUP_SCHEDULE says: startup, daily (the default), or off
+    ^the newest from each of the last BACKUP_KEEP_DAILY (default 7) days and BACKUP_KEEP_WEEKLY (default 4) weeks are kept
+  backup list
+  backup restore <file>
+    ^replace the database with a backup from backup list, or any other backup file. The database is backed up first
+  sync <file>
+    ^combine changes with another database, like the one on another computer. Both get every change made to either one
+    ^fields changed in both since they last synced are asked about. Custom fields, offers, descriptions, attachments, and resumes aren't synced
+  sync export <file>
+    ^save a sync file to carry changes to another computer. Sync with it there, then sync with it here again to get that computer's changes
+  text export <dir>
+    ^save every job application as a text file in a new directory, to keep in git. Set PLAIN_TEXT_DIR to the directory to use it instead of the database
+    ^offers, descriptions, attachments, and resumes aren't saved
+  text import <dir>
+    ^copy every job application from a plain-text directory into this database, which has to be empty
 "
     );
     Ok(())
@@ -103,29 +233,18 @@ Available commands:
 
 /// Prompt a user for all parts of a job application and insert the new element
 fn create<C: JobApplicationRepository>(conn: &mut C) -> Result<(), Box<dyn std::error::Error>> {
-    // Declare the variables here to make sure I define all of them
-    let source: String;
-    let company: String;
-    let job_title: String;
-    let application_date: Date;
-    let time_investment: Option<Duration>;
-    let human_response: HumanResponse;
-    let human_response_date: Option<Date>;
-    let application_website: Option<String>;
-    let notes: Option<String>;
-
     // This will be used by multiple inputs
     let wrap_ok = |s: &str| Result::<_, Infallible>::Ok(s.to_owned());
 
     // Initialize the fields
-    source = input("Source (job board, referral, etc):", wrap_ok)?;
-    company = input("Company:", wrap_ok)?;
-    job_title = input("Job Title:", wrap_ok)?;
-    application_date = input(
+    let source: String = input("Source (job board, referral, etc):", checked_text("source"))?;
+    let company: String = input("Company:", checked_text("company"))?;
+    let job_title: String = input("Job Title:", checked_text("job_title"))?;
+    let application_date: Date = input(
         "Application date (leave blank for today) (mm/dd/yyyy):",
         parse_date,
     )?;
-    time_investment = input(
+    let time_investment: Option<Duration> = input(
         "Time taken to complete application (leave blank for unknown) (mm:ss):",
         |s| {
             if !s.is_empty() {
@@ -147,7 +266,7 @@ fn create<C: JobApplicationRepository>(conn: &mut C) -> Result<(), Box<dyn std::
             }
         },
     )?;
-    human_response = input(
+    let human_response: HumanResponse = input(
         "Response sent by a human. Enter one of the following:
 \tr for rejection
 \ti for interview request
@@ -176,23 +295,26 @@ fn create<C: JobApplicationRepository>(conn: &mut C) -> Result<(), Box<dyn std::
         },
     )?;
     // Only prompt if human response is not null
-    if let HumanResponse::None = human_response {
-        human_response_date = None
+    let human_response_date: Option<Date> = if let HumanResponse::None = human_response {
+        None
     } else {
-        human_response_date = Some(input(
+        Some(input(
             "Response date (leave blank for today) (mm/dd/yyyy):",
-            parse_date,
-        )?);
-    }
-    application_website = Some(input(
+            |s| {
+                let date = parse_date(s).map_err(|e| e.to_string())?;
+                check_human_response_date(application_date, human_response, Some(date))
+                    .map(|_| date)
+            },
+        )?)
+    };
+    let application_website: Option<String> = input(
         "Application website (if applied using the company website):",
-        wrap_ok,
-    )?)
-    .filter(|s| !s.is_empty());
+        parse_website_optional,
+    )?;
     let notes_first_line = Some(input("Notes:", wrap_ok)?).filter(|s| !s.is_empty());
 
     // Support multiline notes
-    notes = match notes_first_line {
+    let notes: Option<String> = match notes_first_line {
         Some(note) if note.starts_with('`') => {
             let mut notes = String::new();
             let mut note_line = note[1..].to_owned();
@@ -215,9 +337,74 @@ fn create<C: JobApplicationRepository>(conn: &mut C) -> Result<(), Box<dyn std::
         _ => notes_first_line,
     };
 
+    // Compensation posted in the job listing
+    let salary_min: Option<f64> = input(
+        "Posted salary minimum (leave blank for unknown):",
+        parse_amount_optional,
+    )?;
+    let salary_max: Option<f64> = input(
+        "Posted salary maximum (leave blank for unknown):",
+        parse_amount_optional,
+    )?;
+    let salary_currency: Option<String> = input(
+        "Salary currency, like USD (leave blank for unknown):",
+        checked_text_optional("salary_currency"),
+    )?;
+    let pay_period: Option<PayPeriod> = input(
+        "Pay period (hourly, daily, weekly, monthly, or yearly) (leave blank for unknown):",
+        parse_pay_period_optional,
+    )?;
+    let equity: Option<String> = input(
+        "Posted equity (leave blank for none):",
+        checked_text_optional("equity"),
+    )?;
+    let bonus: Option<String> = input(
+        "Posted bonus (leave blank for none):",
+        checked_text_optional("bonus"),
+    )?;
+
+    // Location of the job
+    let city: Option<String> = input(
+        "City (leave blank for unknown):",
+        checked_text_optional("city"),
+    )?;
+    let region: Option<String> = input(
+        "Region, like a state or province (leave blank for unknown):",
+        checked_text_optional("region"),
+    )?;
+    let country: Option<String> = input(
+        "Country (leave blank for unknown):",
+        checked_text_optional("country"),
+    )?;
+    let work_arrangement: Option<WorkArrangement> = input(
+        "Work arrangement (remote, hybrid, or onsite) (leave blank for unknown):",
+        parse_work_arrangement_optional,
+    )?;
+    let relocation: Option<bool> = input(
+        "Relocation offered (yes or no) (leave blank for unknown):",
+        parse_yes_no_optional,
+    )?;
+    let visa_sponsorship: Option<bool> = input(
+        "Visa sponsorship offered (yes or no) (leave blank for unknown):",
+        parse_yes_no_optional,
+    )?;
+
+    // Custom fields are optional, so only keep the ones that were filled in
+    let mut custom_fields = CustomFields::new();
+    for custom_field in conn.get_custom_fields()? {
+        let prompt = format!(
+            "{} ({}) (leave blank for none):",
+            custom_field.name, custom_field.field_type
+        );
+        if let Some(value) = input(&prompt, |s| {
+            parse_custom_field_value(&custom_field.field_type, s)
+        })? {
+            custom_fields.insert(custom_field.name, value);
+        }
+    }
+
     // Construct the new application.
-    let new_application = JobApplication {
-        id: 0,
+    let new_application = NewJobApplication {
         source,
         company,
         job_title,
@@ -227,10 +414,52 @@ fn create<C: JobApplicationRepository>(conn: &mut C) -> Result<(), Box<dyn std::
         human_response_date,
         application_website,
         notes,
+        salary_min,
+        salary_max,
+        salary_currency,
+        pay_period,
+        equity,
+        bonus,
+        city,
+        region,
+        country,
+        work_arrangement,
+        relocation,
+        visa_sponsorship,
+        custom_fields,
     };
 
+    // Check before saving a job application that was likely entered already
+    let duplicates = conn.find_duplicates(&new_application, duplicate_window_days())?;
+    if !duplicates.is_empty() {
+        println!("This looks like a job application that is already saved:");
+        for duplicate in &duplicates {
+            println!(
+                "{}: {} at {} (applied {:02}/{:02}/{})",
+                duplicate.id,
+                duplicate.job_title,
+                duplicate.company,
+                duplicate.application_date.month() as u8,
+                duplicate.application_date.day(),
+                duplicate.application_date.year()
+            );
+        }
+        if !input(
+            "Save it anyway? Duplicates can be combined later with merge <id> <duplicate_id>. [y/N]:",
+            |s| Result::<bool, Infallible>::Ok(s.starts_with(['y', 'Y'])),
+        )? {
+            println!("Aborting create");
+            return Ok(());
+        }
+    }
+
     // println!("Job application: {new_application:?}");
-    conn.insert_job_application(&new_application)?;
+    let new_application = conn.insert_job_application(&new_application)?;
+
+    // A job offer has details of its own
+    if new_application.human_response == HumanResponse::JobOffer {
+        offer_command(conn, new_application.id)?;
+    }
     Ok(())
 }
 
@@ -239,12 +468,14 @@ fn read<C: JobApplicationRepository>(
     read_type: ReadType,
     temp_dir: &Path,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let show_description = matches!(read_type, ReadType::Description(_));
+
     // Read the job application(s), depending on read type
     let applications: Vec<JobApplication> = match read_type {
         ReadType::All => conn.get_job_applications()?,
         ReadType::Pending => conn.get_pending_job_applications()?,
-        ReadType::Search(query) => conn.search_job_applications(&query)?,
-        ReadType::One(id) => conn
+        ReadType::Search(query) => conn.query_job_applications(&query)?,
+        ReadType::One(id) | ReadType::Description(id) => conn
             .get_job_application_by_id(id)?
             .map_or(Vec::new(), |a| vec![a]),
     };
@@ -255,14 +486,42 @@ fn read<C: JobApplicationRepository>(
         )),
         1 => {
             // This should never panic, we just verified there is exactly one job application
-            print_job_application_to_terminal(applications.get(0).unwrap());
+            let application = applications.first().unwrap();
+            let tags = conn.get_job_application_tags(application.id)?;
+            let offer = conn.get_offer(application.id)?;
+            let attachments = conn.get_attachments(application.id)?;
+            let resume_version = conn.get_sent_resume_version(application.id)?;
+            print_job_application_to_terminal(
+                application,
+                &tags,
+                offer.as_ref(),
+                &attachments,
+                resume_version.as_ref(),
+            );
+            if show_description {
+                match conn.get_job_description(application.id)? {
+                    Some(job_description) => print_job_description_to_terminal(&job_description),
+                    None => println!("No job description saved"),
+                }
+            }
             Ok(())
         }
-        _ => print_table(applications, temp_dir),
+        _ => print_table(
+            applications,
+            &conn.get_custom_fields()?,
+            &conn.get_job_application_activity()?,
+            temp_dir,
+        ),
     }
 }
 
-fn print_job_application_to_terminal(ja: &JobApplication) {
+fn print_job_application_to_terminal(
+    ja: &JobApplication,
+    tags: &[Tag],
+    offer: Option<&Offer>,
+    attachments: &[Attachment],
+    resume_version: Option<&ResumeVersion>,
+) {
     println!("One job application found:");
     println!(
         "ID: {}
@@ -275,7 +534,16 @@ Response from a human: {}
 Human response date: {}
 Response time (days): {}
 Application website: {}
-Notes: {}",
+Notes: {}
+Posted salary: {}
+Posted equity: {}
+Posted bonus: {}
+Location: {}
+Work arrangement: {}
+Relocation offered: {}
+Visa sponsorship offered: {}
+Tags: {}
+Resume version: {}",
         ja.id,
         ja.source,
         ja.company,
@@ -302,6 +570,86 @@ Notes: {}",
             }),
         ja.application_website.as_deref().unwrap_or_default(),
         ja.notes.as_deref().unwrap_or_default(),
+        format_salary_range(ja),
+        ja.equity.as_deref().unwrap_or_default(),
+        ja.bonus.as_deref().unwrap_or_default(),
+        ja.location(),
+        ja.work_arrangement
+            .map_or("".to_string(), |work_arrangement| work_arrangement
+                .to_string()),
+        format_yes_no(ja.relocation),
+        format_yes_no(ja.visa_sponsorship),
+        format_tags(tags),
+        resume_version.map_or("", |resume_version| resume_version.label.as_str()),
+    );
+    for (name, value) in &ja.custom_fields {
+        println!("{name}: {value}");
+    }
+    if let Some(offer) = offer {
+        print_offer_to_terminal(offer);
+    }
+    if !attachments.is_empty() {
+        println!("Attachments:");
+        for attachment in attachments {
+            println!(
+                "  {}: {} ({}, {}, attached {:02}/{:02}/{})",
+                attachment.id,
+                attachment.file_name,
+                attachment.role,
+                format_size(attachment.size),
+                attachment.added_date.month() as u8,
+                attachment.added_date.day(),
+                attachment.added_date.year(),
+            );
+        }
+    }
+}
+
+fn print_job_description_to_terminal(job_description: &JobDescription) {
+    println!(
+        "Job description (captured {:02}/{:02}/{}):\n{}",
+        job_description.captured_date.month() as u8,
+        job_description.captured_date.day(),
+        job_description.captured_date.year(),
+        job_description.description
+    );
+}
+
+fn print_offer_to_terminal(offer: &Offer) {
+    let currency = offer.currency.as_deref().unwrap_or_default();
+    println!(
+        "Offer base salary: {} {currency} {}
+Offer bonus: {}
+Offer equity: {}
+Offer total per year: {:.2} {currency}
+Offer benefits: {}
+Offer start date: {}
+Offer deadline: {}",
+        offer.base_salary,
+        offer.pay_period,
+        offer
+            .bonus
+            .map_or("".to_string(), |bonus| bonus.to_string()),
+        offer
+            .equity_value
+            .map_or("".to_string(), |equity_value| format!(
+                "{equity_value} over {} years",
+                offer.vesting_years.unwrap_or(Offer::DEFAULT_VESTING_YEARS)
+            )),
+        offer.annual_total(),
+        offer.benefits.as_deref().unwrap_or_default(),
+        offer.start_date.map_or("".to_string(), |d| format!(
+            "{:02}/{:02}/{}",
+            d.month() as u8,
+            d.day(),
+            d.year()
+        )),
+        offer.deadline.map_or("".to_string(), |d| format!(
+            "{:02}/{:02}/{}",
+            d.month() as u8,
+            d.day(),
+            d.year()
+        )),
     );
 }
 
@@ -323,11 +671,8 @@ fn update_human_response_command<C: JobApplicationRepository>(
     conn: &mut C,
     id: i32,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let human_response: HumanResponse;
-    let human_response_date: Option<Date>;
-
     // Get the parameters
-    human_response = input(
+    let human_response: HumanResponse = input(
         "Response sent by a human. Enter one of the following:
 \tr for rejection
 \ti for interview request
@@ -356,18 +701,29 @@ fn update_human_response_command<C: JobApplicationRepository>(
         },
     )?;
     // Only prompt if human response is not null
-    if let HumanResponse::None = human_response {
-        human_response_date = None
+    let human_response_date: Option<Date> = if let HumanResponse::None = human_response {
+        None
     } else {
-        human_response_date = Some(input(
+        Some(input(
             "Response date (leave blank for today) (mm/dd/yyyy):",
             parse_date,
-        )?);
-    }
+        )?)
+    };
 
-    conn.update_human_response(id, human_response, human_response_date)
+    let job_application = conn
+        .update_human_response(id, human_response, human_response_date)
         // Box the error, if any
-        .map_err(Box::<dyn std::error::Error>::from)
+        .map_err(Box::<dyn std::error::Error>::from)?;
+    println!(
+        "{} at {}: {}",
+        job_application.job_title, job_application.company, job_application.human_response
+    );
+
+    // A job offer has details of its own
+    if human_response == HumanResponse::JobOffer {
+        offer_command(conn, id)?;
+    }
+    Ok(())
 }
 
 /// Ask the user what to update and update it
@@ -389,11 +745,21 @@ fn update_other_command<C: JobApplicationRepository>(
     input_optional!(
         partial_application,
         "Source (job board, referral, etc)",
-        wrap_ok,
+        checked_text_optional("source"),
         Source
     );
-    input_optional!(partial_application, "Company", wrap_ok, Company);
-    input_optional!(partial_application, "Job Title", wrap_ok, JobTitle);
+    input_optional!(
+        partial_application,
+        "Company",
+        checked_text_optional("company"),
+        Company
+    );
+    input_optional!(
+        partial_application,
+        "Job Title",
+        checked_text_optional("job_title"),
+        JobTitle
+    );
     input_optional!(
         partial_application,
         "Application date (mm/dd/yyyy)",
@@ -406,7 +772,7 @@ fn update_other_command<C: JobApplicationRepository>(
         |s: &str| {
             match s {
                 // If empty, just ignore this
-                s if s.is_empty() => Ok(None),
+                "" => Ok(None),
                 // If this is the word "remove", Some(None) will result in the element TimeInvestment(None), which will make the entry NULL
                 "remove" => Ok(Some(None)),
                 // For anything else, parse the time
@@ -485,16 +851,7 @@ fn update_other_command<C: JobApplicationRepository>(
     input_optional!(
         partial_application,
         "Application website (if applied using the company website) (enter 'remove' to remove)",
-        |s: &str| {
-            if s == "remove" {
-                Ok(Some(None))
-            } else {
-                // If the result is Ok, check if it's blank
-                // If blank, just return Ok(None) so the item does not get added to the partial application record (i.e. Ok(None)->Ok(None))
-                // If successful parse, return Ok(Some(*parsed*))
-                wrap_ok(s).map(|o| o.map(Option::from))
-            }
-        },
+        |s: &str| parse_removable(s, parse_website_optional),
         ApplicationWebsite
     );
 
@@ -542,18 +899,140 @@ fn update_other_command<C: JobApplicationRepository>(
         None => (),
     }
 
+    input_optional!(
+        partial_application,
+        "Posted salary minimum (enter 'remove' to remove)",
+        |s: &str| parse_removable(s, parse_amount_optional),
+        SalaryMin
+    );
+    input_optional!(
+        partial_application,
+        "Posted salary maximum (enter 'remove' to remove)",
+        |s: &str| parse_removable(s, parse_amount_optional),
+        SalaryMax
+    );
+    input_optional!(
+        partial_application,
+        "Salary currency, like USD (enter 'remove' to remove)",
+        |s: &str| parse_removable(s, checked_text_optional("salary_currency")),
+        SalaryCurrency
+    );
+    input_optional!(
+        partial_application,
+        "Pay period (hourly, daily, weekly, monthly, or yearly) (enter 'remove' to remove)",
+        |s: &str| parse_removable(s, parse_pay_period_optional),
+        PayPeriod
+    );
+    input_optional!(
+        partial_application,
+        "Posted equity (enter 'remove' to remove)",
+        |s: &str| parse_removable(s, checked_text_optional("equity")),
+        Equity
+    );
+    input_optional!(
+        partial_application,
+        "Posted bonus (enter 'remove' to remove)",
+        |s: &str| parse_removable(s, checked_text_optional("bonus")),
+        Bonus
+    );
+    input_optional!(
+        partial_application,
+        "City (enter 'remove' to remove)",
+        |s: &str| parse_removable(s, checked_text_optional("city")),
+        City
+    );
+    input_optional!(
+        partial_application,
+        "Region, like a state or province (enter 'remove' to remove)",
+        |s: &str| parse_removable(s, checked_text_optional("region")),
+        Region
+    );
+    input_optional!(
+        partial_application,
+        "Country (enter 'remove' to remove)",
+        |s: &str| parse_removable(s, checked_text_optional("country")),
+        Country
+    );
+    input_optional!(
+        partial_application,
+        "Work arrangement (remote, hybrid, or onsite) (enter 'remove' to remove)",
+        |s: &str| parse_removable(s, parse_work_arrangement_optional),
+        WorkArrangement
+    );
+    input_optional!(
+        partial_application,
+        "Relocation offered (yes or no) (enter 'remove' to remove)",
+        |s: &str| parse_removable(s, parse_yes_no_optional),
+        Relocation
+    );
+    input_optional!(
+        partial_application,
+        "Visa sponsorship offered (yes or no) (enter 'remove' to remove)",
+        |s: &str| parse_removable(s, parse_yes_no_optional),
+        VisaSponsorship
+    );
+
+    // Custom fields are stored separately, so collect changes to them on their own
+    // Some(None) means the value should be removed, like the other fields
+    let mut custom_field_changes: Vec<(String, Option<CustomFieldValue>)> = Vec::new();
+    for custom_field in conn.get_custom_fields()? {
+        let prompt = format!(
+            "{} ({}) (enter 'remove' to remove)\nLeave blank to leave unchanged:",
+            custom_field.name, custom_field.field_type
+        );
+        let change = input(&prompt, |s| {
+            if s == "remove" {
+                Ok(Some(None))
+            } else {
+                parse_custom_field_value(&custom_field.field_type, s).map(|o| o.map(Some))
+            }
+        })?;
+        if let Some(value) = change {
+            custom_field_changes.push((custom_field.name, value));
+        }
+    }
+
     // Make sure at least one change was made
-    if partial_application.0.is_empty() {
-        Err(Box::<dyn std::error::Error>::from("No changes made"))
-    } else {
-        // Add the ID of the job application to modify
-        partial_application.0.push(JobApplicationField::Id(id));
-        // For confirmation, print the returned job application
-        conn.update_job_application_partial(partial_application)?;
-        // print_job_application_to_terminal(&new_job_application);
+    if partial_application.0.is_empty() && custom_field_changes.is_empty() {
+        return Err(Box::<dyn std::error::Error>::from("No changes made"));
+    }
 
-        Ok(())
+    // Changing the response to a job offer should ask for the offer, unless there already is one
+    let is_new_job_offer = partial_application.0.iter().any(|field| {
+        matches!(
+            field,
+            JobApplicationField::HumanResponse(HumanResponse::JobOffer)
+        )
+    }) && conn.get_offer(id)?.is_none();
+
+    // Save the fields and custom fields together, so an invalid custom field doesn't leave the edit half saved
+    let job_application = conn.with_transaction(|tx| {
+        for (name, value) in custom_field_changes {
+            tx.set_custom_field_value(id, &name, value.as_ref())?;
+        }
+        if partial_application.0.is_empty() {
+            tx.get_job_application_by_id(id)?
+                .ok_or_else(|| RepositoryError::job_application_not_found(id))
+        } else {
+            // Add the ID of the job application to modify
+            partial_application.0.push(JobApplicationField::Id(id));
+            tx.update_job_application_partial(partial_application)
+        }
+    })?;
+
+    // For confirmation, print the updated job application
+    print_job_application_to_terminal(
+        &job_application,
+        &conn.get_job_application_tags(id)?,
+        conn.get_offer(id)?.as_ref(),
+        &conn.get_attachments(id)?,
+        conn.get_sent_resume_version(id)?.as_ref(),
+    );
+    if is_new_job_offer {
+        offer_command(conn, id)?;
     }
+
+    Ok(())
 }
 
 fn delete<C: JobApplicationRepository>(
@@ -563,16 +1042,30 @@ fn delete<C: JobApplicationRepository>(
     // Check if the job application we are trying to delete actually exists
     if let Some(job_application) = conn.get_job_application_by_id(id)? {
         // Print the job application so the user knows exactly what they are deleting
-        print_job_application_to_terminal(&job_application);
+        let tags = conn.get_job_application_tags(id)?;
+        let offer = conn.get_offer(id)?;
+        let attachments = conn.get_attachments(id)?;
+        let resume_version = conn.get_sent_resume_version(id)?;
+        print_job_application_to_terminal(
+            &job_application,
+            &tags,
+            offer.as_ref(),
+            &attachments,
+            resume_version.as_ref(),
+        );
 
         // Confirm delete
         if input(
             "Are you sure you want to delete this job application? [y/N]:",
-            |s| Result::<bool, Infallible>::Ok(s.starts_with(&['y', 'Y'])), // Only do it if y, Y, or something that starts with y
+            |s| Result::<bool, Infallible>::Ok(s.starts_with(['y', 'Y'])), // Only do it if y, Y, or something that starts with y
         )? {
-            conn.delete_job_application(id)
+            let deleted = conn
+                .delete_job_application(id)
                 .map_err(Box::<dyn std::error::Error>::from)?;
-            println!("Successfully deleted job application {id}");
+            println!(
+                "Moved {} at {} to the trash. Use trash restore {id} to undo.",
+                deleted.job_title, deleted.company
+            );
         } else {
             println!("Aborting delete");
         }
@@ -584,103 +1077,1373 @@ fn delete<C: JobApplicationRepository>(
     }
 }
 
-/// Prints a given prompt and returns the input, parsed by `parse` to `T`
-/// Returns an Error if stdin.lines() returns an error, or if stdin.lines() ends (this should not happen because stdin should not have EOF).
-/// If `parse` returns an error, the program will try again, displaying the error message given by `parse`
-fn input<T, U, F>(prompt: &str, parse: F) -> Result<T, io::Error>
-where
-    U: Display,
-    F: Fn(&str) -> Result<T, U>,
-{
-    print!("{prompt} ");
-    std::io::stdout().flush().unwrap();
-    for line in stdin().lines() {
-        // For some reason Rust forces me to create this intermediate variable line_string
-        let line_string = line?;
-        let trimmed = line_string.trim();
-        if trimmed == "abort" {
-            return Err(io::Error::other("Operation aborted"));
-        }
-        match parse(trimmed) {
-            Ok(o) => return Ok(o),
-            Err(e) => println!("Invalid input: {e}"),
-        }
-        print!("{prompt} ");
-        std::io::stdout().flush().unwrap();
+/// Merge job application `merged_id` into `keep_id` after confirming with the user
+fn merge_command<C: JobApplicationRepository>(
+    conn: &mut C,
+    keep_id: i32,
+    merged_id: i32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Show what is being combined, so the user can check the ids are the right way around
+    for id in [keep_id, merged_id] {
+        let job_application = conn
+            .get_job_application_by_id(id)?
+            .ok_or_else(|| RepositoryError::job_application_not_found(id))?;
+        println!(
+            "{}: {} at {}",
+            job_application.id, job_application.job_title, job_application.company
+        );
     }
 
-    Err(io::Error::other("Reached EOF from stdin"))
+    if input(
+        &format!(
+            "Merge {merged_id} into {keep_id}? {merged_id} will be moved to the trash. [y/N]:"
+        ),
+        |s| Result::<bool, Infallible>::Ok(s.starts_with(['y', 'Y'])),
+    )? {
+        let combined = conn.merge_job_applications(keep_id, merged_id)?;
+        let tags = conn.get_job_application_tags(keep_id)?;
+        let offer = conn.get_offer(keep_id)?;
+        let attachments = conn.get_attachments(keep_id)?;
+        let resume_version = conn.get_sent_resume_version(keep_id)?;
+        print_job_application_to_terminal(
+            &combined,
+            &tags,
+            offer.as_ref(),
+            &attachments,
+            resume_version.as_ref(),
+        );
+    } else {
+        println!("Aborting merge");
+    }
+    Ok(())
 }
 
-/// Print the table, then show the results in the native spreadsheet application.
-/// This is crude, but an easy way to display while other features are being worked on.
-fn print_table(
-    job_applications: Vec<JobApplication>,
-    temp_dir: &Path,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // Create temporary file
-    let mut file = tempfile::Builder::new()
-        // Suffix must be CSV for opener to recognize it
-        .suffix(".csv")
-        // Keep even when `file` goes out of scope.
-        // This relies on the destructor of `temp_dir` to clean files.
-        // The program should clean this as soon as the spreadsheet system is closed, but using a spreadsheet system is a hack anyway.
-        .keep(true)
-        // Create in the temporary directory
-        .tempfile_in(temp_dir)?;
+/// Print every job application in the trash, most recently deleted first
+fn list_trash<C: JobApplicationRepository>(conn: &mut C) -> Result<(), Box<dyn std::error::Error>> {
+    let deleted_job_applications = conn.get_deleted_job_applications()?;
 
-    // Write to that file
-    writeln!(&mut file, "ID,Source,Company,Job Title,Application Date,Time Taken,Human Response,Date,Days to Respond,Website,Notes")?;
-    for job_application in job_applications {
-        writeln!(&mut file, "\"{}\",\"{}\",\"{}\",\"{}\",\"{:02}/{:02}/{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"",
-            job_application.id,
-            job_application.source.replace("\"","\"\""),
-            job_application.company.replace("\"","\"\""),
-            job_application.job_title.replace("\"","\"\""),
-            job_application.application_date.month() as u8,
-            job_application.application_date.day(),
-            job_application.application_date.year(),
-            job_application.time_investment.map_or("".to_string(),
-                |t| format!("{:02}:{:02}", t.whole_minutes(), t.whole_seconds() % 60)
-            ),
-            job_application.human_response,
-            job_application.human_response_date.map_or("".to_string(),
-                |d| format!("{:02}/{:02}/{}", d.month() as u8, d.day(), d.year())
-            ),
-            job_application.human_response_date
-                .map_or("".to_owned(), |resp_date: Date| {
-                    let duration_between_dates = resp_date - job_application.application_date;
-                    duration_between_dates.whole_days().to_string()
-                }),
-            job_application.application_website.map_or("".to_string(), |s| s.replace("\"","\"\"")),
-            job_application.notes.map_or("".to_string(), |s| s.replace("\"","\"\"")),
-        )?;
+    if deleted_job_applications.is_empty() {
+        return Err(Box::<dyn std::error::Error>::from("The trash is empty"));
     }
-    // Not sure if flushing is necessary, but it doesn't hurt
-    file.flush()?;
 
-    // Open whatever is used to open CSV
-    opener::open(file.path())?;
+    for DeletedJobApplication {
+        job_application: ja,
+        deleted_at,
+    } in deleted_job_applications
+    {
+        println!(
+            "{}: {} at {} (deleted {})",
+            ja.id,
+            ja.job_title,
+            ja.company,
+            format_utc_time(deleted_at)
+        );
+    }
+    Ok(())
+}
 
+/// Take a job application back out of the trash
+fn restore_command<C: JobApplicationRepository>(
+    conn: &mut C,
+    id: i32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check that the job application is actually in the trash
+    if !conn
+        .get_deleted_job_applications()?
+        .iter()
+        .any(|deleted| deleted.job_application.id == id)
+    {
+        return Err(Box::<dyn std::error::Error>::from(
+            "No job application found in the trash",
+        ));
+    }
+
+    conn.restore_job_application(id)?;
+    println!("Restored job application {id}");
     Ok(())
 }
 
-/// Parse a date string into a date
-///
-/// Used for input()
-fn parse_date(s: &str) -> Result<Date, time::error::Parse> {
-    if !s.is_empty() {
-        // If a date was given, try to parse it
-        Date::parse(
-            s,
-            format_description!("[month repr:numerical]/[day]/[year]"),
-        )
+/// Permanently delete everything in the trash after confirming with the user
+fn empty_trash_command<C: JobApplicationRepository>(
+    conn: &mut C,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let count = conn.get_deleted_job_applications()?.len();
+    if count == 0 {
+        return Err(Box::<dyn std::error::Error>::from("The trash is empty"));
+    }
+
+    // Confirm delete
+    if input(
+        &format!("Are you sure you want to permanently delete {count} job application(s)? This can't be undone. [y/N]:"),
+        |s| Result::<bool, Infallible>::Ok(s.starts_with(['y', 'Y'])),
+    )? {
+        let deleted = conn.empty_trash()?;
+        println!("Permanently deleted {deleted} job application(s)");
     } else {
-        // The string being empty is fine, just use today
-        Ok(time::OffsetDateTime::now_local()
-            .unwrap_or_else(|_| time::OffsetDateTime::now_utc())
-            .date())
+        println!("Aborting delete");
+    }
+    Ok(())
+}
+
+/// Undo the most recent change to a job application
+fn undo_command<C: JobApplicationRepository>(
+    conn: &mut C,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match conn.undo()? {
+        Some(entry) => {
+            println!(
+                "Undid {} of job application {}",
+                entry.operation, entry.job_application_id
+            );
+            Ok(())
+        }
+        None => Err(Box::<dyn std::error::Error>::from("Nothing to undo")),
+    }
+}
+
+/// Redo the most recently undone change to a job application
+fn redo_command<C: JobApplicationRepository>(
+    conn: &mut C,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match conn.redo()? {
+        Some(entry) => {
+            println!(
+                "Redid {} of job application {}",
+                entry.operation, entry.job_application_id
+            );
+            Ok(())
+        }
+        None => Err(Box::<dyn std::error::Error>::from("Nothing to redo")),
+    }
+}
+
+/// Print every recorded change to a job application, oldest first, with who made it and the fields it changed
+fn history_command<C: JobApplicationRepository>(
+    conn: &mut C,
+    id: i32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let history = conn.get_history(id)?;
+
+    if history.is_empty() {
+        return Err(Box::<dyn std::error::Error>::from(
+            "No history found. Only MySQL databases keep a history of changes",
+        ));
+    }
+
+    for entry in history {
+        println!(
+            "{} by {}: {}",
+            format_utc_time(entry.changed_at),
+            entry.changed_by,
+            entry.operation
+        );
+        for change in entry.changes {
+            println!(
+                "  {}: {} -> {}",
+                change.field,
+                change.old_value.as_deref().unwrap_or("(empty)"),
+                change.new_value.as_deref().unwrap_or("(empty)")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Full-text search all job applications and print them, most relevant first, with the matching part of each
+fn find<C: JobApplicationRepository>(
+    conn: &mut C,
+    query: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let results = conn.full_text_search(query)?;
+
+    if results.is_empty() {
+        return Err(Box::<dyn std::error::Error>::from(
+            "No job application found",
+        ));
+    }
+
+    println!("{} job application(s) found:", results.len());
+    for FullTextSearchResult {
+        job_application: ja,
+        snippet,
+        ..
+    } in results
+    {
+        println!(
+            "{}: {} at {} ({})\n    {}",
+            ja.id, ja.job_title, ja.company, ja.human_response, snippet
+        );
+    }
+
+    Ok(())
+}
+
+/// Put a tag on a job application, then show all of its tags
+fn tag_command<C: JobApplicationRepository>(
+    conn: &mut C,
+    id: i32,
+    tag: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    conn.add_tag(id, tag)?;
+    println!("Tags: {}", format_tags(&conn.get_job_application_tags(id)?));
+    Ok(())
+}
+
+/// Take a tag off of a job application, then show the tags it has left
+fn untag_command<C: JobApplicationRepository>(
+    conn: &mut C,
+    id: i32,
+    tag: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    conn.remove_tag(id, tag)?;
+    println!("Tags: {}", format_tags(&conn.get_job_application_tags(id)?));
+    Ok(())
+}
+
+/// Print every tag that is on at least one job application
+fn list_tags<C: JobApplicationRepository>(conn: &mut C) -> Result<(), Box<dyn std::error::Error>> {
+    let tags = conn.get_tags()?;
+
+    if tags.is_empty() {
+        return Err(Box::<dyn std::error::Error>::from("No tags found"));
+    }
+
+    println!("{}", format_tags(&tags));
+    Ok(())
+}
+
+/// Print every custom field and its type
+fn list_fields<C: JobApplicationRepository>(
+    conn: &mut C,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let custom_fields = conn.get_custom_fields()?;
+
+    if custom_fields.is_empty() {
+        return Err(Box::<dyn std::error::Error>::from("No custom fields found"));
+    }
+
+    for custom_field in custom_fields {
+        println!("{} ({})", custom_field.name, custom_field.field_type);
+    }
+    Ok(())
+}
+
+/// Ask the user for the type of a new custom field and define it
+fn add_field<C: JobApplicationRepository>(
+    conn: &mut C,
+    name: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let field_type: CustomFieldType = input(
+        "Field type. Enter one of the following:
+\ttext
+\tnumber
+\tdate
+\tbool
+\tselect:<option>,<option>,... for one of a list of options\n",
+        str::parse::<CustomFieldType>,
+    )?;
+
+    let custom_field = conn
+        .add_custom_field(name, &field_type)
+        .map_err(|error| match error {
+            RepositoryError::Conflict(_) => {
+                format!("A custom field named {} already exists", name.trim()).into()
+            }
+            error => Box::<dyn std::error::Error>::from(error),
+        })?;
+    println!(
+        "Added custom field {} ({})",
+        custom_field.name, custom_field.field_type
+    );
+    Ok(())
+}
+
+/// Delete a custom field and all of its values after confirming with the user
+fn delete_field<C: JobApplicationRepository>(
+    conn: &mut C,
+    name: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check if the custom field we are trying to delete actually exists
+    let Some(custom_field) = conn
+        .get_custom_fields()?
+        .into_iter()
+        .find(|custom_field| custom_field.name.eq_ignore_ascii_case(name))
+    else {
+        return Err(Box::<dyn std::error::Error>::from("No custom field found"));
+    };
+
+    // Confirm delete
+    if input(
+        &format!(
+            "Are you sure you want to delete {} and its value on every job application? [y/N]:",
+            custom_field.name
+        ),
+        |s| Result::<bool, Infallible>::Ok(s.starts_with(['y', 'Y'])),
+    )? {
+        conn.delete_custom_field(&custom_field.name)?;
+        println!("Successfully deleted custom field {}", custom_field.name);
+    } else {
+        println!("Aborting delete");
+    }
+    Ok(())
+}
+
+/// Prompt a user for the details of the job offer for job application `id` and save it
+///
+/// Replaces the offer the job application already has, if any.
+fn offer_command<C: JobApplicationRepository>(
+    conn: &mut C,
+    id: i32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check that the job application exists before asking for anything
+    let Some(job_application) = conn.get_job_application_by_id(id)? else {
+        return Err(Box::<dyn std::error::Error>::from(
+            "No job application found",
+        ));
+    };
+    println!(
+        "Offer for {} at {}:",
+        job_application.job_title, job_application.company
+    );
+
+    // This will be used by multiple inputs
+    let wrap_ok = |s: &str| {
+        Result::<_, Infallible>::Ok(if s.is_empty() {
+            None
+        } else {
+            Some(s.to_owned())
+        })
+    };
+
+    let base_salary: f64 = input("Base salary:", |s| {
+        parse_amount_optional(s)?.ok_or_else(|| "Base salary is required".to_owned())
+    })?;
+    let pay_period: PayPeriod = input(
+        "Pay period (hourly, daily, weekly, monthly, or yearly) (leave blank for yearly):",
+        |s| parse_pay_period_optional(s).map(Option::unwrap_or_default),
+    )?;
+    // Default to the currency of the posted salary, since it is most likely the same
+    let currency: Option<String> = match job_application.salary_currency {
+        Some(salary_currency) => Some(
+            input(
+                &format!("Currency (leave blank for {salary_currency}):"),
+                wrap_ok,
+            )?
+            .unwrap_or(salary_currency),
+        ),
+        None => input("Currency, like USD (leave blank for none):", wrap_ok)?,
+    };
+    let bonus: Option<f64> = input(
+        "Expected yearly bonus (leave blank for none):",
+        parse_amount_optional,
+    )?;
+    let equity_value: Option<f64> = input(
+        "Total value of the equity grant (leave blank for none):",
+        parse_amount_optional,
+    )?;
+    // Only prompt if there is equity to vest
+    let vesting_years: Option<f64> = if equity_value.is_some() {
+        input(
+            &format!(
+                "Years the equity vests over (leave blank for {}):",
+                Offer::DEFAULT_VESTING_YEARS
+            ),
+            parse_amount_optional,
+        )?
+    } else {
+        None
+    };
+    let benefits: Option<String> = input(
+        "Benefits, like health insurance and PTO (leave blank for none):",
+        wrap_ok,
+    )?;
+    let start_date: Option<Date> = input(
+        "Start date (leave blank for unknown) (mm/dd/yyyy):",
+        parse_date_optional,
+    )?;
+    let deadline: Option<Date> = input(
+        "Deadline to accept (leave blank for none) (mm/dd/yyyy):",
+        parse_date_optional,
+    )?;
+
+    let offer = Offer {
+        job_application_id: id,
+        base_salary,
+        pay_period,
+        currency,
+        bonus,
+        equity_value,
+        vesting_years,
+        benefits,
+        start_date,
+        deadline,
+    };
+    conn.save_offer(&offer)?;
+    println!(
+        "Saved offer. Total per year: {:.2} {}",
+        offer.annual_total(),
+        offer.currency.as_deref().unwrap_or_default()
+    );
+    Ok(())
+}
+
+/// Save the job posting description for job application `id`, captured today
+///
+/// The description is read from `file` if given, otherwise it is prompted for. Replaces the description the job application already has, if any.
+fn description_command<C: JobApplicationRepository>(
+    conn: &mut C,
+    id: i32,
+    file: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check that the job application exists before reading or asking for anything
+    let Some(job_application) = conn.get_job_application_by_id(id)? else {
+        return Err(Box::<dyn std::error::Error>::from(
+            "No job application found",
+        ));
+    };
+
+    let description = match file {
+        Some(file) => read_job_description_file(Path::new(file))?,
+        None => {
+            println!(
+                "Job description for {} at {}:",
+                job_application.job_title, job_application.company
+            );
+            let first_line: String = input(
+                "Description (start with ` for multiple lines, enter 'remove' to remove):",
+                |s| Result::<_, Infallible>::Ok(s.to_owned()),
+            )?;
+            match first_line.as_str() {
+                "remove" => {
+                    conn.delete_job_description(id)?;
+                    println!("Removed job description");
+                    return Ok(());
+                }
+                // Support multiline descriptions, the same way as notes
+                s if s.starts_with('`') => {
+                    let mut description = String::new();
+                    let mut line = s[1..].to_owned();
+                    // Keep reading lines until one contains the closing backtick
+                    loop {
+                        if let Some(backtick) = line.find('`') {
+                            description += &line[..backtick];
+                            break;
+                        }
+                        description += &line;
+                        description += "\n";
+                        line = input("\\`bquote>", |s| Result::<_, Infallible>::Ok(s.to_owned()))?;
+                    }
+                    description
+                }
+                _ => first_line,
+            }
+        }
+    };
+
+    if description.trim().is_empty() {
+        return Err(Box::<dyn std::error::Error>::from(
+            "No job description given",
+        ));
+    }
+
+    conn.save_job_description(&JobDescription {
+        job_application_id: id,
+        description: description.trim().to_owned(),
+        captured_date: parse_date("")?,
+    })?;
+    println!("Saved job description");
+    Ok(())
+}
+
+/// Attach the file at `file` to job application `id`, attached today
+fn attach_command<C: JobApplicationRepository>(
+    conn: &mut C,
+    id: i32,
+    role: AttachmentRole,
+    file: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let contents = fs::read(file)?;
+    let attachment = conn.add_attachment(id, role, file, &contents, parse_date("")?)?;
+    println!(
+        "Attached {} as attachment {}",
+        attachment.file_name, attachment.id
+    );
+
+    // A resume that matches a resume version's file tells which version was sent, unless one is already recorded
+    if role == AttachmentRole::Resume && conn.get_sent_resume_version(id)?.is_none() {
+        if let Some(resume_version) =
+            conn.get_resume_versions()?
+                .into_iter()
+                .find(|resume_version| {
+                    resume_version.file_hash.as_deref() == Some(attachment.file_hash.as_str())
+                })
+        {
+            conn.set_sent_resume_version(id, Some(resume_version.id))?;
+            println!("Recorded resume version {} as sent", resume_version.label);
+        }
+    }
+    Ok(())
+}
+
+/// Detach attachment `id` from its job application
+fn detach_command<C: JobApplicationRepository>(
+    conn: &mut C,
+    id: i32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(attachment) = conn.get_attachment(id)? else {
+        return Err(Box::<dyn std::error::Error>::from("No attachment found"));
+    };
+    conn.delete_attachment(id)?;
+    println!("Detached {}", attachment.file_name);
+    Ok(())
+}
+
+/// Write attachment `id` to the temporary directory and open it with the default application for its file type
+fn open_command<C: JobApplicationRepository>(
+    conn: &mut C,
+    id: i32,
+    temp_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (Some(attachment), Some(contents)) =
+        (conn.get_attachment(id)?, conn.get_attachment_contents(id)?)
+    else {
+        return Err(Box::<dyn std::error::Error>::from("No attachment found"));
+    };
+
+    // Keep the file name so the right application is used, in a directory per attachment so names don't collide.
+    // Like the table, this relies on the destructor of `temp_dir` to clean files.
+    let directory = temp_dir.join(id.to_string());
+    fs::create_dir_all(&directory)?;
+    let path = directory.join(&attachment.file_name);
+    fs::write(&path, contents)?;
+    opener::open(path)?;
+
+    Ok(())
+}
+
+/// Print every resume version
+fn list_resumes<C: JobApplicationRepository>(
+    conn: &mut C,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let resume_versions = conn.get_resume_versions()?;
+
+    if resume_versions.is_empty() {
+        return Err(Box::<dyn std::error::Error>::from(
+            "No resume versions found",
+        ));
+    }
+
+    for resume_version in resume_versions {
+        println!(
+            "{}: {} ({:02}/{:02}/{}){}",
+            resume_version.id,
+            resume_version.label,
+            resume_version.created_date.month() as u8,
+            resume_version.created_date.day(),
+            resume_version.created_date.year(),
+            resume_version
+                .notes
+                .map_or("".to_string(), |notes| format!(" {notes}")),
+        );
+    }
+    Ok(())
+}
+
+/// Prompt a user for the file, date, and notes of a new resume version and add it
+///
+/// Only the hash of the file is kept, so resumes attached to job applications can be matched to the version.
+fn add_resume<C: JobApplicationRepository>(
+    conn: &mut C,
+    label: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file_hash: Option<String> =
+        input("Path to the resume file (leave blank for none):", |s| {
+            if s.is_empty() {
+                Ok(None)
+            } else {
+                fs::read(s).map(|contents| Some(Attachment::hash_contents(&contents)))
+            }
+        })?;
+    let created_date: Date = input(
+        "Date written (leave blank for today) (mm/dd/yyyy):",
+        parse_date,
+    )?;
+    let notes: String = input(
+        "Notes, like what changed in this version (leave blank for none):",
+        |s| Result::<_, Infallible>::Ok(s.to_owned()),
+    )?;
+
+    let resume_version =
+        conn.add_resume_version(label, file_hash.as_deref(), created_date, Some(&notes))?;
+    println!(
+        "Added resume version {}: {}",
+        resume_version.id, resume_version.label
+    );
+    Ok(())
+}
+
+/// Delete a resume version after confirming with the user
+fn delete_resume<C: JobApplicationRepository>(
+    conn: &mut C,
+    id: i32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Check if the resume version we are trying to delete actually exists
+    let Some(resume_version) = conn
+        .get_resume_versions()?
+        .into_iter()
+        .find(|resume_version| resume_version.id == id)
+    else {
+        return Err(Box::<dyn std::error::Error>::from(
+            "No resume version found",
+        ));
+    };
+
+    // Confirm delete
+    if input(
+        &format!(
+            "Are you sure you want to delete {}? Job applications sent with it will no longer have a resume version. [y/N]:",
+            resume_version.label
+        ),
+        |s| Result::<bool, Infallible>::Ok(s.starts_with(['y', 'Y'])),
+    )? {
+        conn.delete_resume_version(id)?;
+        println!("Successfully deleted resume version {}", resume_version.label);
+    } else {
+        println!("Aborting delete");
+    }
+    Ok(())
+}
+
+/// Record which resume version was sent with job application `id`, or clear it if `resume_id` is `None`
+fn sent_resume_command<C: JobApplicationRepository>(
+    conn: &mut C,
+    id: i32,
+    resume_id: Option<i32>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if conn.get_job_application_by_id(id)?.is_none() {
+        return Err(Box::<dyn std::error::Error>::from(
+            "No job application found",
+        ));
+    }
+
+    conn.set_sent_resume_version(id, resume_id)
+        .map_err(|error| match error {
+            // The job application was checked above, so only the resume version can be missing
+            RepositoryError::Constraint(_) => format!(
+                "No resume version with id {}",
+                resume_id.unwrap_or_default()
+            )
+            .into(),
+            error => Box::<dyn std::error::Error>::from(error),
+        })?;
+    match conn.get_sent_resume_version(id)? {
+        Some(resume_version) => println!("Resume version: {}", resume_version.label),
+        None => println!("Cleared resume version"),
+    }
+    Ok(())
+}
+
+/// Print every offer side by side, best first, scored by `weights`
+fn compare_command<C: JobApplicationRepository>(
+    conn: &mut C,
+    weights: &CriteriaWeights,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let offers = conn.get_offers()?;
+
+    if offers.is_empty() {
+        return Err(Box::<dyn std::error::Error>::from("No offers found"));
+    }
+
+    // Amounts are compared as-is, so warn if they can't be compared fairly
+    let currencies: BTreeSet<String> = offers
+        .iter()
+        .map(|offer| offer.currency.as_deref().unwrap_or_default().to_uppercase())
+        .collect();
+    if currencies.len() > 1 {
+        println!(
+            "Warning: the offers use different currencies, which are not converted when comparing"
+        );
+    }
+
+    println!("Weights: {weights}");
+    println!(
+        "{:>4}  {:<24} {:>8} {:>12} {:>12} {:>12} {:>12} {:>6}  {:<10}",
+        "ID", "Company", "Currency", "Base", "Bonus", "Equity", "Total", "Score", "Deadline"
+    );
+    for scored_offer in score_offers(offers, weights) {
+        let company = conn
+            .get_job_application_by_id(scored_offer.offer.job_application_id)?
+            .map_or("".to_string(), |ja| ja.company);
+        println!(
+            "{:>4}  {:<24} {:>8} {:>12.2} {:>12.2} {:>12.2} {:>12.2} {:>6.1}  {:<10}",
+            scored_offer.offer.job_application_id,
+            // Keep the columns lined up
+            company.chars().take(24).collect::<String>(),
+            scored_offer.offer.currency.as_deref().unwrap_or_default(),
+            scored_offer.annual_base,
+            scored_offer.annual_bonus,
+            scored_offer.annual_equity,
+            scored_offer.annual_total,
+            scored_offer.score,
+            scored_offer
+                .offer
+                .deadline
+                .map_or("".to_string(), |d| format!(
+                    "{:02}/{:02}/{}",
+                    d.month() as u8,
+                    d.day(),
+                    d.year()
+                )),
+        );
+    }
+
+    Ok(())
+}
+
+/// Print how job applications are going for each work arrangement, then for each resume version
+fn stats_command<C: JobApplicationRepository>(
+    conn: &mut C,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let stats = conn.get_work_arrangement_stats()?;
+
+    if stats.is_empty() {
+        return Err(Box::<dyn std::error::Error>::from(
+            "No job applications found",
+        ));
+    }
+
+    println!(
+        "{:<12} {:>12} {:>8} {:>10} {:>10} {:>6} {:>14}",
+        "Arrangement",
+        "Applications",
+        "Pending",
+        "Rejections",
+        "Interviews",
+        "Offers",
+        "Response rate"
+    );
+    for arrangement_stats in stats {
+        let responses = arrangement_stats.applications - arrangement_stats.pending;
+        println!(
+            "{:<12} {:>12} {:>8} {:>10} {:>10} {:>6} {:>13.1}%",
+            arrangement_stats
+                .work_arrangement
+                .map_or("unknown".to_string(), |work_arrangement| work_arrangement
+                    .to_string()),
+            arrangement_stats.applications,
+            arrangement_stats.pending,
+            arrangement_stats.rejections,
+            arrangement_stats.interviews,
+            arrangement_stats.offers,
+            // There is always at least one application in a group
+            responses as f64 / arrangement_stats.applications as f64 * 100.0,
+        );
+    }
+
+    // Without any resume versions, the only group would be job applications without one
+    let resume_stats = conn.get_resume_version_stats()?;
+    if resume_stats
+        .iter()
+        .all(|version_stats| version_stats.resume_version_id.is_none())
+    {
+        return Ok(());
+    }
+
+    // The number of applications is the sample size of both rates
+    println!();
+    println!(
+        "{:<24} {:>12} {:>10} {:>14} {:>10} {:>15} {:>6}",
+        "Resume version",
+        "Applications",
+        "Responses",
+        "Response rate",
+        "Interviews",
+        "Interview rate",
+        "Offers"
+    );
+    for version_stats in resume_stats {
+        println!(
+            "{:<24} {:>12} {:>10} {:>14} {:>10} {:>15} {:>6}",
+            version_stats
+                .label
+                .as_deref()
+                .unwrap_or("none recorded")
+                .chars()
+                .take(24)
+                .collect::<String>(),
+            version_stats.applications,
+            version_stats.responses,
+            format_rate(version_stats.response_rate()),
+            version_stats.interviews,
+            format_rate(version_stats.interview_rate()),
+            version_stats.offers,
+        );
+    }
+
+    Ok(())
+}
+
+/// Format a fraction as a percentage, like 12.5%, or "-" if there is nothing to divide by
+fn format_rate(rate: Option<f64>) -> String {
+    rate.map_or("-".to_string(), |rate| format!("{:.1}%", rate * 100.0))
+}
+
+/// Format an optional yes/no field as "yes", "no", or blank if unknown
+fn format_yes_no(value: Option<bool>) -> &'static str {
+    match value {
+        Some(true) => "yes",
+        Some(false) => "no",
+        None => "",
+    }
+}
+
+/// Format the posted salary range of a job application, like "100000-120000 USD yearly"
+fn format_salary_range(ja: &JobApplication) -> String {
+    let range = match (ja.salary_min, ja.salary_max) {
+        (Some(min), Some(max)) if min != max => format!("{min}-{max}"),
+        (Some(amount), _) | (None, Some(amount)) => amount.to_string(),
+        (None, None) => return "".to_string(),
+    };
+    [
+        Some(range),
+        ja.salary_currency.clone(),
+        ja.pay_period.map(|pay_period| pay_period.to_string()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Format a size in bytes with the largest unit that keeps it at least 1, like 12.3 KB
+fn format_size(bytes: i64) -> String {
+    let mut size = bytes as f64;
+    for unit in ["B", "KB", "MB"] {
+        if size < 1024.0 {
+            return if unit == "B" {
+                format!("{bytes} B")
+            } else {
+                format!("{size:.1} {unit}")
+            };
+        }
+        size /= 1024.0;
+    }
+    format!("{size:.1} GB")
+}
+
+/// Format a time stored in UTC, like when a job application was deleted, in local time, like 01/31/2025 14:05
+///
+/// If the local offset can't be found, UTC is shown instead.
+fn format_utc_time(time: PrimitiveDateTime) -> String {
+    let offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+    time.assume_utc()
+        .to_offset(offset)
+        .format(format_description!("[month]/[day]/[year] [hour]:[minute]"))
+        .unwrap_or_default()
+}
+
+/// Join tag names into a comma-separated list
+fn format_tags(tags: &[Tag]) -> String {
+    tags.iter()
+        .map(|tag| tag.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Run a command that only SQLite databases have, like backups and syncing
+fn sqlite_only<C: JobApplicationRepository>(
+    conn: &mut C,
+    command: impl FnOnce(&mut dyn SqliteRepository) -> Result<(), Box<dyn std::error::Error>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match conn.sqlite() {
+        Some(conn) => command(conn),
+        None => Err(Box::<dyn std::error::Error>::from(
+            "Only SQLite databases can do this. A MySQL server takes care of it itself, and plain-text storage leaves it to git",
+        )),
+    }
+}
+
+/// Encrypt the database with a new passphrase, which is asked for twice
+fn encrypt_command(conn: &mut dyn SqliteRepository) -> Result<(), Box<dyn std::error::Error>> {
+    let current_passphrase = input_current_passphrase(conn)?;
+    let Some(passphrase) = input_passphrase("New passphrase:")? else {
+        return Err(Box::<dyn std::error::Error>::from(
+            "No passphrase given, so nothing was changed",
+        ));
+    };
+    if input_passphrase("Repeat the new passphrase:")?.as_ref() != Some(&passphrase) {
+        return Err(Box::<dyn std::error::Error>::from(
+            "The passphrases don't match, so nothing was changed",
+        ));
+    }
+
+    conn.set_passphrase(Some(&passphrase), current_passphrase.as_deref())?;
+    println!("The database is encrypted. The passphrase will be asked for each time it is opened");
+    Ok(())
+}
+
+/// Store the database as plain text after confirming with the user
+fn decrypt_command(conn: &mut dyn SqliteRepository) -> Result<(), Box<dyn std::error::Error>> {
+    if input(
+        "Anyone who can read the database file will be able to read your job applications. Store it as plain text? [y/N]:",
+        |s| Result::<bool, Infallible>::Ok(s.starts_with(['y', 'Y'])),
+    )? {
+        let current_passphrase = input_current_passphrase(conn)?;
+        conn.set_passphrase(None, current_passphrase.as_deref())?;
+        println!("The database is stored as plain text");
+    } else {
+        println!("Aborting decrypt");
+    }
+    Ok(())
+}
+
+/// Ask for the passphrase the database has now, if it is encrypted
+fn input_current_passphrase(
+    conn: &mut dyn SqliteRepository,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    if conn.is_encrypted()? {
+        Ok(input_passphrase("Current passphrase:")?)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Back up the database, then remove old backups
+fn backup_now(conn: &mut dyn SqliteRepository) -> Result<(), Box<dyn std::error::Error>> {
+    let backup = conn.take_backup(backup_retention())?;
+    println!("Backed up to {}", backup.path.display());
+    Ok(())
+}
+
+/// List every backup of the database, newest first
+fn list_backups(conn: &mut dyn SqliteRepository) -> Result<(), Box<dyn std::error::Error>> {
+    let backups = conn.get_backups()?;
+
+    if backups.is_empty() {
+        return Err(Box::<dyn std::error::Error>::from("No backups found"));
+    }
+
+    for backup in &backups {
+        print_backup(backup);
+    }
+    Ok(())
+}
+
+/// Replace the database with a backup after confirming with the user, asking for its passphrase if it is encrypted
+fn restore_backup_command(
+    conn: &mut dyn SqliteRepository,
+    file: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let encrypted = conn
+        .get_backups()?
+        .iter()
+        .find(|backup| backup.file_name() == file)
+        .map_or_else(
+            || repository::is_encrypted(Path::new(file)),
+            |backup| Ok(backup.encrypted),
+        )?;
+
+    if !input(
+        &format!("Are you sure you want to replace the database with {file}? Changes since it was taken will be lost, unless this restore is undone by restoring the backup taken first. [y/N]:"),
+        |s| Result::<bool, Infallible>::Ok(s.starts_with(['y', 'Y'])),
+    )? {
+        println!("Aborting restore");
+        return Ok(());
+    }
+    let passphrase = if encrypted {
+        input_passphrase("Passphrase the database had when the backup was taken:")?
+    } else {
+        None
+    };
+
+    let current_passphrase = input_current_passphrase(conn)?;
+
+    let before_restore = conn.restore_backup(
+        Path::new(file),
+        passphrase.as_deref(),
+        current_passphrase.as_deref(),
+    )?;
+    println!(
+        "Restored {file}. The database as it was before is backed up as {}",
+        before_restore.file_name()
+    );
+    Ok(())
+}
+
+/// Sync with another database or sync file, asking for its passphrase if it is encrypted and for which value to keep for each conflict
+fn sync_command(
+    conn: &mut dyn SqliteRepository,
+    file: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let passphrase = if repository::is_encrypted(Path::new(file))? {
+        input_passphrase(&format!("{file} is encrypted. Passphrase:"))?
+    } else {
+        None
+    };
+
+    let report = conn.sync_with(
+        Path::new(file),
+        passphrase.as_deref(),
+        &mut resolve_conflict,
+    )?;
+    print_sync_changes("This database", &report.local);
+    print_sync_changes(file, &report.remote);
+    if report.conflicts > 0 {
+        println!("{} conflicts resolved", report.conflicts);
+    }
+    Ok(())
+}
+
+/// Ask which value of a conflicting field to keep in both databases
+fn resolve_conflict(conflict: &SyncConflict) -> SyncChoice {
+    let show = |value: &Option<String>| value.clone().unwrap_or_else(|| "(empty)".to_owned());
+    println!(
+        "{} changed in both databases for {} - {}",
+        conflict.field, conflict.company, conflict.job_title
+    );
+    println!("  mine:   {}", show(&conflict.local_value));
+    println!("  theirs: {}", show(&conflict.remote_value));
+
+    // If the answer can't be read, this database's value is kept
+    input("Keep (m)ine or (t)heirs? [m/t]:", |s| {
+        match s.trim().to_lowercase().as_str() {
+            "m" | "mine" => Ok(SyncChoice::Local),
+            "t" | "theirs" => Ok(SyncChoice::Remote),
+            _ => Err("Enter m or t"),
+        }
+    })
+    .unwrap_or(SyncChoice::Local)
+}
+
+/// Print what a sync changed in one database, like `This database: 2 added, 1 updated, 0 deleted`
+fn print_sync_changes(name: &str, changes: &SyncChanges) {
+    println!(
+        "{name}: {} added, {} updated, {} deleted",
+        changes.added, changes.updated, changes.deleted
+    );
+}
+
+/// Save a new sync file, encrypted if the user gives a passphrase
+fn export_sync_file_command(
+    conn: &mut dyn SqliteRepository,
+    file: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let passphrase = input_passphrase(
+        "Passphrase to encrypt the sync file with (blank to leave it unencrypted):",
+    )?;
+    if passphrase.is_some() && input_passphrase("Repeat the passphrase:")? != passphrase {
+        return Err(Box::<dyn std::error::Error>::from(
+            "The passphrases don't match, so nothing was saved",
+        ));
+    }
+
+    let count = conn.export_sync_file(Path::new(file), passphrase.as_deref())?;
+    println!("Saved {count} job applications to {file}. Sync with it on the other computer, then sync with it here again");
+    Ok(())
+}
+
+/// Save every job application as plain-text files in a new directory
+fn export_plain_text_command(
+    conn: &mut dyn SqliteRepository,
+    dir: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let count = conn.export_plain_text(Path::new(dir))?;
+    println!("Saved {count} job applications to {dir}. Set PLAIN_TEXT_DIR to it to use it instead of the database");
+    Ok(())
+}
+
+/// Copy every job application from a plain-text directory into the database
+fn import_plain_text_command(
+    conn: &mut dyn SqliteRepository,
+    dir: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let count = conn.import_plain_text(Path::new(dir))?;
+    println!("Imported {count} job applications from {dir}");
+    Ok(())
+}
+
+/// Print one line about a backup, like `ats-tracking-2025-01-31T18-30-00.000.db3  01/31/2025 12:30  1.2 MB`
+fn print_backup(backup: &Backup) {
+    println!(
+        "{}  {}  {}{}",
+        backup.file_name(),
+        format_utc_time(backup.taken_at),
+        format_size(backup.size as i64),
+        if backup.encrypted { "  encrypted" } else { "" }
+    );
+}
+
+/// Prints a given prompt and returns the passphrase typed, without showing it in the terminal
+/// Returns `None` if nothing was typed
+pub fn input_passphrase(prompt: &str) -> Result<Option<String>, io::Error> {
+    print!("{prompt} ");
+    stdout().flush()?;
+    let hidden = set_terminal_echo(false);
+    let mut line = String::new();
+    let read = stdin().read_line(&mut line);
+    if hidden {
+        set_terminal_echo(true);
+        // The newline that was typed wasn't shown either
+        println!();
+    }
+    read?;
+
+    let passphrase = line.trim_end_matches(['\r', '\n']);
+    Ok(Some(passphrase.to_owned()).filter(|passphrase| !passphrase.is_empty()))
+}
+
+/// Turn showing typed characters in the terminal on or off, returning whether it worked
+/// This uses `stty`, so it doesn't work on Windows or when stdin isn't a terminal, in which case typing is shown as usual.
+fn set_terminal_echo(echo: bool) -> bool {
+    Command::new("stty")
+        .arg(if echo { "echo" } else { "-echo" })
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Prints a given prompt and returns the input, parsed by `parse` to `T`
+/// Returns an Error if stdin.lines() returns an error, or if stdin.lines() ends (this should not happen because stdin should not have EOF).
+/// If `parse` returns an error, the program will try again, displaying the error message given by `parse`
+fn input<T, U, F>(prompt: &str, parse: F) -> Result<T, io::Error>
+where
+    U: Display,
+    F: Fn(&str) -> Result<T, U>,
+{
+    print!("{prompt} ");
+    std::io::stdout().flush().unwrap();
+    for line in stdin().lines() {
+        // For some reason Rust forces me to create this intermediate variable line_string
+        let line_string = line?;
+        let trimmed = line_string.trim();
+        if trimmed == "abort" {
+            return Err(io::Error::other("Operation aborted"));
+        }
+        match parse(trimmed) {
+            Ok(o) => return Ok(o),
+            Err(e) => println!("Invalid input: {e}"),
+        }
+        print!("{prompt} ");
+        std::io::stdout().flush().unwrap();
+    }
+
+    Err(io::Error::other("Reached EOF from stdin"))
+}
+
+/// Print the table, then show the results in the native spreadsheet application.
+/// This is crude, but an easy way to display while other features are being worked on.
+/// Each custom field gets a column after the built-in fields.
+fn print_table(
+    job_applications: Vec<JobApplication>,
+    custom_fields: &[CustomField],
+    activity: &BTreeMap<i32, Activity>,
+    temp_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Create temporary file
+    let mut file = tempfile::Builder::new()
+        // Suffix must be CSV for opener to recognize it
+        .suffix(".csv")
+        // Keep even when `file` goes out of scope.
+        // This relies on the destructor of `temp_dir` to clean files.
+        // The program should clean this as soon as the spreadsheet system is closed, but using a spreadsheet system is a hack anyway.
+        .disable_cleanup(true)
+        // Create in the temporary directory
+        .tempfile_in(temp_dir)?;
+
+    // Write to that file
+    write!(&mut file, "ID,Source,Company,Job Title,Application Date,Time Taken,Human Response,Date,Days to Respond,Website,Notes,Posted Salary,Posted Equity,Posted Bonus,Location,Work Arrangement,Relocation,Visa Sponsorship,Created,Last Changed,Last Activity")?;
+    for custom_field in custom_fields {
+        write!(
+            &mut file,
+            ",\"{}\"",
+            custom_field.name.replace("\"", "\"\"")
+        )?;
+    }
+    writeln!(&mut file)?;
+    for job_application in job_applications {
+        // In the same order as the header
+        let custom_field_columns: String = custom_fields
+            .iter()
+            .map(|custom_field| {
+                format!(
+                    ",\"{}\"",
+                    job_application
+                        .custom_fields
+                        .get(&custom_field.name)
+                        .map_or("".to_string(), |v| v.to_string().replace("\"", "\"\""))
+                )
+            })
+            .collect();
+        let activity = activity.get(&job_application.id);
+        writeln!(&mut file, "\"{}\",\"{}\",\"{}\",\"{}\",\"{:02}/{:02}/{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"{}",
+            job_application.id,
+            job_application.source.replace("\"","\"\""),
+            job_application.company.replace("\"","\"\""),
+            job_application.job_title.replace("\"","\"\""),
+            job_application.application_date.month() as u8,
+            job_application.application_date.day(),
+            job_application.application_date.year(),
+            job_application.time_investment.map_or("".to_string(),
+                |t| format!("{:02}:{:02}", t.whole_minutes(), t.whole_seconds() % 60)
+            ),
+            job_application.human_response,
+            job_application.human_response_date.map_or("".to_string(),
+                |d| format!("{:02}/{:02}/{}", d.month() as u8, d.day(), d.year())
+            ),
+            job_application.human_response_date
+                .map_or("".to_owned(), |resp_date: Date| {
+                    let duration_between_dates = resp_date - job_application.application_date;
+                    duration_between_dates.whole_days().to_string()
+                }),
+            job_application.application_website.as_deref().map_or("".to_string(), |s| s.replace("\"","\"\"")),
+            job_application.notes.as_deref().map_or("".to_string(), |s| s.replace("\"","\"\"")),
+            format_salary_range(&job_application).replace("\"","\"\""),
+            job_application.equity.as_deref().map_or("".to_string(), |s| s.replace("\"","\"\"")),
+            job_application.bonus.as_deref().map_or("".to_string(), |s| s.replace("\"","\"\"")),
+            job_application.location().replace("\"","\"\""),
+            job_application.work_arrangement.map_or("".to_string(), |w| w.to_string()),
+            format_yes_no(job_application.relocation),
+            format_yes_no(job_application.visa_sponsorship),
+            activity.map_or("".to_string(), |activity| format_utc_time(activity.created_at)),
+            activity.map_or("".to_string(), |activity| format_utc_time(activity.updated_at)),
+            activity.map_or("".to_string(),
+                |activity| format!("{:02}/{:02}/{}", activity.last_activity.month() as u8, activity.last_activity.day(), activity.last_activity.year())
+            ),
+            custom_field_columns,
+        )?;
+    }
+    // Not sure if flushing is necessary, but it doesn't hurt
+    file.flush()?;
+
+    // Open whatever is used to open CSV
+    opener::open(file.path())?;
+
+    Ok(())
+}
+
+/// Parse a date string into a date
+///
+/// Used for input()
+fn parse_date(s: &str) -> Result<Date, time::error::Parse> {
+    if !s.is_empty() {
+        // If a date was given, try to parse it
+        Date::parse(
+            s,
+            format_description!("[month repr:numerical]/[day]/[year]"),
+        )
+    } else {
+        // The string being empty is fine, just use today
+        Ok(time::OffsetDateTime::now_local()
+            .unwrap_or_else(|_| time::OffsetDateTime::now_utc())
+            .date())
+    }
+}
+
+/// Parse a custom field value typed by the user
+///
+/// If the string is "", return Ok(None), otherwise try to parse the string and return Ok(Some(*parsed*))
+fn parse_custom_field_value(
+    field_type: &CustomFieldType,
+    s: &str,
+) -> Result<Option<CustomFieldValue>, String> {
+    if !s.is_empty() {
+        Ok(Some(field_type.parse_value(s)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Parse an amount of money, like a salary
+///
+/// If the string is "", return Ok(None), otherwise try to parse the string and return Ok(Some(*parsed*))
+fn parse_amount_optional(s: &str) -> Result<Option<f64>, String> {
+    if !s.is_empty() {
+        // Allow separators like 100,000
+        match s.replace(',', "").parse::<f64>() {
+            Ok(amount) if amount.is_finite() && amount >= 0.0 => Ok(Some(amount)),
+            _ => Err(format!("'{s}' is not an amount. Use a number 0 or above")),
+        }
+    } else {
+        Ok(None)
+    }
+}
+
+/// Parse a pay period, like "hourly" or "y"
+///
+/// If the string is "", return Ok(None), otherwise try to parse the string and return Ok(Some(*parsed*))
+fn parse_pay_period_optional(s: &str) -> Result<Option<PayPeriod>, String> {
+    if !s.is_empty() {
+        Ok(Some(s.parse()?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Parse a work arrangement, like "remote" or "h"
+///
+/// If the string is "", return Ok(None), otherwise try to parse the string and return Ok(Some(*parsed*))
+fn parse_work_arrangement_optional(s: &str) -> Result<Option<WorkArrangement>, String> {
+    if !s.is_empty() {
+        Ok(Some(s.parse()?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Parse a yes or no answer, like "yes" or "n"
+///
+/// If the string is "", return Ok(None), otherwise try to parse the string and return Ok(Some(*parsed*))
+fn parse_yes_no_optional(s: &str) -> Result<Option<bool>, String> {
+    match s.to_lowercase().as_str() {
+        "" => Ok(None),
+        "yes" | "y" => Ok(Some(true)),
+        "no" | "n" => Ok(Some(false)),
+        _ => Err(format!("'{s}' is not yes or no")),
+    }
+}
+
+/// Parse an input for input_optional! that may be "remove"
+///
+/// "remove" returns Ok(Some(None)), which clears the field. Anything else is parsed by `parse`, where Ok(None) leaves the field unchanged.
+fn parse_removable<T, E>(
+    s: &str,
+    parse: impl Fn(&str) -> Result<Option<T>, E>,
+) -> Result<Option<Option<T>>, E> {
+    if s == "remove" {
+        Ok(Some(None))
+    } else {
+        parse(s).map(|o| o.map(Some))
+    }
+}
+
+/// Make a parser that keeps text as it is, if [check_text_field] accepts it for the column `field`
+///
+/// This points out a problem, like a company that is too long, as soon as it is entered instead of when saving.
+fn checked_text(field: &'static str) -> impl Fn(&str) -> Result<String, String> {
+    move |s| check_text_field(field, s).map(|_| s.to_owned())
+}
+
+/// Same as [checked_text], but "" is `None`
+fn checked_text_optional(field: &'static str) -> impl Fn(&str) -> Result<Option<String>, String> {
+    move |s| {
+        if s.is_empty() {
+            Ok(None)
+        } else {
+            checked_text(field)(s).map(Some)
+        }
+    }
+}
+
+/// Parse a string into an optional website, checking that it looks like a web address
+///
+/// If the string is "", return Ok(None)
+fn parse_website_optional(s: &str) -> Result<Option<String>, String> {
+    if s.is_empty() {
+        return Ok(None);
     }
+    check_text_field("application_website", s)?;
+    check_website(s)?;
+    Ok(Some(s.to_owned()))
 }
 
 /// Parse a string into an optional date
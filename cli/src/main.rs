@@ -1,6 +1,7 @@
 //! Entry point for the CLI version of ats-tracking
 
 use dotenv::dotenv;
+use repository::job_application_repository::JobApplicationRepository;
 
 mod command_line;
 mod shell_option;
@@ -9,7 +10,42 @@ fn main() {
     // Objects that should be owned by the main function
     // We don't really care is a .env file is successfully found because we can just use actual environment variables
     _ = dotenv();
-    let mut conn = repository::get_conn().unwrap();
+    match repository::get_plain_text_repository() {
+        Some(repository) => run(repository.unwrap()),
+        None => {
+            let conn = repository::get_conn_with_passphrase(|wrong_passphrase| {
+                if wrong_passphrase {
+                    println!("Wrong passphrase");
+                }
+                command_line::input_passphrase("The database is encrypted. Passphrase:")
+                    .ok()
+                    .flatten()
+            })
+            .unwrap();
+            run(conn);
+        }
+    }
+}
+
+/// Open the shell on the job applications in `conn`, after backing up and purging the trash if they are due
+fn run<C: JobApplicationRepository>(mut conn: C) {
+    // Back up the database if a backup is due, before the trash is purged so the backup still has what is purged.
+    // Only SQLite databases are backed up here, since MySQL servers and git for plain-text storage keep their own copies.
+    // A backup that fails shouldn't stop the CLI from being used.
+    if let Some(sqlite) = conn.sqlite() {
+        if let Some(schedule) = repository::backup_schedule() {
+            if let Err(e) =
+                repository::backup::backup_if_due(sqlite, schedule, repository::backup_retention())
+            {
+                eprintln!("Unable to back up the database: {e}");
+            }
+        }
+    }
+
+    // Permanently delete job applications that have been in the trash for too long
+    if let Some(days) = repository::trash_retention_days() {
+        conn.purge_trash(days).unwrap();
+    }
 
     command_line::main_loop(&mut conn).unwrap();
 }
@@ -1,3 +1,8 @@
+use repository::{
+    job_application_model::AttachmentRole, offer_comparison::CriteriaWeights,
+    search_query::SearchQuery,
+};
+
 #[derive(Debug)]
 pub(super) enum ShellOption {
     Help,
@@ -6,18 +11,88 @@ pub(super) enum ShellOption {
     Read(ReadType),
     /// Has update type and an id
     Update(UpdateType, i32),
-    /// Delete `id`
+    /// Move `id` to the trash
     Delete(i32),
+    /// List every job application in the trash
+    Trash,
+    /// Take `id` back out of the trash
+    Restore(i32),
+    /// Permanently delete every job application in the trash
+    EmptyTrash,
+    /// Undo the most recent change to a job application
+    Undo,
+    /// Redo the most recently undone change to a job application
+    Redo,
+    /// Show every recorded change to job application `id`
+    History(i32),
+    /// Merge the second job application into the first, keeping the first
+    Merge(i32, i32),
+    /// Full-text search for a query, showing the most relevant job applications first
+    Find(String),
+    /// Put a tag on job application `id`
+    Tag(i32, String),
+    /// Take a tag off of job application `id`
+    Untag(i32, String),
+    /// List every tag in use
+    Tags,
+    /// List every custom field
+    Fields,
+    /// Define a new custom field with this name. The type is prompted for.
+    AddField(String),
+    /// Delete the custom field with this name, along with all of its values
+    DeleteField(String),
+    /// Add or replace the job offer for job application `id`. The details are prompted for.
+    Offer(i32),
+    /// Score every offer side by side using these weights
+    Compare(CriteriaWeights),
+    /// Show how job applications are going for each work arrangement and each resume version
+    Stats,
+    /// Save the job posting description for job application `id`, imported from a text or HTML file if one is given, otherwise prompted for
+    Description(i32, Option<String>),
+    /// Attach the file at this path to job application `id`
+    Attach(i32, AttachmentRole, String),
+    /// Detach attachment `id` from its job application
+    Detach(i32),
+    /// Open attachment `id` with the default application for its file type
+    Open(i32),
+    /// List every resume version
+    Resumes,
+    /// Add a resume version with this label. The file, date, and notes are prompted for.
+    AddResume(String),
+    /// Delete resume version `id`
+    DeleteResume(i32),
+    /// Record which resume version was sent with job application `id`. `None` clears it.
+    SentResume(i32, Option<i32>),
+    /// Encrypt the database, or change its passphrase if it already is. The passphrase is prompted for.
+    Encrypt,
+    /// Store the database as plain text again
+    Decrypt,
+    /// Back up the database now
+    BackupNow,
+    /// List every backup of the database
+    Backups,
+    /// Replace the database with this backup, given by name or path
+    RestoreBackup(String),
+    /// Sync with the database or sync file at this path. Conflicts are prompted for.
+    Sync(String),
+    /// Save a new sync file at this path. The passphrase to encrypt it with is prompted for.
+    ExportSyncFile(String),
+    /// Save every job application as plain-text files in this directory
+    ExportPlainText(String),
+    /// Copy every job application from the plain-text files in this directory into the empty database
+    ImportPlainText(String),
 }
 
 #[derive(Debug)]
 pub(super) enum ReadType {
     All,
     Pending,
-    /// Search for specific string
-    Search(String),
+    /// Search using the query language, like `company:acme status:rejected "backend engineer"`
+    Search(SearchQuery),
     /// Show only `id`
     One(i32),
+    /// Show only `id`, along with its saved job posting description
+    Description(i32),
 }
 
 #[derive(Debug)]
@@ -31,7 +106,7 @@ impl TryFrom<&str> for ShellOption {
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         // Guard clause to make sure some command was given
-        if value == "" {
+        if value.is_empty() {
             return Err("No command given".to_owned());
         }
 
@@ -50,6 +125,27 @@ impl TryFrom<&str> for ShellOption {
             "help" | "h" => Ok(Self::Help),
             "exit" | "quit" => Ok(Self::Exit),
             "create" | "new" => Ok(Self::Create),
+            "undo" => Ok(Self::Undo),
+            "redo" => Ok(Self::Redo),
+            // For history, parse the id
+            "history" => match str::parse::<i32>(args) {
+                Ok(id) => Ok(Self::History(id)),
+                Err(err_message) => {
+                    Err(format!("Unable to parse id '{args}'. Error: {err_message}"))
+                }
+            },
+            // For merge, parse both ids
+            "merge" => match args.split_once(' ') {
+                Some((keep_str, merged_str)) => {
+                    let parse_id = |id_str: &str| {
+                        str::parse::<i32>(id_str.trim()).map_err(|err_message| {
+                            format!("Unable to parse id '{id_str}'. Error: {err_message}")
+                        })
+                    };
+                    Ok(Self::Merge(parse_id(keep_str)?, parse_id(merged_str)?))
+                }
+                None => Err("Use merge <id> <duplicate_id>".to_owned()),
+            },
             // For read command, parse the read type as well
             "read" => Ok(Self::Read(ReadType::try_from(args)?)),
             // Searching, because it is so common, can just used the command "search" instead
@@ -70,6 +166,158 @@ impl TryFrom<&str> for ShellOption {
                     Err("Update type and ID are required".to_owned())
                 }
             }
+            "find" => {
+                if !args.is_empty() {
+                    Ok(Self::Find(args.to_owned()))
+                } else {
+                    Err("Search query is required for find".to_owned())
+                }
+            }
+            // For tag and untag, parse the id. Everything after it is the tag name, which may contain spaces
+            "tag" | "untag" => {
+                if let Some((id_str, tag)) = args.split_once(' ') {
+                    let id = str::parse::<i32>(id_str).map_err(|err_message| {
+                        format!("Unable to parse id '{id_str}'. Error: {err_message}")
+                    })?;
+                    if command_word == "tag" {
+                        Ok(Self::Tag(id, tag.trim().to_owned()))
+                    } else {
+                        Ok(Self::Untag(id, tag.trim().to_owned()))
+                    }
+                } else {
+                    Err(format!("ID and tag are required for {command_word}"))
+                }
+            }
+            "tags" => Ok(Self::Tags),
+            "fields" => Ok(Self::Fields),
+            "stats" => Ok(Self::Stats),
+            "encrypt" | "rekey" => Ok(Self::Encrypt),
+            "decrypt" => Ok(Self::Decrypt),
+            // For backup, parse the action. Everything after restore is the file, which may contain spaces
+            "backup" => match args.split_once(' ').unwrap_or((args, "")) {
+                ("now", "") => Ok(Self::BackupNow),
+                ("list", "") => Ok(Self::Backups),
+                ("restore", file) if !file.trim().is_empty() => {
+                    Ok(Self::RestoreBackup(file.trim().to_owned()))
+                }
+                _ => Err("Use backup now, backup list, or backup restore <file>".to_owned()),
+            },
+            // For sync, everything after export is the file. Otherwise, everything is, and either may contain spaces
+            "sync" => match args.split_once(' ') {
+                Some(("export", file)) if !file.trim().is_empty() => {
+                    Ok(Self::ExportSyncFile(file.trim().to_owned()))
+                }
+                _ if !args.is_empty() && args != "export" => Ok(Self::Sync(args.to_owned())),
+                _ => Err("Use sync <file> or sync export <file>".to_owned()),
+            },
+            // For text, everything after the action is the directory, which may contain spaces
+            "text" => match args.split_once(' ') {
+                Some(("export", dir)) if !dir.trim().is_empty() => {
+                    Ok(Self::ExportPlainText(dir.trim().to_owned()))
+                }
+                Some(("import", dir)) if !dir.trim().is_empty() => {
+                    Ok(Self::ImportPlainText(dir.trim().to_owned()))
+                }
+                _ => Err("Use text export <dir> or text import <dir>".to_owned()),
+            },
+            // For field, parse the action. Everything after it is the field name, which may contain spaces
+            "field" => match args.split_once(' ') {
+                Some(("add", name)) if !name.trim().is_empty() => {
+                    Ok(Self::AddField(name.trim().to_owned()))
+                }
+                Some(("delete", name)) if !name.trim().is_empty() => {
+                    Ok(Self::DeleteField(name.trim().to_owned()))
+                }
+                _ => Err("Use field add <name> or field delete <name>".to_owned()),
+            },
+            "offer" => match str::parse::<i32>(args) {
+                Ok(id) => Ok(Self::Offer(id)),
+                Err(err_message) => {
+                    Err(format!("Unable to parse id '{args}'. Error: {err_message}"))
+                }
+            },
+            // For description, parse the id. Everything after it is the file path, which may contain spaces
+            "description" => {
+                let (id_str, file) = args.split_once(' ').unwrap_or((args, ""));
+                let id = str::parse::<i32>(id_str).map_err(|err_message| {
+                    format!("Unable to parse id '{id_str}'. Error: {err_message}")
+                })?;
+                let file = Some(file.trim()).filter(|file| !file.is_empty());
+                Ok(Self::Description(id, file.map(str::to_owned)))
+            }
+            // For attach, parse the id and role. Everything after them is the file path, which may contain spaces
+            "attach" => {
+                let mut parts = args.splitn(3, ' ');
+                match (parts.next(), parts.next(), parts.next()) {
+                    (Some(id_str), Some(role), Some(file)) if !file.trim().is_empty() => {
+                        Ok(Self::Attach(
+                            str::parse::<i32>(id_str).map_err(|err_message| {
+                                format!("Unable to parse id '{id_str}'. Error: {err_message}")
+                            })?,
+                            role.parse::<AttachmentRole>()?,
+                            file.trim().to_owned(),
+                        ))
+                    }
+                    _ => Err("Use attach <id> <role> <file>".to_owned()),
+                }
+            }
+            // For detach and open, parse the attachment id
+            "detach" | "open" => match str::parse::<i32>(args) {
+                Ok(id) if command_word == "detach" => Ok(Self::Detach(id)),
+                Ok(id) => Ok(Self::Open(id)),
+                Err(err_message) => Err(format!(
+                    "Unable to parse attachment id '{args}'. Error: {err_message}"
+                )),
+            },
+            "resumes" => Ok(Self::Resumes),
+            // For resume, parse the action, or the job application id. A label may contain spaces.
+            "resume" => match args.split_once(' ') {
+                Some(("add", label)) if !label.trim().is_empty() => {
+                    Ok(Self::AddResume(label.trim().to_owned()))
+                }
+                Some(("delete", id_str)) => match str::parse::<i32>(id_str.trim()) {
+                    Ok(id) => Ok(Self::DeleteResume(id)),
+                    Err(err_message) => Err(format!(
+                        "Unable to parse resume id '{id_str}'. Error: {err_message}"
+                    )),
+                },
+                Some((id_str, resume_id_str)) if id_str != "add" => {
+                    let id = str::parse::<i32>(id_str).map_err(|err_message| {
+                        format!("Unable to parse id '{id_str}'. Error: {err_message}")
+                    })?;
+                    match resume_id_str.trim() {
+                        "none" => Ok(Self::SentResume(id, None)),
+                        resume_id_str => str::parse::<i32>(resume_id_str)
+                            .map(|resume_id| Self::SentResume(id, Some(resume_id)))
+                            .map_err(|err_message| {
+                                format!(
+                                    "Unable to parse resume id '{resume_id_str}'. Error: {err_message}"
+                                )
+                            }),
+                    }
+                }
+                _ => Err(
+                    "Use resume add <label>, resume delete <resume_id>, or resume <id> (<resume_id> | none)"
+                        .to_owned(),
+                ),
+            },
+            // No weights means the default weights
+            "compare" => args
+                .parse::<CriteriaWeights>()
+                .map(Self::Compare)
+                .map_err(|err| format!("Invalid weights '{args}'. Error: {err}")),
+            // For trash, parse the action. Listing is the default.
+            "trash" => match args.split_once(' ').unwrap_or((args, "")) {
+                ("" | "list", "") => Ok(Self::Trash),
+                ("empty", "") => Ok(Self::EmptyTrash),
+                ("restore", id_str) => match str::parse::<i32>(id_str.trim()) {
+                    Ok(id) => Ok(Self::Restore(id)),
+                    Err(err_message) => Err(format!(
+                        "Unable to parse id '{id_str}'. Error: {err_message}"
+                    )),
+                },
+                _ => Err("Use trash [list], trash restore <id>, or trash empty".to_owned()),
+            },
             // For delete, parse the id
             "delete" => match str::parse::<i32>(args) {
                 Ok(id) => Ok(Self::Delete(id)),
@@ -103,15 +351,19 @@ impl TryFrom<&str> for ReadType {
             "all" | "" => Ok(Self::All),
             "pending" => Ok(Self::Pending),
             "search" => {
-                if args != "" {
-                    Ok(Self::Search(args.to_owned()))
+                if !args.is_empty() {
+                    args.parse::<SearchQuery>()
+                        .map(Self::Search)
+                        .map_err(|err| format!("Invalid search query '{args}'. Error: {err}"))
                 } else {
                     Err("Search query is required for search".to_owned())
                 }
             }
-            _ => match str::parse::<i32>(command_word) {
-                Ok(id) => Ok(Self::One(id)),
-                Err(err_message) => Err(format!(
+            _ => match (str::parse::<i32>(command_word), args) {
+                (Ok(id), "") => Ok(Self::One(id)),
+                (Ok(id), "--description") => Ok(Self::Description(id)),
+                (Ok(_), _) => Err(format!("Invalid option '{args}' for read")),
+                (Err(err_message), _) => Err(format!(
                     "Unable to parse id '{command_word}'. Error: {err_message}"
                 )),
             },
@@ -1,19 +1,44 @@
 //! Controller functionality to handle actions triggered by or affecting the GUI
 
-use std::{cell::RefCell, iter::once, ops::DerefMut, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    iter::once,
+    ops::DerefMut,
+    path::Path,
+    rc::Rc,
+    str::FromStr,
+};
 
 use crate::model::{
-    self, get_today_as_slint_date, AppWindow, DeleteConfirmation, HumanResponseView,
-    JobApplicationView,
+    self, custom_fields_from_views, format_date, get_today, get_today_as_slint_date,
+    offer_field_views, offer_from_field_views, AppWindow, ApplicationStats, AttachmentView,
+    ConflictResolution, CustomFieldView, DeleteConfirmation, DuplicateWarning, HumanResponseView,
+    JobApplicationErrorsView, JobApplicationView, OfferComparison, PassphraseDialog, Trash,
 };
 use repository::{
-    job_application_model::{HumanResponse, JobApplication},
-    job_application_repository::JobApplicationRepository,
+    change_watcher::{ChangeWatcher, Changes},
+    job_application_model::{
+        Activity, AttachmentRole, CustomFields, HumanResponse, JobApplication, JobDescription,
+        NewJobApplication, Offer,
+    },
+    job_application_repository::{
+        job_application_changes, JobApplicationRepository, RepositoryError,
+    },
+    job_description::read_job_description_file,
+    offer_comparison::{score_offers, CriteriaWeights, Criterion},
+    search_query::{SearchCondition, SearchFilter, SearchQuery},
+    validation::validate_new_job_application,
 };
 use slint::{
-    ComponentHandle, Model, ModelExt, ModelRc, SharedString, StandardListViewItem, ToSharedString,
-    VecModel,
+    ComponentHandle, Model, ModelExt, ModelRc, SharedString, StandardListViewItem, Timer,
+    TimerMode, ToSharedString, VecModel,
 };
+use time::{macros::format_description, PrimitiveDateTime, UtcOffset};
+
+/// How often to check for job applications that were changed outside of the GUI
+const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
 
 // Public functions
 
@@ -29,7 +54,11 @@ pub fn init_ui<C: JobApplicationRepository>(conn: &mut C, ui: &AppWindow) {
             Vec::default()
         });
 
-    update_table(ui, all_applications);
+    update_table(ui, all_applications, &get_activity(conn));
+
+    // Show the custom fields, with no values yet
+    update_selected_custom_fields(conn, ui, &CustomFields::new());
+    update_resume_version_labels(conn, ui);
 
     // Finally, reset the sidebar
     reset_selected_row(ui);
@@ -43,7 +72,7 @@ where
     C: JobApplicationRepository + 'static,
 {
     let ui_clone = ui.as_weak();
-    let conn_clone = Rc::clone(&conn);
+    let conn_clone = Rc::clone(conn);
 
     ui.on_use_job_application(move |application_id| {
         if let Some(ui) = ui_clone.upgrade() {
@@ -71,11 +100,36 @@ where
     ui.on_submit_job_application(move || {
         if let Some(ui) = ui_clone.upgrade() {
             let job_application_view = ui.get_selected_job_application();
-            if let Err(e) = submit_job_application(
+            // Ask first before saving a job application that was likely entered already.
+            // If it can't be checked, saving it reports the problem instead.
+            let duplicates = find_duplicates(
                 RefCell::borrow_mut(&conn_clone).deref_mut(),
                 &ui,
-                job_application_view,
-            ) {
+                &job_application_view,
+            )
+            .unwrap_or_default();
+            let result = if duplicates.is_empty() {
+                let result = submit_job_application(
+                    RefCell::borrow_mut(&conn_clone).deref_mut(),
+                    &ui,
+                    job_application_view.clone(),
+                );
+                match result {
+                    // It was changed somewhere else since it was opened, so ask which version to keep
+                    Err(e)
+                        if matches!(
+                            e.downcast_ref::<RepositoryError>(),
+                            Some(RepositoryError::Conflict(_))
+                        ) && job_application_view.id != 0 =>
+                    {
+                        conflict_resolution(&conn_clone, &ui, job_application_view)
+                    }
+                    result => result,
+                }
+            } else {
+                duplicate_warning(&conn_clone, &ui, job_application_view, &duplicates)
+            };
+            if let Err(e) = result {
                 // Print any errors, but otherwise discard them.
                 // We may want to actually do something with these errors later, though
                 eprintln!("{e}");
@@ -121,6 +175,311 @@ where
     });
 }
 
+/// Handle the callback for `add-tag`
+///
+/// Puts a tag on a job application, then refreshes the tags shown in the sidebar
+pub fn handle_add_tag<C>(conn: &Rc<RefCell<C>>, ui: &AppWindow)
+where
+    C: JobApplicationRepository + 'static,
+{
+    let conn_clone = Rc::clone(conn);
+    let ui_clone = ui.as_weak();
+
+    ui.on_add_tag(move |id: i32, name: SharedString| {
+        if let Some(ui) = ui_clone.upgrade() {
+            let mut conn = RefCell::borrow_mut(&conn_clone);
+            if let Err(e) = conn.add_tag(id, &name) {
+                eprintln!("Error adding tag: {e}");
+            }
+            update_selected_tags(conn.deref_mut(), &ui, id);
+        } else {
+            eprintln!("Error adding tag: AppWindow no longer exists");
+        }
+    });
+}
+
+/// Handle the callback for `remove-tag`
+///
+/// Takes a tag off of a job application, then refreshes the tags shown in the sidebar
+pub fn handle_remove_tag<C>(conn: &Rc<RefCell<C>>, ui: &AppWindow)
+where
+    C: JobApplicationRepository + 'static,
+{
+    let conn_clone = Rc::clone(conn);
+    let ui_clone = ui.as_weak();
+
+    ui.on_remove_tag(move |id: i32, name: SharedString| {
+        if let Some(ui) = ui_clone.upgrade() {
+            let mut conn = RefCell::borrow_mut(&conn_clone);
+            if let Err(e) = conn.remove_tag(id, &name) {
+                eprintln!("Error removing tag: {e}");
+            }
+            update_selected_tags(conn.deref_mut(), &ui, id);
+        } else {
+            eprintln!("Error removing tag: AppWindow no longer exists");
+        }
+    });
+}
+
+/// Handle the callback for `custom-field-edited`
+///
+/// Stores the edited value so it can be saved with the rest of the sidebar
+pub fn handle_custom_field_edited(ui: &AppWindow) {
+    let ui_clone = ui.as_weak();
+
+    ui.on_custom_field_edited(move |index: i32, value: SharedString| {
+        if let Some(ui) = ui_clone.upgrade() {
+            let custom_fields = ui.get_selected_custom_fields();
+            if let Some(mut custom_field) = custom_fields.row_data(index as usize) {
+                custom_field.value = value;
+                custom_fields.set_row_data(index as usize, custom_field);
+            }
+        } else {
+            eprintln!("Error editing custom field: AppWindow no longer exists");
+        }
+    });
+}
+
+/// Handle the callback for `offer-field-edited`
+///
+/// Stores the edited value so it can be saved with the rest of the sidebar
+pub fn handle_offer_field_edited(ui: &AppWindow) {
+    let ui_clone = ui.as_weak();
+
+    ui.on_offer_field_edited(move |index: i32, value: SharedString| {
+        if let Some(ui) = ui_clone.upgrade() {
+            let offer_fields = ui.get_selected_offer_fields();
+            if let Some(mut offer_field) = offer_fields.row_data(index as usize) {
+                offer_field.value = value;
+                offer_fields.set_row_data(index as usize, offer_field);
+            }
+        } else {
+            eprintln!("Error editing offer: AppWindow no longer exists");
+        }
+    });
+}
+
+/// Handle the callback for `compare-offers`
+///
+/// Opens a window comparing every offer, scored by weights the user can change
+pub fn handle_compare_offers<C>(conn: &Rc<RefCell<C>>, ui: &AppWindow)
+where
+    C: JobApplicationRepository + 'static,
+{
+    let conn_clone = Rc::clone(conn);
+
+    ui.on_compare_offers(move || {
+        if let Err(e) = offer_comparison(&conn_clone) {
+            // Print any errors, but otherwise discard them.
+            eprintln!("{e}");
+        }
+    });
+}
+
+/// Handle the callback for `show-stats`
+///
+/// Opens a window showing how job applications are going by work arrangement and by resume version
+pub fn handle_show_stats<C>(conn: &Rc<RefCell<C>>, ui: &AppWindow)
+where
+    C: JobApplicationRepository + 'static,
+{
+    let conn_clone = Rc::clone(conn);
+
+    ui.on_show_stats(move || {
+        if let Err(e) = application_stats(RefCell::borrow_mut(&conn_clone).deref_mut()) {
+            // Print any errors, but otherwise discard them.
+            eprintln!("{e}");
+        }
+    });
+}
+
+/// Handle the callback for `show-trash`
+///
+/// Opens a window listing deleted job applications, where they can be restored or deleted for good.
+/// Restored job applications are added to the table if they match `active_search`.
+pub fn handle_show_trash<C>(
+    conn: &Rc<RefCell<C>>,
+    ui: &AppWindow,
+    active_search: &Rc<RefCell<SearchQuery>>,
+) where
+    C: JobApplicationRepository + 'static,
+{
+    let conn_clone = Rc::clone(conn);
+    let ui_clone = ui.as_weak();
+    let active_search = Rc::clone(active_search);
+
+    ui.on_show_trash(move || {
+        if let Some(ui) = ui_clone.upgrade() {
+            if let Err(e) = trash(&conn_clone, &ui, &active_search) {
+                // Print any errors, but otherwise discard them.
+                eprintln!("{e}");
+            }
+        } else {
+            eprintln!("Error opening trash: AppWindow no longer exists");
+        }
+    });
+}
+
+/// Handle the callback for `undo`
+///
+/// Undoes the most recent change to a job application, then shows the job application that changed.
+/// The table is refreshed with `active_search`, the search shown in it.
+pub fn handle_undo<C>(
+    conn: &Rc<RefCell<C>>,
+    ui: &AppWindow,
+    active_search: &Rc<RefCell<SearchQuery>>,
+) where
+    C: JobApplicationRepository + 'static,
+{
+    let conn_clone = Rc::clone(conn);
+    let ui_clone = ui.as_weak();
+    let active_search = Rc::clone(active_search);
+
+    ui.on_undo(move || {
+        if let Some(ui) = ui_clone.upgrade() {
+            if let Err(e) = undo_or_redo(
+                RefCell::borrow_mut(&conn_clone).deref_mut(),
+                ui,
+                &active_search,
+                false,
+            ) {
+                // Print any errors, but otherwise discard them.
+                eprintln!("{e}");
+            }
+        } else {
+            eprintln!("Error undoing: AppWindow no longer exists");
+        }
+    });
+}
+
+/// Handle the callback for `redo`
+///
+/// Redoes the most recently undone change to a job application, then shows the job application that changed.
+/// The table is refreshed with `active_search`, the search shown in it.
+pub fn handle_redo<C>(
+    conn: &Rc<RefCell<C>>,
+    ui: &AppWindow,
+    active_search: &Rc<RefCell<SearchQuery>>,
+) where
+    C: JobApplicationRepository + 'static,
+{
+    let conn_clone = Rc::clone(conn);
+    let ui_clone = ui.as_weak();
+    let active_search = Rc::clone(active_search);
+
+    ui.on_redo(move || {
+        if let Some(ui) = ui_clone.upgrade() {
+            if let Err(e) = undo_or_redo(
+                RefCell::borrow_mut(&conn_clone).deref_mut(),
+                ui,
+                &active_search,
+                true,
+            ) {
+                // Print any errors, but otherwise discard them.
+                eprintln!("{e}");
+            }
+        } else {
+            eprintln!("Error redoing: AppWindow no longer exists");
+        }
+    });
+}
+
+/// Handle the callback for `import-job-description`
+///
+/// Returns the job description read from a text or HTML file, to be saved with the rest of the sidebar.
+/// If the file can't be read, the current job description is returned unchanged.
+pub fn handle_import_job_description(ui: &AppWindow) {
+    let ui_clone = ui.as_weak();
+
+    ui.on_import_job_description(move |path: SharedString| {
+        match read_job_description_file(Path::new(path.trim())) {
+            Ok(description) => description.into(),
+            Err(e) => {
+                eprintln!("Error importing job description from {path}: {e}");
+                ui_clone
+                    .upgrade()
+                    .map(|ui| ui.get_selected_job_description())
+                    .unwrap_or_default()
+            }
+        }
+    });
+}
+
+/// Handle the callback for `add-attachment`
+///
+/// Attaches the file at a path to a job application, then refreshes the attachments shown in the sidebar
+pub fn handle_add_attachment<C>(conn: &Rc<RefCell<C>>, ui: &AppWindow)
+where
+    C: JobApplicationRepository + 'static,
+{
+    let conn_clone = Rc::clone(conn);
+    let ui_clone = ui.as_weak();
+
+    ui.on_add_attachment(move |id: i32, role: SharedString, path: SharedString| {
+        if let Some(ui) = ui_clone.upgrade() {
+            let mut conn = RefCell::borrow_mut(&conn_clone);
+            if let Err(e) = add_attachment(conn.deref_mut(), id, &role, path.trim()) {
+                eprintln!("Error attaching {path}: {e}");
+            }
+            update_selected_attachments(conn.deref_mut(), &ui, id);
+        } else {
+            eprintln!("Error attaching file: AppWindow no longer exists");
+        }
+    });
+}
+
+/// Handle the callback for `remove-attachment`
+///
+/// Detaches a file from its job application, then refreshes the attachments shown in the sidebar
+pub fn handle_remove_attachment<C>(conn: &Rc<RefCell<C>>, ui: &AppWindow)
+where
+    C: JobApplicationRepository + 'static,
+{
+    let conn_clone = Rc::clone(conn);
+    let ui_clone = ui.as_weak();
+
+    ui.on_remove_attachment(move |attachment_id: i32| {
+        if let Some(ui) = ui_clone.upgrade() {
+            let mut conn = RefCell::borrow_mut(&conn_clone);
+            match conn.get_attachment(attachment_id) {
+                Ok(Some(attachment)) => {
+                    if let Err(e) = conn.delete_attachment(attachment_id) {
+                        eprintln!("Error removing attachment: {e}");
+                    }
+                    update_selected_attachments(
+                        conn.deref_mut(),
+                        &ui,
+                        attachment.job_application_id,
+                    );
+                }
+                Ok(None) => eprintln!("No attachment matches id {attachment_id}"),
+                Err(e) => eprintln!("Error removing attachment: {e}"),
+            }
+        } else {
+            eprintln!("Error removing attachment: AppWindow no longer exists");
+        }
+    });
+}
+
+/// Handle the callback for `open-attachment`
+///
+/// Copies the attached file into `temp_dir`, then opens it with the default application for its file type
+pub fn handle_open_attachment<C>(conn: &Rc<RefCell<C>>, ui: &AppWindow, temp_dir: &Path)
+where
+    C: JobApplicationRepository + 'static,
+{
+    let conn_clone = Rc::clone(conn);
+    let temp_dir = temp_dir.to_path_buf();
+
+    ui.on_open_attachment(move |attachment_id: i32| {
+        let mut conn = RefCell::borrow_mut(&conn_clone);
+        if let Err(e) = open_attachment(conn.deref_mut(), attachment_id, &temp_dir) {
+            // Print any errors, but otherwise discard them.
+            eprintln!("Error opening attachment: {e}");
+        }
+    });
+}
+
 /// Handle the callback for `date-diff`
 ///
 /// Returns the difference between two dates in days (to - from)
@@ -153,13 +512,18 @@ pub fn handle_date_diff(ui: &AppWindow) {
 
 /// Handle the callback for `search-job-application`
 ///
-/// Queries the database using the search parameters and updates the table
-pub fn handle_search_job_application<C>(conn: &Rc<RefCell<C>>, ui: &AppWindow)
-where
+/// Queries the database using the search parameters and updates the table.
+/// The search that was run is saved in `active_search`, so the table can be kept to it when it is refreshed.
+pub fn handle_search_job_application<C>(
+    conn: &Rc<RefCell<C>>,
+    ui: &AppWindow,
+    active_search: &Rc<RefCell<SearchQuery>>,
+) where
     C: JobApplicationRepository + 'static,
 {
     let conn_clone = Rc::clone(conn);
     let ui_clone = ui.as_weak();
+    let active_search = Rc::clone(active_search);
 
     ui.on_search_job_application(
         move |by_human_response: bool,
@@ -174,14 +538,12 @@ where
                     None
                 };
 
-                // Same with search query: Only use if nonempty
-                let search_query: Option<&str> = if query.is_empty() { None } else { Some(&query) };
-
                 if let Err(e) = search_job_application(
                     RefCell::borrow_mut(&conn_clone).deref_mut(),
                     &ui,
+                    &active_search,
                     human_response,
-                    search_query,
+                    &query,
                 ) {
                     // Print any errors, but otherwise discard them.
                     // We may want to actually do something with these errors later, though
@@ -194,12 +556,121 @@ where
     );
 }
 
+/// Keep the table up to date with job applications that were changed outside of the GUI, like with the CLI
+///
+/// Only job applications matching `active_search`, the search shown in the table, are added to it or kept in it.
+/// Checks stop when the returned timer is dropped, so it should be kept until the GUI closes.
+pub fn watch_for_changes<C>(
+    conn: &Rc<RefCell<C>>,
+    ui: &AppWindow,
+    active_search: &Rc<RefCell<SearchQuery>>,
+) -> Result<Timer, RepositoryError>
+where
+    C: JobApplicationRepository + 'static,
+{
+    let mut watcher = ChangeWatcher::new(RefCell::borrow_mut(conn).deref_mut())?;
+    let conn_clone = Rc::clone(conn);
+    let ui_clone = ui.as_weak();
+    let active_search = Rc::clone(active_search);
+
+    let timer = Timer::default();
+    timer.start(TimerMode::Repeated, REFRESH_INTERVAL, move || {
+        // If the connection is in use, like by an open dialog, just wait for the next check
+        let Ok(mut conn) = conn_clone.try_borrow_mut() else {
+            return;
+        };
+        let changes = match watcher.check(conn.deref_mut()) {
+            Ok(changes) if changes.is_empty() => return,
+            Ok(changes) => changes,
+            Err(e) => {
+                eprintln!("{e}");
+                return;
+            }
+        };
+        // Run the search again to find which of the changed job applications belong in the table
+        let matching: BTreeSet<i32> =
+            match conn.query_job_applications(&RefCell::borrow(&active_search)) {
+                Ok(job_applications) => job_applications
+                    .iter()
+                    .map(|job_application| job_application.id)
+                    .collect(),
+                Err(e) => {
+                    eprintln!("{e}");
+                    return;
+                }
+            };
+        let activity = get_activity(conn.deref_mut());
+        drop(conn);
+
+        if let Some(ui) = ui_clone.upgrade() {
+            refresh_table_rows(&ui, &changes, &matching, &activity);
+        }
+    });
+
+    Ok(timer)
+}
+
+/// Ask for the passphrase of an encrypted database in a dialog, and wait until it is closed
+///
+/// This runs before the main window is shown, so the dialog has the event loop to itself.
+/// Returns `None` if the dialog is cancelled.
+pub fn ask_passphrase(wrong_passphrase: bool) -> Option<String> {
+    let dialog_window = match PassphraseDialog::new() {
+        Ok(dialog_window) => dialog_window,
+        Err(e) => {
+            eprintln!("{e}");
+            return None;
+        }
+    };
+    dialog_window.set_wrong_passphrase(wrong_passphrase);
+    let passphrase = Rc::new(RefCell::new(None));
+
+    // Handle "cancel"
+    {
+        let dialog_window_clone = dialog_window.as_weak();
+        dialog_window.on_cancel_clicked(move || {
+            if let Some(dialog_window) = dialog_window_clone.upgrade() {
+                dialog_window
+                    .hide()
+                    .expect("Error closing passphrase dialog window");
+            }
+        });
+    }
+
+    // Handle "unlock"
+    {
+        let dialog_window_clone = dialog_window.as_weak();
+        let passphrase_clone = Rc::clone(&passphrase);
+        dialog_window.on_unlock_clicked(move || {
+            if let Some(dialog_window) = dialog_window_clone.upgrade() {
+                let entered = dialog_window.get_passphrase();
+                // Nothing entered means nothing to try yet
+                if entered.is_empty() {
+                    return;
+                }
+                *RefCell::borrow_mut(&passphrase_clone) = Some(entered.into());
+                dialog_window
+                    .hide()
+                    .expect("Error closing passphrase dialog window");
+            }
+        });
+    }
+
+    if let Err(e) = dialog_window.run() {
+        eprintln!("{e}");
+    }
+    RefCell::take(&passphrase)
+}
+
 // Helper functions
 
 /// Put a job application into a row that can be used by the Slint StandardTableView
 ///
 /// The design is similar to cli::command_line::print_job_application_to_terminal(&JobApplication)
-fn job_application_into_row(ja: &JobApplication) -> ModelRc<StandardListViewItem> {
+fn job_application_into_row(
+    ja: &JobApplication,
+    activity: Option<&Activity>,
+) -> ModelRc<StandardListViewItem> {
     [
         // Using From::from to make sure StandardListViewItem is inferred
         StandardListViewItem::from(ja.id.to_string().as_str()),
@@ -238,15 +709,52 @@ fn job_application_into_row(ja: &JobApplication) -> ModelRc<StandardListViewItem
             .into(),
         ja.application_website.as_deref().unwrap_or_default().into(),
         ja.notes.as_deref().unwrap_or_default().into(),
+        format_location(ja).as_str().into(),
+        activity
+            .map_or("".to_string(), |activity| {
+                let d = activity.last_activity;
+                format!("{:02}/{:02}/{}", d.month() as u8, d.day(), d.year())
+            })
+            .as_str()
+            .into(),
     ]
     .into()
 }
 
+/// Get the [Activity] of every job application for the table
+///
+/// The table is still worth showing without it, so errors are printed and an empty map is returned instead.
+fn get_activity<C: JobApplicationRepository>(conn: &mut C) -> BTreeMap<i32, Activity> {
+    conn.get_job_application_activity().unwrap_or_else(|error| {
+        eprintln!("Error getting job application activity: {error}");
+        BTreeMap::new()
+    })
+}
+
+/// Format the location of a job application for the table, like "Berlin, Germany (hybrid)"
+fn format_location(ja: &JobApplication) -> String {
+    let location = ja.location();
+    match (location.is_empty(), ja.work_arrangement) {
+        (_, None) => location,
+        (true, Some(work_arrangement)) => work_arrangement.to_string(),
+        (false, Some(work_arrangement)) => format!("{location} ({work_arrangement})"),
+    }
+}
+
 /// Set the sidebar job application to the job application denoted by `application_id`
 fn select_row<C: JobApplicationRepository>(conn: &mut C, ui: AppWindow, application_id: i32) {
     match conn.get_job_application_by_id(application_id) {
-        // Put job application into selected-job-application
-        Ok(Some(ja)) => ui.set_selected_job_application(ja.into()),
+        // Put job application into selected-job-application, along with its tags and custom fields
+        Ok(Some(ja)) => {
+            ui.set_selected_field_errors(JobApplicationErrorsView::default());
+            update_selected_custom_fields(conn, &ui, &ja.custom_fields);
+            ui.set_selected_job_application(ja.into());
+            update_selected_tags(conn, &ui, application_id);
+            update_selected_offer(conn, &ui, application_id);
+            update_selected_job_description(conn, &ui, application_id);
+            update_selected_attachments(conn, &ui, application_id);
+            update_selected_resume_version(conn, &ui, application_id);
+        }
         Ok(None) => eprintln!("No job application matches id {application_id}"),
         Err(error) => eprintln!("{error}"),
     };
@@ -266,17 +774,224 @@ fn reset_selected_row(ui: &AppWindow) {
         // - strings are ""
         ..JobApplicationView::default()
     });
+    ui.set_selected_field_errors(JobApplicationErrorsView::default());
+    // A new job application has no tags yet
+    ui.set_selected_tags(ModelRc::default());
+    // Keep the custom fields, but clear their values.
+    // This uses a new model so inputs the user typed in are recreated, since typing breaks their bindings.
+    ui.set_selected_custom_fields(ModelRc::new(
+        ui.get_selected_custom_fields()
+            .iter()
+            .map(|custom_field| CustomFieldView {
+                value: SharedString::default(),
+                ..custom_field
+            })
+            .collect::<VecModel<CustomFieldView>>(),
+    ));
+    // A new job application has no offer yet. Like the custom fields, this uses a new model.
+    ui.set_selected_offer_fields(ModelRc::new(VecModel::from(offer_field_views(None))));
+    // A new job application has no job description yet
+    ui.set_selected_job_description(SharedString::default());
+    ui.set_selected_job_description_date(SharedString::default());
+    // A new job application has no attachments yet
+    ui.set_selected_attachments(ModelRc::default());
+    ui.set_selected_resume_version(SharedString::default());
     ui.invoke_re_bind_selected();
 }
 
-/// If this is a debug build, print the job application to stdout
-#[cfg(debug_assertions)]
-fn print_job_application_to_terminal(job_application_view: &JobApplicationView) {
-    println!(
-        "ID: {}
-Source: {}
-Company: {}
-Job Title: {}
+/// Show every custom field in the sidebar, filled in with `values`
+///
+/// The custom fields are loaded every time since they can be defined from the CLI while the GUI is open.
+fn update_selected_custom_fields<C: JobApplicationRepository>(
+    conn: &mut C,
+    ui: &AppWindow,
+    values: &CustomFields,
+) {
+    let custom_fields = conn.get_custom_fields().unwrap_or_else(|error| {
+        eprintln!("Error getting custom fields: {error}");
+        Vec::default()
+    });
+
+    ui.set_selected_custom_fields(ModelRc::new(
+        custom_fields
+            .iter()
+            .map(|custom_field| CustomFieldView::new(custom_field, values.get(&custom_field.name)))
+            .collect::<VecModel<CustomFieldView>>(),
+    ));
+}
+
+/// Show the tags of the job application denoted by `application_id` in the sidebar
+fn update_selected_tags<C: JobApplicationRepository>(
+    conn: &mut C,
+    ui: &AppWindow,
+    application_id: i32,
+) {
+    let tags = conn
+        .get_job_application_tags(application_id)
+        .unwrap_or_else(|error| {
+            eprintln!("Error getting tags for job application {application_id}: {error}");
+            Vec::default()
+        });
+
+    ui.set_selected_tags(ModelRc::new(
+        tags.into_iter()
+            .map(|tag| SharedString::from(tag.name))
+            .collect::<VecModel<SharedString>>(),
+    ));
+}
+
+/// Show the offer of the job application denoted by `application_id` in the sidebar
+///
+/// The fields are shown empty if it has no offer, so one can be filled in.
+fn update_selected_offer<C: JobApplicationRepository>(
+    conn: &mut C,
+    ui: &AppWindow,
+    application_id: i32,
+) {
+    let offer = conn.get_offer(application_id).unwrap_or_else(|error| {
+        eprintln!("Error getting offer for job application {application_id}: {error}");
+        None
+    });
+
+    ui.set_selected_offer_fields(ModelRc::new(VecModel::from(offer_field_views(
+        offer.as_ref(),
+    ))));
+}
+
+/// Show the job description of the job application denoted by `application_id` in the sidebar, along with the date it was captured
+fn update_selected_job_description<C: JobApplicationRepository>(
+    conn: &mut C,
+    ui: &AppWindow,
+    application_id: i32,
+) {
+    let job_description = conn
+        .get_job_description(application_id)
+        .unwrap_or_else(|error| {
+            eprintln!(
+                "Error getting job description for job application {application_id}: {error}"
+            );
+            None
+        });
+
+    match job_description {
+        Some(job_description) => {
+            ui.set_selected_job_description(job_description.description.into());
+            ui.set_selected_job_description_date(format_date(job_description.captured_date));
+        }
+        None => {
+            ui.set_selected_job_description(SharedString::default());
+            ui.set_selected_job_description_date(SharedString::default());
+        }
+    }
+}
+
+/// Offer every resume version in the sidebar, with an empty option first for none
+///
+/// Like the custom fields, these are loaded every time since they can be added from the CLI while the GUI is open.
+fn update_resume_version_labels<C: JobApplicationRepository>(conn: &mut C, ui: &AppWindow) {
+    let resume_versions = conn.get_resume_versions().unwrap_or_else(|error| {
+        eprintln!("Error getting resume versions: {error}");
+        Vec::default()
+    });
+
+    ui.set_resume_version_labels(ModelRc::new(
+        once(SharedString::default())
+            .chain(
+                resume_versions
+                    .into_iter()
+                    .map(|resume_version| resume_version.label.into()),
+            )
+            .collect::<VecModel<SharedString>>(),
+    ));
+}
+
+/// Show the resume version sent with the job application denoted by `application_id` in the sidebar
+fn update_selected_resume_version<C: JobApplicationRepository>(
+    conn: &mut C,
+    ui: &AppWindow,
+    application_id: i32,
+) {
+    update_resume_version_labels(conn, ui);
+
+    let resume_version = conn
+        .get_sent_resume_version(application_id)
+        .unwrap_or_else(|error| {
+            eprintln!("Error getting resume version for job application {application_id}: {error}");
+            None
+        });
+
+    ui.set_selected_resume_version(
+        resume_version.map_or(SharedString::default(), |resume_version| {
+            resume_version.label.into()
+        }),
+    );
+}
+
+/// Show the files attached to the job application denoted by `application_id` in the sidebar
+fn update_selected_attachments<C: JobApplicationRepository>(
+    conn: &mut C,
+    ui: &AppWindow,
+    application_id: i32,
+) {
+    let attachments = conn
+        .get_attachments(application_id)
+        .unwrap_or_else(|error| {
+            eprintln!("Error getting attachments for job application {application_id}: {error}");
+            Vec::default()
+        });
+
+    ui.set_selected_attachments(ModelRc::new(
+        attachments
+            .iter()
+            .map(AttachmentView::from)
+            .collect::<VecModel<AttachmentView>>(),
+    ));
+}
+
+/// Read the file at `path` and attach it to job application `id` as `role`, attached today
+fn add_attachment<C: JobApplicationRepository>(
+    conn: &mut C,
+    id: i32,
+    role: &str,
+    path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let role: AttachmentRole = role.parse()?;
+    let contents = fs::read(path)?;
+    conn.add_attachment(id, role, path, &contents, get_today())?;
+    Ok(())
+}
+
+/// Copy attachment `id` into `temp_dir` and open it with the default application for its file type
+///
+/// Each attachment gets its own directory so file names don't collide, and the file name is kept so the right application is used.
+fn open_attachment<C: JobApplicationRepository>(
+    conn: &mut C,
+    id: i32,
+    temp_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (Some(attachment), Some(contents)) =
+        (conn.get_attachment(id)?, conn.get_attachment_contents(id)?)
+    else {
+        return Err(format!("No attachment matches id {id}").into());
+    };
+
+    let directory = temp_dir.join(id.to_string());
+    fs::create_dir_all(&directory)?;
+    let path = directory.join(&attachment.file_name);
+    fs::write(&path, contents)?;
+    opener::open(path)?;
+
+    Ok(())
+}
+
+/// If this is a debug build, print the job application to stdout
+#[cfg(debug_assertions)]
+fn print_job_application_to_terminal(job_application_view: &JobApplicationView) {
+    println!(
+        "ID: {}
+Source: {}
+Company: {}
+Job Title: {}
 Application date: {:?}
 Time investment: {}
 Human response: {:?}
@@ -301,6 +1016,33 @@ Notes: {}",
 /// If `job_application_view.id == 0`, an insert will be performed.
 /// Otherwise, an update to the value at `job_application_view.id` will be performed.
 /// When finished, the ui table will be updated
+/// Find saved job applications that a new job application is likely a duplicate of
+///
+/// Job applications that are already saved are never duplicates, since they are being edited.
+fn find_duplicates<C: JobApplicationRepository>(
+    conn: &mut C,
+    ui: &AppWindow,
+    job_application_view: &JobApplicationView,
+) -> Result<Vec<JobApplication>, Box<dyn std::error::Error>> {
+    if job_application_view.id != 0 {
+        return Ok(Vec::new());
+    }
+
+    let job_application = selected_job_application(ui, job_application_view.clone())?;
+    Ok(conn.find_duplicates(&job_application, repository::duplicate_window_days())?)
+}
+
+/// Convert the job application in the sidebar, along with its custom fields
+fn selected_job_application(
+    ui: &AppWindow,
+    job_application_view: JobApplicationView,
+) -> Result<NewJobApplication, Box<dyn std::error::Error>> {
+    let mut job_application: NewJobApplication = job_application_view.try_into()?;
+    job_application.custom_fields =
+        custom_fields_from_views(ui.get_selected_custom_fields().iter())?;
+    Ok(job_application)
+}
+
 fn submit_job_application<C: JobApplicationRepository>(
     conn: &mut C,
     ui: &AppWindow,
@@ -312,16 +1054,82 @@ fn submit_job_application<C: JobApplicationRepository>(
         print_job_application_to_terminal(&job_application_view);
     }
 
+    // An id of 0 means the job application hasn't been saved yet
+    let id = Some(job_application_view.id).filter(|id| *id != 0);
+    let version = job_application_view.version;
+
     // Try to convert the job application
-    // The variable is mutable because we will pull from the database after the insert
-    let mut job_application: JobApplication = job_application_view.try_into()?;
+    let job_application = selected_job_application(ui, job_application_view)?;
+    // Parse the offer before saving anything, so an invalid offer doesn't leave the job application half saved.
+    // The id is filled in after the insert for new job applications.
+    let offer = offer_from_field_views(
+        id.unwrap_or_default(),
+        ui.get_selected_offer_fields().iter(),
+    )?;
 
-    let is_new = job_application.id == 0;
+    // Show every problem next to its field instead of saving.
+    // The repository checks this too, but checking here first means the errors can be put in the right places.
+    if let Err(errors) = validate_new_job_application(&job_application) {
+        ui.set_selected_field_errors(errors.as_slice().into());
+        return Err(RepositoryError::InvalidFields(errors).into());
+    }
+
+    // Save the job application along with its offer, description, and resume version together,
+    // so an error part way through doesn't leave it half saved
+    let job_application = conn.with_transaction(|tx| -> Result<_, Box<dyn std::error::Error>> {
+        let job_application = match id {
+            // Only saved if it hasn't been changed since the version that was opened
+            Some(id) => tx.update_job_application(&JobApplication {
+                version,
+                ..job_application.with_id(id)
+            })?,
+            None => tx.insert_job_application(&job_application)?,
+        };
+
+        // Only job offers have an offer. Clearing the base salary removes it.
+        if job_application.human_response == HumanResponse::JobOffer {
+            match offer {
+                Some(offer) => tx.save_offer(&Offer {
+                    job_application_id: job_application.id,
+                    ..offer
+                })?,
+                None => tx.delete_offer(job_application.id)?,
+            }
+        }
 
-    if is_new {
-        // Insert
-        job_application = conn.insert_job_application(&job_application)?;
+        // Only save the job description if it changed, so the captured date stays the date the posting was saved.
+        // Clearing the job description removes it.
+        let description = ui.get_selected_job_description().trim().to_owned();
+        let saved_description = tx
+            .get_job_description(job_application.id)?
+            .map(|job_description| job_description.description);
+        if description.is_empty() {
+            if saved_description.is_some() {
+                tx.delete_job_description(job_application.id)?;
+            }
+        } else if saved_description.as_ref() != Some(&description) {
+            tx.save_job_description(&JobDescription {
+                job_application_id: job_application.id,
+                description,
+                captured_date: get_today(),
+            })?;
+        }
+
+        // The empty label means no resume version
+        let resume_version_label = ui.get_selected_resume_version();
+        let resume_version_id = tx
+            .get_resume_versions()?
+            .into_iter()
+            .find(|resume_version| resume_version.label == resume_version_label.as_str())
+            .map(|resume_version| resume_version.id);
+        tx.set_sent_resume_version(job_application.id, resume_version_id)?;
 
+        Ok(job_application)
+    })?;
+
+    let all_activity = get_activity(conn);
+    let activity = all_activity.get(&job_application.id);
+    if id.is_none() {
         // Since this is an insert, we should insert a row into the table instead of trying to edit an existing entry
         let table_rows: ModelRc<ModelRc<StandardListViewItem>> = ui.get_table_rows();
 
@@ -330,7 +1138,7 @@ fn submit_job_application<C: JobApplicationRepository>(
             .as_any()
             .downcast_ref::<VecModel<ModelRc<StandardListViewItem>>>()
         {
-            table_rows_vec.push(job_application_into_row(&job_application));
+            table_rows_vec.push(job_application_into_row(&job_application, activity));
         } else {
             // If that isn't possible, we need to recreate the model
             #[cfg(debug_assertions)]
@@ -339,34 +1147,125 @@ fn submit_job_application<C: JobApplicationRepository>(
             ui.set_table_rows(ModelRc::new(
                 table_rows
                     .iter()
-                    .chain(once(job_application_into_row(&job_application)))
+                    .chain(once(job_application_into_row(&job_application, activity)))
                     .collect::<VecModel<ModelRc<StandardListViewItem>>>(),
             ));
         }
     } else {
-        // Update
-        conn.update_job_application(&job_application)?;
-
         // Since this is an update, we should just update the row that contains the updated data
-        let id = job_application.id;
+        replace_table_row(ui, &job_application, activity);
+    }
 
-        let table_rows: ModelRc<ModelRc<StandardListViewItem>> = ui.get_table_rows();
+    reset_selected_row(ui);
 
-        // There may be a better way than this, but I can't find one
-        // Loop over rows in the table. Whenever the id matches the id we are looking for, replace that row.
-        for i in 0..table_rows.row_count() {
-            if let Some(table_row) = table_rows.row_data(i) {
-                if table_row.row_data(0) == Some(id.to_shared_string().into()) {
-                    table_rows.set_row_data(i, job_application_into_row(&job_application));
-                    break;
-                }
+    Ok(())
+}
+
+/// Replace the row in the table for a job application that was changed
+fn replace_table_row(
+    ui: &AppWindow,
+    job_application: &JobApplication,
+    activity: Option<&Activity>,
+) {
+    let id = job_application.id;
+
+    let table_rows: ModelRc<ModelRc<StandardListViewItem>> = ui.get_table_rows();
+
+    // There may be a better way than this, but I can't find one
+    // Loop over rows in the table. Whenever the id matches the id we are looking for, replace that row.
+    for i in 0..table_rows.row_count() {
+        if let Some(table_row) = table_rows.row_data(i) {
+            if table_row.row_data(0) == Some(id.to_shared_string().into()) {
+                table_rows.set_row_data(i, job_application_into_row(job_application, activity));
+                break;
             }
         }
     }
+}
 
-    reset_selected_row(ui);
+/// Get the id of a job application from its row in the table
+fn table_row_id(table_row: &ModelRc<StandardListViewItem>) -> Option<i32> {
+    table_row.row_data(0)?.text.parse().ok()
+}
 
-    Ok(())
+/// Show changes from outside of the GUI in the table, without changing the sidebar
+///
+/// Changed rows are replaced where they are and new ones are added at the end, like when submitting from the sidebar.
+/// Only job applications whose ids are in `matching`, the results of the search shown in the table, are kept or added,
+/// so changed ones that no longer match are removed and ones that match now are added.
+/// Anything being edited in the sidebar is kept, and saving it over a newer change asks which version to keep.
+fn refresh_table_rows(
+    ui: &AppWindow,
+    changes: &Changes,
+    matching: &BTreeSet<i32>,
+    activity: &BTreeMap<i32, Activity>,
+) {
+    let table_rows: ModelRc<ModelRc<StandardListViewItem>> = ui.get_table_rows();
+
+    // Rows can only be removed from a VecModel, so recreate the model if it is something else, like after a delete
+    let table_rows = if table_rows
+        .as_any()
+        .downcast_ref::<VecModel<ModelRc<StandardListViewItem>>>()
+        .is_some()
+    {
+        table_rows
+    } else {
+        let table_rows_vec = ModelRc::new(
+            table_rows
+                .iter()
+                .collect::<VecModel<ModelRc<StandardListViewItem>>>(),
+        );
+        ui.set_table_rows(table_rows_vec.clone());
+        table_rows_vec
+    };
+    let Some(table_rows_vec) = table_rows
+        .as_any()
+        .downcast_ref::<VecModel<ModelRc<StandardListViewItem>>>()
+    else {
+        return;
+    };
+
+    // Go backwards so removing a row doesn't move the rows that are left to check
+    let mut shown = BTreeSet::new();
+    for i in (0..table_rows_vec.row_count()).rev() {
+        let Some(id) = table_rows_vec
+            .row_data(i)
+            .and_then(|row| table_row_id(&row))
+        else {
+            continue;
+        };
+        let changed = changes.changed.iter().find(|ja| ja.id == id);
+        if changes.removed.contains(&id) || (changed.is_some() && !matching.contains(&id)) {
+            table_rows_vec.remove(i);
+            continue;
+        }
+        if let Some(job_application) = changed {
+            table_rows_vec.set_row_data(
+                i,
+                job_application_into_row(job_application, activity.get(&id)),
+            );
+        }
+        shown.insert(id);
+    }
+    // Changed job applications may match the search now, even though they didn't before
+    for job_application in changes.added.iter().chain(&changes.changed) {
+        if matching.contains(&job_application.id) && shown.insert(job_application.id) {
+            table_rows_vec.push(job_application_into_row(
+                job_application,
+                activity.get(&job_application.id),
+            ));
+        }
+    }
+
+    // Rows before the selected one may have been removed, so move the highlight back onto it.
+    // Setting the current row directly doesn't load the job application into the sidebar again.
+    let selected_id = ui.get_selected_job_application().id;
+    if selected_id != 0 {
+        let current_row = table_rows_vec
+            .iter()
+            .position(|row| table_row_id(&row) == Some(selected_id));
+        ui.set_table_current_row(current_row.map_or(-1, |i| i as i32));
+    }
 }
 
 /// Delete a job application using the repository, then remove the application from the displayed table
@@ -380,14 +1279,14 @@ fn delete_job_application<C: JobApplicationRepository>(
     println!("Deleting job application {id}");
 
     // Delete the given job application
-    conn.delete_job_application(id)?;
+    let deleted = conn.delete_job_application(id)?;
 
     // If possible, delete the job application from the table
     let table_rows = ui.get_table_rows();
 
     // Filter table rows by id != table_row.id
     // To revisit: Because of how filter works, this may create a memory leak, or at least a bunch of pointer dereferencing.
-    let id_as_standard_list_view_item: StandardListViewItem = id.to_shared_string().into();
+    let id_as_standard_list_view_item: StandardListViewItem = deleted.id.to_shared_string().into();
     let filtered = table_rows.filter(move |row| -> bool {
         // Exclude rows where row id is `id_as_standard_list_view_item`
         row.row_data(0)
@@ -470,39 +1369,629 @@ where
     Ok(())
 }
 
+/// Create a dialog box to confirm if a job application that looks like a duplicate should be saved anyway
+fn duplicate_warning<C>(
+    conn: &Rc<RefCell<C>>,
+    ui: &AppWindow,
+    job_application_view: JobApplicationView,
+    duplicates: &[JobApplication],
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    C: JobApplicationRepository + 'static,
+{
+    // Create the window
+    let dialog_window: DuplicateWarning = DuplicateWarning::new()?;
+    let duplicates: Vec<SharedString> = duplicates
+        .iter()
+        .map(|duplicate| {
+            format!(
+                "{}: {} at {} (applied {})",
+                duplicate.id,
+                duplicate.job_title,
+                duplicate.company,
+                format_date(duplicate.application_date)
+            )
+            .into()
+        })
+        .collect();
+    dialog_window.set_duplicates(ModelRc::new(VecModel::from(duplicates)));
+
+    // Handle "cancel"
+    {
+        let dialog_window_clone = dialog_window.as_weak();
+        dialog_window.on_cancel_clicked(move || {
+            // Close window
+            if let Some(dialog_window) = dialog_window_clone.upgrade() {
+                dialog_window
+                    .hide()
+                    .expect("Error closing duplicate warning dialog window");
+            } else {
+                println!("Cannot close dialog window because it doesn't exist")
+            }
+        });
+    }
+
+    // Handle "save anyway"
+    {
+        // Get references for parent function variables
+        let dialog_window_clone = dialog_window.as_weak();
+        let conn_clone = Rc::clone(conn);
+        let ui_clone = ui.as_weak();
+
+        dialog_window.on_save_clicked(move || {
+            // Save
+            if let Some(ui) = ui_clone.upgrade() {
+                if let Err(e) = submit_job_application(
+                    RefCell::borrow_mut(&conn_clone).deref_mut(),
+                    &ui,
+                    job_application_view.clone(),
+                ) {
+                    // Print any errors, but otherwise discard them.
+                    // We may want to actually do something with these errors later, though
+                    eprintln!("{e}");
+                }
+            } else {
+                eprintln!("Error submitting job application: AppWindow no longer exists");
+            }
+
+            // Close window
+            if let Some(dialog_window) = dialog_window_clone.upgrade() {
+                dialog_window
+                    .hide()
+                    .expect("Error closing duplicate warning dialog window")
+            } else {
+                println!("Cannot close dialog window because it doesn't exist")
+            }
+        });
+    }
+
+    dialog_window.show()?;
+
+    Ok(())
+}
+
+/// Create a dialog box showing how the job application being saved differs from the version saved since it was opened,
+/// so either one can be kept
+fn conflict_resolution<C>(
+    conn: &Rc<RefCell<C>>,
+    ui: &AppWindow,
+    job_application_view: JobApplicationView,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    C: JobApplicationRepository + 'static,
+{
+    let id = job_application_view.id;
+    let mine = selected_job_application(ui, job_application_view.clone())?.with_id(id);
+    let saved = RefCell::borrow_mut(conn)
+        .get_job_application_by_id(id)?
+        .ok_or_else(|| RepositoryError::job_application_not_found(id))?;
+
+    // The newest version has to be the one replaced from now on
+    let job_application_view = JobApplicationView {
+        version: saved.version,
+        ..job_application_view
+    };
+
+    let changes = job_application_changes(&saved, &mine);
+    if changes.is_empty() {
+        // Both versions are the same, so there is nothing to choose
+        return submit_job_application(
+            RefCell::borrow_mut(conn).deref_mut(),
+            ui,
+            job_application_view,
+        );
+    }
+
+    // Create the window
+    let dialog_window: ConflictResolution = ConflictResolution::new()?;
+    dialog_window.set_id(id);
+    let empty = || "(empty)".to_owned();
+    let rows: Vec<ModelRc<StandardListViewItem>> = changes
+        .into_iter()
+        .map(|change| {
+            ModelRc::new(VecModel::from(vec![
+                StandardListViewItem::from(change.field.as_str()),
+                StandardListViewItem::from(change.new_value.unwrap_or_else(empty).as_str()),
+                StandardListViewItem::from(change.old_value.unwrap_or_else(empty).as_str()),
+            ]))
+        })
+        .collect();
+    dialog_window.set_rows(ModelRc::new(VecModel::from(rows)));
+
+    // Handle "cancel", which leaves the changes in the sidebar unsaved
+    {
+        let dialog_window_clone = dialog_window.as_weak();
+        dialog_window.on_cancel_clicked(move || {
+            // Close window
+            if let Some(dialog_window) = dialog_window_clone.upgrade() {
+                dialog_window
+                    .hide()
+                    .expect("Error closing conflict resolution dialog window");
+            } else {
+                println!("Cannot close dialog window because it doesn't exist")
+            }
+        });
+    }
+
+    // Handle "keep my version", saving over the newer version
+    {
+        let dialog_window_clone = dialog_window.as_weak();
+        let conn_clone = Rc::clone(conn);
+        let ui_clone = ui.as_weak();
+
+        dialog_window.on_keep_mine_clicked(move || {
+            if let Some(ui) = ui_clone.upgrade() {
+                if let Err(e) = submit_job_application(
+                    RefCell::borrow_mut(&conn_clone).deref_mut(),
+                    &ui,
+                    job_application_view.clone(),
+                ) {
+                    // Print any errors, but otherwise discard them.
+                    // We may want to actually do something with these errors later, though
+                    eprintln!("{e}");
+                }
+            } else {
+                eprintln!("Error submitting job application: AppWindow no longer exists");
+            }
+
+            // Close window
+            if let Some(dialog_window) = dialog_window_clone.upgrade() {
+                dialog_window
+                    .hide()
+                    .expect("Error closing conflict resolution dialog window")
+            } else {
+                println!("Cannot close dialog window because it doesn't exist")
+            }
+        });
+    }
+
+    // Handle "keep saved version", throwing away the changes in the sidebar
+    {
+        let dialog_window_clone = dialog_window.as_weak();
+        let conn_clone = Rc::clone(conn);
+        let ui_clone = ui.as_weak();
+
+        dialog_window.on_keep_saved_clicked(move || {
+            if let Some(ui) = ui_clone.upgrade() {
+                let activity = get_activity(RefCell::borrow_mut(&conn_clone).deref_mut());
+                replace_table_row(&ui, &saved, activity.get(&id));
+                select_row(RefCell::borrow_mut(&conn_clone).deref_mut(), ui, id);
+            } else {
+                eprintln!("Error loading job application: AppWindow no longer exists");
+            }
+
+            // Close window
+            if let Some(dialog_window) = dialog_window_clone.upgrade() {
+                dialog_window
+                    .hide()
+                    .expect("Error closing conflict resolution dialog window")
+            } else {
+                println!("Cannot close dialog window because it doesn't exist")
+            }
+        });
+    }
+
+    dialog_window.show()?;
+
+    Ok(())
+}
+
+/// Create a window listing the job applications in the trash
+///
+/// Restored job applications are put back in the table.
+fn trash<C>(
+    conn: &Rc<RefCell<C>>,
+    ui: &AppWindow,
+    active_search: &Rc<RefCell<SearchQuery>>,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    C: JobApplicationRepository + 'static,
+{
+    // Create the window
+    let trash_window: Trash = Trash::new()?;
+    update_trash_rows(RefCell::borrow_mut(conn).deref_mut(), &trash_window)?;
+
+    // Handle "restore"
+    {
+        let trash_window_clone = trash_window.as_weak();
+        let conn_clone = Rc::clone(conn);
+        let ui_clone = ui.as_weak();
+        let active_search = Rc::clone(active_search);
+
+        trash_window.on_restore(move |row: i32| {
+            let (Some(trash_window), Some(ui)) = (trash_window_clone.upgrade(), ui_clone.upgrade())
+            else {
+                println!("Cannot restore job application because a window doesn't exist");
+                return;
+            };
+
+            // The id is in the first column of the row
+            let Some(id) = trash_window
+                .get_rows()
+                .row_data(row as usize)
+                .and_then(|row| row.row_data(0))
+                .and_then(|id| id.text.parse::<i32>().ok())
+            else {
+                return;
+            };
+
+            let mut conn = RefCell::borrow_mut(&conn_clone);
+            if let Err(e) = restore_job_application(conn.deref_mut(), &ui, &active_search, id) {
+                eprintln!("Error restoring job application: {e}");
+            }
+            if let Err(e) = update_trash_rows(conn.deref_mut(), &trash_window) {
+                eprintln!("{e}");
+            }
+        });
+    }
+
+    // Handle "empty trash"
+    {
+        let trash_window_clone = trash_window.as_weak();
+        let conn_clone = Rc::clone(conn);
+
+        trash_window.on_empty_trash(move || {
+            let Some(trash_window) = trash_window_clone.upgrade() else {
+                println!("Cannot empty the trash because the window doesn't exist");
+                return;
+            };
+
+            let mut conn = RefCell::borrow_mut(&conn_clone);
+            if let Err(e) = conn.empty_trash() {
+                eprintln!("Error emptying the trash: {e}");
+            }
+            if let Err(e) = update_trash_rows(conn.deref_mut(), &trash_window) {
+                eprintln!("{e}");
+            }
+        });
+    }
+
+    trash_window.show()?;
+
+    Ok(())
+}
+
+/// Show every job application in the trash in the trash window, most recently deleted first
+fn update_trash_rows<C: JobApplicationRepository>(
+    conn: &mut C,
+    trash_window: &Trash,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rows: VecModel<ModelRc<StandardListViewItem>> = VecModel::default();
+    for deleted in conn.get_deleted_job_applications()? {
+        rows.push(
+            [
+                StandardListViewItem::from(deleted.job_application.id.to_string().as_str()),
+                deleted.job_application.company.as_str().into(),
+                deleted.job_application.job_title.as_str().into(),
+                format_deleted_at(deleted.deleted_at).as_str().into(),
+            ]
+            .into(),
+        );
+    }
+    trash_window.set_rows(ModelRc::new(rows));
+
+    Ok(())
+}
+
+/// Take a job application out of the trash, then run the search shown in the table again so it is included if it matches
+fn restore_job_application<C: JobApplicationRepository>(
+    conn: &mut C,
+    ui: &AppWindow,
+    active_search: &RefCell<SearchQuery>,
+    id: i32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Debug print statement
+    #[cfg(debug_assertions)]
+    println!("Restoring job application {id}");
+
+    conn.restore_job_application(id)?;
+    update_table(
+        ui,
+        conn.query_job_applications(&RefCell::borrow(active_search))?,
+        &get_activity(conn),
+    );
+
+    Ok(())
+}
+
+/// Undo (or redo) the most recent change, then run the search shown in the table again and select the job application that changed
+///
+/// If that job application is now in the trash, the sidebar is cleared instead.
+fn undo_or_redo<C: JobApplicationRepository>(
+    conn: &mut C,
+    ui: AppWindow,
+    active_search: &RefCell<SearchQuery>,
+    redo: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let entry = if redo { conn.redo()? } else { conn.undo()? };
+    let Some(entry) = entry else {
+        // Debug print statement
+        #[cfg(debug_assertions)]
+        println!("Nothing to {}", if redo { "redo" } else { "undo" });
+        return Ok(());
+    };
+
+    // Debug print statement
+    #[cfg(debug_assertions)]
+    println!(
+        "{} {} of job application {}",
+        if redo { "Redid" } else { "Undid" },
+        entry.operation,
+        entry.job_application_id
+    );
+
+    update_table(
+        &ui,
+        conn.query_job_applications(&RefCell::borrow(active_search))?,
+        &get_activity(conn),
+    );
+    if conn
+        .get_job_application_by_id(entry.job_application_id)?
+        .is_some()
+    {
+        select_row(conn, ui, entry.job_application_id);
+    } else if ui.get_selected_job_application().id == entry.job_application_id {
+        reset_selected_row(&ui);
+    }
+
+    Ok(())
+}
+
+/// Format when a job application was deleted in local time, like 01/31/2025 14:05
+///
+/// Deletion times are stored in UTC. If the local offset can't be found, UTC is shown instead.
+fn format_deleted_at(deleted_at: PrimitiveDateTime) -> String {
+    let offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+    deleted_at
+        .assume_utc()
+        .to_offset(offset)
+        .format(format_description!("[month]/[day]/[year] [hour]:[minute]"))
+        .unwrap_or_default()
+}
+
+/// Create a window showing how job applications are going by work arrangement and by resume version
+fn application_stats<C: JobApplicationRepository>(
+    conn: &mut C,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let stats_window = ApplicationStats::new()?;
+
+    let work_arrangement_rows: VecModel<ModelRc<StandardListViewItem>> = VecModel::default();
+    for arrangement_stats in conn.get_work_arrangement_stats()? {
+        let responses = arrangement_stats.applications - arrangement_stats.pending;
+        work_arrangement_rows.push(
+            [
+                StandardListViewItem::from(
+                    arrangement_stats
+                        .work_arrangement
+                        .map_or("unknown".to_string(), |work_arrangement| {
+                            work_arrangement.to_string()
+                        })
+                        .as_str(),
+                ),
+                arrangement_stats.applications.to_string().as_str().into(),
+                arrangement_stats.pending.to_string().as_str().into(),
+                arrangement_stats.rejections.to_string().as_str().into(),
+                arrangement_stats.interviews.to_string().as_str().into(),
+                arrangement_stats.offers.to_string().as_str().into(),
+                // There is always at least one application in a group
+                format_rate(Some(
+                    responses as f64 / arrangement_stats.applications as f64,
+                ))
+                .as_str()
+                .into(),
+            ]
+            .into(),
+        );
+    }
+    stats_window.set_work_arrangement_rows(ModelRc::new(work_arrangement_rows));
+
+    let resume_version_rows: VecModel<ModelRc<StandardListViewItem>> = VecModel::default();
+    for version_stats in conn.get_resume_version_stats()? {
+        resume_version_rows.push(
+            [
+                StandardListViewItem::from(
+                    version_stats.label.as_deref().unwrap_or("none recorded"),
+                ),
+                version_stats.applications.to_string().as_str().into(),
+                version_stats.responses.to_string().as_str().into(),
+                format_rate(version_stats.response_rate()).as_str().into(),
+                version_stats.interviews.to_string().as_str().into(),
+                format_rate(version_stats.interview_rate()).as_str().into(),
+                version_stats.offers.to_string().as_str().into(),
+            ]
+            .into(),
+        );
+    }
+    stats_window.set_resume_version_rows(ModelRc::new(resume_version_rows));
+
+    stats_window.show()?;
+
+    Ok(())
+}
+
+/// Format a fraction as a percentage, like 12.5%, or "-" if there is nothing to divide by
+fn format_rate(rate: Option<f64>) -> String {
+    rate.map_or("-".to_string(), |rate| format!("{:.1}%", rate * 100.0))
+}
+
+/// Create a window to compare every offer side by side
+///
+/// The offers are compared again with new weights whenever the user asks.
+fn offer_comparison<C>(conn: &Rc<RefCell<C>>) -> Result<(), Box<dyn std::error::Error>>
+where
+    C: JobApplicationRepository + 'static,
+{
+    // Create the window
+    let comparison_window: OfferComparison = OfferComparison::new()?;
+
+    // The weight inputs start out as the default weights
+    compare_offers(
+        RefCell::borrow_mut(conn).deref_mut(),
+        &comparison_window,
+        &CriteriaWeights::default(),
+    )?;
+
+    // Handle "compare"
+    {
+        let comparison_window_clone = comparison_window.as_weak();
+        let conn_clone = Rc::clone(conn);
+
+        comparison_window.on_compare(
+            move |base: SharedString,
+                  bonus: SharedString,
+                  equity: SharedString,
+                  total: SharedString| {
+                let Some(comparison_window) = comparison_window_clone.upgrade() else {
+                    println!("Cannot compare offers because the window doesn't exist");
+                    return;
+                };
+
+                // Write the weights the same way as the CLI, leaving out empty inputs
+                let weights: String = Criterion::ALL
+                    .iter()
+                    .zip([base, bonus, equity, total])
+                    .filter(|(_, weight)| !weight.trim().is_empty())
+                    .map(|(criterion, weight)| format!("{criterion}={}", weight.trim()))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                match CriteriaWeights::from_str(&weights) {
+                    Ok(weights) => {
+                        if let Err(e) = compare_offers(
+                            RefCell::borrow_mut(&conn_clone).deref_mut(),
+                            &comparison_window,
+                            &weights,
+                        ) {
+                            eprintln!("{e}");
+                        }
+                    }
+                    Err(e) => comparison_window.set_message(e.into()),
+                }
+            },
+        );
+    }
+
+    comparison_window.show()?;
+
+    Ok(())
+}
+
+/// Score every offer by `weights` and show them in the comparison window, best first
+fn compare_offers<C: JobApplicationRepository>(
+    conn: &mut C,
+    comparison_window: &OfferComparison,
+    weights: &CriteriaWeights,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let offers = conn.get_offers()?;
+
+    // Amounts are compared as-is, so warn if they can't be compared fairly
+    let currencies: BTreeSet<String> = offers
+        .iter()
+        .map(|offer| offer.currency.as_deref().unwrap_or_default().to_uppercase())
+        .collect();
+    comparison_window.set_message(
+        if offers.is_empty() {
+            "No offers yet. Set the response of a job application to job offer to add one."
+        } else if currencies.len() > 1 {
+            "The offers use different currencies, which are not converted when comparing."
+        } else {
+            ""
+        }
+        .into(),
+    );
+
+    let rows: VecModel<ModelRc<StandardListViewItem>> = VecModel::default();
+    for scored_offer in score_offers(offers, weights) {
+        let company = conn
+            .get_job_application_by_id(scored_offer.offer.job_application_id)?
+            .map_or("".to_string(), |ja| ja.company);
+        rows.push(
+            [
+                StandardListViewItem::from(
+                    scored_offer.offer.job_application_id.to_string().as_str(),
+                ),
+                company.as_str().into(),
+                scored_offer
+                    .offer
+                    .currency
+                    .as_deref()
+                    .unwrap_or_default()
+                    .into(),
+                format!("{:.2}", scored_offer.annual_base).as_str().into(),
+                format!("{:.2}", scored_offer.annual_bonus).as_str().into(),
+                format!("{:.2}", scored_offer.annual_equity).as_str().into(),
+                format!("{:.2}", scored_offer.annual_total).as_str().into(),
+                format!("{:.1}", scored_offer.score).as_str().into(),
+                scored_offer
+                    .offer
+                    .deadline
+                    .map_or("".to_string(), |d| {
+                        format!("{:02}/{:02}/{}", d.month() as u8, d.day(), d.year())
+                    })
+                    .as_str()
+                    .into(),
+            ]
+            .into(),
+        );
+    }
+    comparison_window.set_rows(ModelRc::new(rows));
+
+    Ok(())
+}
+
 /// Search for job applications, given a human response to filter by and a search query
 ///
-/// Both are optional. If a parameter is `None`, that parameter is ignored
+/// The human response is ignored if it is `None`, and an empty query matches everything.
+/// If the query can't be parsed, the error is shown on the search bar instead of searching.
 fn search_job_application<C: JobApplicationRepository>(
     conn: &mut C,
     ui: &AppWindow,
+    active_search: &RefCell<SearchQuery>,
     human_response: Option<HumanResponse>,
-    search_query: Option<&str>,
+    query: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Get job applications using the above options
-    // This is probably not a very efficient way of doing things, but there are only 2 optional parameters
-    let job_applications = match (human_response, search_query) {
-        (None, None) => conn.get_job_applications(),
-        (None, Some(search_string)) => conn.search_job_applications(search_string),
-        (Some(human_response), None) => conn.search_by_human_response(human_response),
-        (Some(human_response), Some(search_string)) => {
-            conn.search_by_query_and_human_response(search_string, human_response)
+    let mut search_query = match SearchQuery::from_str(query) {
+        Ok(search_query) => search_query,
+        Err(e) => {
+            ui.set_search_error(e.to_shared_string());
+            return Ok(());
         }
-    }?;
+    };
+    ui.set_search_error(SharedString::default());
+
+    // The response filter works just like a `status:` filter typed in the query
+    if let Some(human_response) = human_response {
+        search_query.filters.push(SearchFilter {
+            negated: false,
+            condition: SearchCondition::HumanResponse(human_response),
+        });
+    }
 
     // Use the new job application list to update the table
-    update_table(ui, job_applications);
+    update_table(
+        ui,
+        conn.query_job_applications(&search_query)?,
+        &get_activity(conn),
+    );
+    *RefCell::borrow_mut(active_search) = search_query;
     Ok(())
 }
 
 /// Update the displayed job application table with the given vector of job applications
-fn update_table(ui: &AppWindow, job_applications: Vec<JobApplication>) {
+fn update_table(
+    ui: &AppWindow,
+    job_applications: Vec<JobApplication>,
+    activity: &BTreeMap<i32, Activity>,
+) {
     // Initialize vec model to map to
     let table_rows: VecModel<ModelRc<StandardListViewItem>> = VecModel::default();
 
     // Map the applications to `table_rows`
     for application in job_applications {
-        table_rows.push(job_application_into_row(&application));
+        table_rows.push(job_application_into_row(
+            &application,
+            activity.get(&application.id),
+        ));
     }
 
     // Set the table rows
@@ -8,11 +8,16 @@
 use std::{cell::RefCell, error::Error, ops::DerefMut, rc::Rc};
 
 use controller::{
-    handle_date_diff, handle_delete_job_application, handle_new_job_application,
-    handle_search_job_application, handle_submit_job_application, handle_use_job_application,
-    init_ui,
+    ask_passphrase, handle_add_attachment, handle_add_tag, handle_compare_offers,
+    handle_custom_field_edited, handle_date_diff, handle_delete_job_application,
+    handle_import_job_description, handle_new_job_application, handle_offer_field_edited,
+    handle_open_attachment, handle_redo, handle_remove_attachment, handle_remove_tag,
+    handle_search_job_application, handle_show_stats, handle_show_trash,
+    handle_submit_job_application, handle_undo, handle_use_job_application, init_ui,
+    watch_for_changes,
 };
 use dotenv::dotenv;
+use repository::{job_application_repository::JobApplicationRepository, search_query::SearchQuery};
 use slint::ComponentHandle as _;
 
 mod model;
@@ -23,7 +28,41 @@ fn main() -> Result<(), Box<dyn Error>> {
     // Objects that should be owned by the main function
     // We don't really care is a .env file is successfully found because we can just use actual environment variables
     _ = dotenv();
-    let conn = Rc::new(RefCell::new(repository::get_conn()?));
+    match repository::get_plain_text_repository() {
+        Some(repository) => run(repository?),
+        None => {
+            // An encrypted database asks for its passphrase before the main window opens
+            let conn = repository::get_conn_with_passphrase(ask_passphrase)?;
+            run(conn)
+        }
+    }
+}
+
+/// Open the main window on the job applications in `conn`, after backing up and purging the trash if they are due
+fn run<C>(conn: C) -> Result<(), Box<dyn Error>>
+where
+    C: JobApplicationRepository + 'static,
+{
+    let conn = Rc::new(RefCell::new(conn));
+
+    // Back up the database if a backup is due, before the trash is purged so the backup still has what is purged.
+    // Only SQLite databases are backed up here, since MySQL servers and git for plain-text storage keep their own copies.
+    // A backup that fails shouldn't stop the GUI from opening.
+    if let Some(sqlite) = RefCell::borrow_mut(&conn).sqlite() {
+        if let Some(schedule) = repository::backup_schedule() {
+            if let Err(e) =
+                repository::backup::backup_if_due(sqlite, schedule, repository::backup_retention())
+            {
+                eprintln!("Unable to back up the database: {e}");
+            }
+        }
+    }
+
+    // Permanently delete job applications that have been in the trash for too long
+    if let Some(days) = repository::trash_retention_days() {
+        RefCell::borrow_mut(&conn).purge_trash(days)?;
+    }
+
     let ui = AppWindow::new()?;
 
     // Set initial state
@@ -35,7 +74,26 @@ fn main() -> Result<(), Box<dyn Error>> {
     handle_new_job_application(&ui);
     handle_delete_job_application(&conn, &ui);
     handle_date_diff(&ui);
-    handle_search_job_application(&conn, &ui);
+    // The search shown in the table, so refreshing the table keeps to it
+    let active_search = Rc::new(RefCell::new(SearchQuery::default()));
+    handle_search_job_application(&conn, &ui, &active_search);
+    handle_add_tag(&conn, &ui);
+    handle_remove_tag(&conn, &ui);
+    handle_custom_field_edited(&ui);
+    handle_offer_field_edited(&ui);
+    handle_compare_offers(&conn, &ui);
+    handle_show_stats(&conn, &ui);
+    handle_show_trash(&conn, &ui, &active_search);
+    handle_undo(&conn, &ui, &active_search);
+    handle_redo(&conn, &ui, &active_search);
+    handle_import_job_description(&ui);
+    handle_add_attachment(&conn, &ui);
+    handle_remove_attachment(&conn, &ui);
+    // Attachments are opened from copies in this directory, which is deleted when the GUI closes
+    let temp_dir = tempfile::TempDir::new()?;
+    handle_open_attachment(&conn, &ui, temp_dir.path());
+    // Show job applications changed elsewhere, like with the CLI, for as long as the GUI is open
+    let _refresh_timer = watch_for_changes(&conn, &ui, &active_search)?;
 
     // Finally, run the UI
     ui.run()?;
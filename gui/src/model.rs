@@ -2,21 +2,31 @@
 //!
 //! This also implements `From<>` for some objects
 
-use repository::job_application_model::{HumanResponse, JobApplication};
-use time::{error::ComponentRange, ext::NumericalDuration as _, Month};
+use std::{error::Error, iter::once};
+
+use repository::{
+    job_application_model::{
+        Attachment, CustomField, CustomFieldType, CustomFieldValue, CustomFields, HumanResponse,
+        JobApplication, NewJobApplication, Offer, PayPeriod, WorkArrangement,
+    },
+    validation::FieldError,
+};
+use slint::{ModelRc, SharedString, ToSharedString as _, VecModel};
+use time::{error::ComponentRange, ext::NumericalDuration as _, macros::format_description, Month};
 
 // rust-analyzer sometimes doesn't like the `include!` macro. Use `cargo check` for a more accurate check.
 // Slint macro to include all generated code for the Slint UI
 // This automatically applies `pub use` to all necessary objects as well.
 slint::include_modules!();
 
-impl TryFrom<JobApplicationView> for JobApplication {
-    type Error = ComponentRange;
+/// The id and version are left out, since `id == 0` means the job application hasn't been saved yet.
+/// Use [NewJobApplication::with_id] and set the version for a job application that has been.
+impl TryFrom<JobApplicationView> for NewJobApplication {
+    type Error = Box<dyn Error>;
 
     fn try_from(value: JobApplicationView) -> Result<Self, Self::Error> {
         Ok(Self {
             // These are simple
-            id: value.id,
             source: value.source.into(),
             company: value.company.into(),
             job_title: value.job_title.into(),
@@ -35,14 +45,49 @@ impl TryFrom<JobApplicationView> for JobApplication {
                 .filter(|s| !s.is_empty())
                 .map(Into::into),
             notes: Some(value.notes).filter(|s| !s.is_empty()).map(Into::into),
+            // Compensation is typed in, so it needs to be parsed
+            salary_min: parse_amount(&value.salary_min)?,
+            salary_max: parse_amount(&value.salary_max)?,
+            salary_currency: Some(value.salary_currency)
+                .filter(|s| !s.trim().is_empty())
+                .map(Into::into),
+            pay_period: parse_pay_period(&value.pay_period)?,
+            equity: Some(value.equity).filter(|s| !s.is_empty()).map(Into::into),
+            bonus: Some(value.bonus).filter(|s| !s.is_empty()).map(Into::into),
+            city: Some(value.city)
+                .filter(|s| !s.trim().is_empty())
+                .map(Into::into),
+            region: Some(value.region)
+                .filter(|s| !s.trim().is_empty())
+                .map(Into::into),
+            country: Some(value.country)
+                .filter(|s| !s.trim().is_empty())
+                .map(Into::into),
+            // These are picked from a list, so they should always parse
+            work_arrangement: parse_work_arrangement(&value.work_arrangement)?,
+            relocation: parse_yes_no(&value.relocation),
+            visa_sponsorship: parse_yes_no(&value.visa_sponsorship),
+            // Custom fields are edited separately from the rest of the sidebar
+            custom_fields: CustomFields::new(),
         })
     }
 }
 
+impl From<&Attachment> for AttachmentView {
+    fn from(value: &Attachment) -> Self {
+        Self {
+            id: value.id,
+            file_name: value.file_name.as_str().into(),
+            role: value.role.to_shared_string(),
+        }
+    }
+}
+
 impl From<JobApplication> for JobApplicationView {
     fn from(value: JobApplication) -> Self {
         Self {
             id: value.id,
+            version: value.version,
             source: value.source.into(),
             company: value.company.into(),
             job_title: value.job_title.into(),
@@ -62,8 +107,258 @@ impl From<JobApplication> for JobApplicationView {
                 .unwrap_or_default()
                 .into(),
             notes: value.notes.as_deref().unwrap_or_default().into(),
+            salary_min: value
+                .salary_min
+                .map(|amount| amount.to_shared_string())
+                .unwrap_or_default(),
+            salary_max: value
+                .salary_max
+                .map(|amount| amount.to_shared_string())
+                .unwrap_or_default(),
+            salary_currency: value.salary_currency.as_deref().unwrap_or_default().into(),
+            pay_period: value
+                .pay_period
+                .map(|pay_period| pay_period.to_shared_string())
+                .unwrap_or_default(),
+            equity: value.equity.as_deref().unwrap_or_default().into(),
+            bonus: value.bonus.as_deref().unwrap_or_default().into(),
+            city: value.city.as_deref().unwrap_or_default().into(),
+            region: value.region.as_deref().unwrap_or_default().into(),
+            country: value.country.as_deref().unwrap_or_default().into(),
+            work_arrangement: value
+                .work_arrangement
+                .map(|work_arrangement| work_arrangement.to_shared_string())
+                .unwrap_or_default(),
+            relocation: format_yes_no(value.relocation),
+            visa_sponsorship: format_yes_no(value.visa_sponsorship),
+        }
+    }
+}
+
+impl From<&[FieldError]> for JobApplicationErrorsView {
+    fn from(value: &[FieldError]) -> Self {
+        let mut errors = Self::default();
+        for error in value {
+            let message = match error.field {
+                "source" => &mut errors.source,
+                "company" => &mut errors.company,
+                "job_title" => &mut errors.job_title,
+                "human_response_date" => &mut errors.human_response_date,
+                "application_website" => &mut errors.application_website,
+                "salary_currency" => &mut errors.salary_currency,
+                "equity" => &mut errors.equity,
+                "bonus" => &mut errors.bonus,
+                "city" => &mut errors.city,
+                "region" => &mut errors.region,
+                "country" => &mut errors.country,
+                // Every field that can be invalid is shown
+                _ => continue,
+            };
+            *message = error.message.as_str().into();
+        }
+        errors
+    }
+}
+
+impl CustomFieldView {
+    /// Show `custom_field` in the sidebar, filled in with `value` if there is one
+    pub fn new(custom_field: &CustomField, value: Option<&CustomFieldValue>) -> Self {
+        let value: SharedString = value.map(|v| v.to_shared_string()).unwrap_or_default();
+
+        // Bool and select fields are picked from a list, starting with an empty option to clear the value
+        let mut options: Vec<SharedString> = match &custom_field.field_type {
+            CustomFieldType::Bool => vec!["".into(), "yes".into(), "no".into()],
+            CustomFieldType::SingleSelect(options) => once(SharedString::default())
+                .chain(options.iter().map(|option| option.as_str().into()))
+                .collect(),
+            _ => Vec::new(),
+        };
+        // The option may have been removed since this value was set. Keep it so it isn't lost.
+        if !options.is_empty() && !options.contains(&value) {
+            options.push(value.clone());
         }
+
+        Self {
+            name: custom_field.name.as_str().into(),
+            field_type: custom_field.field_type.to_shared_string(),
+            options: ModelRc::new(VecModel::from(options)),
+            value,
+        }
+    }
+}
+
+/// Parse the custom field values in the sidebar, leaving out empty ones
+pub fn custom_fields_from_views(
+    views: impl Iterator<Item = CustomFieldView>,
+) -> Result<CustomFields, String> {
+    let mut custom_fields = CustomFields::new();
+    for view in views {
+        if view.value.trim().is_empty() {
+            continue;
+        }
+
+        let field_type: CustomFieldType = view.field_type.parse()?;
+        let value = match field_type.parse_value(&view.value) {
+            Ok(value) => value,
+            // Keep an option that was removed after the value was set
+            Err(_) if matches!(field_type, CustomFieldType::SingleSelect(_)) => {
+                CustomFieldValue::SingleSelect(view.value.into())
+            }
+            Err(e) => return Err(format!("Invalid value for {}: {e}", view.name)),
+        };
+        custom_fields.insert(view.name.into(), value);
     }
+    Ok(custom_fields)
+}
+
+/// The name and placeholder of each part of an offer, in the order they are shown in the sidebar
+const OFFER_FIELDS: [(&str, &str); 9] = [
+    ("Base Salary", "Required"),
+    ("Pay Period", "yearly"),
+    ("Currency", "USD"),
+    ("Bonus", "Per year"),
+    ("Equity", "Total grant value"),
+    ("Vesting Years", "4"),
+    ("Benefits", ""),
+    ("Start Date", "mm/dd/yyyy"),
+    ("Deadline", "mm/dd/yyyy"),
+];
+
+/// Show the parts of `offer` in the sidebar, or empty fields for a new offer
+pub fn offer_field_views(offer: Option<&Offer>) -> Vec<OfferFieldView> {
+    let values: [SharedString; 9] = match offer {
+        Some(offer) => [
+            offer.base_salary.to_shared_string(),
+            offer.pay_period.to_shared_string(),
+            offer.currency.as_deref().unwrap_or_default().into(),
+            offer
+                .bonus
+                .map(|amount| amount.to_shared_string())
+                .unwrap_or_default(),
+            offer
+                .equity_value
+                .map(|amount| amount.to_shared_string())
+                .unwrap_or_default(),
+            offer
+                .vesting_years
+                .map(|years| years.to_shared_string())
+                .unwrap_or_default(),
+            offer.benefits.as_deref().unwrap_or_default().into(),
+            offer.start_date.map(format_date).unwrap_or_default(),
+            offer.deadline.map(format_date).unwrap_or_default(),
+        ],
+        None => Default::default(),
+    };
+
+    OFFER_FIELDS
+        .iter()
+        .zip(values)
+        .map(|((name, placeholder), value)| OfferFieldView {
+            name: (*name).into(),
+            placeholder: (*placeholder).into(),
+            value,
+        })
+        .collect()
+}
+
+/// Parse the offer in the sidebar for the job application `job_application_id`
+///
+/// Returns `Ok(None)` if the base salary is empty, since every offer needs one.
+pub fn offer_from_field_views(
+    job_application_id: i32,
+    views: impl Iterator<Item = OfferFieldView>,
+) -> Result<Option<Offer>, Box<dyn Error>> {
+    let values: Vec<SharedString> = views.map(|view| view.value).collect();
+    // Look up each part by its position in `OFFER_FIELDS`
+    let value = |index: usize| values.get(index).map_or("", |value| value.trim());
+    let text = |index: usize| Some(value(index).to_owned()).filter(|s| !s.is_empty());
+
+    let Some(base_salary) = parse_amount(value(0))? else {
+        return Ok(None);
+    };
+    Ok(Some(Offer {
+        job_application_id,
+        base_salary,
+        pay_period: parse_pay_period(value(1))?.unwrap_or_default(),
+        currency: text(2),
+        bonus: parse_amount(value(3))?,
+        equity_value: parse_amount(value(4))?,
+        vesting_years: parse_amount(value(5))?,
+        benefits: text(6),
+        start_date: parse_date(value(7))?,
+        deadline: parse_date(value(8))?,
+    }))
+}
+
+/// Parse an amount of money typed into the sidebar. An empty string is `None`.
+fn parse_amount(s: &str) -> Result<Option<f64>, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Ok(None);
+    }
+    // Allow separators like 100,000
+    match s.replace(',', "").parse::<f64>() {
+        Ok(amount) if amount.is_finite() && amount >= 0.0 => Ok(Some(amount)),
+        _ => Err(format!("'{s}' is not an amount. Use a number 0 or above")),
+    }
+}
+
+/// Parse a pay period typed into the sidebar. An empty string is `None`.
+fn parse_pay_period(s: &str) -> Result<Option<PayPeriod>, String> {
+    Some(s.trim())
+        .filter(|s| !s.is_empty())
+        .map(str::parse)
+        .transpose()
+}
+
+/// Parse a work arrangement picked in the sidebar. An empty string is `None`.
+fn parse_work_arrangement(s: &str) -> Result<Option<WorkArrangement>, String> {
+    Some(s.trim())
+        .filter(|s| !s.is_empty())
+        .map(str::parse)
+        .transpose()
+}
+
+/// Parse a yes/no option picked in the sidebar. Anything other than `yes` or `no` is `None`.
+fn parse_yes_no(s: &str) -> Option<bool> {
+    match s {
+        "yes" => Some(true),
+        "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// The yes/no option for a value, the reverse of [parse_yes_no]
+fn format_yes_no(value: Option<bool>) -> SharedString {
+    match value {
+        Some(true) => "yes".into(),
+        Some(false) => "no".into(),
+        None => SharedString::default(),
+    }
+}
+
+/// Parse a date typed into the sidebar as mm/dd/yyyy. An empty string is `None`.
+fn parse_date(s: &str) -> Result<Option<time::Date>, time::error::Parse> {
+    Some(s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            time::Date::parse(
+                s,
+                format_description!("[month repr:numerical]/[day]/[year]"),
+            )
+        })
+        .transpose()
+}
+
+/// Format a date as mm/dd/yyyy, the way dates are typed into the sidebar
+pub fn format_date(date: time::Date) -> SharedString {
+    format!(
+        "{:02}/{:02}/{}",
+        date.month() as u8,
+        date.day(),
+        date.year()
+    )
+    .into()
 }
 
 impl From<time::Date> for Date {
@@ -122,10 +417,14 @@ impl From<HumanResponseView> for HumanResponse {
     }
 }
 
-/// Get today as a slint struct Date
-pub fn get_today_as_slint_date() -> Date {
+/// Get today in the local time zone, falling back to UTC
+pub fn get_today() -> time::Date {
     time::OffsetDateTime::now_local()
         .unwrap_or_else(|_| time::OffsetDateTime::now_utc())
         .date()
-        .into()
+}
+
+/// Get today as a slint struct Date
+pub fn get_today_as_slint_date() -> Date {
+    get_today().into()
 }
@@ -0,0 +1,540 @@
+//! Backups are snapshots of the whole database, saved in a directory next to it and named for when they were taken.
+//!
+//! They are taken automatically when ats-tracking starts, as often as [BackupSchedule] says, or by hand.
+//! Old backups are removed as new ones are taken, keeping what [BackupRetention] asks for.
+//! MySQL databases are backed up by the server instead, so only SQLite databases have backups.
+
+use std::{cmp::Reverse, collections::BTreeSet, path::PathBuf};
+
+use time::PrimitiveDateTime;
+
+use crate::job_application_repository::{RepositoryError, SqliteRepository};
+
+#[cfg(not(feature = "mysql"))]
+pub(crate) use sqlite::{get_backups, restore_backup, take_backup};
+
+/// A snapshot of the database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    /// Where the backup is saved
+    pub path: PathBuf,
+    /// When the backup was taken, in UTC
+    pub taken_at: PrimitiveDateTime,
+    /// Size of the backup in bytes
+    pub size: u64,
+    /// Whether the backup is encrypted, in which case restoring it needs the passphrase the database had when it was taken
+    pub encrypted: bool,
+}
+
+impl Backup {
+    /// The name of the backup file, which is how it is chosen for [SqliteRepository::restore_backup]
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+/// How often backups are taken automatically
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupSchedule {
+    /// Every time ats-tracking starts
+    Startup,
+    /// When ats-tracking starts, unless a backup was already taken that day (in UTC)
+    Daily,
+}
+
+impl BackupSchedule {
+    /// Whether a backup should be taken at `now`, given the backups that already exist
+    pub fn is_due(self, backups: &[Backup], now: PrimitiveDateTime) -> bool {
+        match self {
+            Self::Startup => true,
+            Self::Daily => !backups
+                .iter()
+                .any(|backup| backup.taken_at.date() == now.date()),
+        }
+    }
+}
+
+/// How many backups are kept when old ones are removed
+///
+/// The newest backup is always kept, so the one that was just taken is never removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackupRetention {
+    /// Keep the newest backup from each of the last this many days that have backups
+    pub daily: usize,
+    /// Keep the newest backup from each of the last this many weeks (ISO weeks, starting on Monday) that have backups
+    pub weekly: usize,
+}
+
+/// The default number of daily backups to keep
+pub const DEFAULT_DAILY_BACKUPS: usize = 7;
+/// The default number of weekly backups to keep
+pub const DEFAULT_WEEKLY_BACKUPS: usize = 4;
+
+impl Default for BackupRetention {
+    fn default() -> Self {
+        Self {
+            daily: DEFAULT_DAILY_BACKUPS,
+            weekly: DEFAULT_WEEKLY_BACKUPS,
+        }
+    }
+}
+
+/// Find the backups that `retention` doesn't keep, which can be in any order
+pub fn backups_to_remove(backups: &[Backup], retention: BackupRetention) -> Vec<&Backup> {
+    let mut newest_first: Vec<&Backup> = backups.iter().collect();
+    newest_first.sort_by_key(|backup| Reverse(backup.taken_at));
+
+    let mut kept_days = BTreeSet::new();
+    let mut kept_weeks = BTreeSet::new();
+    let mut remove = Vec::new();
+    for (index, backup) in newest_first.into_iter().enumerate() {
+        let day = backup.taken_at.date();
+        let (year, week, _) = day.to_iso_week_date();
+        let week = (year, week);
+
+        // Since these are newest first, the first backup seen from a day or week is the newest one from it
+        let keep_day = !kept_days.contains(&day) && kept_days.len() < retention.daily;
+        let keep_week = !kept_weeks.contains(&week) && kept_weeks.len() < retention.weekly;
+        if keep_day {
+            kept_days.insert(day);
+        }
+        if keep_week {
+            kept_weeks.insert(week);
+        }
+        if !(index == 0 || keep_day || keep_week) {
+            remove.push(backup);
+        }
+    }
+    remove
+}
+
+/// Take a backup if `schedule` says one is due, like when ats-tracking starts
+///
+/// Returns the backup that was taken, if one was.
+pub fn backup_if_due(
+    conn: &mut dyn SqliteRepository,
+    schedule: BackupSchedule,
+    retention: BackupRetention,
+) -> Result<Option<Backup>, RepositoryError> {
+    let now = time::OffsetDateTime::now_utc();
+    let now = PrimitiveDateTime::new(now.date(), now.time());
+    if schedule.is_due(&conn.get_backups()?, now) {
+        Ok(Some(conn.take_backup(retention)?))
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(not(feature = "mysql"))]
+mod sqlite {
+    use std::{
+        ffi::OsString,
+        fs, io,
+        path::{Path, PathBuf},
+    };
+
+    use rusqlite::{Connection, DatabaseName, OpenFlags};
+    use time::{
+        format_description::BorrowedFormatItem, macros::format_description, OffsetDateTime,
+        PrimitiveDateTime,
+    };
+
+    use super::{backups_to_remove, Backup, BackupRetention, Reverse};
+    use crate::{
+        backend_connection::{db_file_path, replace_db_file, MIGRATIONS},
+        encryption,
+        job_application_repository::RepositoryError,
+    };
+
+    /// When a backup was taken, as it is written in its file name
+    ///
+    /// Colons aren't allowed in file names on Windows, so the time uses dashes instead.
+    const TAKEN_AT_FORMAT: &[BorrowedFormatItem] =
+        format_description!("[year]-[month]-[day]T[hour]-[minute]-[second].[subsecond digits:3]");
+
+    /// Where the backups of the database at `db_path` are saved
+    ///
+    /// For `ats-tracking.db3`, this is the directory `ats-tracking-backups` next to it.
+    fn backup_dir(db_path: &Path) -> PathBuf {
+        let mut dir_name = db_path.file_stem().unwrap_or_default().to_owned();
+        dir_name.push("-backups");
+        db_path.with_file_name(dir_name)
+    }
+
+    /// The name of a backup of the database at `db_path`, taken at `taken_at`, like `ats-tracking-2025-01-31T18-30-00.000.db3`
+    fn backup_file_name(
+        db_path: &Path,
+        taken_at: PrimitiveDateTime,
+    ) -> Result<OsString, RepositoryError> {
+        let mut file_name = db_path.file_stem().unwrap_or_default().to_owned();
+        file_name.push("-");
+        file_name.push(
+            taken_at
+                .format(TAKEN_AT_FORMAT)
+                .map_err(|error| RepositoryError::Backend(Box::new(error)))?,
+        );
+        if let Some(extension) = db_path.extension() {
+            file_name.push(".");
+            file_name.push(extension);
+        }
+        Ok(file_name)
+    }
+
+    /// When the backup of the database at `db_path` named `file_name` was taken, or `None` if it isn't named like one
+    fn parse_backup_file_name(db_path: &Path, file_name: &str) -> Option<PrimitiveDateTime> {
+        let stem = db_path.file_stem()?.to_str()?;
+        let taken_at = file_name.strip_prefix(stem)?.strip_prefix('-')?;
+        let taken_at = match db_path.extension() {
+            Some(extension) => taken_at
+                .strip_suffix(extension.to_str()?)?
+                .strip_suffix('.')?,
+            None => taken_at,
+        };
+        PrimitiveDateTime::parse(taken_at, TAKEN_AT_FORMAT).ok()
+    }
+
+    /// Save a snapshot of the database `conn` has open in its backups directory, then remove the old backups `retention` doesn't keep
+    pub fn take_backup(
+        conn: &Connection,
+        retention: BackupRetention,
+    ) -> Result<Backup, RepositoryError> {
+        let backup = snapshot(conn)?;
+        for old_backup in backups_to_remove(&get_backups(conn)?, retention) {
+            fs::remove_file(&old_backup.path)?;
+        }
+        Ok(backup)
+    }
+
+    /// Save a snapshot of the database `conn` has open in its backups directory
+    ///
+    /// SQLCipher can't use the online backup API for encrypted databases, so they are copied with `VACUUM INTO` instead,
+    /// which keeps the copy encrypted with the same passphrase.
+    fn snapshot(conn: &Connection) -> Result<Backup, RepositoryError> {
+        let db_path = db_file_path(conn)?;
+        let dir = backup_dir(&db_path);
+        fs::create_dir_all(&dir)?;
+
+        let now = OffsetDateTime::now_utc();
+        // Only milliseconds are written in the file name
+        let taken_at = PrimitiveDateTime::new(
+            now.date(),
+            now.time()
+                .replace_millisecond(now.millisecond())
+                .map_err(|error| RepositoryError::Backend(Box::new(error)))?,
+        );
+        let path = dir.join(backup_file_name(&db_path, taken_at)?);
+
+        // Written under another name first, so a backup that is interrupted isn't mistaken for a complete one
+        let mut partial_path = path.clone().into_os_string();
+        partial_path.push(".partial");
+        let partial_path = PathBuf::from(partial_path);
+        let encrypted = encryption::is_encrypted(&db_path)?;
+        let copied = if encrypted {
+            let partial_path_str = partial_path.to_str().ok_or_else(|| {
+                RepositoryError::Validation(format!(
+                    "Unable to back up the database because {} is not valid UTF-8",
+                    partial_path.display()
+                ))
+            })?;
+            conn.execute("VACUUM INTO ?1", (partial_path_str,))
+                .map(|_| ())
+        } else {
+            conn.backup(DatabaseName::Main, &partial_path, None)
+        };
+        if let Err(error) = copied {
+            _ = fs::remove_file(&partial_path);
+            return Err(error.into());
+        }
+        fs::rename(&partial_path, &path)?;
+
+        Ok(Backup {
+            size: fs::metadata(&path)?.len(),
+            path,
+            taken_at,
+            encrypted,
+        })
+    }
+
+    /// Every backup of the database `conn` has open, newest first
+    pub fn get_backups(conn: &Connection) -> Result<Vec<Backup>, RepositoryError> {
+        let db_path = db_file_path(conn)?;
+        let entries = match fs::read_dir(backup_dir(&db_path)) {
+            Ok(entries) => entries,
+            // No backups have been taken yet
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut backups = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let Some(taken_at) = entry
+                .file_name()
+                .to_str()
+                .and_then(|file_name| parse_backup_file_name(&db_path, file_name))
+            else {
+                continue;
+            };
+            let path = entry.path();
+            backups.push(Backup {
+                size: entry.metadata()?.len(),
+                encrypted: encryption::is_encrypted(&path)?,
+                path,
+                taken_at,
+            });
+        }
+        backups.sort_by_key(|backup| Reverse(backup.taken_at));
+        Ok(backups)
+    }
+
+    /// Replace the database `conn` has open with a backup, after checking that the backup isn't damaged
+    ///
+    /// `file` is the name of a backup in the backups directory, or the path to one anywhere else.
+    /// The database is backed up first, so the restore can be undone by restoring that backup, which is returned.
+    /// `current_passphrase` is what the database is encrypted with now, if it is, to open it again if the restore fails.
+    pub fn restore_backup(
+        conn: &mut Connection,
+        file: &Path,
+        passphrase: Option<&str>,
+        current_passphrase: Option<&str>,
+    ) -> Result<Backup, RepositoryError> {
+        let db_path = db_file_path(conn)?;
+        let in_backup_dir = backup_dir(&db_path).join(file);
+        let path = if file.components().count() == 1 && in_backup_dir.exists() {
+            in_backup_dir
+        } else if file.exists() {
+            file.to_path_buf()
+        } else {
+            return Err(RepositoryError::NotFound(format!(
+                "No backup named {}",
+                file.display()
+            )));
+        };
+
+        let encrypted = encryption::is_encrypted(&path)?;
+        let passphrase = passphrase.filter(|_| encrypted);
+        check_backup(&path, encrypted, passphrase)?;
+
+        // Copied next to the database first, since moving a file into place can't be interrupted halfway
+        let mut restoring_path = db_path.clone().into_os_string();
+        restoring_path.push(".restoring");
+        let restoring_path = PathBuf::from(restoring_path);
+        fs::copy(&path, &restoring_path)?;
+
+        let before_restore = match snapshot(conn) {
+            Ok(backup) => backup,
+            Err(error) => {
+                _ = fs::remove_file(&restoring_path);
+                return Err(error);
+            }
+        };
+        replace_db_file(conn, &restoring_path, passphrase, current_passphrase)?;
+        Ok(before_restore)
+    }
+
+    /// Make sure the backup at `path` can be restored: it opens, passes SQLite's integrity check, and is an ats-tracking database this version understands
+    fn check_backup(
+        path: &Path,
+        encrypted: bool,
+        passphrase: Option<&str>,
+    ) -> Result<(), RepositoryError> {
+        let name = path.display();
+        let conn = match (encrypted, passphrase) {
+            (false, _) => Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?,
+            #[cfg(feature = "sqlcipher")]
+            (true, Some(passphrase)) => encryption::open_encrypted(path, passphrase)?,
+            #[cfg(not(feature = "sqlcipher"))]
+            (true, Some(_)) => {
+                return Err(RepositoryError::Validation(format!(
+                    "{name} is encrypted. Build ats-tracking with the sqlcipher feature to restore it"
+                )))
+            }
+            (true, None) => {
+                return Err(RepositoryError::Validation(format!(
+                    "{name} is either encrypted or not a database. Restoring an encrypted backup needs the passphrase the database had when it was taken"
+                )))
+            }
+        };
+
+        let problems = conn
+            .prepare("PRAGMA integrity_check")
+            .and_then(|mut stmt| {
+                stmt.query_map((), |row| row.get::<_, String>(0))?
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|error| match error.sqlite_error_code() {
+                Some(rusqlite::ErrorCode::NotADatabase) if encrypted => {
+                    RepositoryError::Validation(format!("Wrong passphrase for {name}"))
+                }
+                Some(rusqlite::ErrorCode::NotADatabase) => {
+                    RepositoryError::Validation(format!("{name} is not a database"))
+                }
+                // Some damage is too bad for the integrity check to even list
+                Some(rusqlite::ErrorCode::DatabaseCorrupt) => RepositoryError::Validation(format!(
+                    "{name} is damaged, so it wasn't restored: {error}"
+                )),
+                _ => error.into(),
+            })?;
+        if problems != ["ok"] {
+            return Err(RepositoryError::Validation(format!(
+                "{name} is damaged, so it wasn't restored: {}",
+                problems.join("; ")
+            )));
+        }
+
+        let is_ats_tracking: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_schema WHERE type = 'table' AND name = 'job_applications')",
+            (),
+            |row| row.get(0),
+        )?;
+        if !is_ats_tracking {
+            return Err(RepositoryError::Validation(format!(
+                "{name} is not an ats-tracking database"
+            )));
+        }
+        // Older backups are fine, since they are upgraded when they are opened
+        let applied_migrations: usize =
+            conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if applied_migrations > MIGRATIONS.len() {
+            return Err(RepositoryError::Validation(format!(
+                "{name} was made by a newer version of ats-tracking"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn backup_at(taken_at: PrimitiveDateTime) -> Backup {
+        Backup {
+            path: PathBuf::from(format!("{taken_at}.db3")),
+            taken_at,
+            size: 0,
+            encrypted: false,
+        }
+    }
+
+    #[test]
+    fn test_backups_to_remove() {
+        let backups: Vec<Backup> = [
+            // Wednesday, with two backups that day
+            datetime!(2025-01-15 09:00),
+            datetime!(2025-01-15 18:00),
+            datetime!(2025-01-14 12:00),
+            // Sunday of the week before
+            datetime!(2025-01-12 12:00),
+            datetime!(2025-01-10 12:00),
+            datetime!(2025-01-03 12:00),
+            datetime!(2024-12-27 12:00),
+        ]
+        .into_iter()
+        .map(backup_at)
+        .collect();
+        let removed = |retention| -> Vec<PrimitiveDateTime> {
+            backups_to_remove(&backups, retention)
+                .into_iter()
+                .map(|backup| backup.taken_at)
+                .collect()
+        };
+
+        // The newest of each of the last two days, and the newest of each of the last three weeks
+        assert_eq!(
+            removed(BackupRetention {
+                daily: 2,
+                weekly: 3
+            }),
+            vec![
+                datetime!(2025-01-15 09:00),
+                datetime!(2025-01-10 12:00),
+                datetime!(2024-12-27 12:00),
+            ]
+        );
+        // Enough to keep one from every day
+        assert_eq!(
+            removed(BackupRetention {
+                daily: 10,
+                weekly: 0
+            }),
+            vec![datetime!(2025-01-15 09:00)]
+        );
+        // The newest is kept even when nothing should be
+        assert_eq!(
+            removed(BackupRetention {
+                daily: 0,
+                weekly: 0
+            })
+            .len(),
+            backups.len() - 1
+        );
+        assert!(backups_to_remove(&[], BackupRetention::default()).is_empty());
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[test]
+    fn test_encrypted_backups() -> Result<(), Box<dyn std::error::Error>> {
+        use rusqlite::Connection;
+
+        use crate::{encryption, ensure_schema};
+
+        let dir = tempfile::TempDir::new()?;
+        let path = dir.path().join("test_db.db3");
+        let mut conn = encryption::open_encrypted(&path, "first passphrase")?;
+        ensure_schema(&mut conn)?;
+
+        // Backups of an encrypted database are encrypted with the same passphrase
+        let backup = take_backup(&conn, BackupRetention::default())?;
+        assert!(backup.encrypted);
+        assert!(encryption::is_encrypted(&backup.path)?);
+        let backup_name = PathBuf::from(backup.file_name());
+
+        // Even after the passphrase is changed, they need the passphrase they were taken with
+        encryption::set_passphrase(
+            &mut conn,
+            Some("second passphrase"),
+            Some("first passphrase"),
+        )?;
+        for passphrase in [None, Some("second passphrase")] {
+            assert!(matches!(
+                restore_backup(
+                    &mut conn,
+                    &backup_name,
+                    passphrase,
+                    Some("second passphrase")
+                ),
+                Err(RepositoryError::Validation(_))
+            ));
+        }
+        restore_backup(
+            &mut conn,
+            &backup_name,
+            Some("first passphrase"),
+            Some("second passphrase"),
+        )?;
+        drop(conn);
+        // The restored database has the passphrase it had when the backup was taken
+        ensure_schema(&mut encryption::open_encrypted(&path, "first passphrase")?)?;
+        assert!(Connection::open(&path)?
+            .query_row("SELECT COUNT(*) FROM job_applications", (), |_| Ok(()))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_backup_schedule_is_due() {
+        let backups = vec![backup_at(datetime!(2025-01-15 09:00))];
+
+        assert!(BackupSchedule::Startup.is_due(&backups, datetime!(2025-01-15 10:00)));
+        assert!(!BackupSchedule::Daily.is_due(&backups, datetime!(2025-01-15 23:59)));
+        assert!(BackupSchedule::Daily.is_due(&backups, datetime!(2025-01-16 00:00)));
+        assert!(BackupSchedule::Daily.is_due(&[], datetime!(2025-01-15 10:00)));
+    }
+}
@@ -0,0 +1,121 @@
+//! Notice job applications that were changed somewhere else, like in the CLI while the GUI is open
+//!
+//! Every change to a job application gives it a new [JobApplication::version], so comparing the versions
+//! from one check to the next finds everything that was added, changed, or removed, for any backend.
+//! Backends that can tell when another connection changed anything skip the comparison when nothing did.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    job_application_model::JobApplication,
+    job_application_repository::{JobApplicationRepository, RepositoryError},
+};
+
+/// What changed since the last time a [ChangeWatcher] checked
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Changes {
+    /// Job applications that were added or taken out of the trash, sorted by id
+    pub added: Vec<JobApplication>,
+    /// Job applications that were already there and were changed, sorted by id
+    pub changed: Vec<JobApplication>,
+    /// The ids of job applications that were moved to the trash or permanently deleted, sorted
+    pub removed: Vec<i32>,
+}
+
+impl Changes {
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Keeps track of the saved job applications, to find which ones changed since they were last checked
+///
+/// Changes made through the same connection can be found as well, which doesn't matter for showing what is saved.
+#[derive(Debug, Clone)]
+pub struct ChangeWatcher {
+    /// From [JobApplicationRepository::data_version] at the last check
+    data_version: Option<i64>,
+    /// From [JobApplicationRepository::get_job_application_versions] at the last check
+    versions: BTreeMap<i32, i32>,
+}
+
+impl ChangeWatcher {
+    /// Start watching from the job applications saved now
+    pub fn new<C: JobApplicationRepository>(conn: &mut C) -> Result<Self, RepositoryError> {
+        Ok(Self {
+            data_version: conn.data_version()?,
+            versions: conn.get_job_application_versions()?,
+        })
+    }
+
+    /// Find what changed since the last check, or since this was made
+    pub fn check<C: JobApplicationRepository>(
+        &mut self,
+        conn: &mut C,
+    ) -> Result<Changes, RepositoryError> {
+        let data_version = conn.data_version()?;
+        if data_version.is_some() && data_version == self.data_version {
+            return Ok(Changes::default());
+        }
+
+        let versions = conn.get_job_application_versions()?;
+        let (added_ids, changed_ids, removed) = compare_versions(&self.versions, &versions);
+        // One could be removed between getting the versions and getting it, which the next check will find
+        let mut get_job_applications = |ids: Vec<i32>| -> Result<Vec<_>, RepositoryError> {
+            ids.into_iter()
+                .filter_map(|id| conn.get_job_application_by_id(id).transpose())
+                .collect()
+        };
+        let added = get_job_applications(added_ids)?;
+        let changed = get_job_applications(changed_ids)?;
+
+        self.data_version = data_version;
+        self.versions = versions;
+        Ok(Changes {
+            added,
+            changed,
+            removed,
+        })
+    }
+}
+
+/// Find the ids that are new, the ids that have a different version, and the ids that are gone
+fn compare_versions(
+    before: &BTreeMap<i32, i32>,
+    after: &BTreeMap<i32, i32>,
+) -> (Vec<i32>, Vec<i32>, Vec<i32>) {
+    let (added, changed) = after
+        .iter()
+        .filter(|(id, version)| before.get(id) != Some(version))
+        .map(|(id, _)| *id)
+        .partition(|id| !before.contains_key(id));
+    let removed = before
+        .keys()
+        .filter(|id| !after.contains_key(id))
+        .copied()
+        .collect();
+    (added, changed, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        let before = BTreeMap::from([(1, 1), (2, 4), (3, 1)]);
+        let after = BTreeMap::from([(1, 1), (2, 5), (4, 1)]);
+
+        let none = Vec::<i32>::new;
+        assert_eq!(
+            compare_versions(&before, &after),
+            (vec![4], vec![2], vec![3])
+        );
+        assert_eq!(compare_versions(&after, &after), (none(), none(), none()));
+        assert_eq!(
+            compare_versions(&BTreeMap::new(), &before),
+            (vec![1, 2, 3], none(), none())
+        );
+    }
+}
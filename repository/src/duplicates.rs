@@ -0,0 +1,373 @@
+//! Find job applications that were likely entered twice, and combine them
+//!
+//! A job application is a likely duplicate of another if both were sent through the same website,
+//! or if they are for the same company and a similar job title, sent close enough together.
+
+use std::collections::BTreeSet;
+
+use crate::job_application_model::{HumanResponse, JobApplication, NewJobApplication};
+
+/// How many days apart two job applications for the same job can be sent to still be duplicates, if `DUPLICATE_WINDOW_DAYS` isn't set
+pub const DEFAULT_DUPLICATE_WINDOW_DAYS: u32 = 30;
+
+/// Words at the end of a company name that don't tell companies apart, like "Inc"
+const COMPANY_SUFFIXES: [&str; 14] = [
+    "inc",
+    "incorporated",
+    "llc",
+    "ltd",
+    "limited",
+    "corp",
+    "corporation",
+    "co",
+    "company",
+    "gmbh",
+    "plc",
+    "ag",
+    "sa",
+    "bv",
+];
+
+/// Short forms in job titles, and the word they are short for
+const TITLE_ABBREVIATIONS: [(&str, &str); 8] = [
+    ("sr", "senior"),
+    ("jr", "junior"),
+    ("eng", "engineer"),
+    ("engr", "engineer"),
+    ("dev", "developer"),
+    ("mgr", "manager"),
+    ("assoc", "associate"),
+    ("swe", "software"),
+];
+
+/// Query parameters that only track where a visitor came from, so they are left out when comparing websites
+const TRACKING_PARAMETERS: [&str; 6] = ["ref", "source", "src", "gh_src", "lever-source", "trk"];
+
+/// Find every job application in `existing` that `job_application` is likely a duplicate of
+///
+/// `window_days` is how many days apart the application dates can be for job applications
+/// to the same company with a similar job title. Job applications sent through the same website are always duplicates.
+pub fn find_likely_duplicates<'a>(
+    job_application: &NewJobApplication,
+    existing: &'a [JobApplication],
+    window_days: u32,
+) -> Vec<&'a JobApplication> {
+    let website = job_application
+        .application_website
+        .as_deref()
+        .map(canonical_website);
+    let company = normalize_company(&job_application.company);
+
+    existing
+        .iter()
+        .filter(|other| {
+            let same_website = website.is_some()
+                && other.application_website.as_deref().map(canonical_website) == website;
+            let same_job = !company.is_empty()
+                && normalize_company(&other.company) == company
+                && similar_job_titles(&job_application.job_title, &other.job_title)
+                && (job_application.application_date - other.application_date)
+                    .whole_days()
+                    .unsigned_abs()
+                    <= u64::from(window_days);
+            same_website || same_job
+        })
+        .collect()
+}
+
+/// Put a company name in a form that is the same for every way of writing it, like "Acme, Inc." and "acme"
+pub fn normalize_company(company: &str) -> String {
+    let mut words = words(company);
+    if words.first().is_some_and(|word| word == "the") {
+        words.remove(0);
+    }
+    while words.len() > 1
+        && words
+            .last()
+            .is_some_and(|word| COMPANY_SUFFIXES.contains(&word.as_str()))
+    {
+        words.pop();
+    }
+    words.concat()
+}
+
+/// Check whether two job titles are likely for the same job, like "Sr. Software Engineer" and "Senior Software Engineer (Remote)"
+///
+/// The titles are similar if one has every word of the other, or if they share most of their words.
+pub fn similar_job_titles(job_title: &str, other: &str) -> bool {
+    let title_words = |job_title: &str| -> BTreeSet<String> {
+        words(job_title)
+            .into_iter()
+            .map(|word| {
+                TITLE_ABBREVIATIONS
+                    .iter()
+                    .find(|(short, _)| *short == word)
+                    .map_or(word, |(_, long)| (*long).to_owned())
+            })
+            .collect()
+    };
+    let job_title = title_words(job_title);
+    let other = title_words(other);
+    if job_title.is_empty() || other.is_empty() {
+        return false;
+    }
+
+    let shared = job_title.intersection(&other).count();
+    let total = job_title.union(&other).count();
+    shared == job_title.len().min(other.len()) || shared * 3 >= total * 2
+}
+
+/// Put a website in a form that is the same for every way of writing the same address
+///
+/// The scheme, `www.`, a trailing `/`, the fragment, and tracking parameters like `utm_source` are left out, and the host is lowercase.
+pub fn canonical_website(website: &str) -> String {
+    let website = website.trim();
+    let address = website
+        .split_once("://")
+        .map_or(website, |(_, address)| address);
+    let address = address.split('#').next().unwrap_or_default();
+    let (address, query) = address.split_once('?').unwrap_or((address, ""));
+    let (host, path) = address.split_once('/').unwrap_or((address, ""));
+
+    let host = host.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let path = path.trim_end_matches('/');
+    let mut parameters: Vec<&str> = query
+        .split('&')
+        .filter(|parameter| {
+            let name = parameter.split('=').next().unwrap_or_default();
+            !name.is_empty()
+                && !name.to_lowercase().starts_with("utm_")
+                && !TRACKING_PARAMETERS.contains(&name.to_lowercase().as_str())
+        })
+        .collect();
+    parameters.sort_unstable();
+
+    let mut canonical = host.to_owned();
+    if !path.is_empty() {
+        canonical.push('/');
+        canonical.push_str(path);
+    }
+    if !parameters.is_empty() {
+        canonical.push('?');
+        canonical.push_str(&parameters.join("&"));
+    }
+    canonical
+}
+
+/// Combine two job applications for the same job into `keep`
+///
+/// Fields `keep` doesn't have are filled in from `merged`, notes from both are kept, and the response is whichever got further.
+/// The application date is the earlier of the two, since that is when the job was first applied to.
+pub fn merge_job_applications(keep: &JobApplication, merged: &JobApplication) -> JobApplication {
+    let merged = merged.clone();
+    let (human_response, human_response_date) =
+        if progress(merged.human_response) > progress(keep.human_response) {
+            (merged.human_response, merged.human_response_date)
+        } else {
+            (keep.human_response, keep.human_response_date)
+        };
+    let notes = match (&keep.notes, merged.notes) {
+        (Some(notes), Some(merged_notes)) if *notes != merged_notes => {
+            Some(format!("{notes}\n\n{merged_notes}"))
+        }
+        (notes, merged_notes) => notes.clone().or(merged_notes),
+    };
+    let mut custom_fields = merged.custom_fields;
+    custom_fields.extend(keep.custom_fields.clone());
+
+    JobApplication {
+        id: keep.id,
+        version: keep.version,
+        source: keep.source.clone(),
+        company: keep.company.clone(),
+        job_title: keep.job_title.clone(),
+        application_date: keep.application_date.min(merged.application_date),
+        time_investment: keep.time_investment.or(merged.time_investment),
+        human_response,
+        human_response_date,
+        application_website: keep
+            .application_website
+            .clone()
+            .or(merged.application_website),
+        notes,
+        salary_min: keep.salary_min.or(merged.salary_min),
+        salary_max: keep.salary_max.or(merged.salary_max),
+        salary_currency: keep.salary_currency.clone().or(merged.salary_currency),
+        pay_period: keep.pay_period.or(merged.pay_period),
+        equity: keep.equity.clone().or(merged.equity),
+        bonus: keep.bonus.clone().or(merged.bonus),
+        city: keep.city.clone().or(merged.city),
+        region: keep.region.clone().or(merged.region),
+        country: keep.country.clone().or(merged.country),
+        work_arrangement: keep.work_arrangement.or(merged.work_arrangement),
+        relocation: keep.relocation.or(merged.relocation),
+        visa_sponsorship: keep.visa_sponsorship.or(merged.visa_sponsorship),
+        custom_fields,
+    }
+}
+
+/// How far along the hiring process a response is
+fn progress(human_response: HumanResponse) -> u8 {
+    match human_response {
+        HumanResponse::None => 0,
+        HumanResponse::Rejection => 1,
+        HumanResponse::InterviewRequest => 2,
+        HumanResponse::InterviewedThenRejected => 3,
+        HumanResponse::JobOffer => 4,
+    }
+}
+
+/// Split text into lowercase words, dropping punctuation
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{Date, Month};
+
+    use super::*;
+    use crate::job_application_model::{CustomFieldValue, CustomFields};
+
+    fn job_application(id: i32, company: &str, job_title: &str, day: u8) -> JobApplication {
+        NewJobApplication::builder("LinkedIn", company, job_title)
+            .application_date(Date::from_calendar_date(2025, Month::March, day).unwrap())
+            .build()
+            .with_id(id)
+    }
+
+    #[test]
+    fn test_normalize_company() {
+        assert_eq!(normalize_company("Acme, Inc."), "acme");
+        assert_eq!(normalize_company("The ACME Corp"), "acme");
+        assert_eq!(normalize_company("Acme Widgets Co. Ltd"), "acmewidgets");
+        assert_eq!(normalize_company("Company"), "company");
+    }
+
+    #[test]
+    fn test_similar_job_titles() {
+        assert!(similar_job_titles(
+            "Sr. Software Engineer",
+            "Senior Software Engineer (Remote)"
+        ));
+        assert!(similar_job_titles("Backend Developer", "backend dev"));
+        assert!(!similar_job_titles("Software Engineer", "Product Manager"));
+        assert!(!similar_job_titles(
+            "Senior Software Engineer",
+            "Engineering Manager"
+        ));
+    }
+
+    #[test]
+    fn test_canonical_website() {
+        assert_eq!(
+            canonical_website("https://www.Example.com/jobs/1/?utm_source=linkedin&id=2#apply"),
+            "example.com/jobs/1?id=2"
+        );
+        assert_eq!(
+            canonical_website("example.com/jobs/1"),
+            canonical_website("http://EXAMPLE.com/jobs/1/")
+        );
+        assert_ne!(
+            canonical_website("example.com/jobs/1"),
+            canonical_website("example.com/jobs/2")
+        );
+    }
+
+    #[test]
+    fn test_find_likely_duplicates() {
+        let existing = vec![
+            job_application(1, "Acme Inc", "Senior Software Engineer", 1),
+            job_application(2, "Acme Inc", "Product Manager", 1),
+            job_application(3, "Other", "Designer", 1),
+        ];
+        let new_job_application =
+            NewJobApplication::from(job_application(0, "ACME", "Sr Software Engineer", 20));
+
+        let ids = |duplicates: Vec<&JobApplication>| {
+            duplicates
+                .into_iter()
+                .map(|duplicate| duplicate.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ids(find_likely_duplicates(&new_job_application, &existing, 30)),
+            vec![1]
+        );
+        assert_eq!(
+            ids(find_likely_duplicates(&new_job_application, &existing, 7)),
+            Vec::<i32>::new(),
+            "Too far apart"
+        );
+
+        let same_website = NewJobApplication {
+            application_website: Some("https://designjobs.example.com/apply".to_owned()),
+            ..NewJobApplication::from(job_application(0, "Someone else", "Anything", 1))
+        };
+        let existing = vec![JobApplication {
+            application_website: Some("designjobs.example.com/apply/".to_owned()),
+            ..existing[2].clone()
+        }];
+        assert_eq!(
+            ids(find_likely_duplicates(&same_website, &existing, 0)),
+            vec![3]
+        );
+    }
+
+    #[test]
+    fn test_merge_job_applications() {
+        let keep = JobApplication {
+            notes: Some("Applied online".to_owned()),
+            human_response: HumanResponse::Rejection,
+            human_response_date: Some(Date::from_calendar_date(2025, Month::March, 15).unwrap()),
+            custom_fields: CustomFields::from([(
+                "Agency".to_owned(),
+                CustomFieldValue::Text("Hays".to_owned()),
+            )]),
+            ..job_application(1, "Acme", "Engineer", 10)
+        };
+        let merged = JobApplication {
+            notes: Some("Recruiter reached out".to_owned()),
+            human_response: HumanResponse::InterviewRequest,
+            human_response_date: Some(Date::from_calendar_date(2025, Month::March, 12).unwrap()),
+            city: Some("Austin".to_owned()),
+            custom_fields: CustomFields::from([
+                (
+                    "Agency".to_owned(),
+                    CustomFieldValue::Text("Other".to_owned()),
+                ),
+                ("Req ID".to_owned(), CustomFieldValue::Number(7.0)),
+            ]),
+            ..job_application(2, "Acme Inc", "Software Engineer", 5)
+        };
+
+        let combined = merge_job_applications(&keep, &merged);
+        assert_eq!(combined.id, 1);
+        assert_eq!(combined.company, "Acme");
+        assert_eq!(
+            combined.application_date,
+            Date::from_calendar_date(2025, Month::March, 5).unwrap()
+        );
+        assert_eq!(combined.human_response, HumanResponse::InterviewRequest);
+        assert_eq!(combined.human_response_date, merged.human_response_date);
+        assert_eq!(
+            combined.notes.as_deref(),
+            Some("Applied online\n\nRecruiter reached out")
+        );
+        assert_eq!(combined.city.as_deref(), Some("Austin"));
+        assert_eq!(
+            combined.custom_fields,
+            CustomFields::from([
+                (
+                    "Agency".to_owned(),
+                    CustomFieldValue::Text("Hays".to_owned())
+                ),
+                ("Req ID".to_owned(), CustomFieldValue::Number(7.0)),
+            ])
+        );
+    }
+}
@@ -0,0 +1,307 @@
+//! Encrypting the SQLite database file with SQLCipher, when built with the `sqlcipher` feature
+//!
+//! SQLCipher encrypts every page of the file, including the header, so an encrypted database is told apart from a plain one
+//! by the header every plain SQLite database starts with.
+
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+#[cfg(feature = "sqlcipher")]
+use std::{ffi::OsString, fs, path::PathBuf};
+
+#[cfg(feature = "sqlcipher")]
+use rusqlite::{Connection, DatabaseName, ErrorCode};
+
+#[cfg(feature = "sqlcipher")]
+use crate::{
+    backend_connection::{db_file_path, open_db, replace_db_file},
+    job_application_repository::RepositoryError,
+};
+
+/// Every plain SQLite database file starts with this
+const PLAIN_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// Whether the SQLite database file at `path`, or a backup of one, is encrypted
+///
+/// A file that doesn't exist or is empty isn't, since a new database starts out as plain text.
+pub fn is_encrypted(path: &Path) -> io::Result<bool> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(error) => return Err(error),
+    };
+    let mut header = Vec::with_capacity(PLAIN_HEADER.len());
+    file.take(PLAIN_HEADER.len() as u64)
+        .read_to_end(&mut header)?;
+    Ok(!header.is_empty() && header != PLAIN_HEADER)
+}
+
+/// Open the encrypted database at `path` with its passphrase
+///
+/// SQLCipher doesn't check the passphrase until the database is read, which fails in a way [is_wrong_passphrase] recognizes.
+#[cfg(feature = "sqlcipher")]
+pub fn open_encrypted(path: &Path, passphrase: &str) -> Result<Connection, rusqlite::Error> {
+    let conn = Connection::open(path)?;
+    conn.pragma_update(None, "key", passphrase)?;
+    Ok(conn)
+}
+
+/// Whether reading an encrypted database failed because its passphrase was wrong
+#[cfg(feature = "sqlcipher")]
+pub fn is_wrong_passphrase(error: &rusqlite::Error) -> bool {
+    error.sqlite_error_code() == Some(ErrorCode::NotADatabase)
+}
+
+/// Encrypt the database `conn` is open on with `passphrase`, or store it as plain text if `passphrase` is `None`
+///
+/// If the database is already encrypted, `current_passphrase` has to be the passphrase it has now, and the passphrase is changed in place.
+/// Otherwise, the database is copied into a converted file that replaces it, and `conn` is opened on that file instead.
+#[cfg(feature = "sqlcipher")]
+pub fn set_passphrase(
+    conn: &mut Connection,
+    passphrase: Option<&str>,
+    current_passphrase: Option<&str>,
+) -> Result<(), RepositoryError> {
+    if passphrase.is_some_and(str::is_empty) {
+        return Err(RepositoryError::Validation(
+            "The passphrase can't be empty".to_owned(),
+        ));
+    }
+    let path = db_file_path(conn)?;
+    let encrypted = is_encrypted(&path)?;
+    // Having the database open isn't enough to change its passphrase, so nobody else at the computer can
+    if encrypted {
+        drop(open_db(&path, current_passphrase)?);
+    }
+
+    match (encrypted, passphrase) {
+        (true, Some(passphrase)) => Ok(conn.pragma_update(None, "rekey", passphrase)?),
+        (false, None) => Ok(()),
+        // SQLCipher treats an empty key as no encryption
+        (_, passphrase) => convert(
+            conn,
+            &path,
+            passphrase.unwrap_or_default(),
+            current_passphrase,
+        ),
+    }
+}
+
+/// Copy the database `conn` is open on into a file encrypted with `passphrase` (or plain, if it is empty), then replace it with the copy
+///
+/// `current_passphrase` is what the database is encrypted with now, if it is, to open it again if it can't be replaced.
+#[cfg(feature = "sqlcipher")]
+fn convert(
+    conn: &mut Connection,
+    path: &Path,
+    passphrase: &str,
+    current_passphrase: Option<&str>,
+) -> Result<(), RepositoryError> {
+    let mut converted_path = OsString::from(path);
+    converted_path.push(".converting");
+    let converted_path = PathBuf::from(converted_path);
+
+    // This would only be left over from a conversion that was interrupted, before it replaced the database
+    match fs::remove_file(&converted_path) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error.into()),
+        _ => {}
+    }
+
+    let converted_path_str = converted_path.to_str().ok_or_else(|| {
+        RepositoryError::Validation(format!(
+            "Unable to convert the database because {} is not valid UTF-8",
+            converted_path.display()
+        ))
+    })?;
+    conn.execute(
+        "ATTACH DATABASE ?1 AS converted KEY ?2",
+        (converted_path_str, passphrase),
+    )?;
+    // sqlcipher_export copies the tables, indexes, and triggers, but not which migrations have been applied
+    let exported = conn
+        .query_row("SELECT sqlcipher_export('converted')", (), |_| Ok(()))
+        .and_then(|_| conn.pragma_query_value(None, "user_version", |row| row.get::<_, i64>(0)))
+        .and_then(|user_version| {
+            conn.pragma_update(
+                Some(DatabaseName::Attached("converted")),
+                "user_version",
+                user_version,
+            )
+        });
+    conn.execute("DETACH DATABASE converted", ())?;
+    if let Err(error) = exported {
+        _ = fs::remove_file(&converted_path);
+        return Err(error.into());
+    }
+
+    replace_db_file(
+        conn,
+        &converted_path,
+        Some(passphrase).filter(|passphrase| !passphrase.is_empty()),
+        current_passphrase,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    #[cfg(feature = "sqlcipher")]
+    use crate::ensure_schema;
+
+    #[test]
+    fn test_is_encrypted() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("test_db.db3");
+
+        // A database that doesn't exist yet will be made as plain text
+        assert!(!is_encrypted(&path)?);
+        fs::write(&path, b"")?;
+        assert!(!is_encrypted(&path)?);
+
+        rusqlite::Connection::open(&path)?.execute("CREATE TABLE test (id INTEGER)", ())?;
+        assert!(!is_encrypted(&path)?);
+
+        // Anything else is what an encrypted database looks like
+        fs::write(&path, [0x5a; 4096])?;
+        assert!(is_encrypted(&path)?);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[test]
+    fn test_set_passphrase() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("test_db.db3");
+        let count_rows = |conn: &Connection| {
+            conn.query_row("SELECT COUNT(*) FROM job_applications", (), |row| {
+                row.get::<_, i32>(0)
+            })
+        };
+
+        let mut conn = Connection::open(&path)?;
+        ensure_schema(&mut conn)?;
+        conn.execute(
+            "INSERT INTO job_applications (source, company, job_title, application_date) \
+            VALUES ('test source', 'test company', 'test job title', '2000-01-01')",
+            (),
+        )?;
+        let user_version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        // Encrypting keeps everything, including which migrations were applied and the full-text index
+        set_passphrase(&mut conn, Some("first passphrase"), None)?;
+        assert!(is_encrypted(&path)?);
+        assert_eq!(count_rows(&conn)?, 1);
+        assert_eq!(
+            conn.pragma_query_value(None, "user_version", |row| row.get::<_, i64>(0))?,
+            user_version
+        );
+        assert_eq!(
+            conn.query_row(
+                "SELECT COUNT(*) FROM job_applications_fts WHERE job_applications_fts MATCH 'company'",
+                (),
+                |row| row.get::<_, i32>(0)
+            )?,
+            1
+        );
+        drop(conn);
+
+        // It can only be opened with the passphrase
+        assert!(is_wrong_passphrase(
+            &count_rows(&open_encrypted(&path, "wrong passphrase")?).unwrap_err()
+        ));
+        let mut conn = open_encrypted(&path, "first passphrase")?;
+        assert_eq!(count_rows(&conn)?, 1);
+
+        // Changing the passphrase needs the current one
+        for current_passphrase in [None, Some("wrong passphrase")] {
+            assert!(matches!(
+                set_passphrase(&mut conn, Some("second passphrase"), current_passphrase),
+                Err(RepositoryError::Validation(_))
+            ));
+        }
+        assert!(matches!(
+            set_passphrase(&mut conn, None, Some("wrong passphrase")),
+            Err(RepositoryError::Validation(_))
+        ));
+        assert!(is_encrypted(&path)?);
+        assert_eq!(count_rows(&conn)?, 1);
+
+        // Changing the passphrase keeps it encrypted
+        set_passphrase(
+            &mut conn,
+            Some("second passphrase"),
+            Some("first passphrase"),
+        )?;
+        drop(conn);
+        assert!(is_wrong_passphrase(
+            &count_rows(&open_encrypted(&path, "first passphrase")?).unwrap_err()
+        ));
+        let mut conn = open_encrypted(&path, "second passphrase")?;
+
+        // Decrypting makes it a plain database again
+        set_passphrase(&mut conn, None, Some("second passphrase"))?;
+        assert!(!is_encrypted(&path)?);
+        assert_eq!(count_rows(&Connection::open(&path)?)?, 1);
+        assert_eq!(count_rows(&conn)?, 1);
+
+        assert!(matches!(
+            set_passphrase(&mut conn, Some(""), None),
+            Err(RepositoryError::Validation(_))
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[test]
+    fn test_set_passphrase_failure() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("test_db.db3");
+        let count_rows = |conn: &Connection| {
+            conn.query_row("SELECT COUNT(*) FROM job_applications", (), |row| {
+                row.get::<_, i32>(0)
+            })
+        };
+        // The database can't be set aside while it is replaced, so converting it fails after it is closed
+        let set_aside = dir.path().join("test_db.db3.replaced");
+        let block_replacing = || -> io::Result<()> {
+            fs::create_dir(&set_aside)?;
+            fs::write(set_aside.join("in the way"), "")
+        };
+
+        let mut conn = Connection::open(&path)?;
+        ensure_schema(&mut conn)?;
+        conn.execute(
+            "INSERT INTO job_applications (source, company, job_title, application_date) \
+            VALUES ('test source', 'test company', 'test job title', '2000-01-01')",
+            (),
+        )?;
+
+        // Encrypting a plain database that can't be replaced leaves it open as it was
+        block_replacing()?;
+        assert!(set_passphrase(&mut conn, Some("passphrase"), None).is_err());
+        assert!(!is_encrypted(&path)?);
+        assert_eq!(db_file_path(&conn)?, path);
+        assert_eq!(count_rows(&conn)?, 1);
+
+        // Decrypting does too, opening it again with the passphrase it still has
+        fs::remove_dir_all(&set_aside)?;
+        set_passphrase(&mut conn, Some("passphrase"), None)?;
+        block_replacing()?;
+        assert!(set_passphrase(&mut conn, None, Some("passphrase")).is_err());
+        assert!(is_encrypted(&path)?);
+        assert_eq!(db_file_path(&conn)?, path);
+        assert_eq!(count_rows(&conn)?, 1);
+        assert!(!dir.path().join("test_db.db3.converting").exists());
+
+        Ok(())
+    }
+}
@@ -1,6 +1,11 @@
-use std::fmt::{Debug, Display};
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Display},
+    str::FromStr,
+};
 
-use time::{Date, Duration};
+use sha2::{Digest as _, Sha256};
+use time::{macros::format_description, Date, Duration, PrimitiveDateTime};
 
 #[cfg(feature = "mysql")]
 use mysql::prelude::FromRow;
@@ -12,14 +17,19 @@ mod mysql_backend;
 /// Implementation with an sqlite backend
 #[cfg(not(feature = "mysql"))]
 mod sqlite_backend;
+#[cfg(not(feature = "mysql"))]
+pub(crate) use sqlite_backend::compress_text;
 
 /// A row in the job application table
-#[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "mysql", derive(FromRow))]
-#[cfg_attr(feature = "mysql", mysql(table_name = "job_applications"))]
+#[derive(Debug, Clone, PartialEq)]
 pub struct JobApplication {
     /// The table primary key
     pub id: i32,
+    /// Goes up by one every time the job application is changed, starting at [JobApplication::FIRST_VERSION]
+    ///
+    /// [JobApplicationRepository::update_job_application](crate::job_application_repository::JobApplicationRepository::update_job_application)
+    /// only saves a job application if this is still the saved version, so changes made somewhere else in the meantime aren't lost.
+    pub version: i32,
     /// LinkedIn, Indeed, referral, etc
     pub source: String,
     /// The company that is hiring
@@ -38,8 +48,949 @@ pub struct JobApplication {
     pub application_website: Option<String>,
     /// Notes on anything notable about the application process or company
     pub notes: Option<String>,
+    /// The bottom of the posted salary range
+    pub salary_min: Option<f64>,
+    /// The top of the posted salary range. This is the same as `salary_min` if only one number was posted.
+    pub salary_max: Option<f64>,
+    /// Currency code of the posted salary, like USD
+    pub salary_currency: Option<String>,
+    /// How often the posted salary is paid
+    pub pay_period: Option<PayPeriod>,
+    /// Posted equity, like "0.1% - 0.25%" or "RSUs"
+    pub equity: Option<String>,
+    /// Posted bonus, like "10% target"
+    pub bonus: Option<String>,
+    /// The city the job is in
+    pub city: Option<String>,
+    /// The state, province, or other region the job is in
+    pub region: Option<String>,
+    /// The country the job is in
+    pub country: Option<String>,
+    /// Whether the job is remote, hybrid, or onsite
+    pub work_arrangement: Option<WorkArrangement>,
+    /// Whether the company helps with relocating. `None` if unknown.
+    pub relocation: Option<bool>,
+    /// Whether the company sponsors work visas. `None` if unknown.
+    pub visa_sponsorship: Option<bool>,
+    /// Values for the user-defined [CustomField]s this job application has a value for
+    pub custom_fields: CustomFields,
+}
+
+impl JobApplication {
+    /// The version of a job application that was just inserted
+    pub const FIRST_VERSION: i32 = 1;
+
+    /// The city, region, and country that are set, separated by commas, like "Austin, TX, USA"
+    pub fn location(&self) -> String {
+        [&self.city, &self.region, &self.country]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|part| !part.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// A job application that hasn't been saved yet, so it doesn't have an id
+///
+/// Saving one with [JobApplicationRepository::insert_job_application](crate::job_application_repository::JobApplicationRepository::insert_job_application)
+/// gives back the [JobApplication] it was saved as. The fields are the same as [JobApplication].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewJobApplication {
+    pub source: String,
+    pub company: String,
+    pub job_title: String,
+    pub application_date: Date,
+    pub time_investment: Option<Duration>,
+    pub human_response: HumanResponse,
+    pub human_response_date: Option<Date>,
+    pub application_website: Option<String>,
+    pub notes: Option<String>,
+    pub salary_min: Option<f64>,
+    pub salary_max: Option<f64>,
+    pub salary_currency: Option<String>,
+    pub pay_period: Option<PayPeriod>,
+    pub equity: Option<String>,
+    pub bonus: Option<String>,
+    pub city: Option<String>,
+    pub region: Option<String>,
+    pub country: Option<String>,
+    pub work_arrangement: Option<WorkArrangement>,
+    pub relocation: Option<bool>,
+    pub visa_sponsorship: Option<bool>,
+    pub custom_fields: CustomFields,
+}
+
+impl NewJobApplication {
+    /// Start a job application for `job_title` at `company`, found through `source`
+    ///
+    /// Everything else starts out empty, except the application date, which is today.
+    pub fn builder(
+        source: impl Into<String>,
+        company: impl Into<String>,
+        job_title: impl Into<String>,
+    ) -> NewJobApplicationBuilder {
+        NewJobApplicationBuilder(Self {
+            source: source.into(),
+            company: company.into(),
+            job_title: job_title.into(),
+            application_date: today(),
+            time_investment: None,
+            human_response: HumanResponse::None,
+            human_response_date: None,
+            application_website: None,
+            notes: None,
+            salary_min: None,
+            salary_max: None,
+            salary_currency: None,
+            pay_period: None,
+            equity: None,
+            bonus: None,
+            city: None,
+            region: None,
+            country: None,
+            work_arrangement: None,
+            relocation: None,
+            visa_sponsorship: None,
+            custom_fields: CustomFields::new(),
+        })
+    }
+
+    /// Give this job application an id, like the id of the saved job application it is replacing
+    ///
+    /// The version is [JobApplication::FIRST_VERSION]. Set it to the version that was loaded before updating a saved job application.
+    pub fn with_id(self, id: i32) -> JobApplication {
+        JobApplication {
+            id,
+            version: JobApplication::FIRST_VERSION,
+            source: self.source,
+            company: self.company,
+            job_title: self.job_title,
+            application_date: self.application_date,
+            time_investment: self.time_investment,
+            human_response: self.human_response,
+            human_response_date: self.human_response_date,
+            application_website: self.application_website,
+            notes: self.notes,
+            salary_min: self.salary_min,
+            salary_max: self.salary_max,
+            salary_currency: self.salary_currency,
+            pay_period: self.pay_period,
+            equity: self.equity,
+            bonus: self.bonus,
+            city: self.city,
+            region: self.region,
+            country: self.country,
+            work_arrangement: self.work_arrangement,
+            relocation: self.relocation,
+            visa_sponsorship: self.visa_sponsorship,
+            custom_fields: self.custom_fields,
+        }
+    }
+}
+
+/// Copy a saved job application, so it can be saved again as a new one
+impl From<JobApplication> for NewJobApplication {
+    fn from(value: JobApplication) -> Self {
+        Self {
+            source: value.source,
+            company: value.company,
+            job_title: value.job_title,
+            application_date: value.application_date,
+            time_investment: value.time_investment,
+            human_response: value.human_response,
+            human_response_date: value.human_response_date,
+            application_website: value.application_website,
+            notes: value.notes,
+            salary_min: value.salary_min,
+            salary_max: value.salary_max,
+            salary_currency: value.salary_currency,
+            pay_period: value.pay_period,
+            equity: value.equity,
+            bonus: value.bonus,
+            city: value.city,
+            region: value.region,
+            country: value.country,
+            work_arrangement: value.work_arrangement,
+            relocation: value.relocation,
+            visa_sponsorship: value.visa_sponsorship,
+            custom_fields: value.custom_fields,
+        }
+    }
+}
+
+/// Fills in a [NewJobApplication], started with [NewJobApplication::builder]
+#[derive(Debug, Clone)]
+pub struct NewJobApplicationBuilder(NewJobApplication);
+
+impl NewJobApplicationBuilder {
+    /// When the job application was sent, instead of today
+    pub fn application_date(mut self, application_date: Date) -> Self {
+        self.0.application_date = application_date;
+        self
+    }
+
+    /// The amount of time spent filling out the job application
+    pub fn time_investment(mut self, time_investment: Duration) -> Self {
+        self.0.time_investment = Some(time_investment);
+        self
+    }
+
+    /// The response that was given, and the date it was given
+    pub fn human_response(
+        mut self,
+        human_response: HumanResponse,
+        human_response_date: Date,
+    ) -> Self {
+        self.0.human_response = human_response;
+        self.0.human_response_date = Some(human_response_date);
+        self
+    }
+
+    /// The website the job application was sent through
+    pub fn application_website(mut self, application_website: impl Into<String>) -> Self {
+        self.0.application_website = Some(application_website.into());
+        self
+    }
+
+    /// Notes on the job application
+    pub fn notes(mut self, notes: impl Into<String>) -> Self {
+        self.0.notes = Some(notes.into());
+        self
+    }
+
+    /// The posted salary range, paid every `pay_period` in `currency`
+    ///
+    /// Use the same amount for `min` and `max` if only one number was posted.
+    pub fn salary(
+        mut self,
+        min: f64,
+        max: f64,
+        currency: impl Into<String>,
+        pay_period: PayPeriod,
+    ) -> Self {
+        self.0.salary_min = Some(min);
+        self.0.salary_max = Some(max);
+        self.0.salary_currency = Some(currency.into());
+        self.0.pay_period = Some(pay_period);
+        self
+    }
+
+    /// The posted equity
+    pub fn equity(mut self, equity: impl Into<String>) -> Self {
+        self.0.equity = Some(equity.into());
+        self
+    }
+
+    /// The posted bonus
+    pub fn bonus(mut self, bonus: impl Into<String>) -> Self {
+        self.0.bonus = Some(bonus.into());
+        self
+    }
+
+    /// The city the job is in
+    pub fn city(mut self, city: impl Into<String>) -> Self {
+        self.0.city = Some(city.into());
+        self
+    }
+
+    /// The state, province, or other region the job is in
+    pub fn region(mut self, region: impl Into<String>) -> Self {
+        self.0.region = Some(region.into());
+        self
+    }
+
+    /// The country the job is in
+    pub fn country(mut self, country: impl Into<String>) -> Self {
+        self.0.country = Some(country.into());
+        self
+    }
+
+    /// Whether the job is remote, hybrid, or onsite
+    pub fn work_arrangement(mut self, work_arrangement: WorkArrangement) -> Self {
+        self.0.work_arrangement = Some(work_arrangement);
+        self
+    }
+
+    /// Whether the company helps with relocating
+    pub fn relocation(mut self, relocation: bool) -> Self {
+        self.0.relocation = Some(relocation);
+        self
+    }
+
+    /// Whether the company sponsors work visas
+    pub fn visa_sponsorship(mut self, visa_sponsorship: bool) -> Self {
+        self.0.visa_sponsorship = Some(visa_sponsorship);
+        self
+    }
+
+    /// The value of the custom field `name`
+    pub fn custom_field(mut self, name: impl Into<String>, value: CustomFieldValue) -> Self {
+        self.0.custom_fields.insert(name.into(), value);
+        self
+    }
+
+    /// Finish the job application
+    pub fn build(self) -> NewJobApplication {
+        self.0
+    }
+}
+
+/// Today in the local time zone, falling back to UTC if the local offset can't be found
+fn today() -> Date {
+    time::OffsetDateTime::now_local()
+        .unwrap_or_else(|_| time::OffsetDateTime::now_utc())
+        .date()
+}
+
+/// Values of user-defined fields, keyed by [CustomField::name]
+pub type CustomFields = BTreeMap<String, CustomFieldValue>;
+
+/// A job application found by a full-text search
+#[derive(Debug, Clone, PartialEq)]
+pub struct FullTextSearchResult {
+    /// The job application that matched
+    pub job_application: JobApplication,
+    /// How well the job application matched the query. Higher is better.
+    ///
+    /// This is only meaningful when compared to other results of the same query.
+    pub relevance: f64,
+    /// An excerpt of the best matching field, with the matching terms surrounded by [SNIPPET_MATCH_START] and [SNIPPET_MATCH_END]
+    pub snippet: String,
+}
+
+/// A job application that was deleted, but is still in the trash and can be restored
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeletedJobApplication {
+    /// The job application as it was when it was deleted
+    pub job_application: JobApplication,
+    /// When the job application was deleted, in UTC
+    pub deleted_at: PrimitiveDateTime,
+}
+
+/// When a job application was saved and changed, and when anything last happened with it
+///
+/// These are kept by the repository, so they aren't part of [JobApplication] and can't be set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Activity {
+    /// When the job application was first saved, in UTC
+    ///
+    /// Job applications saved before this was tracked use their application date.
+    pub created_at: PrimitiveDateTime,
+    /// When the job application was last changed, in UTC. The same as `created_at` if it was never changed.
+    ///
+    /// Only changes that give the job application a new [JobApplication::version] count, so moving it to the trash doesn't.
+    pub updated_at: PrimitiveDateTime,
+    /// The latest of the application date, the human response date,
+    /// the date the job description was saved, and the dates attachments were added
+    pub last_activity: Date,
+}
+
+/// A change to a job application that can be undone, and redone once it has been undone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "mysql", derive(FromRow))]
+#[cfg_attr(feature = "mysql", mysql(table_name = "journal_entries"))]
+pub struct JournalEntry {
+    /// The table primary key. Later changes have larger ids.
+    pub id: i32,
+    /// The job application that was changed
+    pub job_application_id: i32,
+    /// What was done to the job application
+    pub operation: JournalOperation,
+}
+
+/// The kinds of changes to job applications that are recorded in the journal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalOperation {
+    /// The job application was created. Undoing this moves it to the trash.
+    Insert,
+    /// Any of the job application's fields were changed
+    Update,
+    /// The job application was moved to the trash
+    Delete,
+    /// The job application was taken out of the trash
+    Restore,
+}
+
+impl Display for JournalOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Insert => "create",
+            Self::Update => "edit",
+            Self::Delete => "delete",
+            Self::Restore => "restore",
+        })
+    }
 }
 
+/// A change to a job application recorded in the audit log, saying who changed what
+///
+/// Only a shared (MySQL) database keeps an audit log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditLogEntry {
+    /// The table primary key. Later changes have larger ids.
+    pub id: i32,
+    /// The job application that was changed. It may have been permanently deleted since.
+    pub job_application_id: i32,
+    /// What was done to the job application
+    pub operation: AuditOperation,
+    /// When the change was made, in UTC
+    pub changed_at: PrimitiveDateTime,
+    /// Who made the change, from `AUDIT_USER`, or the database user if that isn't set
+    pub changed_by: String,
+    /// The fields that were changed, sorted by field name. Empty for moving to and from the trash.
+    pub changes: Vec<FieldChange>,
+}
+
+/// The kinds of changes to job applications that are recorded in the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditOperation {
+    /// The job application was created
+    Insert,
+    /// Any of the job application's fields were changed, including by undoing or redoing an edit
+    Update,
+    /// The job application was moved to the trash
+    Delete,
+    /// The job application was taken out of the trash
+    Restore,
+    /// The job application was permanently deleted from the trash
+    Purge,
+}
+
+impl Display for AuditOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Insert => "create",
+            Self::Update => "edit",
+            Self::Delete => "delete",
+            Self::Restore => "restore",
+            Self::Purge => "permanently delete",
+        })
+    }
+}
+
+/// One field of a job application that was changed, with the values it had before and after the change
+///
+/// Values are written the way they would be shown to the user. `None` means the field was empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    /// The name of the column, like `company`, or `field.<name>` for a custom field
+    pub field: String,
+    /// The value before the change
+    pub old_value: Option<String>,
+    /// The value after the change
+    pub new_value: Option<String>,
+}
+
+/// A label, like "remote" or "dream job", that can be put on any number of job applications
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "mysql", derive(FromRow))]
+#[cfg_attr(feature = "mysql", mysql(table_name = "tags"))]
+pub struct Tag {
+    /// The table primary key
+    pub id: i32,
+    /// The name of the tag. Names are unique, ignoring case.
+    pub name: String,
+}
+
+/// A field defined by the user, like "recruiter agency" or "req ID", that any job application can have a value for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomField {
+    /// The table primary key
+    pub id: i32,
+    /// The name of the field. Names are unique, ignoring case.
+    pub name: String,
+    /// The type of value the field holds
+    pub field_type: CustomFieldType,
+}
+
+/// The type of value a [CustomField] holds
+///
+/// This is written and parsed as `text`, `number`, `date`, `bool`, or `select:` followed by comma-separated options.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    Date,
+    Bool,
+    /// One of a fixed list of options
+    SingleSelect(Vec<String>),
+}
+
+impl CustomFieldType {
+    /// Parse a value typed by a user
+    ///
+    /// Dates may be `mm/dd/yyyy` or `yyyy-mm-dd`. Booleans may be yes/no, y/n, or true/false.
+    /// Options are matched ignoring case, but the returned value uses the option as it was defined.
+    pub fn parse_value(&self, value: &str) -> Result<CustomFieldValue, String> {
+        let value = value.trim();
+        match self {
+            Self::Text => Ok(CustomFieldValue::Text(value.to_owned())),
+            Self::Number => value
+                .parse::<f64>()
+                .ok()
+                // NaN and infinity can't be stored in every backend, and NaN can't be compared
+                .filter(|number| number.is_finite())
+                .map(CustomFieldValue::Number)
+                .ok_or_else(|| format!("'{value}' is not a number")),
+            Self::Date => Date::parse(
+                value,
+                format_description!("[month padding:none]/[day padding:none]/[year]"),
+            )
+            .or_else(|_| Date::parse(value, format_description!("[year]-[month]-[day]")))
+            .map(CustomFieldValue::Date)
+            .map_err(|_| format!("'{value}' is not a date. Use mm/dd/yyyy")),
+            Self::Bool => match value.to_lowercase().as_str() {
+                "yes" | "y" | "true" => Ok(CustomFieldValue::Bool(true)),
+                "no" | "n" | "false" => Ok(CustomFieldValue::Bool(false)),
+                _ => Err(format!("'{value}' is not yes or no")),
+            },
+            Self::SingleSelect(options) => options
+                .iter()
+                .find(|option| option.to_lowercase() == value.to_lowercase())
+                .map(|option| CustomFieldValue::SingleSelect(option.clone()))
+                .ok_or_else(|| format!("'{value}' is not one of: {}", options.join(", "))),
+        }
+    }
+
+    /// Whether `value` is the kind of value this type holds
+    ///
+    /// Options are not checked for [CustomFieldType::SingleSelect], since they may have changed after the value was set.
+    pub fn accepts(&self, value: &CustomFieldValue) -> bool {
+        matches!(
+            (self, value),
+            (Self::Text, CustomFieldValue::Text(_))
+                | (Self::Number, CustomFieldValue::Number(_))
+                | (Self::Date, CustomFieldValue::Date(_))
+                | (Self::Bool, CustomFieldValue::Bool(_))
+                | (Self::SingleSelect(_), CustomFieldValue::SingleSelect(_))
+        )
+    }
+
+    /// Read a value written by [CustomFieldValue::to_stored_string]
+    pub(crate) fn value_from_stored_string(&self, value: &str) -> Result<CustomFieldValue, String> {
+        match self {
+            // Options may have been removed since this was stored, so keep the value as-is
+            Self::SingleSelect(_) => Ok(CustomFieldValue::SingleSelect(value.to_owned())),
+            _ => self.parse_value(value),
+        }
+    }
+}
+
+impl Display for CustomFieldType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text => f.write_str("text"),
+            Self::Number => f.write_str("number"),
+            Self::Date => f.write_str("date"),
+            Self::Bool => f.write_str("bool"),
+            Self::SingleSelect(options) => write!(f, "select:{}", options.join(",")),
+        }
+    }
+}
+
+impl FromStr for CustomFieldType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (type_name, options) = s
+            .split_once(':')
+            .map_or((s, None), |(type_name, options)| (type_name, Some(options)));
+
+        match (type_name.trim().to_lowercase().as_str(), options) {
+            ("text", None) => Ok(Self::Text),
+            ("number", None) => Ok(Self::Number),
+            ("date", None) => Ok(Self::Date),
+            ("bool", None) => Ok(Self::Bool),
+            ("select", Some(options)) => {
+                let options: Vec<String> = options
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|option| !option.is_empty())
+                    .map(str::to_owned)
+                    .collect();
+                if options.is_empty() {
+                    Err("A select field needs at least one option, e.g. select:a,b,c".to_owned())
+                } else {
+                    Ok(Self::SingleSelect(options))
+                }
+            }
+            ("select", None) => {
+                Err("A select field needs at least one option, e.g. select:a,b,c".to_owned())
+            }
+            _ => Err(format!(
+                "Unknown field type '{s}'. Use text, number, date, bool, or select:a,b,c"
+            )),
+        }
+    }
+}
+
+/// The value of a [CustomField] on a job application
+#[derive(Debug, Clone, PartialEq)]
+pub enum CustomFieldValue {
+    Text(String),
+    /// Always finite
+    Number(f64),
+    Date(Date),
+    Bool(bool),
+    /// One of the options of a [CustomFieldType::SingleSelect]
+    SingleSelect(String),
+}
+
+impl CustomFieldValue {
+    /// Write the value in the form it is stored in the database
+    ///
+    /// Dates are written as `yyyy-mm-dd` so that they compare correctly as text.
+    pub(crate) fn to_stored_string(&self) -> String {
+        match self {
+            Self::Text(text) | Self::SingleSelect(text) => text.clone(),
+            Self::Number(number) => number.to_string(),
+            Self::Date(date) => date.to_string(),
+            Self::Bool(value) => value.to_string(),
+        }
+    }
+}
+
+impl Display for CustomFieldValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text(text) | Self::SingleSelect(text) => f.write_str(text),
+            Self::Number(number) => write!(f, "{number}"),
+            Self::Date(date) => write!(
+                f,
+                "{:02}/{:02}/{}",
+                date.month() as u8,
+                date.day(),
+                date.year()
+            ),
+            Self::Bool(true) => f.write_str("yes"),
+            Self::Bool(false) => f.write_str("no"),
+        }
+    }
+}
+
+/// How often a salary is paid
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PayPeriod {
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    #[default]
+    Yearly,
+}
+
+impl PayPeriod {
+    /// Number of pay periods in a year, assuming full-time work (8 hour days, 5 days a week, 52 weeks a year)
+    pub fn periods_per_year(self) -> f64 {
+        match self {
+            Self::Hourly => 2080.0,
+            Self::Daily => 260.0,
+            Self::Weekly => 52.0,
+            Self::Monthly => 12.0,
+            Self::Yearly => 1.0,
+        }
+    }
+
+    /// Convert an amount paid every period into a yearly amount
+    pub fn annualize(self, amount: f64) -> f64 {
+        amount * self.periods_per_year()
+    }
+}
+
+impl Display for PayPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Hourly => "hourly",
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+            Self::Yearly => "yearly",
+        })
+    }
+}
+
+impl FromStr for PayPeriod {
+    type Err = String;
+
+    /// Parse a pay period from its name or its first letter, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "hourly" | "hour" | "h" => Ok(Self::Hourly),
+            "daily" | "day" | "d" => Ok(Self::Daily),
+            "weekly" | "week" | "w" => Ok(Self::Weekly),
+            "monthly" | "month" | "m" => Ok(Self::Monthly),
+            "yearly" | "year" | "annual" | "annually" | "y" => Ok(Self::Yearly),
+            _ => Err(format!(
+                "Unknown pay period '{s}'. Use hourly, daily, weekly, monthly, or yearly"
+            )),
+        }
+    }
+}
+
+/// A job offer, for a job application that got a [HumanResponse::JobOffer]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Offer {
+    /// The job application this offer was made for. Each job application has at most one offer.
+    pub job_application_id: i32,
+    /// Base pay for each `pay_period`
+    pub base_salary: f64,
+    /// How often `base_salary` is paid
+    pub pay_period: PayPeriod,
+    /// Currency code of every amount in the offer, like USD
+    pub currency: Option<String>,
+    /// Expected yearly bonus
+    pub bonus: Option<f64>,
+    /// Total value of the equity grant
+    pub equity_value: Option<f64>,
+    /// How many years the equity grant vests over
+    pub vesting_years: Option<f64>,
+    /// Health insurance, retirement matching, PTO, etc
+    pub benefits: Option<String>,
+    /// The first day of work
+    pub start_date: Option<Date>,
+    /// The last day to accept the offer
+    pub deadline: Option<Date>,
+}
+
+impl Offer {
+    /// Vesting period assumed when an equity grant doesn't have one, since four years is the most common
+    pub const DEFAULT_VESTING_YEARS: f64 = 4.0;
+
+    /// Base pay over a year
+    pub fn annual_base(&self) -> f64 {
+        self.pay_period.annualize(self.base_salary)
+    }
+
+    /// Expected bonus over a year
+    pub fn annual_bonus(&self) -> f64 {
+        self.bonus.unwrap_or_default()
+    }
+
+    /// The part of the equity grant that vests each year
+    pub fn annual_equity(&self) -> f64 {
+        let vesting_years = self
+            .vesting_years
+            .filter(|years| *years > 0.0)
+            .unwrap_or(Self::DEFAULT_VESTING_YEARS);
+        self.equity_value.unwrap_or_default() / vesting_years
+    }
+
+    /// Base, bonus, and equity over a year
+    pub fn annual_total(&self) -> f64 {
+        self.annual_base() + self.annual_bonus() + self.annual_equity()
+    }
+}
+
+/// The description from a job posting, saved in case the posting is taken down
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "mysql", derive(FromRow))]
+#[cfg_attr(feature = "mysql", mysql(table_name = "job_descriptions"))]
+pub struct JobDescription {
+    /// The job application this description was posted for. Each job application has at most one description.
+    pub job_application_id: i32,
+    /// The text of the posting
+    pub description: String,
+    /// When the description was saved
+    pub captured_date: Date,
+}
+
+/// A file attached to a job application, like the resume that was sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "mysql", derive(FromRow))]
+#[cfg_attr(feature = "mysql", mysql(table_name = "attachments"))]
+pub struct Attachment {
+    /// The table primary key
+    pub id: i32,
+    /// The job application the file is attached to
+    pub job_application_id: i32,
+    /// What the file is for
+    pub role: AttachmentRole,
+    /// The name of the file when it was attached, without any directories
+    pub file_name: String,
+    /// The SHA-256 hash of the contents, as lowercase hex. Attachments with the same contents share one stored copy.
+    pub file_hash: String,
+    /// Size of the contents in bytes
+    pub size: i64,
+    /// When the file was attached
+    pub added_date: Date,
+}
+
+impl Attachment {
+    /// Hash file contents the way [Attachment::file_hash] is
+    pub fn hash_contents(contents: &[u8]) -> String {
+        Sha256::digest(contents)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+}
+
+/// What a file attached to a job application is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AttachmentRole {
+    Resume,
+    CoverLetter,
+    OfferLetter,
+    /// A take-home assignment, or what was submitted for one
+    TakeHome,
+    Other,
+}
+
+impl AttachmentRole {
+    /// Every role, in the order they are shown
+    pub const ALL: [AttachmentRole; 5] = [
+        Self::Resume,
+        Self::CoverLetter,
+        Self::OfferLetter,
+        Self::TakeHome,
+        Self::Other,
+    ];
+}
+
+impl Display for AttachmentRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Resume => "resume",
+            Self::CoverLetter => "cover letter",
+            Self::OfferLetter => "offer letter",
+            Self::TakeHome => "take-home",
+            Self::Other => "other",
+        })
+    }
+}
+
+impl FromStr for AttachmentRole {
+    type Err = String;
+
+    /// Parse a role from its name or a common spelling, ignoring case. Words may be separated by spaces, dashes, or underscores.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace(['-', '_'], " ").as_str() {
+            "resume" | "cv" => Ok(Self::Resume),
+            "cover letter" | "cover" => Ok(Self::CoverLetter),
+            "offer letter" | "offer" => Ok(Self::OfferLetter),
+            "take home" | "takehome" | "assignment" => Ok(Self::TakeHome),
+            "other" => Ok(Self::Other),
+            _ => Err(format!(
+                "Unknown attachment role '{s}'. Use resume, cover-letter, offer-letter, take-home, or other"
+            )),
+        }
+    }
+}
+
+/// A version of the user's resume, so versions can be compared by how job applications sent with them went
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "mysql", derive(FromRow))]
+#[cfg_attr(feature = "mysql", mysql(table_name = "resume_versions"))]
+pub struct ResumeVersion {
+    /// The table primary key
+    pub id: i32,
+    /// A name for the version, like "v3 - backend focus". Labels are unique, ignoring case.
+    pub label: String,
+    /// The SHA-256 hash of the resume file, hashed the same way as [Attachment::file_hash]
+    pub file_hash: Option<String>,
+    /// When the version was written
+    pub created_date: Date,
+    /// What changed in this version
+    pub notes: Option<String>,
+}
+
+/// How job applications sent with one resume version are going
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumeVersionStats {
+    /// `None` for job applications without a recorded resume version
+    pub resume_version_id: Option<i32>,
+    /// [ResumeVersion::label], or `None` for job applications without a recorded resume version
+    pub label: Option<String>,
+    /// Number of job applications, which is the sample size of the rates
+    pub applications: i64,
+    /// Job applications that got any response, including rejections
+    pub responses: i64,
+    /// Job applications that got an interview, including ones that led to an offer
+    pub interviews: i64,
+    /// Job applications that got an offer
+    pub offers: i64,
+}
+
+impl ResumeVersionStats {
+    /// The fraction of job applications that got any response, or `None` if there are no job applications
+    pub fn response_rate(&self) -> Option<f64> {
+        self.rate(self.responses)
+    }
+
+    /// The fraction of job applications that got an interview, or `None` if there are no job applications
+    pub fn interview_rate(&self) -> Option<f64> {
+        self.rate(self.interviews)
+    }
+
+    fn rate(&self, count: i64) -> Option<f64> {
+        (self.applications > 0).then(|| count as f64 / self.applications as f64)
+    }
+}
+
+/// Where the work for a job is done
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WorkArrangement {
+    Remote,
+    /// Partly remote, partly at an office
+    Hybrid,
+    Onsite,
+}
+
+impl WorkArrangement {
+    /// Every work arrangement, in the order they are shown
+    pub const ALL: [WorkArrangement; 3] = [Self::Remote, Self::Hybrid, Self::Onsite];
+}
+
+impl Display for WorkArrangement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Remote => "remote",
+            Self::Hybrid => "hybrid",
+            Self::Onsite => "onsite",
+        })
+    }
+}
+
+impl FromStr for WorkArrangement {
+    type Err = String;
+
+    /// Parse a work arrangement from its name, a common spelling, or its first letter, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace(['-', '_'], " ").as_str() {
+            "remote" | "r" => Ok(Self::Remote),
+            "hybrid" | "h" => Ok(Self::Hybrid),
+            "onsite" | "on site" | "in office" | "office" | "o" => Ok(Self::Onsite),
+            _ => Err(format!(
+                "Unknown work arrangement '{s}'. Use remote, hybrid, or onsite"
+            )),
+        }
+    }
+}
+
+/// How job applications with one work arrangement are going
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkArrangementStats {
+    /// `None` for job applications without a work arrangement
+    pub work_arrangement: Option<WorkArrangement>,
+    /// Number of job applications
+    pub applications: i64,
+    /// Job applications that have no response yet
+    pub pending: i64,
+    /// Job applications that were rejected, before or after an interview
+    pub rejections: i64,
+    /// Job applications that got an interview, including ones that led to an offer
+    pub interviews: i64,
+    /// Job applications that got an offer
+    pub offers: i64,
+}
+
+/// Marks the start of a matching term in [FullTextSearchResult::snippet]
+pub const SNIPPET_MATCH_START: &str = "[";
+/// Marks the end of a matching term in [FullTextSearchResult::snippet]
+pub const SNIPPET_MATCH_END: &str = "]";
+
 /// Enum to hold possible human responses
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum HumanResponse {
@@ -86,7 +1037,7 @@ impl TryFrom<&str> for HumanResponse {
 impl From<String> for HumanResponse {
     /// Tries to parse a `String` as a `HumanResponse`, if unrecognized, `HumanResponse::None` is returned
     fn from(value: String) -> Self {
-        return TryFrom::<&str>::try_from(&value).unwrap_or_default();
+        TryFrom::<&str>::try_from(&value).unwrap_or_default()
     }
 }
 
@@ -112,6 +1063,30 @@ pub enum JobApplicationField {
     ApplicationWebsite(Option<String>),
     /// Notes on anything notable about the application process or company
     Notes(Option<String>),
+    /// The bottom of the posted salary range
+    SalaryMin(Option<f64>),
+    /// The top of the posted salary range
+    SalaryMax(Option<f64>),
+    /// Currency code of the posted salary
+    SalaryCurrency(Option<String>),
+    /// How often the posted salary is paid
+    PayPeriod(Option<PayPeriod>),
+    /// Posted equity
+    Equity(Option<String>),
+    /// Posted bonus
+    Bonus(Option<String>),
+    /// The city the job is in
+    City(Option<String>),
+    /// The state, province, or other region the job is in
+    Region(Option<String>),
+    /// The country the job is in
+    Country(Option<String>),
+    /// Whether the job is remote, hybrid, or onsite
+    WorkArrangement(Option<WorkArrangement>),
+    /// Whether the company helps with relocating
+    Relocation(Option<bool>),
+    /// Whether the company sponsors work visas
+    VisaSponsorship(Option<bool>),
 }
 
 impl JobApplicationField {
@@ -127,6 +1102,18 @@ impl JobApplicationField {
             JobApplicationField::HumanResponseDate(_) => "human_response_date",
             JobApplicationField::ApplicationWebsite(_) => "application_website",
             JobApplicationField::Notes(_) => "notes",
+            JobApplicationField::SalaryMin(_) => "salary_min",
+            JobApplicationField::SalaryMax(_) => "salary_max",
+            JobApplicationField::SalaryCurrency(_) => "salary_currency",
+            JobApplicationField::PayPeriod(_) => "pay_period",
+            JobApplicationField::Equity(_) => "equity",
+            JobApplicationField::Bonus(_) => "bonus",
+            JobApplicationField::City(_) => "city",
+            JobApplicationField::Region(_) => "region",
+            JobApplicationField::Country(_) => "country",
+            JobApplicationField::WorkArrangement(_) => "work_arrangement",
+            JobApplicationField::Relocation(_) => "relocation",
+            JobApplicationField::VisaSponsorship(_) => "visa_sponsorship",
         }
         .to_owned()
     }
@@ -134,3 +1121,128 @@ impl JobApplicationField {
 
 /// Newtype to allow impl Into<Params>
 pub struct PartialJobApplication(pub Vec<JobApplicationField>);
+
+#[cfg(test)]
+mod tests {
+    use time::Month;
+
+    use super::*;
+
+    /// Field types should survive being written and parsed
+    #[test]
+    fn test_custom_field_type_round_trip() {
+        for field_type in [
+            CustomFieldType::Text,
+            CustomFieldType::Number,
+            CustomFieldType::Date,
+            CustomFieldType::Bool,
+            CustomFieldType::SingleSelect(vec!["Recruiter".to_owned(), "Direct".to_owned()]),
+        ] {
+            assert_eq!(field_type.to_string().parse(), Ok(field_type));
+        }
+
+        assert_eq!(
+            " Select: a, ,b ".parse(),
+            Ok(CustomFieldType::SingleSelect(vec![
+                "a".to_owned(),
+                "b".to_owned()
+            ])),
+            "Options are trimmed and empty options are ignored"
+        );
+        assert!("select:".parse::<CustomFieldType>().is_err());
+        assert!("text:a,b".parse::<CustomFieldType>().is_err());
+        assert!("integer".parse::<CustomFieldType>().is_err());
+    }
+
+    /// Values typed by users are parsed according to the field type
+    #[test]
+    fn test_custom_field_parse_value() {
+        let date = Date::from_calendar_date(2025, Month::March, 4).unwrap();
+        let select = CustomFieldType::SingleSelect(vec!["Recruiter".to_owned()]);
+
+        assert_eq!(
+            CustomFieldType::Number.parse_value(" 1.5 "),
+            Ok(CustomFieldValue::Number(1.5))
+        );
+        assert!(CustomFieldType::Number.parse_value("NaN").is_err());
+        assert_eq!(
+            CustomFieldType::Date.parse_value("3/4/2025"),
+            Ok(CustomFieldValue::Date(date))
+        );
+        assert_eq!(
+            CustomFieldType::Date.parse_value("2025-03-04"),
+            Ok(CustomFieldValue::Date(date))
+        );
+        assert_eq!(
+            CustomFieldType::Bool.parse_value("Y"),
+            Ok(CustomFieldValue::Bool(true))
+        );
+        assert!(CustomFieldType::Bool.parse_value("maybe").is_err());
+        assert_eq!(
+            select.parse_value("recruiter"),
+            Ok(CustomFieldValue::SingleSelect("Recruiter".to_owned())),
+            "Options ignore case but keep their defined spelling"
+        );
+        assert!(select.parse_value("Direct").is_err());
+    }
+
+    /// Stored values can be read back as the same value
+    #[test]
+    fn test_custom_field_value_stored_string() {
+        for (field_type, value) in [
+            (
+                CustomFieldType::Text,
+                CustomFieldValue::Text("Hays".to_owned()),
+            ),
+            (CustomFieldType::Number, CustomFieldValue::Number(-2.25)),
+            (
+                CustomFieldType::Date,
+                CustomFieldValue::Date(Date::from_calendar_date(2025, Month::March, 4).unwrap()),
+            ),
+            (CustomFieldType::Bool, CustomFieldValue::Bool(false)),
+            (
+                CustomFieldType::SingleSelect(Vec::new()),
+                CustomFieldValue::SingleSelect("Removed option".to_owned()),
+            ),
+        ] {
+            assert_eq!(
+                field_type.value_from_stored_string(&value.to_stored_string()),
+                Ok(value)
+            );
+        }
+    }
+
+    /// Roles should survive being written and parsed, and hashes should be lowercase hex SHA-256
+    #[test]
+    fn test_attachment_role_and_hash() {
+        for role in AttachmentRole::ALL {
+            assert_eq!(role.to_string().parse(), Ok(role));
+        }
+        assert_eq!("Cover-Letter".parse(), Ok(AttachmentRole::CoverLetter));
+        assert!("photo".parse::<AttachmentRole>().is_err());
+
+        assert_eq!(
+            Attachment::hash_contents(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    /// Rates are fractions of the job applications, and undefined without any
+    #[test]
+    fn test_resume_version_rates() {
+        let mut stats = ResumeVersionStats {
+            resume_version_id: Some(1),
+            label: Some("v1".to_owned()),
+            applications: 8,
+            responses: 4,
+            interviews: 2,
+            offers: 1,
+        };
+        assert_eq!(stats.response_rate(), Some(0.5));
+        assert_eq!(stats.interview_rate(), Some(0.25));
+
+        stats.applications = 0;
+        assert_eq!(stats.response_rate(), None);
+        assert_eq!(stats.interview_rate(), None);
+    }
+}
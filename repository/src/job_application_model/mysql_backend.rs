@@ -1,32 +1,237 @@
 use crate::job_application_model::{
-    HumanResponse, JobApplication, JobApplicationField, PartialJobApplication,
+    AttachmentRole, AuditLogEntry, AuditOperation, CustomField, CustomFieldType, CustomFields,
+    DeletedJobApplication, HumanResponse, JobApplication, JobApplicationField, JobDescription,
+    JournalOperation, NewJobApplication, Offer, PartialJobApplication, PayPeriod,
+    ResumeVersionStats, WorkArrangement, WorkArrangementStats,
 };
 
 use mysql::{
     params,
-    prelude::{FromValue, ToValue},
-    Params, Value,
+    prelude::{FromRow, FromValue, ToValue},
+    FromRowError, FromValueError, Params, Row, Value,
 };
 
 use std::collections::HashMap;
 
-impl Into<Params> for &JobApplication {
-    fn into(self) -> Params {
+impl From<&JobApplication> for Params {
+    fn from(value: &JobApplication) -> Self {
         params! {
-            "id" => &self.id,
-            "source" => &self.source,
-            "company" => &self.company,
-            "job_title" => &self.job_title,
-            "application_date" => &self.application_date,
-            "time_investment" => &self.time_investment,
-            "human_response" => &self.human_response,
-            "human_response_date" => &self.human_response_date,
-            "application_website" => &self.application_website,
-            "notes" => &self.notes,
+            "id" => &value.id,
+            "version" => &value.version,
+            "source" => &value.source,
+            "company" => &value.company,
+            "job_title" => &value.job_title,
+            "application_date" => &value.application_date,
+            "time_investment" => &value.time_investment,
+            "human_response" => &value.human_response,
+            "human_response_date" => &value.human_response_date,
+            "application_website" => &value.application_website,
+            "notes" => &value.notes,
+            "salary_min" => &value.salary_min,
+            "salary_max" => &value.salary_max,
+            "salary_currency" => &value.salary_currency,
+            "pay_period" => &value.pay_period,
+            "equity" => &value.equity,
+            "bonus" => &value.bonus,
+            "city" => &value.city,
+            "region" => &value.region,
+            "country" => &value.country,
+            "work_arrangement" => &value.work_arrangement,
+            "relocation" => &value.relocation,
+            "visa_sponsorship" => &value.visa_sponsorship,
         }
     }
 }
 
+impl From<&NewJobApplication> for Params {
+    fn from(value: &NewJobApplication) -> Self {
+        params! {
+            "source" => &value.source,
+            "company" => &value.company,
+            "job_title" => &value.job_title,
+            "application_date" => &value.application_date,
+            "time_investment" => &value.time_investment,
+            "human_response" => &value.human_response,
+            "human_response_date" => &value.human_response_date,
+            "application_website" => &value.application_website,
+            "notes" => &value.notes,
+            "salary_min" => &value.salary_min,
+            "salary_max" => &value.salary_max,
+            "salary_currency" => &value.salary_currency,
+            "pay_period" => &value.pay_period,
+            "equity" => &value.equity,
+            "bonus" => &value.bonus,
+            "city" => &value.city,
+            "region" => &value.region,
+            "country" => &value.country,
+            "work_arrangement" => &value.work_arrangement,
+            "relocation" => &value.relocation,
+            "visa_sponsorship" => &value.visa_sponsorship,
+        }
+    }
+}
+
+impl From<&Offer> for Params {
+    fn from(value: &Offer) -> Self {
+        params! {
+            "job_application_id" => &value.job_application_id,
+            "base_salary" => &value.base_salary,
+            "pay_period" => &value.pay_period,
+            "currency" => &value.currency,
+            "bonus" => &value.bonus,
+            "equity_value" => &value.equity_value,
+            "vesting_years" => &value.vesting_years,
+            "benefits" => &value.benefits,
+            "start_date" => &value.start_date,
+            "deadline" => &value.deadline,
+        }
+    }
+}
+
+impl From<&JobDescription> for Params {
+    fn from(value: &JobDescription) -> Self {
+        params! {
+            "job_application_id" => &value.job_application_id,
+            "description" => &value.description,
+            "captured_date" => &value.captured_date,
+        }
+    }
+}
+
+/// Take the value of a column, failing with the whole row if it is missing or the wrong type
+fn take_column<T: FromValue>(row: &mut Row, original: &Row, name: &str) -> Result<T, FromRowError> {
+    row.take_opt(name)
+        .and_then(Result::ok)
+        .ok_or_else(|| FromRowError(original.clone()))
+}
+
+impl FromRow for JobApplication {
+    // This can't be derived because custom fields are stored in another table
+    fn from_row_opt(mut row: Row) -> Result<Self, FromRowError> {
+        let original = row.clone();
+        Ok(JobApplication {
+            id: take_column(&mut row, &original, "id")?,
+            version: take_column(&mut row, &original, "version")?,
+            source: take_column(&mut row, &original, "source")?,
+            company: take_column(&mut row, &original, "company")?,
+            job_title: take_column(&mut row, &original, "job_title")?,
+            application_date: take_column(&mut row, &original, "application_date")?,
+            time_investment: take_column(&mut row, &original, "time_investment")?,
+            human_response: take_column(&mut row, &original, "human_response")?,
+            human_response_date: take_column(&mut row, &original, "human_response_date")?,
+            application_website: take_column(&mut row, &original, "application_website")?,
+            notes: take_column(&mut row, &original, "notes")?,
+            salary_min: take_column(&mut row, &original, "salary_min")?,
+            salary_max: take_column(&mut row, &original, "salary_max")?,
+            salary_currency: take_column(&mut row, &original, "salary_currency")?,
+            pay_period: take_column(&mut row, &original, "pay_period")?,
+            equity: take_column(&mut row, &original, "equity")?,
+            bonus: take_column(&mut row, &original, "bonus")?,
+            city: take_column(&mut row, &original, "city")?,
+            region: take_column(&mut row, &original, "region")?,
+            country: take_column(&mut row, &original, "country")?,
+            work_arrangement: take_column(&mut row, &original, "work_arrangement")?,
+            relocation: take_column(&mut row, &original, "relocation")?,
+            visa_sponsorship: take_column(&mut row, &original, "visa_sponsorship")?,
+            // Filled in by the repository
+            custom_fields: CustomFields::new(),
+        })
+    }
+}
+
+impl FromRow for DeletedJobApplication {
+    fn from_row_opt(mut row: Row) -> Result<Self, FromRowError> {
+        let original = row.clone();
+        let deleted_at = take_column(&mut row, &original, "deleted_at")?;
+        Ok(DeletedJobApplication {
+            job_application: JobApplication::from_row_opt(row)?,
+            deleted_at,
+        })
+    }
+}
+
+impl FromRow for AuditLogEntry {
+    // This can't be derived because the changed fields are stored in another table
+    fn from_row_opt(mut row: Row) -> Result<Self, FromRowError> {
+        let original = row.clone();
+        Ok(AuditLogEntry {
+            id: take_column(&mut row, &original, "id")?,
+            job_application_id: take_column(&mut row, &original, "job_application_id")?,
+            operation: take_column(&mut row, &original, "operation")?,
+            changed_at: take_column(&mut row, &original, "changed_at")?,
+            changed_by: take_column(&mut row, &original, "changed_by")?,
+            // Filled in by the repository
+            changes: Vec::new(),
+        })
+    }
+}
+
+impl FromRow for Offer {
+    fn from_row_opt(mut row: Row) -> Result<Self, FromRowError> {
+        let original = row.clone();
+        Ok(Offer {
+            job_application_id: take_column(&mut row, &original, "job_application_id")?,
+            base_salary: take_column(&mut row, &original, "base_salary")?,
+            pay_period: take_column(&mut row, &original, "pay_period")?,
+            currency: take_column(&mut row, &original, "currency")?,
+            bonus: take_column(&mut row, &original, "bonus")?,
+            equity_value: take_column(&mut row, &original, "equity_value")?,
+            vesting_years: take_column(&mut row, &original, "vesting_years")?,
+            benefits: take_column(&mut row, &original, "benefits")?,
+            start_date: take_column(&mut row, &original, "start_date")?,
+            deadline: take_column(&mut row, &original, "deadline")?,
+        })
+    }
+}
+
+impl FromRow for WorkArrangementStats {
+    fn from_row_opt(mut row: Row) -> Result<Self, FromRowError> {
+        let original = row.clone();
+        Ok(WorkArrangementStats {
+            work_arrangement: take_column(&mut row, &original, "work_arrangement")?,
+            applications: take_column(&mut row, &original, "applications")?,
+            pending: take_column(&mut row, &original, "pending")?,
+            rejections: take_column(&mut row, &original, "rejections")?,
+            interviews: take_column(&mut row, &original, "interviews")?,
+            offers: take_column(&mut row, &original, "offers")?,
+        })
+    }
+}
+
+impl FromRow for ResumeVersionStats {
+    fn from_row_opt(mut row: Row) -> Result<Self, FromRowError> {
+        let original = row.clone();
+        Ok(ResumeVersionStats {
+            resume_version_id: take_column(&mut row, &original, "resume_version_id")?,
+            label: take_column(&mut row, &original, "label")?,
+            applications: take_column(&mut row, &original, "applications")?,
+            responses: take_column(&mut row, &original, "responses")?,
+            interviews: take_column(&mut row, &original, "interviews")?,
+            offers: take_column(&mut row, &original, "offers")?,
+        })
+    }
+}
+
+impl FromRow for CustomField {
+    fn from_row_opt(mut row: Row) -> Result<Self, FromRowError> {
+        let original = row.clone();
+        let field_type: String = take_column(&mut row, &original, "field_type")?;
+        Ok(CustomField {
+            id: take_column(&mut row, &original, "id")?,
+            name: take_column(&mut row, &original, "name")?,
+            field_type: field_type
+                .parse()
+                .map_err(|_| FromRowError(original.clone()))?,
+        })
+    }
+}
+
+impl ToValue for CustomFieldType {
+    fn to_value(&self) -> Value {
+        self.to_string().to_value()
+    }
+}
+
 impl ToValue for HumanResponse {
     fn to_value(&self) -> Value {
         match self {
@@ -46,6 +251,246 @@ impl FromValue for HumanResponse {
     type Intermediate = String;
 }
 
+// Implementing `From` instead of `ToValue` lets `Option<PayPeriod>` be used as a parameter too
+impl From<PayPeriod> for Value {
+    fn from(value: PayPeriod) -> Self {
+        match value {
+            PayPeriod::Hourly => "H",
+            PayPeriod::Daily => "D",
+            PayPeriod::Weekly => "W",
+            PayPeriod::Monthly => "M",
+            PayPeriod::Yearly => "Y",
+        }
+        .to_value()
+    }
+}
+
+/// Intermediate for [PayPeriod], since parsing a stored pay period can fail
+pub struct PayPeriodIr(PayPeriod);
+
+impl TryFrom<Value> for PayPeriodIr {
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        // The stored letters are also accepted by `FromStr`
+        match &value {
+            Value::Bytes(bytes) => std::str::from_utf8(bytes)
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .map(PayPeriodIr)
+                .ok_or(FromValueError(value)),
+            _ => Err(FromValueError(value)),
+        }
+    }
+}
+
+impl From<PayPeriodIr> for PayPeriod {
+    fn from(value: PayPeriodIr) -> Self {
+        value.0
+    }
+}
+
+impl FromValue for PayPeriod {
+    type Intermediate = PayPeriodIr;
+}
+
+// Implemented like `PayPeriod`, so `Option<WorkArrangement>` can be used as a parameter
+impl From<WorkArrangement> for Value {
+    fn from(value: WorkArrangement) -> Self {
+        match value {
+            WorkArrangement::Remote => "R",
+            WorkArrangement::Hybrid => "H",
+            WorkArrangement::Onsite => "O",
+        }
+        .to_value()
+    }
+}
+
+/// Intermediate for [WorkArrangement], since parsing a stored work arrangement can fail
+pub struct WorkArrangementIr(WorkArrangement);
+
+impl TryFrom<Value> for WorkArrangementIr {
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        // The stored letters are also accepted by `FromStr`
+        match &value {
+            Value::Bytes(bytes) => std::str::from_utf8(bytes)
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .map(WorkArrangementIr)
+                .ok_or(FromValueError(value)),
+            _ => Err(FromValueError(value)),
+        }
+    }
+}
+
+impl From<WorkArrangementIr> for WorkArrangement {
+    fn from(value: WorkArrangementIr) -> Self {
+        value.0
+    }
+}
+
+impl FromValue for WorkArrangement {
+    type Intermediate = WorkArrangementIr;
+}
+
+// Implemented like `PayPeriod`, so `AttachmentRole` can be used as a parameter
+impl From<AttachmentRole> for Value {
+    fn from(value: AttachmentRole) -> Self {
+        match value {
+            AttachmentRole::Resume => "R",
+            AttachmentRole::CoverLetter => "C",
+            AttachmentRole::OfferLetter => "L",
+            AttachmentRole::TakeHome => "T",
+            AttachmentRole::Other => "O",
+        }
+        .to_value()
+    }
+}
+
+/// Intermediate for [AttachmentRole], since parsing a stored role can fail
+pub struct AttachmentRoleIr(AttachmentRole);
+
+impl TryFrom<Value> for AttachmentRoleIr {
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let role = match &value {
+            Value::Bytes(bytes) => match bytes.as_slice() {
+                b"R" => AttachmentRole::Resume,
+                b"C" => AttachmentRole::CoverLetter,
+                b"L" => AttachmentRole::OfferLetter,
+                b"T" => AttachmentRole::TakeHome,
+                b"O" => AttachmentRole::Other,
+                _ => return Err(FromValueError(value)),
+            },
+            _ => return Err(FromValueError(value)),
+        };
+        Ok(AttachmentRoleIr(role))
+    }
+}
+
+impl From<AttachmentRoleIr> for AttachmentRole {
+    fn from(value: AttachmentRoleIr) -> Self {
+        value.0
+    }
+}
+
+// Needed to derive `FromRow` for `Attachment`, which gives back the value when a row can't be read
+impl From<AttachmentRoleIr> for Value {
+    fn from(value: AttachmentRoleIr) -> Self {
+        value.0.into()
+    }
+}
+
+impl FromValue for AttachmentRole {
+    type Intermediate = AttachmentRoleIr;
+}
+
+// Implemented like `AttachmentRole`, so `JournalOperation` can be used as a parameter
+impl From<JournalOperation> for Value {
+    fn from(value: JournalOperation) -> Self {
+        match value {
+            JournalOperation::Insert => "I",
+            JournalOperation::Update => "U",
+            JournalOperation::Delete => "D",
+            JournalOperation::Restore => "R",
+        }
+        .to_value()
+    }
+}
+
+/// Intermediate for [JournalOperation], since parsing a stored operation can fail
+pub struct JournalOperationIr(JournalOperation);
+
+impl TryFrom<Value> for JournalOperationIr {
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let operation = match &value {
+            Value::Bytes(bytes) => match bytes.as_slice() {
+                b"I" => JournalOperation::Insert,
+                b"U" => JournalOperation::Update,
+                b"D" => JournalOperation::Delete,
+                b"R" => JournalOperation::Restore,
+                _ => return Err(FromValueError(value)),
+            },
+            _ => return Err(FromValueError(value)),
+        };
+        Ok(JournalOperationIr(operation))
+    }
+}
+
+impl From<JournalOperationIr> for JournalOperation {
+    fn from(value: JournalOperationIr) -> Self {
+        value.0
+    }
+}
+
+// Needed to derive `FromRow` for `JournalEntry`, which gives back the value when a row can't be read
+impl From<JournalOperationIr> for Value {
+    fn from(value: JournalOperationIr) -> Self {
+        value.0.into()
+    }
+}
+
+impl FromValue for JournalOperation {
+    type Intermediate = JournalOperationIr;
+}
+
+// Implemented like `JournalOperation`
+impl From<AuditOperation> for Value {
+    fn from(value: AuditOperation) -> Self {
+        match value {
+            AuditOperation::Insert => "I",
+            AuditOperation::Update => "U",
+            AuditOperation::Delete => "D",
+            AuditOperation::Restore => "R",
+            AuditOperation::Purge => "P",
+        }
+        .to_value()
+    }
+}
+
+/// Intermediate for [AuditOperation], since parsing a stored operation can fail
+pub struct AuditOperationIr(AuditOperation);
+
+impl TryFrom<Value> for AuditOperationIr {
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let operation = match &value {
+            Value::Bytes(bytes) => match bytes.as_slice() {
+                b"I" => AuditOperation::Insert,
+                b"U" => AuditOperation::Update,
+                b"D" => AuditOperation::Delete,
+                b"R" => AuditOperation::Restore,
+                b"P" => AuditOperation::Purge,
+                _ => return Err(FromValueError(value)),
+            },
+            _ => return Err(FromValueError(value)),
+        };
+        Ok(AuditOperationIr(operation))
+    }
+}
+
+impl From<AuditOperationIr> for AuditOperation {
+    fn from(value: AuditOperationIr) -> Self {
+        value.0
+    }
+}
+
+impl From<AuditOperationIr> for Value {
+    fn from(value: AuditOperationIr) -> Self {
+        value.0.into()
+    }
+}
+
+impl FromValue for AuditOperation {
+    type Intermediate = AuditOperationIr;
+}
+
 impl ToValue for JobApplicationField {
     fn to_value(&self) -> Value {
         match self {
@@ -59,14 +504,26 @@ impl ToValue for JobApplicationField {
             JobApplicationField::HumanResponseDate(o) => o.to_value(),
             JobApplicationField::ApplicationWebsite(o) => o.to_value(),
             JobApplicationField::Notes(o) => o.to_value(),
+            JobApplicationField::SalaryMin(o) => o.to_value(),
+            JobApplicationField::SalaryMax(o) => o.to_value(),
+            JobApplicationField::SalaryCurrency(o) => o.to_value(),
+            JobApplicationField::PayPeriod(o) => o.to_value(),
+            JobApplicationField::Equity(o) => o.to_value(),
+            JobApplicationField::Bonus(o) => o.to_value(),
+            JobApplicationField::City(o) => o.to_value(),
+            JobApplicationField::Region(o) => o.to_value(),
+            JobApplicationField::Country(o) => o.to_value(),
+            JobApplicationField::WorkArrangement(o) => o.to_value(),
+            JobApplicationField::Relocation(o) => o.to_value(),
+            JobApplicationField::VisaSponsorship(o) => o.to_value(),
         }
     }
 }
 
-impl Into<Params> for PartialJobApplication {
-    fn into(self) -> Params {
-        let mut params_map: HashMap<Vec<u8>, Value> = HashMap::with_capacity(self.0.len());
-        for field in self.0 {
+impl From<PartialJobApplication> for Params {
+    fn from(value: PartialJobApplication) -> Self {
+        let mut params_map: HashMap<Vec<u8>, Value> = HashMap::with_capacity(value.0.len());
+        for field in value.0 {
             params_map.insert(field.name().as_bytes().to_vec(), field.to_value());
         }
         Params::Named(params_map)
@@ -85,6 +542,7 @@ mod tests {
         // Example job application
         let example_job_application = JobApplication {
             id: 12,
+            version: 3,
             source: "foo source".to_owned(),
             company: "foo company".to_owned(),
             job_title: "foo job".to_owned(),
@@ -94,6 +552,19 @@ mod tests {
             human_response_date: Some(Date::from_calendar_date(2001, Month::February, 3).unwrap()),
             application_website: Some("foo website".to_owned()),
             notes: Some("foo notes".to_owned()),
+            salary_min: Some(100000.0),
+            salary_max: Some(120000.5),
+            salary_currency: Some("USD".to_owned()),
+            pay_period: Some(PayPeriod::Yearly),
+            equity: Some("foo equity".to_owned()),
+            bonus: Some("foo bonus".to_owned()),
+            city: Some("foo city".to_owned()),
+            region: Some("foo region".to_owned()),
+            country: Some("foo country".to_owned()),
+            work_arrangement: Some(WorkArrangement::Hybrid),
+            relocation: Some(true),
+            visa_sponsorship: Some(false),
+            custom_fields: CustomFields::new(),
         };
 
         // Convert using impl Into<Params> for &JobApplication
@@ -102,6 +573,7 @@ mod tests {
             // Into<Params> never applies id because MySQL auto increment handles that
             // It still is converted because of the full `update_job_application`
             (b"id".to_vec(), Value::Int(12)),
+            (b"version".to_vec(), Value::Int(3)),
             (b"source".to_vec(), Value::Bytes(b"foo source".to_vec())),
             (b"company".to_vec(), Value::Bytes(b"foo company".to_vec())),
             (b"job_title".to_vec(), Value::Bytes(b"foo job".to_vec())),
@@ -123,6 +595,18 @@ mod tests {
                 Value::Bytes(b"foo website".to_vec()),
             ),
             (b"notes".to_vec(), Value::Bytes(b"foo notes".to_vec())),
+            (b"salary_min".to_vec(), Value::Double(100000.0)),
+            (b"salary_max".to_vec(), Value::Double(120000.5)),
+            (b"salary_currency".to_vec(), Value::Bytes(b"USD".to_vec())),
+            (b"pay_period".to_vec(), Value::Bytes(b"Y".to_vec())),
+            (b"equity".to_vec(), Value::Bytes(b"foo equity".to_vec())),
+            (b"bonus".to_vec(), Value::Bytes(b"foo bonus".to_vec())),
+            (b"city".to_vec(), Value::Bytes(b"foo city".to_vec())),
+            (b"region".to_vec(), Value::Bytes(b"foo region".to_vec())),
+            (b"country".to_vec(), Value::Bytes(b"foo country".to_vec())),
+            (b"work_arrangement".to_vec(), Value::Bytes(b"H".to_vec())),
+            (b"relocation".to_vec(), Value::Int(1)),
+            (b"visa_sponsorship".to_vec(), Value::Int(0)),
         ]);
 
         // Ensure the params are named
@@ -146,6 +630,7 @@ mod tests {
         // Example job application
         let example_job_application = JobApplication {
             id: 12,
+            version: 3,
             source: "foo source".to_owned(),
             company: "foo company".to_owned(),
             job_title: "foo job".to_owned(),
@@ -155,6 +640,19 @@ mod tests {
             human_response_date: None,
             application_website: None,
             notes: None,
+            salary_min: None,
+            salary_max: None,
+            salary_currency: None,
+            pay_period: None,
+            equity: None,
+            bonus: None,
+            city: None,
+            region: None,
+            country: None,
+            work_arrangement: None,
+            relocation: None,
+            visa_sponsorship: None,
+            custom_fields: CustomFields::new(),
         };
 
         // Convert using impl Into<Params> for &JobApplication
@@ -163,6 +661,7 @@ mod tests {
             // Into<Params> never applies id because MySQL auto increment handles that
             // It still is converted because of the full `update_job_application`
             (b"id".to_vec(), Value::Int(12)),
+            (b"version".to_vec(), Value::Int(3)),
             (b"source".to_vec(), Value::Bytes(b"foo source".to_vec())),
             (b"company".to_vec(), Value::Bytes(b"foo company".to_vec())),
             (b"job_title".to_vec(), Value::Bytes(b"foo job".to_vec())),
@@ -175,6 +674,18 @@ mod tests {
             (b"human_response_date".to_vec(), Value::NULL),
             (b"application_website".to_vec(), Value::NULL),
             (b"notes".to_vec(), Value::NULL),
+            (b"salary_min".to_vec(), Value::NULL),
+            (b"salary_max".to_vec(), Value::NULL),
+            (b"salary_currency".to_vec(), Value::NULL),
+            (b"pay_period".to_vec(), Value::NULL),
+            (b"equity".to_vec(), Value::NULL),
+            (b"bonus".to_vec(), Value::NULL),
+            (b"city".to_vec(), Value::NULL),
+            (b"region".to_vec(), Value::NULL),
+            (b"country".to_vec(), Value::NULL),
+            (b"work_arrangement".to_vec(), Value::NULL),
+            (b"relocation".to_vec(), Value::NULL),
+            (b"visa_sponsorship".to_vec(), Value::NULL),
         ]);
 
         // Ensure the params are named
@@ -1,10 +1,18 @@
+use std::io::{Read, Write};
+
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
 use rusqlite::{
     types::{FromSql, FromSqlError, ToSqlOutput, ValueRef},
     Row, ToSql,
 };
 use time::{ext::NumericalDuration, Duration};
 
-use super::{HumanResponse, JobApplication, JobApplicationField, PartialJobApplication};
+use super::{
+    Attachment, AttachmentRole, CustomField, CustomFieldType, CustomFields, DeletedJobApplication,
+    HumanResponse, JobApplication, JobApplicationField, JobDescription, JournalEntry,
+    JournalOperation, Offer, PartialJobApplication, PayPeriod, ResumeVersion, ResumeVersionStats,
+    Tag, WorkArrangement, WorkArrangementStats,
+};
 
 impl TryFrom<&Row<'_>> for JobApplication {
     type Error = rusqlite::Error;
@@ -12,6 +20,7 @@ impl TryFrom<&Row<'_>> for JobApplication {
     fn try_from(value: &Row) -> Result<Self, Self::Error> {
         Ok(JobApplication {
             id: value.get("id")?,
+            version: value.get("version")?,
             source: value.get("source")?,
             company: value.get("company")?,
             job_title: value.get("job_title")?,
@@ -23,10 +32,162 @@ impl TryFrom<&Row<'_>> for JobApplication {
             human_response_date: value.get("human_response_date")?,
             application_website: value.get("application_website")?,
             notes: value.get("notes")?,
+            salary_min: value.get("salary_min")?,
+            salary_max: value.get("salary_max")?,
+            salary_currency: value.get("salary_currency")?,
+            pay_period: value.get("pay_period")?,
+            equity: value.get("equity")?,
+            bonus: value.get("bonus")?,
+            city: value.get("city")?,
+            region: value.get("region")?,
+            country: value.get("country")?,
+            work_arrangement: value.get("work_arrangement")?,
+            relocation: value.get("relocation")?,
+            visa_sponsorship: value.get("visa_sponsorship")?,
+            custom_fields: CustomFields::new(),
+        })
+    }
+}
+
+impl TryFrom<&Row<'_>> for DeletedJobApplication {
+    type Error = rusqlite::Error;
+
+    fn try_from(value: &Row) -> Result<Self, Self::Error> {
+        Ok(DeletedJobApplication {
+            job_application: value.try_into()?,
+            deleted_at: value.get("deleted_at")?,
+        })
+    }
+}
+
+impl TryFrom<&Row<'_>> for Offer {
+    type Error = rusqlite::Error;
+
+    fn try_from(value: &Row) -> Result<Self, Self::Error> {
+        Ok(Offer {
+            job_application_id: value.get("job_application_id")?,
+            base_salary: value.get("base_salary")?,
+            pay_period: value.get("pay_period")?,
+            currency: value.get("currency")?,
+            bonus: value.get("bonus")?,
+            equity_value: value.get("equity_value")?,
+            vesting_years: value.get("vesting_years")?,
+            benefits: value.get("benefits")?,
+            start_date: value.get("start_date")?,
+            deadline: value.get("deadline")?,
+        })
+    }
+}
+
+impl TryFrom<&Row<'_>> for JobDescription {
+    type Error = rusqlite::Error;
+
+    fn try_from(value: &Row) -> Result<Self, Self::Error> {
+        Ok(JobDescription {
+            job_application_id: value.get("job_application_id")?,
+            description: value.get::<&str, CompressedText>("description")?.0,
+            captured_date: value.get("captured_date")?,
+        })
+    }
+}
+
+impl TryFrom<&Row<'_>> for Attachment {
+    type Error = rusqlite::Error;
+
+    fn try_from(value: &Row) -> Result<Self, Self::Error> {
+        Ok(Attachment {
+            id: value.get("id")?,
+            job_application_id: value.get("job_application_id")?,
+            role: value.get("role")?,
+            file_name: value.get("file_name")?,
+            file_hash: value.get("file_hash")?,
+            size: value.get("size")?,
+            added_date: value.get("added_date")?,
+        })
+    }
+}
+
+impl TryFrom<&Row<'_>> for WorkArrangementStats {
+    type Error = rusqlite::Error;
+
+    fn try_from(value: &Row) -> Result<Self, Self::Error> {
+        Ok(WorkArrangementStats {
+            work_arrangement: value.get("work_arrangement")?,
+            applications: value.get("applications")?,
+            pending: value.get("pending")?,
+            rejections: value.get("rejections")?,
+            interviews: value.get("interviews")?,
+            offers: value.get("offers")?,
+        })
+    }
+}
+
+impl TryFrom<&Row<'_>> for ResumeVersion {
+    type Error = rusqlite::Error;
+
+    fn try_from(value: &Row) -> Result<Self, Self::Error> {
+        Ok(ResumeVersion {
+            id: value.get("id")?,
+            label: value.get("label")?,
+            file_hash: value.get("file_hash")?,
+            created_date: value.get("created_date")?,
+            notes: value.get("notes")?,
+        })
+    }
+}
+
+impl TryFrom<&Row<'_>> for ResumeVersionStats {
+    type Error = rusqlite::Error;
+
+    fn try_from(value: &Row) -> Result<Self, Self::Error> {
+        Ok(ResumeVersionStats {
+            resume_version_id: value.get("resume_version_id")?,
+            label: value.get("label")?,
+            applications: value.get("applications")?,
+            responses: value.get("responses")?,
+            interviews: value.get("interviews")?,
+            offers: value.get("offers")?,
+        })
+    }
+}
+
+impl TryFrom<&Row<'_>> for Tag {
+    type Error = rusqlite::Error;
+
+    fn try_from(value: &Row) -> Result<Self, Self::Error> {
+        Ok(Tag {
+            id: value.get("id")?,
+            name: value.get("name")?,
+        })
+    }
+}
+
+impl TryFrom<&Row<'_>> for CustomField {
+    type Error = rusqlite::Error;
+
+    fn try_from(value: &Row) -> Result<Self, Self::Error> {
+        Ok(CustomField {
+            id: value.get("id")?,
+            name: value.get("name")?,
+            field_type: value.get("field_type")?,
         })
     }
 }
 
+impl ToSql for CustomFieldType {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>, rusqlite::Error> {
+        Ok(self.to_string().into())
+    }
+}
+
+impl FromSql for CustomFieldType {
+    fn column_result(value: ValueRef<'_>) -> Result<Self, FromSqlError> {
+        value
+            .as_str()
+            .and_then(|s| s.parse().map_err(|e: String| FromSqlError::Other(e.into())))
+    }
+}
+
 impl ToSql for HumanResponse {
     fn to_sql(&self) -> Result<ToSqlOutput<'_>, rusqlite::Error> {
         Ok(match self {
@@ -55,6 +216,137 @@ impl FromSql for HumanResponse {
     }
 }
 
+impl ToSql for PayPeriod {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>, rusqlite::Error> {
+        Ok(match self {
+            PayPeriod::Hourly => "H",
+            PayPeriod::Daily => "D",
+            PayPeriod::Weekly => "W",
+            PayPeriod::Monthly => "M",
+            PayPeriod::Yearly => "Y",
+        }
+        .into())
+    }
+}
+
+impl FromSql for PayPeriod {
+    fn column_result(value: ValueRef<'_>) -> Result<Self, FromSqlError> {
+        // The stored letters are also accepted by `FromStr`
+        value
+            .as_str()
+            .and_then(|s| s.parse().map_err(|e: String| FromSqlError::Other(e.into())))
+    }
+}
+
+/// Text that is stored zlib compressed, since job descriptions can be long
+struct CompressedText(String);
+
+/// Compress text to be stored in a column read as [CompressedText]
+pub(crate) fn compress_text(text: &str) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    // Writing to a `Vec` can't fail
+    encoder
+        .write_all(text.as_bytes())
+        .and_then(|_| encoder.finish())
+        .expect("compressing into memory should not fail")
+}
+
+impl FromSql for CompressedText {
+    fn column_result(value: ValueRef<'_>) -> Result<Self, FromSqlError> {
+        let mut text = String::new();
+        ZlibDecoder::new(value.as_blob()?)
+            .read_to_string(&mut text)
+            .map_err(|e| FromSqlError::Other(e.into()))?;
+        Ok(CompressedText(text))
+    }
+}
+
+impl ToSql for WorkArrangement {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>, rusqlite::Error> {
+        Ok(match self {
+            WorkArrangement::Remote => "R",
+            WorkArrangement::Hybrid => "H",
+            WorkArrangement::Onsite => "O",
+        }
+        .into())
+    }
+}
+
+impl FromSql for WorkArrangement {
+    fn column_result(value: ValueRef<'_>) -> Result<Self, FromSqlError> {
+        // The stored letters are also accepted by `FromStr`
+        value
+            .as_str()
+            .and_then(|s| s.parse().map_err(|e: String| FromSqlError::Other(e.into())))
+    }
+}
+
+impl ToSql for AttachmentRole {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>, rusqlite::Error> {
+        Ok(match self {
+            AttachmentRole::Resume => "R",
+            AttachmentRole::CoverLetter => "C",
+            AttachmentRole::OfferLetter => "L",
+            AttachmentRole::TakeHome => "T",
+            AttachmentRole::Other => "O",
+        }
+        .into())
+    }
+}
+
+impl FromSql for AttachmentRole {
+    fn column_result(value: ValueRef<'_>) -> Result<Self, FromSqlError> {
+        match value.as_str()? {
+            "R" => Ok(AttachmentRole::Resume),
+            "C" => Ok(AttachmentRole::CoverLetter),
+            "L" => Ok(AttachmentRole::OfferLetter),
+            "T" => Ok(AttachmentRole::TakeHome),
+            "O" => Ok(AttachmentRole::Other),
+            s => Err(FromSqlError::Other(
+                format!("Unknown attachment role '{s}'").into(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<&Row<'_>> for JournalEntry {
+    type Error = rusqlite::Error;
+
+    fn try_from(value: &Row) -> Result<Self, Self::Error> {
+        Ok(JournalEntry {
+            id: value.get("id")?,
+            job_application_id: value.get("job_application_id")?,
+            operation: value.get("operation")?,
+        })
+    }
+}
+
+impl ToSql for JournalOperation {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>, rusqlite::Error> {
+        Ok(match self {
+            JournalOperation::Insert => "I",
+            JournalOperation::Update => "U",
+            JournalOperation::Delete => "D",
+            JournalOperation::Restore => "R",
+        }
+        .into())
+    }
+}
+
+impl FromSql for JournalOperation {
+    fn column_result(value: ValueRef<'_>) -> Result<Self, FromSqlError> {
+        match value.as_str()? {
+            "I" => Ok(JournalOperation::Insert),
+            "U" => Ok(JournalOperation::Update),
+            "D" => Ok(JournalOperation::Delete),
+            "R" => Ok(JournalOperation::Restore),
+            s => Err(FromSqlError::Other(
+                format!("Unknown journal operation '{s}'").into(),
+            )),
+        }
+    }
+}
+
 impl From<JobApplicationField> for Box<dyn ToSql> {
     fn from(value: JobApplicationField) -> Self {
         match value {
@@ -68,9 +360,21 @@ impl From<JobApplicationField> for Box<dyn ToSql> {
             }
             JobApplicationField::HumanResponse(value) => Box::new(value),
             JobApplicationField::HumanResponseDate(value) => Box::new(value),
-            JobApplicationField::ApplicationWebsite(value) | JobApplicationField::Notes(value) => {
+            JobApplicationField::ApplicationWebsite(value)
+            | JobApplicationField::Notes(value)
+            | JobApplicationField::SalaryCurrency(value)
+            | JobApplicationField::Equity(value)
+            | JobApplicationField::Bonus(value)
+            | JobApplicationField::City(value)
+            | JobApplicationField::Region(value)
+            | JobApplicationField::Country(value) => Box::new(value),
+            JobApplicationField::SalaryMin(value) | JobApplicationField::SalaryMax(value) => {
                 Box::new(value)
             }
+            JobApplicationField::PayPeriod(value) => Box::new(value),
+            JobApplicationField::WorkArrangement(value) => Box::new(value),
+            JobApplicationField::Relocation(value)
+            | JobApplicationField::VisaSponsorship(value) => Box::new(value),
         }
     }
 }
@@ -1,8 +1,27 @@
-use std::{error::Error, fmt::Display};
+use std::{collections::BTreeMap, error::Error, fmt::Display, io, path::Path};
 
 use time::Date;
 
-use super::job_application_model::{HumanResponse, JobApplication, PartialJobApplication};
+use super::{
+    backup::{Backup, BackupRetention},
+    duplicates::{find_likely_duplicates, merge_job_applications},
+    job_application_model::{
+        Activity, Attachment, AttachmentRole, AuditLogEntry, CustomField, CustomFieldType,
+        CustomFieldValue, DeletedJobApplication, FieldChange, FullTextSearchResult, HumanResponse,
+        JobApplication, JobDescription, JournalEntry, NewJobApplication, Offer,
+        PartialJobApplication, ResumeVersion, ResumeVersionStats, Tag, WorkArrangementStats,
+    },
+    search_query::SearchQuery,
+    sync::{SyncChoice, SyncConflict, SyncReport},
+    validation::{validate_job_application, validate_new_job_application, FieldError},
+};
+
+/// Finding what changed in a job application, for the audit log and for showing conflicting changes
+mod audit_log;
+/// Checking custom field values and filters for the backends
+mod custom_fields;
+/// Splitting full-text queries into terms for the backends
+mod full_text;
 
 /// Implementation using a mysql backend
 #[cfg(feature = "mysql")]
@@ -16,70 +35,720 @@ mod sqlite_backend;
 ///
 /// This exists to uncouple dependents from the database implementation
 pub trait JobApplicationRepository {
-    /// The error that may be returned from CRUD operations
-    type Error: Error + Display + 'static;
-
     /// Get all job applications
-    fn get_job_applications(&mut self) -> Result<Vec<JobApplication>, Self::Error>;
+    fn get_job_applications(&mut self) -> Result<Vec<JobApplication>, RepositoryError>;
 
     /// Get all job applications where `human_response == None`
-    fn get_pending_job_applications(&mut self) -> Result<Vec<JobApplication>, Self::Error> {
+    fn get_pending_job_applications(&mut self) -> Result<Vec<JobApplication>, RepositoryError> {
         self.search_by_human_response(HumanResponse::None)
     }
 
     /// Get the job application matching the specified `id`
-    fn get_job_application_by_id(&mut self, id: i32)
-        -> Result<Option<JobApplication>, Self::Error>;
+    fn get_job_application_by_id(
+        &mut self,
+        id: i32,
+    ) -> Result<Option<JobApplication>, RepositoryError>;
+
+    /// Get the [JobApplication::version] of every job application outside the trash, keyed by id
+    ///
+    /// This is much quicker than getting the job applications themselves,
+    /// so it can be checked often to find the ones that were changed somewhere else.
+    fn get_job_application_versions(&mut self) -> Result<BTreeMap<i32, i32>, RepositoryError>;
+
+    /// Get a number that changes whenever another connection saves a change to the database, if the backend has one
+    ///
+    /// Changes made through this connection don't change it.
+    /// `None` means there is no quick way to tell, so [JobApplicationRepository::get_job_application_versions] has to be checked instead.
+    fn data_version(&mut self) -> Result<Option<i64>, RepositoryError>;
+
+    /// Get the [Activity] of every job application outside the trash, keyed by id
+    fn get_job_application_activity(&mut self) -> Result<BTreeMap<i32, Activity>, RepositoryError>;
 
     /// Get all job application where source, company, or job_title contains `query`. Case insensitive.
-    fn search_job_applications(&mut self, query: &str) -> Result<Vec<JobApplication>, Self::Error>;
+    fn search_job_applications(
+        &mut self,
+        query: &str,
+    ) -> Result<Vec<JobApplication>, RepositoryError>;
+
+    /// Get all job applications where any text field matches a full-text `query`, most relevant first
+    ///
+    /// Unlike [JobApplicationRepository::search_job_applications], this matches whole words instead of substrings,
+    /// includes `application_website`, `notes`, and the saved [JobDescription], and ignores case and accents for all of Unicode.
+    /// `query` may contain words, prefixes ending in `*` (e.g. `eng*`), and phrases in double quotes.
+    /// All of them must match either the job application's fields or its job description for it to be returned.
+    fn full_text_search(
+        &mut self,
+        query: &str,
+    ) -> Result<Vec<FullTextSearchResult>, RepositoryError>;
 
     /// Get all job applications with a certain human response
     fn search_by_human_response(
         &mut self,
         human_response: HumanResponse,
-    ) -> Result<Vec<JobApplication>, Self::Error>;
+    ) -> Result<Vec<JobApplication>, RepositoryError>;
 
     /// Get all job applications that matches a given human response AND a given search query
     fn search_by_query_and_human_response(
         &mut self,
         query: &str,
         human_response: HumanResponse,
-    ) -> Result<Vec<JobApplication>, Self::Error>;
+    ) -> Result<Vec<JobApplication>, RepositoryError>;
+
+    /// Get all job applications that match every filter in a parsed search query
+    ///
+    /// An empty query returns all job applications.
+    fn query_job_applications(
+        &mut self,
+        query: &SearchQuery,
+    ) -> Result<Vec<JobApplication>, RepositoryError>;
 
-    /// Insert a new job application, returning the new application with generated `id` and `application_date`.
+    /// Insert a new job application, returning it as it was saved, with its generated `id`
     ///
-    /// `id` and `application_date` are automatically generated by the next available id and the current date, respectively.
+    /// Every value in `custom_fields` must belong to a defined custom field and have the right type.
     fn insert_job_application(
         &mut self,
-        application: &JobApplication,
-    ) -> Result<JobApplication, Self::Error>;
+        application: &NewJobApplication,
+    ) -> Result<JobApplication, RepositoryError>;
 
-    /// Update the human response of a job application
+    /// Update the human response of a job application, returning the updated application
     ///
     /// `human_response_date` is optional. If `None`, the date is generated as today.
+    /// In the event there is no job application with this id outside the trash, [RepositoryError::NotFound] will be returned.
     fn update_human_response(
         &mut self,
         id: i32,
         human_response: HumanResponse,
         human_response_date: Option<Date>,
-    ) -> Result<(), Self::Error>;
+    ) -> Result<JobApplication, RepositoryError>;
 
-    /// Update non-id fields of a job application
+    /// Update non-id fields of a job application, returning the updated application
     ///
     /// Finds a job application using the id of `application` and replaces all other fields with the data contained in `application`.
-    /// In the event there is no job application with a matching id, the database will remain unaffected and `Ok(())` will be returned.
-    fn update_job_application(&mut self, application: &JobApplication) -> Result<(), Self::Error>;
+    /// This includes `custom_fields`: custom fields missing from `application` are cleared.
+    /// In the event there is no job application with a matching id outside the trash, [RepositoryError::NotFound] will be returned.
+    /// If the saved job application has a different `version` than `application`, because it was changed since `application` was loaded,
+    /// nothing is saved and [RepositoryError::Conflict] is returned.
+    fn update_job_application(
+        &mut self,
+        application: &JobApplication,
+    ) -> Result<JobApplication, RepositoryError>;
 
     /// Update a job application, returning the updated application.
     ///
-    /// `partial_application` must contain one [JobApplicationField::Id] element or a runtime error will occur.
-    /// In the event there is no job application with a matching id, the database will remain unaffected and `Ok(())` will be returned.
+    /// `partial_application` must contain one [JobApplicationField::Id] element or [RepositoryError::Validation] will be returned.
+    /// In the event there is no job application with a matching id outside the trash, [RepositoryError::NotFound] will be returned.
     fn update_job_application_partial(
         &mut self,
         partial_application: PartialJobApplication,
-    ) -> Result<(), Self::Error>;
+    ) -> Result<JobApplication, RepositoryError>;
+
+    /// Move the job application with the specified `id` to the trash, returning the application that was moved
+    ///
+    /// Job applications in the trash are left out of every other query, even by id, but keep their tags, offer, description, and attachments
+    /// until the trash is emptied, so they can be restored with [JobApplicationRepository::restore_job_application].
+    /// In the event there is no job application with this id outside the trash, [RepositoryError::NotFound] will be returned.
+    fn delete_job_application(&mut self, id: i32) -> Result<JobApplication, RepositoryError>;
+
+    /// Insert several job applications at once, returning them with their generated `id`s in the same order
+    ///
+    /// Either all of them are inserted, or none are if any of them fails.
+    fn insert_many(
+        &mut self,
+        applications: &[NewJobApplication],
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
+        self.with_transaction(|tx| {
+            applications
+                .iter()
+                .map(|application| tx.insert_job_application(application))
+                .collect()
+        })
+    }
+
+    /// Update several job applications at once, like [JobApplicationRepository::update_job_application], returning them in the same order
+    ///
+    /// Either all of them are updated, or none are if any of them fails.
+    fn update_many(
+        &mut self,
+        applications: &[JobApplication],
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
+        self.with_transaction(|tx| {
+            applications
+                .iter()
+                .map(|application| tx.update_job_application(application))
+                .collect()
+        })
+    }
+
+    /// Move several job applications to the trash at once, like [JobApplicationRepository::delete_job_application], returning them in the same order
+    ///
+    /// Either all of them are moved, or none are if any of them fails.
+    fn delete_many(&mut self, ids: &[i32]) -> Result<Vec<JobApplication>, RepositoryError> {
+        self.with_transaction(|tx| {
+            ids.iter()
+                .map(|id| tx.delete_job_application(*id))
+                .collect()
+        })
+    }
+
+    /// Find the job applications that `application` is likely a duplicate of, using [find_likely_duplicates]
+    ///
+    /// `window_days` is how many days apart job applications to the same job can be sent, usually [crate::duplicate_window_days].
+    fn find_duplicates(
+        &mut self,
+        application: &NewJobApplication,
+        window_days: u32,
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
+        let job_applications = self.get_job_applications()?;
+        Ok(
+            find_likely_duplicates(application, &job_applications, window_days)
+                .into_iter()
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Merge the job application `merged_id` into `keep_id`, returning the combined job application
+    ///
+    /// The fields are combined with [merge_job_applications], everything related to `merged_id` is moved to `keep_id`
+    /// with [JobApplicationRepository::move_related_records], then `merged_id` is moved to the trash. Either all of that is saved, or none of it is.
+    /// In the event either job application doesn't exist outside the trash, [RepositoryError::NotFound] will be returned.
+    fn merge_job_applications(
+        &mut self,
+        keep_id: i32,
+        merged_id: i32,
+    ) -> Result<JobApplication, RepositoryError> {
+        if keep_id == merged_id {
+            return Err(RepositoryError::Validation(
+                "A job application can't be merged into itself".to_owned(),
+            ));
+        }
+
+        self.with_transaction(|tx| {
+            let mut get = |id| {
+                tx.get_job_application_by_id(id)?
+                    .ok_or_else(|| RepositoryError::job_application_not_found(id))
+            };
+            let keep = get(keep_id)?;
+            let merged = get(merged_id)?;
+
+            tx.move_related_records(merged_id, keep_id)?;
+            let combined = tx.update_job_application(&merge_job_applications(&keep, &merged))?;
+            tx.delete_job_application(merged_id)?;
+            Ok(combined)
+        })
+    }
+
+    /// Move the tags, offer, job description, attachments, and sent resume version of job application `from_id` to `to_id`
+    ///
+    /// Anything `to_id` already has is kept, so tags and attachments it already has, and an offer, job description,
+    /// or resume version if it already has one, are left with `from_id`.
+    fn move_related_records(&mut self, from_id: i32, to_id: i32) -> Result<(), RepositoryError>;
+
+    /// Run `transaction` so that every change it makes is saved together, or none are if it returns an error
+    ///
+    /// The connection given to `transaction` can be used like any other, and transactions can be nested.
+    /// Every write method already runs in its own transaction, so this is only needed to group several of them.
+    /// With MySQL, the connection must not already be in a transaction that wasn't started by this.
+    fn with_transaction<T, E, F>(&mut self, transaction: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut Self) -> Result<T, E>,
+        E: From<RepositoryError>;
+
+    /// Get every job application in the trash, most recently deleted first
+    fn get_deleted_job_applications(
+        &mut self,
+    ) -> Result<Vec<DeletedJobApplication>, RepositoryError>;
+
+    /// Take the job application with the specified `id` back out of the trash
+    ///
+    /// In the event there is no job application with this id in the trash, the database will remain unaffected and [RepositoryError::NotFound] will be returned.
+    fn restore_job_application(&mut self, id: i32) -> Result<(), RepositoryError>;
+
+    /// Permanently delete every job application in the trash, returning how many were deleted
+    fn empty_trash(&mut self) -> Result<usize, RepositoryError>;
+
+    /// Permanently delete the job applications that have been in the trash for at least `days` days, returning how many were deleted
+    fn purge_trash(&mut self, days: u32) -> Result<usize, RepositoryError>;
+
+    /// Undo the most recent change to a job application that hasn't been undone yet, returning what was undone
+    ///
+    /// Creating, updating, deleting, and restoring job applications are recorded in a journal that keeps the last [JOURNAL_LENGTH] changes,
+    /// except for job applications that have been permanently deleted. Undoing the creation of a job application moves it to the trash.
+    /// `None` means there was nothing to undo.
+    /// If the job application was changed since in a way that isn't in the journal, like by syncing,
+    /// [RepositoryError::Conflict] is returned instead of losing that change.
+    fn undo(&mut self) -> Result<Option<JournalEntry>, RepositoryError>;
+
+    /// Redo the most recently undone change, returning what was redone
+    ///
+    /// Undone changes can only be redone until another change is made. `None` means there was nothing to redo.
+    /// Like [JobApplicationRepository::undo], [RepositoryError::Conflict] is returned if the job application was changed since it was undone.
+    fn redo(&mut self) -> Result<Option<JournalEntry>, RepositoryError>;
+
+    /// Get every recorded change to the job application with the specified `job_application_id`, oldest first
+    ///
+    /// Only MySQL keeps an audit log, since it is the backend that can be shared. It records who made each change, and is written
+    /// in the same transaction as the change. The history is kept after the job application is permanently deleted.
+    /// SQLite always returns an empty list.
+    fn get_history(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Vec<AuditLogEntry>, RepositoryError>;
+
+    /// Get every tag that is on at least one job application, sorted by name
+    fn get_tags(&mut self) -> Result<Vec<Tag>, RepositoryError>;
+
+    /// Get the tags on the job application with the specified `job_application_id`, sorted by name
+    fn get_job_application_tags(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Vec<Tag>, RepositoryError>;
+
+    /// Put a tag on a job application, returning the tag
+    ///
+    /// The tag is created if no tag has this name yet (ignoring case). Leading and trailing whitespace is removed from `name`.
+    /// Adding a tag that the job application already has does nothing.
+    fn add_tag(&mut self, job_application_id: i32, name: &str) -> Result<Tag, RepositoryError>;
+
+    /// Take a tag off of a job application
+    ///
+    /// In the event the job application does not have the tag, the database will remain unaffected and `Ok(())` will be returned.
+    fn remove_tag(&mut self, job_application_id: i32, name: &str) -> Result<(), RepositoryError>;
+
+    /// Rename a tag everywhere it is used
+    ///
+    /// If another tag already has `new_name`, the two tags are merged.
+    /// In the event there is no tag named `name`, the database will remain unaffected and `Ok(())` will be returned.
+    fn rename_tag(&mut self, name: &str, new_name: &str) -> Result<(), RepositoryError>;
+
+    /// Get every custom field, sorted by name
+    fn get_custom_fields(&mut self) -> Result<Vec<CustomField>, RepositoryError>;
+
+    /// Define a new custom field, returning it
+    ///
+    /// Leading and trailing whitespace is removed from `name`. It is an error if a custom field already has this name (ignoring case).
+    fn add_custom_field(
+        &mut self,
+        name: &str,
+        field_type: &CustomFieldType,
+    ) -> Result<CustomField, RepositoryError>;
+
+    /// Delete a custom field, along with its values on every job application
+    ///
+    /// In the event there is no custom field named `name`, the database will remain unaffected and `Ok(())` will be returned.
+    fn delete_custom_field(&mut self, name: &str) -> Result<(), RepositoryError>;
+
+    /// Set the value of a custom field on a job application, or clear it if `value` is `None`
+    ///
+    /// It is an error if there is no custom field named `name` (ignoring case) or `value` is the wrong type for it.
+    fn set_custom_field_value(
+        &mut self,
+        job_application_id: i32,
+        name: &str,
+        value: Option<&CustomFieldValue>,
+    ) -> Result<(), RepositoryError>;
+
+    /// Get the offer for the job application with the specified `job_application_id`, if it has one
+    fn get_offer(&mut self, job_application_id: i32) -> Result<Option<Offer>, RepositoryError>;
+
+    /// Get every offer, sorted by job application id
+    fn get_offers(&mut self) -> Result<Vec<Offer>, RepositoryError>;
+
+    /// Save an offer for the job application `offer.job_application_id`, replacing the offer it already has, if any
+    fn save_offer(&mut self, offer: &Offer) -> Result<(), RepositoryError>;
+
+    /// Delete the offer for a job application
+    ///
+    /// In the event the job application has no offer, the database will remain unaffected and `Ok(())` will be returned.
+    fn delete_offer(&mut self, job_application_id: i32) -> Result<(), RepositoryError>;
+
+    /// Get how job applications are going for each work arrangement
+    ///
+    /// Sorted remote, hybrid, then onsite, with job applications that have no work arrangement last.
+    /// Work arrangements without any job applications are left out.
+    fn get_work_arrangement_stats(&mut self) -> Result<Vec<WorkArrangementStats>, RepositoryError>;
+
+    /// Get the saved job posting description for the job application with the specified `job_application_id`, if it has one
+    fn get_job_description(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Option<JobDescription>, RepositoryError>;
+
+    /// Save a job posting description for the job application `job_description.job_application_id`, replacing the one it already has, if any
+    fn save_job_description(
+        &mut self,
+        job_description: &JobDescription,
+    ) -> Result<(), RepositoryError>;
+
+    /// Delete the saved job posting description for a job application
+    ///
+    /// In the event the job application has no description, the database will remain unaffected and `Ok(())` will be returned.
+    fn delete_job_description(&mut self, job_application_id: i32) -> Result<(), RepositoryError>;
+
+    /// Get the files attached to the job application with the specified `job_application_id`, in the order they were attached
+    fn get_attachments(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Vec<Attachment>, RepositoryError>;
+
+    /// Get the attachment with the specified `id`, if it exists
+    fn get_attachment(&mut self, id: i32) -> Result<Option<Attachment>, RepositoryError>;
+
+    /// Attach a file to a job application, returning the attachment
+    ///
+    /// Files are stored once for each unique contents, so attaching the same file to another job application doesn't store it again.
+    /// Attaching the same contents with the same role to the same job application returns the existing attachment.
+    /// Any directories are removed from `file_name`.
+    fn add_attachment(
+        &mut self,
+        job_application_id: i32,
+        role: AttachmentRole,
+        file_name: &str,
+        contents: &[u8],
+        added_date: Date,
+    ) -> Result<Attachment, RepositoryError>;
+
+    /// Get the contents of the file attached by the attachment with the specified `id`, if it exists
+    fn get_attachment_contents(&mut self, id: i32) -> Result<Option<Vec<u8>>, RepositoryError>;
+
+    /// Detach a file from its job application
+    ///
+    /// The file itself is deleted once nothing is attached to it.
+    /// In the event there is no attachment with this id, the database will remain unaffected and `Ok(())` will be returned.
+    fn delete_attachment(&mut self, id: i32) -> Result<(), RepositoryError>;
+
+    /// Get every resume version, in the order they were added
+    fn get_resume_versions(&mut self) -> Result<Vec<ResumeVersion>, RepositoryError>;
+
+    /// Add a resume version, returning it with its id
+    ///
+    /// Labels are unique, ignoring case, so adding one with an existing label is an error.
+    fn add_resume_version(
+        &mut self,
+        label: &str,
+        file_hash: Option<&str>,
+        created_date: Date,
+        notes: Option<&str>,
+    ) -> Result<ResumeVersion, RepositoryError>;
+
+    /// Delete a resume version
+    ///
+    /// Job applications that were sent with it no longer have a recorded resume version.
+    /// In the event there is no resume version with this id, the database will remain unaffected and `Ok(())` will be returned.
+    fn delete_resume_version(&mut self, id: i32) -> Result<(), RepositoryError>;
+
+    /// Get the resume version that was sent with the job application with the specified `job_application_id`, if one was recorded
+    fn get_sent_resume_version(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Option<ResumeVersion>, RepositoryError>;
+
+    /// Record which resume version was sent with a job application, replacing the one already recorded, if any
+    ///
+    /// `None` clears the recorded resume version.
+    fn set_sent_resume_version(
+        &mut self,
+        job_application_id: i32,
+        resume_version_id: Option<i32>,
+    ) -> Result<(), RepositoryError>;
+
+    /// Get how job applications are going for each resume version
+    ///
+    /// Sorted in the order the resume versions were added, with job applications that have no recorded resume version last.
+    /// Every resume version is included, even without any job applications, but job applications without a resume version are left out if there are none.
+    fn get_resume_version_stats(&mut self) -> Result<Vec<ResumeVersionStats>, RepositoryError>;
+
+    /// Get the actions only an SQLite database has, like backups and syncing, or `None` for any other backend
+    fn sqlite(&mut self) -> Option<&mut dyn SqliteRepository> {
+        None
+    }
+}
+
+/// Actions that only an SQLite database has, since a MySQL server and plain-text storage take care of these themselves
+///
+/// Get one from [JobApplicationRepository::sqlite].
+pub trait SqliteRepository {
+    /// Whether the database is encrypted, in which case changing its passphrase needs the current one
+    fn is_encrypted(&mut self) -> Result<bool, RepositoryError>;
+
+    /// Encrypt the database with `passphrase`, or store it as plain text if `passphrase` is `None`
+    ///
+    /// A database that is already encrypted has its passphrase changed, but only if `current_passphrase` is the one it has now.
+    /// Without the `sqlcipher` feature, this returns [RepositoryError::Validation].
+    fn set_passphrase(
+        &mut self,
+        passphrase: Option<&str>,
+        current_passphrase: Option<&str>,
+    ) -> Result<(), RepositoryError>;
+
+    /// Save a backup of the database, then remove the old backups that `retention` doesn't keep
+    fn take_backup(&mut self, retention: BackupRetention) -> Result<Backup, RepositoryError>;
+
+    /// Get every backup of the database, newest first
+    fn get_backups(&mut self) -> Result<Vec<Backup>, RepositoryError>;
+
+    /// Replace the database with a backup, after checking that the backup isn't damaged
+    ///
+    /// `file` is the name of a backup from [SqliteRepository::get_backups], or the path to one anywhere else.
+    /// An encrypted backup needs the passphrase the database had when it was taken, which the database has again once it is restored.
+    /// The database is backed up first, so the restore can be undone by restoring that backup, which is returned.
+    /// `current_passphrase` is the passphrase the database has now, if it is encrypted, so it can be opened again if the restore fails.
+    fn restore_backup(
+        &mut self,
+        file: &Path,
+        passphrase: Option<&str>,
+        current_passphrase: Option<&str>,
+    ) -> Result<Backup, RepositoryError>;
+
+    /// Sync with the database or sync file at `file`, so both have every change made to either one since they last synced
+    ///
+    /// `passphrase` is for `file`, if it is encrypted. `resolve` picks which value to keep for each field that was changed in both since they last synced.
+    /// Databases that haven't synced before keep the newest value of each field without asking.
+    fn sync_with(
+        &mut self,
+        file: &Path,
+        passphrase: Option<&str>,
+        resolve: &mut dyn FnMut(&SyncConflict) -> SyncChoice,
+    ) -> Result<SyncReport, RepositoryError>;
+
+    /// Save a new sync file at `file`, encrypted with `passphrase` if one is given, returning how many job applications it has
+    ///
+    /// Syncing another database with the file brings this database's changes into it, and its own changes into the file,
+    /// which this database gets by syncing with the file again.
+    fn export_sync_file(
+        &mut self,
+        file: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<usize, RepositoryError>;
+
+    /// Save every job application, with its tags and custom fields, as plain-text files in `dir`, returning how many there are
+    ///
+    /// `dir` can't have job applications in it already. Offers, job descriptions, attachments, and resume versions aren't saved.
+    fn export_plain_text(&mut self, dir: &Path) -> Result<usize, RepositoryError>;
+
+    /// Copy every job application, with its tags and custom fields, from the plain-text files in `dir`, returning how many there are
+    ///
+    /// The database has to be empty, so nothing in it is replaced or duplicated.
+    fn import_plain_text(&mut self, dir: &Path) -> Result<usize, RepositoryError>;
+}
+
+/// An error from any [JobApplicationRepository] method, the same for every backend
+///
+/// The variants other than [RepositoryError::Backend] are problems the user can do something about, so their messages are written to be shown to them.
+#[derive(Debug)]
+pub enum RepositoryError {
+    /// Something that was asked for doesn't exist, like a job application id or custom field name
+    NotFound(String),
+    /// The data given can't be saved as it is, like an empty tag name or a custom field value of the wrong type
+    Validation(String),
+    /// The job application given can't be saved as it is, with every field that is wrong
+    InvalidFields(Vec<FieldError>),
+    /// The change conflicts with something already saved, like a custom field name that is already used
+    Conflict(String),
+    /// The database refused the change because it would break one of its constraints, like a reference to a row that doesn't exist
+    Constraint(String),
+    /// Anything else that went wrong in the database or its connection
+    Backend(Box<dyn Error + Send + Sync>),
+}
+
+impl Display for RepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound(message)
+            | Self::Validation(message)
+            | Self::Conflict(message)
+            | Self::Constraint(message) => f.write_str(message),
+            Self::InvalidFields(errors) => {
+                let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+                f.write_str(&messages.join("\n"))
+            }
+            Self::Backend(source) => write!(f, "Database error: {source}"),
+        }
+    }
+}
+
+impl RepositoryError {
+    /// The error for a job application `id` that doesn't exist, or is in the trash
+    pub fn job_application_not_found(id: i32) -> Self {
+        Self::NotFound(format!("No application with id {id}"))
+    }
+
+    /// The error for saving job application `id` over a change made since it was loaded
+    pub(crate) fn job_application_changed(id: i32) -> Self {
+        Self::Conflict(format!(
+            "Application {id} was changed since it was loaded. Load it again to see the changes before saving"
+        ))
+    }
+
+    /// The error for undoing or redoing `entry` after its job application was changed in a way that isn't in the journal
+    pub(crate) fn journal_entry_changed(entry: &JournalEntry, undo: bool) -> Self {
+        let (action, undone) = if undo {
+            ("made", "undone")
+        } else {
+            ("undone", "redone")
+        };
+        Self::Conflict(format!(
+            "Application {} was changed since this change was {action}, so it can't be {undone}",
+            entry.job_application_id
+        ))
+    }
+}
+
+/// Check a job application with [validate_job_application] before it is saved
+fn validate(job_application: &JobApplication) -> Result<(), RepositoryError> {
+    validate_job_application(job_application).map_err(RepositoryError::InvalidFields)
+}
+
+/// Check a job application after some of its fields were changed, only reporting problems with the fields in `changed`
+///
+/// Job applications saved before the checks were added can break them, like with a website that isn't a web address,
+/// and that shouldn't stop their other fields from being changed, like to record a rejection.
+/// The response date is checked whenever the response or either date changes, since it has to make sense with them.
+fn validate_changed(
+    job_application: &JobApplication,
+    changed: &[String],
+) -> Result<(), RepositoryError> {
+    let is_changed = |field: &str| {
+        changed.iter().any(|changed| changed == field)
+            || (field == "human_response_date"
+                && ["human_response", "application_date"]
+                    .iter()
+                    .any(|date_field| changed.iter().any(|changed| changed == date_field)))
+    };
+    let Err(errors) = validate_job_application(job_application) else {
+        return Ok(());
+    };
+    let errors: Vec<FieldError> = errors
+        .into_iter()
+        .filter(|error| is_changed(error.field))
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(RepositoryError::InvalidFields(errors))
+    }
+}
+
+/// Check a new job application with [validate_new_job_application] before it is inserted
+fn validate_new(job_application: &NewJobApplication) -> Result<(), RepositoryError> {
+    validate_new_job_application(job_application).map_err(RepositoryError::InvalidFields)
+}
+
+impl Error for RepositoryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Backend(source) => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// For files kept alongside the database, like backups
+impl From<io::Error> for RepositoryError {
+    fn from(error: io::Error) -> Self {
+        Self::Backend(Box::new(error))
+    }
+}
+
+/// Find every field that differs between two versions of a job application, sorted by field name
+///
+/// Values are written the same way as in the audit log.
+/// This is useful for showing what was changed by someone else after [RepositoryError::Conflict].
+pub fn job_application_changes(
+    before: &JobApplication,
+    after: &JobApplication,
+) -> Vec<FieldChange> {
+    audit_log::field_changes(Some(before), Some(after))
+}
+
+/// How many changes to job applications are kept for [JobApplicationRepository::undo]
+pub const JOURNAL_LENGTH: u32 = 100;
+
+/// How many job applications the backends' [JobApplicationRepository::insert_many] and [JobApplicationRepository::delete_many] write with one statement
+///
+/// This keeps the statements well under the number of parameters SQLite and MySQL allow in one.
+const BATCH_SIZE: usize = 500;
+
+/// Remove any directories from a file name, and make sure there is something left
+///
+/// Both `/` and `\` are treated as separators, since the file may have been attached on another OS.
+fn clean_file_name(file_name: &str) -> Option<&str> {
+    Some(
+        file_name
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default()
+            .trim(),
+    )
+    .filter(|file_name| !file_name.is_empty())
+}
+
+/// SQL for [Activity::last_activity], given the function that takes the largest of its arguments (`MAX` in SQLite and `GREATEST` in MySQL)
+///
+/// Both give `NULL` if any argument is `NULL`, so dates that aren't set fall back to the application date.
+pub(crate) fn last_activity(greatest: &str) -> String {
+    format!(
+        "{greatest}(job_applications.application_date, \
+        COALESCE(job_applications.human_response_date, job_applications.application_date), \
+        COALESCE((SELECT captured_date FROM job_descriptions WHERE job_application_id = job_applications.id), job_applications.application_date), \
+        COALESCE((SELECT MAX(added_date) FROM attachments WHERE job_application_id = job_applications.id), job_applications.application_date))"
+    )
+}
+
+/// Select every column of [Activity] for job applications outside the trash, used by the backends' [JobApplicationRepository::get_job_application_activity]
+fn activity_query(greatest: &str) -> String {
+    format!(
+        "SELECT id, created_at, updated_at, {} AS last_activity \
+        FROM job_applications \
+        WHERE deleted_at IS NULL",
+        last_activity(greatest)
+    )
+}
+
+/// Counts per work arrangement, used by the backends' [JobApplicationRepository::get_work_arrangement_stats]
+///
+/// Rejections include interviews that ended in a rejection, and interviews include offers.
+const WORK_ARRANGEMENT_STATS_QUERY: &str = "SELECT work_arrangement, \
+    COUNT(*) AS applications, \
+    COUNT(CASE WHEN human_response = 'N' THEN 1 END) AS pending, \
+    COUNT(CASE WHEN human_response IN ('R', 'IR') THEN 1 END) AS rejections, \
+    COUNT(CASE WHEN human_response IN ('I', 'IR', 'J') THEN 1 END) AS interviews, \
+    COUNT(CASE WHEN human_response = 'J' THEN 1 END) AS offers \
+    FROM job_applications \
+    WHERE deleted_at IS NULL \
+    GROUP BY work_arrangement";
+
+/// Counts per resume version, used by the backends' [JobApplicationRepository::get_resume_version_stats]
+///
+/// Left joins keep resume versions without any job applications. The second half counts job applications without a resume version.
+const RESUME_VERSION_STATS_QUERY: &str = "SELECT resume_versions.id AS resume_version_id, \
+    resume_versions.label, \
+    COUNT(job_applications.id) AS applications, \
+    COUNT(CASE WHEN human_response <> 'N' THEN 1 END) AS responses, \
+    COUNT(CASE WHEN human_response IN ('I', 'IR', 'J') THEN 1 END) AS interviews, \
+    COUNT(CASE WHEN human_response = 'J' THEN 1 END) AS offers \
+    FROM resume_versions \
+    LEFT JOIN sent_resume_versions ON sent_resume_versions.resume_version_id = resume_versions.id \
+    LEFT JOIN job_applications ON job_applications.id = sent_resume_versions.job_application_id AND job_applications.deleted_at IS NULL \
+    GROUP BY resume_versions.id, resume_versions.label \
+    UNION ALL \
+    SELECT NULL, NULL, \
+    COUNT(*), \
+    COUNT(CASE WHEN human_response <> 'N' THEN 1 END), \
+    COUNT(CASE WHEN human_response IN ('I', 'IR', 'J') THEN 1 END), \
+    COUNT(CASE WHEN human_response = 'J' THEN 1 END) \
+    FROM job_applications \
+    WHERE deleted_at IS NULL AND id NOT IN (SELECT job_application_id FROM sent_resume_versions) \
+    HAVING COUNT(*) > 0";
+
+/// Put resume version stats in the order documented on [JobApplicationRepository::get_resume_version_stats]
+///
+/// Resume version ids increase as they are added, so sorting by id keeps that order.
+fn sort_resume_version_stats(stats: &mut [ResumeVersionStats]) {
+    stats.sort_by_key(|stats| (stats.resume_version_id.is_none(), stats.resume_version_id));
+}
 
-    /// Delete the job application with the specified `id`
-    fn delete_job_application(&mut self, id: i32) -> Result<(), Self::Error>;
+/// Put work arrangement stats in the order documented on [JobApplicationRepository::get_work_arrangement_stats]
+fn sort_work_arrangement_stats(stats: &mut [WorkArrangementStats]) {
+    stats.sort_by_key(|stats| (stats.work_arrangement.is_none(), stats.work_arrangement));
 }
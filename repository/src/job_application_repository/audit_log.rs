@@ -0,0 +1,238 @@
+//! Backend-independent comparison of job applications for the audit log
+//!
+//! The audit log stores each changed field as text, so the backends only have to save what is found here.
+//! The same comparison shows what changed when saving runs into someone else's change.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use time::{Date, Duration};
+
+use crate::job_application_model::{FieldChange, JobApplication};
+
+/// Get who is making changes, from `AUDIT_USER`, then `DB_USER`
+///
+/// `None` means neither is set, so the backend should use its own idea of the current user.
+#[cfg(feature = "mysql")]
+pub(crate) fn audit_user() -> Option<String> {
+    ["AUDIT_USER", "DB_USER"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .map(|user| user.trim().to_owned())
+        .find(|user| !user.is_empty())
+}
+
+/// Find every field that differs between `before` and `after`, sorted by field name
+///
+/// `None` stands for a job application that doesn't exist, so creating a job application gives every field it has,
+/// and permanently deleting one gives every field it had.
+pub(crate) fn field_changes(
+    before: Option<&JobApplication>,
+    after: Option<&JobApplication>,
+) -> Vec<FieldChange> {
+    let before = before.map(field_values).unwrap_or_default();
+    let after = after.map(field_values).unwrap_or_default();
+
+    before
+        .keys()
+        .chain(after.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|field| before.get(*field) != after.get(*field))
+        .map(|field| FieldChange {
+            field: field.clone(),
+            old_value: before.get(field).cloned(),
+            new_value: after.get(field).cloned(),
+        })
+        .collect()
+}
+
+/// Get the value of every field of a job application that isn't empty, keyed by column name
+///
+/// Custom fields are keyed by `field.<name>`.
+fn field_values(job_application: &JobApplication) -> BTreeMap<String, String> {
+    // Written the same way as the frontends show them
+    let format_date = |date: Date| {
+        format!(
+            "{:02}/{:02}/{}",
+            date.month() as u8,
+            date.day(),
+            date.year()
+        )
+    };
+    let format_minutes = |time: Duration| {
+        format!(
+            "{:02}:{:02}",
+            time.whole_minutes(),
+            time.whole_seconds() % 60
+        )
+    };
+    let yes_or_no = |value: bool| if value { "yes" } else { "no" }.to_owned();
+
+    [
+        ("source", Some(job_application.source.clone())),
+        ("company", Some(job_application.company.clone())),
+        ("job_title", Some(job_application.job_title.clone())),
+        (
+            "application_date",
+            Some(format_date(job_application.application_date)),
+        ),
+        (
+            "time_investment",
+            job_application.time_investment.map(format_minutes),
+        ),
+        (
+            "human_response",
+            Some(job_application.human_response.to_string()),
+        ),
+        (
+            "human_response_date",
+            job_application.human_response_date.map(format_date),
+        ),
+        (
+            "application_website",
+            job_application.application_website.clone(),
+        ),
+        ("notes", job_application.notes.clone()),
+        (
+            "salary_min",
+            job_application.salary_min.map(|salary| salary.to_string()),
+        ),
+        (
+            "salary_max",
+            job_application.salary_max.map(|salary| salary.to_string()),
+        ),
+        ("salary_currency", job_application.salary_currency.clone()),
+        (
+            "pay_period",
+            job_application.pay_period.map(|period| period.to_string()),
+        ),
+        ("equity", job_application.equity.clone()),
+        ("bonus", job_application.bonus.clone()),
+        ("city", job_application.city.clone()),
+        ("region", job_application.region.clone()),
+        ("country", job_application.country.clone()),
+        (
+            "work_arrangement",
+            job_application
+                .work_arrangement
+                .map(|arrangement| arrangement.to_string()),
+        ),
+        ("relocation", job_application.relocation.map(yes_or_no)),
+        (
+            "visa_sponsorship",
+            job_application.visa_sponsorship.map(yes_or_no),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(field, value)| Some((field.to_owned(), value?)))
+    .chain(
+        job_application
+            .custom_fields
+            .iter()
+            .map(|(name, value)| (format!("field.{name}"), value.to_string())),
+    )
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use time::Month;
+
+    use super::*;
+    use crate::job_application_model::{CustomFieldValue, CustomFields, HumanResponse};
+
+    fn job_application() -> JobApplication {
+        JobApplication {
+            id: 1,
+            version: 1,
+            source: "LinkedIn".to_owned(),
+            company: "Acme".to_owned(),
+            job_title: "Engineer".to_owned(),
+            application_date: Date::from_calendar_date(2025, Month::January, 1).unwrap(),
+            time_investment: None,
+            human_response: HumanResponse::None,
+            human_response_date: None,
+            application_website: None,
+            notes: None,
+            salary_min: None,
+            salary_max: None,
+            salary_currency: None,
+            pay_period: None,
+            equity: None,
+            bonus: None,
+            city: None,
+            region: None,
+            country: None,
+            work_arrangement: None,
+            relocation: None,
+            visa_sponsorship: None,
+            custom_fields: CustomFields::new(),
+        }
+    }
+
+    #[test]
+    fn test_field_changes() {
+        let before = job_application();
+        let after = JobApplication {
+            company: "Acme Corp".to_owned(),
+            notes: Some("Referred by a friend".to_owned()),
+            relocation: Some(false),
+            custom_fields: CustomFields::from([(
+                "Req ID".to_owned(),
+                CustomFieldValue::Number(42.0),
+            )]),
+            ..before.clone()
+        };
+
+        assert_eq!(
+            field_changes(Some(&before), Some(&after)),
+            vec![
+                FieldChange {
+                    field: "company".to_owned(),
+                    old_value: Some("Acme".to_owned()),
+                    new_value: Some("Acme Corp".to_owned()),
+                },
+                FieldChange {
+                    field: "field.Req ID".to_owned(),
+                    old_value: None,
+                    new_value: Some("42".to_owned()),
+                },
+                FieldChange {
+                    field: "notes".to_owned(),
+                    old_value: None,
+                    new_value: Some("Referred by a friend".to_owned()),
+                },
+                FieldChange {
+                    field: "relocation".to_owned(),
+                    old_value: None,
+                    new_value: Some("no".to_owned()),
+                },
+            ]
+        );
+        assert_eq!(field_changes(Some(&after), Some(&after)), vec![]);
+    }
+
+    #[test]
+    fn test_field_changes_created_and_deleted() {
+        let created = field_changes(None, Some(&job_application()));
+        assert_eq!(
+            created
+                .iter()
+                .map(|change| change.field.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "application_date",
+                "company",
+                "human_response",
+                "job_title",
+                "source"
+            ]
+        );
+        assert!(created.iter().all(|change| change.old_value.is_none()));
+        assert_eq!(created[0].new_value.as_deref(), Some("01/01/2025"));
+
+        let deleted = field_changes(Some(&job_application()), None);
+        assert_eq!(deleted.len(), created.len());
+        assert!(deleted.iter().all(|change| change.new_value.is_none()));
+    }
+}
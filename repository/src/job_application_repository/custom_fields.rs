@@ -0,0 +1,200 @@
+//! Backend-independent handling of custom field values and custom field search filters
+//!
+//! Values are stored as text, one row per job application and field, so each backend only has to translate
+//! the checks made here into SQL.
+
+use crate::{
+    job_application_model::{CustomField, CustomFieldType, CustomFieldValue, CustomFields},
+    search_query::Comparison,
+};
+
+/// Find the custom field named `name`, ignoring case
+pub(crate) fn find_custom_field<'a>(
+    custom_fields: &'a [CustomField],
+    name: &str,
+) -> Result<&'a CustomField, String> {
+    custom_fields
+        .iter()
+        .find(|custom_field| custom_field.name.to_lowercase() == name.trim().to_lowercase())
+        .ok_or_else(|| format!("There is no custom field named '{}'", name.trim()))
+}
+
+/// Check that `value` can be stored in `custom_field`
+pub(crate) fn check_custom_field_value(
+    custom_field: &CustomField,
+    value: &CustomFieldValue,
+) -> Result<(), String> {
+    if custom_field.field_type.accepts(value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "'{value}' can't be stored in '{}', which is a {} field",
+            custom_field.name, custom_field.field_type
+        ))
+    }
+}
+
+/// Check every value of a job application against the defined custom fields
+///
+/// Returns the id of each value's custom field along with the value as it should be stored.
+pub(crate) fn prepare_custom_field_values(
+    custom_fields: &[CustomField],
+    values: &CustomFields,
+) -> Result<Vec<(i32, String)>, String> {
+    values
+        .iter()
+        .map(|(name, value)| {
+            let custom_field = find_custom_field(custom_fields, name)?;
+            check_custom_field_value(custom_field, value)?;
+            Ok((custom_field.id, value.to_stored_string()))
+        })
+        .collect()
+}
+
+/// How a custom field filter compares the stored text of a value
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum CustomFieldFilter {
+    /// The stored text contains this, ignoring case
+    Contains(String),
+    /// The stored text is this, ignoring case
+    EqualsIgnoringCase(String),
+    /// The stored text, as a number, compares to this
+    Number(Comparison, f64),
+    /// The stored text compares to this exactly. Used for dates, which are stored as `yyyy-mm-dd`, and booleans.
+    Text(Comparison, String),
+}
+
+/// Work out how to filter by a custom field from the comparison and value in a search query
+///
+/// Text fields match if they contain the value, and select fields match an option ignoring case.
+/// Only numbers and dates can use comparisons other than [Comparison::Equal].
+pub(crate) fn custom_field_filter(
+    custom_field: &CustomField,
+    comparison: Comparison,
+    value: &str,
+) -> Result<CustomFieldFilter, String> {
+    let is_orderable = matches!(
+        custom_field.field_type,
+        CustomFieldType::Number | CustomFieldType::Date
+    );
+    if comparison != Comparison::Equal && !is_orderable {
+        return Err(format!(
+            "'{}' is a {} field, so it can't be compared with {}",
+            custom_field.name,
+            custom_field.field_type,
+            comparison.operator()
+        ));
+    }
+
+    match (
+        &custom_field.field_type,
+        custom_field.field_type.parse_value(value)?,
+    ) {
+        (CustomFieldType::Text, _) => Ok(CustomFieldFilter::Contains(value.to_owned())),
+        (CustomFieldType::SingleSelect(_), value) => Ok(CustomFieldFilter::EqualsIgnoringCase(
+            value.to_stored_string(),
+        )),
+        (_, CustomFieldValue::Number(number)) => Ok(CustomFieldFilter::Number(comparison, number)),
+        (_, value) => Ok(CustomFieldFilter::Text(
+            comparison,
+            value.to_stored_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn custom_field(id: i32, name: &str, field_type: CustomFieldType) -> CustomField {
+        CustomField {
+            id,
+            name: name.to_owned(),
+            field_type,
+        }
+    }
+
+    /// Values are matched to fields by name, ignoring case, and must be the right type
+    ///
+    /// Keys are sorted case sensitively, so "Req ID" comes before "agency".
+    #[test]
+    fn test_prepare_custom_field_values() {
+        let custom_fields = [
+            custom_field(1, "Agency", CustomFieldType::Text),
+            custom_field(2, "Req ID", CustomFieldType::Number),
+        ];
+
+        assert_eq!(
+            prepare_custom_field_values(
+                &custom_fields,
+                &BTreeMap::from([
+                    (
+                        "agency".to_owned(),
+                        CustomFieldValue::Text("Hays".to_owned())
+                    ),
+                    ("Req ID".to_owned(), CustomFieldValue::Number(1234.0)),
+                ])
+            ),
+            Ok(vec![(2, "1234".to_owned()), (1, "Hays".to_owned())])
+        );
+        assert!(prepare_custom_field_values(
+            &custom_fields,
+            &BTreeMap::from([("Team".to_owned(), CustomFieldValue::Bool(true))])
+        )
+        .is_err());
+        assert!(prepare_custom_field_values(
+            &custom_fields,
+            &BTreeMap::from([("Req ID".to_owned(), CustomFieldValue::Bool(true))])
+        )
+        .is_err());
+    }
+
+    /// Each field type produces the right kind of filter, and comparisons are only allowed where they make sense
+    #[test]
+    fn test_custom_field_filter() {
+        let text = custom_field(1, "Agency", CustomFieldType::Text);
+        let select = custom_field(
+            2,
+            "Channel",
+            CustomFieldType::SingleSelect(vec!["Recruiter".to_owned()]),
+        );
+        let number = custom_field(3, "Req ID", CustomFieldType::Number);
+        let date = custom_field(4, "Follow up", CustomFieldType::Date);
+        let bool = custom_field(5, "Visa", CustomFieldType::Bool);
+
+        assert_eq!(
+            custom_field_filter(&text, Comparison::Equal, "hays"),
+            Ok(CustomFieldFilter::Contains("hays".to_owned()))
+        );
+        assert_eq!(
+            custom_field_filter(&select, Comparison::Equal, "recruiter"),
+            Ok(CustomFieldFilter::EqualsIgnoringCase(
+                "Recruiter".to_owned()
+            ))
+        );
+        assert_eq!(
+            custom_field_filter(&number, Comparison::Greater, "10"),
+            Ok(CustomFieldFilter::Number(Comparison::Greater, 10.0))
+        );
+        assert_eq!(
+            custom_field_filter(&date, Comparison::Less, "3/4/2025"),
+            Ok(CustomFieldFilter::Text(
+                Comparison::Less,
+                "2025-03-04".to_owned()
+            ))
+        );
+        assert_eq!(
+            custom_field_filter(&bool, Comparison::Equal, "yes"),
+            Ok(CustomFieldFilter::Text(
+                Comparison::Equal,
+                "true".to_owned()
+            ))
+        );
+
+        assert!(custom_field_filter(&text, Comparison::Less, "hays").is_err());
+        assert!(custom_field_filter(&number, Comparison::Equal, "lots").is_err());
+        assert!(custom_field_filter(&select, Comparison::Equal, "Direct").is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! Backend-independent handling of full-text search queries
+//!
+//! Users type queries like `backend eng* "senior engineer"`, which are split into [FullTextTerm]s here.
+//! Each backend translates the terms into its own full-text syntax so that user input never reaches the database as raw query syntax.
+
+/// A single term in a full-text search query. All terms in a query must match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FullTextTerm<'a> {
+    /// A word that must match exactly (ignoring case and accents)
+    Word(&'a str),
+    /// A word ending in `*`, matching any word that starts with it
+    Prefix(&'a str),
+    /// Words in double quotes that must appear next to each other in this order
+    Phrase(&'a str),
+}
+
+/// Split a user's full-text search query into terms
+///
+/// Words are separated by whitespace. Anything between double quotes is a phrase.
+/// An unterminated quote is treated as a phrase that continues to the end of the query.
+pub(crate) fn parse_full_text_query(query: &str) -> Vec<FullTextTerm<'_>> {
+    let mut terms = Vec::new();
+    let mut rest = query.trim_start();
+
+    while !rest.is_empty() {
+        if let Some(after_quote) = rest.strip_prefix('"') {
+            // Phrase: take everything until the closing quote (or the end of the query)
+            let (phrase, after_phrase) = after_quote.split_once('"').unwrap_or((after_quote, ""));
+            if !phrase.trim().is_empty() {
+                terms.push(FullTextTerm::Phrase(phrase.trim()));
+            }
+            rest = after_phrase;
+        } else {
+            // Word: take everything until the next whitespace or quote
+            let end = rest
+                .find(|c: char| c.is_whitespace() || c == '"')
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            match word.strip_suffix('*') {
+                // A lone "*" isn't a useful prefix, so just ignore it
+                Some("") => (),
+                Some(prefix) => terms.push(FullTextTerm::Prefix(prefix)),
+                None => terms.push(FullTextTerm::Word(word)),
+            }
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start();
+    }
+
+    terms
+}
+
+/// A `LIKE` pattern matching any value that contains `term`, for filters on a single field
+///
+/// `%`, `_`, and `\` in the term are escaped with `\`, which is MySQL's default escape character. SQLite has none, so it needs `ESCAPE '\'`.
+pub(crate) fn contains_pattern(term: &FullTextTerm) -> String {
+    let (FullTextTerm::Word(text) | FullTextTerm::Prefix(text) | FullTextTerm::Phrase(text)) = term;
+    let mut pattern = String::with_capacity(text.len() + 2);
+    pattern.push('%');
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// Build an excerpt of `text` around the first term that matches, surrounding matching words with snippet markers
+///
+/// MySQL has no equivalent of SQLite's built-in `snippet()` function, and SQLite's can't read compressed job descriptions.
+/// Returns `None` if no term matches any word in `text`.
+pub(crate) fn make_snippet(text: &str, terms: &[FullTextTerm]) -> Option<String> {
+    use crate::job_application_model::{SNIPPET_MATCH_END, SNIPPET_MATCH_START};
+
+    /// Number of words to show on either side of the first match
+    const CONTEXT_WORDS: usize = 5;
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let lowercase_words: Vec<String> = words
+        .iter()
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .collect();
+
+    // Mark every word that is part of a matching term
+    let mut is_match = vec![false; words.len()];
+    for term in terms {
+        match term {
+            FullTextTerm::Word(word) => {
+                let word = word.to_lowercase();
+                for (index, candidate) in lowercase_words.iter().enumerate() {
+                    is_match[index] |= *candidate == word;
+                }
+            }
+            FullTextTerm::Prefix(prefix) => {
+                let prefix = prefix.to_lowercase();
+                for (index, candidate) in lowercase_words.iter().enumerate() {
+                    is_match[index] |= candidate.starts_with(&prefix);
+                }
+            }
+            FullTextTerm::Phrase(phrase) => {
+                let phrase_words: Vec<String> =
+                    phrase.split_whitespace().map(str::to_lowercase).collect();
+                if phrase_words.is_empty() || phrase_words.len() > words.len() {
+                    continue;
+                }
+                for start in 0..=(words.len() - phrase_words.len()) {
+                    if lowercase_words[start..start + phrase_words.len()] == phrase_words[..] {
+                        is_match[start..start + phrase_words.len()].fill(true);
+                    }
+                }
+            }
+        }
+    }
+
+    let first_match = is_match.iter().position(|matched| *matched)?;
+    let start = first_match.saturating_sub(CONTEXT_WORDS);
+    let end = (first_match + CONTEXT_WORDS + 1).min(words.len());
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet += "...";
+    }
+    for index in start..end {
+        if !snippet.is_empty() && !snippet.ends_with("...") {
+            snippet += " ";
+        }
+        if is_match[index] {
+            snippet += SNIPPET_MATCH_START;
+            snippet += words[index];
+            snippet += SNIPPET_MATCH_END;
+        } else {
+            snippet += words[index];
+        }
+    }
+    if end < words.len() {
+        snippet += "...";
+    }
+
+    Some(snippet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ensure words, prefixes, and phrases are all recognized
+    #[test]
+    fn test_parse_full_text_query() {
+        assert_eq!(
+            parse_full_text_query(r#"backend eng* "senior engineer""#),
+            vec![
+                FullTextTerm::Word("backend"),
+                FullTextTerm::Prefix("eng"),
+                FullTextTerm::Phrase("senior engineer"),
+            ]
+        );
+    }
+
+    /// Ensure odd spacing and quoting does not produce empty terms
+    #[test]
+    fn test_parse_full_text_query_edge_cases() {
+        assert_eq!(parse_full_text_query("   "), vec![], "Blank query");
+        assert_eq!(parse_full_text_query(r#""""#), vec![], "Empty phrase");
+        assert_eq!(parse_full_text_query("*"), vec![], "Lone asterisk");
+        assert_eq!(
+            parse_full_text_query(r#"rust"remote work"#),
+            vec![
+                FullTextTerm::Word("rust"),
+                FullTextTerm::Phrase("remote work")
+            ],
+            "Unterminated phrase directly after a word"
+        );
+    }
+
+    /// Ensure snippets mark matches and are trimmed to the area around the first match
+    #[test]
+    fn test_make_snippet() {
+        let text =
+            "one two three four five six seven eight Engineering nine ten eleven twelve thirteen";
+
+        assert_eq!(
+            make_snippet(text, &[FullTextTerm::Prefix("engineer")]),
+            Some(
+                "...four five six seven eight [Engineering] nine ten eleven twelve thirteen"
+                    .to_owned()
+            )
+        );
+        assert_eq!(
+            make_snippet(
+                "senior software engineer",
+                &[FullTextTerm::Phrase("software engineer")]
+            ),
+            Some("senior [software] [engineer]".to_owned())
+        );
+        assert_eq!(make_snippet(text, &[FullTextTerm::Word("engineer")]), None);
+    }
+
+    /// Ensure wildcards in a field filter are matched literally
+    #[test]
+    fn test_contains_pattern() {
+        assert_eq!(contains_pattern(&FullTextTerm::Word("acm")), "%acm%");
+        assert_eq!(contains_pattern(&FullTextTerm::Prefix("eng")), "%eng%");
+        assert_eq!(
+            contains_pattern(&FullTextTerm::Phrase(r"100% C_\")),
+            r"%100\% C\_\\%"
+        );
+    }
+}
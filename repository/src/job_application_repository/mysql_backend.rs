@@ -1,95 +1,372 @@
-use mysql::{params, prelude::Queryable};
+use mysql::{
+    from_row, params,
+    prelude::{Queryable, ToValue},
+    Params, Row, Statement, Value,
+};
 
-use crate::job_application_model::JobApplicationField;
+use std::collections::HashMap;
 
-use super::*;
+use crate::{
+    job_application_model::{
+        Attachment, AttachmentRole, AuditLogEntry, AuditOperation, FieldChange,
+        JobApplicationField, JournalEntry, JournalOperation, Offer, ResumeVersion, Tag,
+    },
+    search_query::{LocationField, SearchCondition, SearchQuery},
+};
+
+use super::{
+    audit_log::{audit_user, field_changes},
+    custom_fields::{
+        check_custom_field_value, custom_field_filter, find_custom_field,
+        prepare_custom_field_values, CustomFieldFilter,
+    },
+    full_text::{contains_pattern, make_snippet, parse_full_text_query, FullTextTerm},
+    *,
+};
 
 impl<C> JobApplicationRepository for C
 where
     C: Queryable,
 {
-    type Error = mysql::Error;
-
-    fn get_job_applications(&mut self) -> Result<Vec<JobApplication>, mysql::Error> {
-        self.query(
-        "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes
-        FROM job_applications"
-    )
+    fn get_job_applications(&mut self) -> Result<Vec<JobApplication>, RepositoryError> {
+        let mut job_applications = self.query(
+        "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version
+        FROM job_applications
+        WHERE deleted_at IS NULL"
+    )?;
+        load_custom_fields(self, &mut job_applications)?;
+        Ok(job_applications)
     }
 
     fn get_job_application_by_id(
         &mut self,
         id: i32,
-    ) -> Result<Option<JobApplication>, mysql::Error> {
-        self.exec_first(
-        "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes
+    ) -> Result<Option<JobApplication>, RepositoryError> {
+        let mut job_application = self.exec_first(
+        "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version
         FROM job_applications
-        WHERE id = ?",
+        WHERE id = ? AND deleted_at IS NULL",
         (id,),
-    )
+    )?;
+        load_custom_fields(self, job_application.iter_mut())?;
+        Ok(job_application)
+    }
+
+    fn get_job_application_versions(&mut self) -> Result<BTreeMap<i32, i32>, RepositoryError> {
+        Ok(self
+            .query("SELECT id, version FROM job_applications WHERE deleted_at IS NULL")?
+            .into_iter()
+            .collect())
+    }
+
+    fn data_version(&mut self) -> Result<Option<i64>, RepositoryError> {
+        // MySQL has nothing like SQLite's data_version, so the versions are always checked
+        Ok(None)
+    }
+
+    fn get_job_application_activity(&mut self) -> Result<BTreeMap<i32, Activity>, RepositoryError> {
+        Ok(self
+            .query_map(
+                activity_query(GREATEST),
+                |(id, created_at, updated_at, last_activity)| {
+                    (
+                        id,
+                        Activity {
+                            created_at,
+                            updated_at,
+                            last_activity,
+                        },
+                    )
+                },
+            )?
+            .into_iter()
+            .collect())
     }
 
     fn search_job_applications(
         &mut self,
         query: &str,
-    ) -> Result<Vec<JobApplication>, mysql::Error> {
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
         // Add wildcards to the beginning and end of the query
         let query_with_wildcards = "%".to_owned() + &query.to_lowercase() + "%";
-        self.exec(
-        "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes
+        let mut job_applications = self.exec(
+        "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version
         FROM job_applications
-        WHERE LOWER(source) LIKE :query
-        OR LOWER(company) LIKE :query
-        OR LOWER(job_title) LIKE :query",
+        WHERE deleted_at IS NULL AND (
+            LOWER(source) LIKE :query
+            OR LOWER(company) LIKE :query
+            OR LOWER(job_title) LIKE :query
+        )",
         params! {"query" => query_with_wildcards}
-    )
+    )?;
+        load_custom_fields(self, &mut job_applications)?;
+        Ok(job_applications)
+    }
+
+    fn full_text_search(
+        &mut self,
+        query: &str,
+    ) -> Result<Vec<FullTextSearchResult>, RepositoryError> {
+        let terms = parse_full_text_query(query);
+        let boolean_mode_query = to_boolean_mode_query(&terms);
+
+        // An empty boolean mode query matches nothing anyway, so skip the round trip
+        if boolean_mode_query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut results = self.exec_map(
+        "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version, description,
+        MATCH (source, company, job_title, application_website, notes) AGAINST (:query IN BOOLEAN MODE)
+            + IFNULL(MATCH (description) AGAINST (:query IN BOOLEAN MODE), 0) AS relevance
+        FROM job_applications
+        LEFT JOIN job_descriptions ON job_descriptions.job_application_id = job_applications.id
+        WHERE deleted_at IS NULL AND (
+            MATCH (source, company, job_title, application_website, notes) AGAINST (:query IN BOOLEAN MODE)
+            OR MATCH (description) AGAINST (:query IN BOOLEAN MODE)
+        )
+        ORDER BY relevance DESC",
+        params! {"query" => boolean_mode_query},
+        |mut row: Row| {
+            let relevance: f64 = row.take("relevance").unwrap_or_default();
+            let description: Option<String> = row.take("description").unwrap_or_default();
+            let job_application: JobApplication = from_row(row);
+
+            // MySQL has no equivalent to SQLite's `snippet()`, so find the best field here
+            let snippet = [
+                Some(job_application.job_title.as_str()),
+                Some(job_application.company.as_str()),
+                Some(job_application.source.as_str()),
+                job_application.notes.as_deref(),
+                job_application.application_website.as_deref(),
+                description.as_deref(),
+            ]
+            .into_iter()
+            .flatten()
+            .find_map(|text| make_snippet(text, &terms))
+            .unwrap_or_default();
+
+            FullTextSearchResult {
+                job_application,
+                relevance,
+                snippet,
+            }
+        },
+    )?;
+        load_custom_fields(
+            self,
+            results.iter_mut().map(|result| &mut result.job_application),
+        )?;
+        Ok(results)
     }
 
     fn search_by_human_response(
         &mut self,
         human_response: HumanResponse,
-    ) -> Result<Vec<JobApplication>, mysql::Error> {
-        self.exec(
-        "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
+        let mut job_applications = self.exec(
+        "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version
         FROM job_applications
-        WHERE human_response = :human_response",
+        WHERE human_response = :human_response AND deleted_at IS NULL",
         params! {"human_response" => &human_response}
-    )
+    )?;
+        load_custom_fields(self, &mut job_applications)?;
+        Ok(job_applications)
     }
 
     fn search_by_query_and_human_response(
         &mut self,
         query: &str,
         human_response: HumanResponse,
-    ) -> Result<Vec<JobApplication>, mysql::Error> {
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
         // Add wildcards to the beginning and end of the query
         let query_with_wildcards = "%".to_owned() + &query.to_lowercase() + "%";
-        self.exec(
-        "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes
+        let mut job_applications = self.exec(
+        "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version
         FROM job_applications
         WHERE (
             LOWER(source) LIKE :query
             OR LOWER(company) LIKE :query
             OR LOWER(job_title) LIKE :query
-        ) AND human_response = :human_response",
+        ) AND human_response = :human_response AND deleted_at IS NULL",
         params! {
             "query" => query_with_wildcards,
             "human_response" => &human_response
         }
-    )
+    )?;
+        load_custom_fields(self, &mut job_applications)?;
+        Ok(job_applications)
+    }
+
+    fn query_job_applications(
+        &mut self,
+        query: &SearchQuery,
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
+        // Only column names and operators, which are defined statically in `search_query`, are put in the string.
+        // Everything from the query itself is passed as a parameter.
+        let mut query_builder = "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version
+        FROM job_applications
+        WHERE deleted_at IS NULL".to_owned();
+        let mut params: Vec<Value> = Vec::new();
+
+        // Custom field filters need to know the type of the field
+        let has_custom_field_filter = query
+            .filters
+            .iter()
+            .any(|filter| matches!(filter.condition, SearchCondition::CustomField(..)));
+        let custom_fields = if has_custom_field_filter {
+            self.get_custom_fields()?
+        } else {
+            Vec::new()
+        };
+
+        for filter in &query.filters {
+            let condition = match &filter.condition {
+                SearchCondition::FullText(text) => {
+                    let boolean_mode_query = to_boolean_mode_query(&parse_full_text_query(text));
+                    if boolean_mode_query.is_empty() {
+                        "TRUE".to_owned()
+                    } else {
+                        // Job descriptions are in their own table, so either index may match
+                        params.push(boolean_mode_query.clone().into());
+                        params.push(boolean_mode_query.into());
+                        "MATCH (source, company, job_title, application_website, notes) AGAINST (? IN BOOLEAN MODE)
+                        OR id IN (SELECT job_application_id FROM job_descriptions WHERE MATCH (description) AGAINST (? IN BOOLEAN MODE))".to_owned()
+                    }
+                }
+                SearchCondition::Field(field, text) => {
+                    // FULLTEXT indexes can only be matched against all of their columns at once, so use LIKE for a single column.
+                    // The table's collation makes LIKE case and accent insensitive.
+                    let terms = parse_full_text_query(text);
+                    if terms.is_empty() {
+                        "TRUE".to_owned()
+                    } else {
+                        terms
+                            .iter()
+                            .map(|term| {
+                                params.push(contains_pattern(term).into());
+                                format!("{} LIKE ?", field.column_name())
+                            })
+                            .collect::<Vec<_>>()
+                            .join(" AND ")
+                    }
+                }
+                SearchCondition::HumanResponse(human_response) => {
+                    params.push(human_response.to_value());
+                    "human_response = ?".to_owned()
+                }
+                SearchCondition::Date(field, comparison, date) => {
+                    params.push(date.to_value());
+                    format!("{} {} ?", field.expression(GREATEST), comparison.operator())
+                }
+                SearchCondition::Tag(name) => {
+                    params.push(name.into());
+                    "id IN (SELECT job_application_id FROM job_application_tags
+                    INNER JOIN tags ON tags.id = job_application_tags.tag_id
+                    WHERE tags.name = ?)"
+                        .to_owned()
+                }
+                SearchCondition::CustomField(name, comparison, value) => {
+                    let custom_field = find_custom_field(&custom_fields, name)
+                        .map_err(RepositoryError::NotFound)?;
+                    params.push(custom_field.id.into());
+                    // The table's collation makes `=` and LIKE case insensitive
+                    let value_condition =
+                        match custom_field_filter(custom_field, *comparison, value)
+                            .map_err(RepositoryError::Validation)?
+                        {
+                            CustomFieldFilter::Contains(text) => {
+                                params.push(format!("%{text}%").into());
+                                "value LIKE ?".to_owned()
+                            }
+                            CustomFieldFilter::EqualsIgnoringCase(text) => {
+                                params.push(text.into());
+                                "value = ?".to_owned()
+                            }
+                            CustomFieldFilter::Number(comparison, number) => {
+                                params.push(number.into());
+                                format!(
+                                    "CAST(value AS DECIMAL(65, 10)) {} ?",
+                                    comparison.operator()
+                                )
+                            }
+                            CustomFieldFilter::Text(comparison, text) => {
+                                params.push(text.into());
+                                format!("value {} ?", comparison.operator())
+                            }
+                        };
+                    format!(
+                        "id IN (SELECT job_application_id FROM custom_field_values
+                    WHERE custom_field_id = ? AND {value_condition})"
+                    )
+                }
+                // The table's collation makes LIKE case insensitive
+                SearchCondition::Location(field, text) => {
+                    let fields = field.map_or(LocationField::ALL.to_vec(), |field| vec![field]);
+                    fields
+                        .iter()
+                        .map(|field| {
+                            params.push(format!("%{text}%").into());
+                            format!("{} LIKE ?", field.column_name())
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" OR ")
+                }
+                SearchCondition::WorkArrangement(work_arrangement) => {
+                    params.push((*work_arrangement).into());
+                    "work_arrangement = ?".to_owned()
+                }
+                SearchCondition::Bool(field, value) => {
+                    params.push((*value).into());
+                    format!("{} = ?", field.column_name())
+                }
+            };
+
+            query_builder += "\nAND ";
+            if filter.negated {
+                // NULL (e.g. comparing a missing response date) counts as not matching, so the negation should match
+                query_builder += &format!("NOT IFNULL(({condition}), FALSE)");
+            } else {
+                query_builder += &format!("({condition})");
+            }
+        }
+
+        let mut job_applications = self.exec(query_builder, params)?;
+        load_custom_fields(self, &mut job_applications)?;
+        Ok(job_applications)
     }
 
     fn insert_job_application(
         &mut self,
-        application: &JobApplication,
-    ) -> Result<JobApplication, mysql::Error> {
-        self.exec_first(
-        "INSERT INTO job_applications (source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes)
-        VALUES (:source, :company, :job_title, :application_date, :time_investment, :human_response, :human_response_date, :application_website, :notes)
+        application: &NewJobApplication,
+    ) -> Result<JobApplication, RepositoryError> {
+        validate_new(application)?;
+
+        // Check the custom fields before writing anything
+        let custom_field_values =
+            prepare_custom_field_values(&self.get_custom_fields()?, &application.custom_fields)
+                .map_err(RepositoryError::Validation)?;
+
+        in_transaction(self, |conn| {
+            let new_id: Option<i32> = conn.exec_first(
+        "INSERT INTO job_applications (source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, created_at, updated_at)
+        VALUES (:source, :company, :job_title, :application_date, :time_investment, :human_response, :human_response_date, :application_website, :notes, :salary_min, :salary_max, :salary_currency, :pay_period, :equity, :bonus, :city, :region, :country, :work_arrangement, :relocation, :visa_sponsorship, UTC_TIMESTAMP(), UTC_TIMESTAMP())
         RETURNING id",
         application
-    )
-    .map(|new_id| JobApplication {id: new_id.unwrap_or_default(), ..application.clone()})
+    )?;
+            let id = new_id.unwrap_or_default();
+
+            write_custom_field_values(conn, id, &custom_field_values)?;
+            add_journal_entry(conn, id, JournalOperation::Insert)?;
+            let inserted = application.clone().with_id(id);
+            add_audit_log_entry(
+                conn,
+                id,
+                AuditOperation::Insert,
+                &field_changes(None, Some(&inserted)),
+            )?;
+            Ok(inserted)
+        })
     }
 
     fn update_human_response(
@@ -97,40 +374,64 @@ where
         id: i32,
         human_response: HumanResponse,
         human_response_date: Option<Date>,
-    ) -> Result<(), mysql::Error> {
-        self.exec_drop(
-            "UPDATE job_applications
-        SET human_response = :human_response, human_response_date = :human_response_date
+    ) -> Result<JobApplication, RepositoryError> {
+        journaled_update(self, id, |conn| {
+            conn.exec_drop(
+                "UPDATE job_applications
+        SET human_response = :human_response, human_response_date = :human_response_date, version = version + 1, updated_at = UTC_TIMESTAMP()
         WHERE id = :id",
-            params! {
-                "id" => id,
-                "human_response" => &human_response,
-                "human_response_date" => human_response_date
-            },
-        )
+                params! {
+                    "id" => id,
+                    "human_response" => &human_response,
+                    "human_response_date" => human_response_date
+                },
+            )?;
+
+            // The new response date has to make sense with the rest of the job application
+            let job_application = existing_job_application(conn, id)?;
+            validate_changed(
+                &job_application,
+                &[
+                    "human_response".to_owned(),
+                    "human_response_date".to_owned(),
+                ],
+            )?;
+            Ok(job_application)
+        })
     }
 
-    fn update_job_application(&mut self, application: &JobApplication) -> Result<(), mysql::Error> {
-        self.exec_drop(
-            "UPDATE job_applications
-        SET source = :source,
-        company = :company,
-        job_title = :job_title,
-        application_date = :application_date,
-        time_investment = :time_investment,
-        human_response = :human_response,
-        human_response_date = :human_response_date,
-        application_website = :application_website,
-        notes = :notes
-        WHERE id = :id",
+    fn update_job_application(
+        &mut self,
+        application: &JobApplication,
+    ) -> Result<JobApplication, RepositoryError> {
+        validate(application)?;
+
+        // Check the custom fields before writing anything
+        let custom_field_values =
+            prepare_custom_field_values(&self.get_custom_fields()?, &application.custom_fields)
+                .map_err(RepositoryError::Validation)?;
+
+        // Saving a job application without changing anything shouldn't need to be undone
+        if self.get_job_application_by_id(application.id)?.as_ref() == Some(application) {
+            return Ok(application.clone());
+        }
+
+        let version_stmt = self.prep(VERSION_FOR_UPDATE_QUERY)?;
+        let update_stmt = self.prep(UPDATE_JOB_APPLICATION_QUERY)?;
+        update_row(
+            self,
+            &version_stmt,
+            &update_stmt,
             application,
-        )
+            &custom_field_values,
+        )?;
+        existing_job_application(self, application.id)
     }
 
     fn update_job_application_partial(
         &mut self,
         partial_application: PartialJobApplication,
-    ) -> Result<(), mysql::Error> {
+    ) -> Result<JobApplication, RepositoryError> {
         let mut query_builder = "UPDATE job_applications".to_owned();
 
         // Loop over all field names
@@ -151,24 +452,1434 @@ where
 
         // Assert there is at least one change
         if is_first {
-            // Use `std::io::Error` to return an arbitrary `mysql::Error`
-            return Err(std::io::Error::other(
-                "Unable to generate SQL statement because there are no changes",
-            )
-            .into());
+            return Err(RepositoryError::Validation(
+                "Unable to generate SQL statement because there are no changes".to_owned(),
+            ));
         }
 
         // End with the WHERE clause
-        query_builder += "\nWHERE id = :id";
-        // RETURNING id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes";
+        // MySQL has no RETURNING for UPDATE, so the updated job application is selected afterwards
+        query_builder += ",\nversion = version + 1,\nupdated_at = UTC_TIMESTAMP()\nWHERE id = :id";
 
-        self.exec_drop(query_builder, partial_application)
+        let mut ids = partial_application
+            .0
+            .iter()
+            .filter_map(|field| match field {
+                JobApplicationField::Id(id) => Some(*id),
+                _ => None,
+            });
+        let id = ids.next().ok_or_else(|| {
+            RepositoryError::Validation(
+                "Unable to generate SQL statement because there is no id field".to_owned(),
+            )
+        })?;
+        if ids.next().is_some() {
+            return Err(RepositoryError::Validation(
+                "Unable to generate SQL statement because there are multiple id fields".to_owned(),
+            ));
+        }
+
+        let changed: Vec<String> = partial_application
+            .0
+            .iter()
+            .map(JobApplicationField::name)
+            .collect();
+        journaled_update(self, id, |conn| {
+            conn.exec_drop(query_builder, partial_application)?;
+
+            // The changed fields have to make sense with the others, which may not have been checked when they were saved
+            let job_application = existing_job_application(conn, id)?;
+            validate_changed(&job_application, &changed)?;
+            Ok(job_application)
+        })
     }
 
-    fn delete_job_application(&mut self, id: i32) -> Result<(), mysql::Error> {
+    fn delete_job_application(&mut self, id: i32) -> Result<JobApplication, RepositoryError> {
+        in_transaction(self, |conn| {
+            let job_application = existing_job_application(conn, id)?;
+            set_deleted(conn, id, true)?;
+            add_journal_entry(conn, id, JournalOperation::Delete)?;
+            Ok(job_application)
+        })
+    }
+
+    fn insert_many(
+        &mut self,
+        applications: &[NewJobApplication],
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
+        for application in applications {
+            validate_new(application)?;
+        }
+
+        // Check the custom fields before writing anything
+        let custom_fields = self.get_custom_fields()?;
+        let custom_field_values = applications
+            .iter()
+            .map(|application| {
+                prepare_custom_field_values(&custom_fields, &application.custom_fields)
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(RepositoryError::Validation)?;
+
+        in_transaction(self, |conn| {
+            let mut ids = Vec::with_capacity(applications.len());
+            for batch in applications.chunks(BATCH_SIZE) {
+                // Each row's parameters are named after their column and the row's index in the batch
+                let mut params = HashMap::new();
+                let mut values = Vec::with_capacity(batch.len());
+                for (index, application) in batch.iter().enumerate() {
+                    if let Params::Named(application_params) = Params::from(application) {
+                        params.extend(application_params.into_iter().map(|(name, value)| {
+                            ([name, format!("_{index}").into_bytes()].concat(), value)
+                        }));
+                    }
+                    values.push(format!(
+                        "({}, UTC_TIMESTAMP(), UTC_TIMESTAMP())",
+                        INSERTED_COLUMNS
+                            .map(|column| format!(":{column}_{index}"))
+                            .join(", ")
+                    ));
+                }
+
+                let mut batch_ids: Vec<i32> = conn.exec(
+                    format!(
+                        "INSERT INTO job_applications ({}, created_at, updated_at)
+        VALUES {}
+        RETURNING id",
+                        INSERTED_COLUMNS.join(", "),
+                        values.join(", ")
+                    ),
+                    Params::Named(params),
+                )?;
+                // The rows are given ids in the order they are inserted, but RETURNING can give them back in any order
+                batch_ids.sort_unstable();
+                ids.extend(batch_ids);
+            }
+
+            let mut inserted = Vec::with_capacity(applications.len());
+            for ((application, id), custom_field_values) in
+                applications.iter().zip(ids).zip(&custom_field_values)
+            {
+                write_custom_field_values(conn, id, custom_field_values)?;
+                add_journal_entry(conn, id, JournalOperation::Insert)?;
+                let job_application = application.clone().with_id(id);
+                add_audit_log_entry(
+                    conn,
+                    id,
+                    AuditOperation::Insert,
+                    &field_changes(None, Some(&job_application)),
+                )?;
+                inserted.push(job_application);
+            }
+            Ok(inserted)
+        })
+    }
+
+    fn update_many(
+        &mut self,
+        applications: &[JobApplication],
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
+        for application in applications {
+            validate(application)?;
+        }
+
+        // Check the custom fields before writing anything
+        let custom_fields = self.get_custom_fields()?;
+        let custom_field_values = applications
+            .iter()
+            .map(|application| {
+                prepare_custom_field_values(&custom_fields, &application.custom_fields)
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(RepositoryError::Validation)?;
+
+        in_transaction(self, |conn| {
+            // The statements are only prepared once for all of them
+            let version_stmt = conn.prep(VERSION_FOR_UPDATE_QUERY)?;
+            let update_stmt = conn.prep(UPDATE_JOB_APPLICATION_QUERY)?;
+
+            let mut updated = Vec::with_capacity(applications.len());
+            for (application, custom_field_values) in applications.iter().zip(&custom_field_values)
+            {
+                // Saving a job application without changing anything shouldn't need to be undone
+                if conn.get_job_application_by_id(application.id)?.as_ref() != Some(application) {
+                    update_row(
+                        conn,
+                        &version_stmt,
+                        &update_stmt,
+                        application,
+                        custom_field_values,
+                    )?;
+                }
+                updated.push(existing_job_application(conn, application.id)?);
+            }
+            Ok(updated)
+        })
+    }
+
+    fn delete_many(&mut self, ids: &[i32]) -> Result<Vec<JobApplication>, RepositoryError> {
+        in_transaction(self, |conn| {
+            let mut deleted = HashMap::new();
+            for batch in ids.chunks(BATCH_SIZE) {
+                let placeholders = vec!["?"; batch.len()].join(", ");
+                // The rows stay locked until the transaction ends, so they are still outside the trash when they are moved
+                let job_applications: Vec<JobApplication> = conn.exec(
+                    format!(
+                        "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version
+        FROM job_applications
+        WHERE id IN ({placeholders}) AND deleted_at IS NULL
+        FOR UPDATE"
+                    ),
+                    batch.to_vec(),
+                )?;
+                conn.exec_drop(
+                    format!(
+                        "UPDATE job_applications SET deleted_at = UTC_TIMESTAMP()
+        WHERE id IN ({placeholders}) AND deleted_at IS NULL"
+                    ),
+                    batch.to_vec(),
+                )?;
+                deleted.extend(
+                    job_applications
+                        .into_iter()
+                        .map(|job_application| (job_application.id, job_application)),
+                );
+            }
+
+            // Every id has to have been moved, and an id that is given twice was already moved the second time
+            let mut job_applications = ids
+                .iter()
+                .map(|id| {
+                    deleted
+                        .remove(id)
+                        .ok_or_else(|| RepositoryError::job_application_not_found(*id))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            for id in ids {
+                add_audit_log_entry(conn, *id, AuditOperation::Delete, &[])?;
+                add_journal_entry(conn, *id, JournalOperation::Delete)?;
+            }
+            load_custom_fields(conn, &mut job_applications)?;
+            Ok(job_applications)
+        })
+    }
+
+    fn with_transaction<T, E, F>(&mut self, transaction: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut Self) -> Result<T, E>,
+        E: From<RepositoryError>,
+    {
+        in_transaction(self, transaction)
+    }
+
+    fn move_related_records(&mut self, from_id: i32, to_id: i32) -> Result<(), RepositoryError> {
+        in_transaction(self, |conn| {
+            // Rows that would conflict with ones `to_id` already has are skipped, leaving them with `from_id`
+            for table in [
+                "job_application_tags",
+                "offers",
+                "job_descriptions",
+                "attachments",
+                "sent_resume_versions",
+            ] {
+                conn.exec_drop(
+                    format!(
+                        "UPDATE IGNORE {table} SET job_application_id = :to_id WHERE job_application_id = :from_id"
+                    ),
+                    params! {"from_id" => from_id, "to_id" => to_id},
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    fn get_deleted_job_applications(
+        &mut self,
+    ) -> Result<Vec<DeletedJobApplication>, RepositoryError> {
+        let mut deleted_job_applications: Vec<DeletedJobApplication> = self.query(
+        "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version, deleted_at
+        FROM job_applications
+        WHERE deleted_at IS NOT NULL
+        ORDER BY deleted_at DESC, id DESC"
+    )?;
+        load_custom_fields(
+            self,
+            deleted_job_applications
+                .iter_mut()
+                .map(|deleted| &mut deleted.job_application),
+        )?;
+        Ok(deleted_job_applications)
+    }
+
+    fn restore_job_application(&mut self, id: i32) -> Result<(), RepositoryError> {
+        in_transaction(self, |conn| {
+            if !set_deleted(conn, id, false)? {
+                return Err(RepositoryError::job_application_not_found(id));
+            }
+            add_journal_entry(conn, id, JournalOperation::Restore)?;
+            Ok(())
+        })
+    }
+
+    fn empty_trash(&mut self) -> Result<usize, RepositoryError> {
+        in_transaction(self, |conn| {
+            let ids: Vec<i32> =
+                conn.query("SELECT id FROM job_applications WHERE deleted_at IS NOT NULL")?;
+            purge_job_applications(conn, &ids)
+        })
+    }
+
+    fn purge_trash(&mut self, days: u32) -> Result<usize, RepositoryError> {
+        in_transaction(self, |conn| {
+            let ids: Vec<i32> = conn.exec(
+                "SELECT id FROM job_applications
+        WHERE deleted_at <= UTC_TIMESTAMP() - INTERVAL :days DAY",
+                params! {"days" => days},
+            )?;
+            purge_job_applications(conn, &ids)
+        })
+    }
+
+    fn undo(&mut self) -> Result<Option<JournalEntry>, RepositoryError> {
+        in_transaction(self, |conn| {
+            let entry: Option<JournalEntry> = conn.query_first(
+                "SELECT id, job_application_id, operation FROM journal_entries
+        WHERE NOT undone
+        ORDER BY id DESC
+        LIMIT 1",
+            )?;
+
+            if let Some(entry) = &entry {
+                apply_journal_entry(conn, entry, true)?;
+            }
+
+            Ok(entry)
+        })
+    }
+
+    fn redo(&mut self) -> Result<Option<JournalEntry>, RepositoryError> {
+        // Undone entries are always the newest, so the oldest of them was undone last
+        in_transaction(self, |conn| {
+            let entry: Option<JournalEntry> = conn.query_first(
+                "SELECT id, job_application_id, operation FROM journal_entries
+        WHERE undone
+        ORDER BY id
+        LIMIT 1",
+            )?;
+
+            if let Some(entry) = &entry {
+                apply_journal_entry(conn, entry, false)?;
+            }
+
+            Ok(entry)
+        })
+    }
+
+    fn get_history(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Vec<AuditLogEntry>, RepositoryError> {
+        let mut history: Vec<AuditLogEntry> = self.exec(
+            "SELECT id, job_application_id, operation, changed_at, changed_by
+        FROM audit_log
+        WHERE job_application_id = :job_application_id
+        ORDER BY id",
+            params! {"job_application_id" => job_application_id},
+        )?;
+
+        let changes: Vec<(i32, String, Option<String>, Option<String>)> = self.exec(
+            "SELECT audit_log_id, field, old_value, new_value
+        FROM audit_log_changes
+        INNER JOIN audit_log ON audit_log.id = audit_log_changes.audit_log_id
+        WHERE audit_log.job_application_id = :job_application_id
+        ORDER BY field",
+            params! {"job_application_id" => job_application_id},
+        )?;
+        let mut entries_by_id: HashMap<i32, &mut AuditLogEntry> =
+            history.iter_mut().map(|entry| (entry.id, entry)).collect();
+        for (audit_log_id, field, old_value, new_value) in changes {
+            if let Some(entry) = entries_by_id.get_mut(&audit_log_id) {
+                entry.changes.push(FieldChange {
+                    field,
+                    old_value,
+                    new_value,
+                });
+            }
+        }
+
+        Ok(history)
+    }
+
+    fn get_tags(&mut self) -> Result<Vec<Tag>, RepositoryError> {
+        Ok(self.query(
+            "SELECT id, name FROM tags
+        WHERE id IN (
+            SELECT tag_id FROM job_application_tags
+            INNER JOIN job_applications ON job_applications.id = job_application_tags.job_application_id
+            WHERE job_applications.deleted_at IS NULL
+        )
+        ORDER BY name",
+        )?)
+    }
+
+    fn get_job_application_tags(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Vec<Tag>, RepositoryError> {
+        Ok(self.exec(
+            "SELECT tags.id, tags.name FROM tags
+        INNER JOIN job_application_tags ON job_application_tags.tag_id = tags.id
+        INNER JOIN job_applications ON job_applications.id = job_application_tags.job_application_id
+        WHERE job_application_tags.job_application_id = :job_application_id AND job_applications.deleted_at IS NULL
+        ORDER BY tags.name",
+            params! {"job_application_id" => job_application_id},
+        )?)
+    }
+
+    fn add_tag(&mut self, job_application_id: i32, name: &str) -> Result<Tag, RepositoryError> {
+        let name = validate_tag_name(name)?;
+        check_job_application_exists(self, job_application_id)?;
+
+        // The unique index uses the table's case insensitive collation, so this reuses "Remote" when adding "remote"
         self.exec_drop(
-            "DELETE FROM job_applications WHERE id = :id",
+            "INSERT IGNORE INTO tags (name) VALUES (:name)",
+            params! {"name" => name},
+        )?;
+        let tag: Tag = self
+            .exec_first(
+                "SELECT id, name FROM tags WHERE name = :name",
+                params! {"name" => name},
+            )?
+            .ok_or_else(|| {
+                RepositoryError::NotFound("Unable to find the tag after creating it".to_owned())
+            })?;
+
+        // `ON DUPLICATE KEY` is used instead of `INSERT IGNORE` so that a missing job application is still an error
+        self.exec_drop(
+            "INSERT INTO job_application_tags (job_application_id, tag_id)
+        VALUES (:job_application_id, :tag_id)
+        ON DUPLICATE KEY UPDATE tag_id = tag_id",
+            params! {"job_application_id" => job_application_id, "tag_id" => tag.id},
+        )?;
+
+        Ok(tag)
+    }
+
+    fn remove_tag(&mut self, job_application_id: i32, name: &str) -> Result<(), RepositoryError> {
+        check_job_application_exists(self, job_application_id)?;
+        Ok(self.exec_drop(
+            "DELETE job_application_tags FROM job_application_tags
+        INNER JOIN tags ON tags.id = job_application_tags.tag_id
+        WHERE job_application_tags.job_application_id = :job_application_id
+        AND tags.name = :name",
+            params! {"job_application_id" => job_application_id, "name" => name.trim()},
+        )?)
+    }
+
+    fn rename_tag(&mut self, name: &str, new_name: &str) -> Result<(), RepositoryError> {
+        let new_name = validate_tag_name(new_name)?;
+
+        let find_tag = "SELECT id FROM tags WHERE name = :name";
+        let Some(id) = self.exec_first::<i32, _, _>(find_tag, params! {"name" => name.trim()})?
+        else {
+            return Ok(());
+        };
+
+        match self.exec_first::<i32, _, _>(find_tag, params! {"name" => new_name})? {
+            // Merge into the existing tag by moving every job application over to it, then deleting this tag
+            Some(existing_id) if existing_id != id => {
+                self.exec_drop(
+                    "INSERT IGNORE INTO job_application_tags (job_application_id, tag_id)
+                SELECT job_application_id, :existing_id FROM job_application_tags WHERE tag_id = :id",
+                    params! {"existing_id" => existing_id, "id" => id},
+                )?;
+                // Deleting the tag cascades to its rows in job_application_tags
+                self.exec_drop("DELETE FROM tags WHERE id = :id", params! {"id" => id})
+            }
+            // Otherwise, this is a plain rename (possibly only changing case)
+            _ => self.exec_drop(
+                "UPDATE tags SET name = :new_name WHERE id = :id",
+                params! {"new_name" => new_name, "id" => id},
+            ),
+        }?;
+        Ok(())
+    }
+
+    fn get_custom_fields(&mut self) -> Result<Vec<CustomField>, RepositoryError> {
+        Ok(self.query("SELECT id, name, field_type FROM custom_fields ORDER BY name")?)
+    }
+
+    fn add_custom_field(
+        &mut self,
+        name: &str,
+        field_type: &CustomFieldType,
+    ) -> Result<CustomField, RepositoryError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(RepositoryError::Validation(
+                "Custom field names can't be empty".to_owned(),
+            ));
+        }
+
+        let new_id: Option<i32> = self.exec_first(
+            "INSERT INTO custom_fields (name, field_type) VALUES (:name, :field_type)
+        RETURNING id",
+            params! {"name" => name, "field_type" => field_type},
+        )?;
+
+        Ok(CustomField {
+            id: new_id.unwrap_or_default(),
+            name: name.to_owned(),
+            field_type: field_type.clone(),
+        })
+    }
+
+    fn delete_custom_field(&mut self, name: &str) -> Result<(), RepositoryError> {
+        // Deleting the field cascades to its values
+        Ok(self.exec_drop(
+            "DELETE FROM custom_fields WHERE name = :name",
+            params! {"name" => name.trim()},
+        )?)
+    }
+
+    fn set_custom_field_value(
+        &mut self,
+        job_application_id: i32,
+        name: &str,
+        value: Option<&CustomFieldValue>,
+    ) -> Result<(), RepositoryError> {
+        let custom_fields = self.get_custom_fields()?;
+        let custom_field =
+            find_custom_field(&custom_fields, name).map_err(RepositoryError::NotFound)?;
+
+        if let Some(value) = value {
+            check_custom_field_value(custom_field, value).map_err(RepositoryError::Validation)?;
+        }
+
+        journaled_update(self, job_application_id, |conn| {
+            if let Some(value) = value {
+                conn.exec_drop(
+                    "INSERT INTO custom_field_values (job_application_id, custom_field_id, value)
+            VALUES (:job_application_id, :custom_field_id, :value)
+            ON DUPLICATE KEY UPDATE value = VALUES(value)",
+                    params! {
+                        "job_application_id" => job_application_id,
+                        "custom_field_id" => custom_field.id,
+                        "value" => value.to_stored_string(),
+                    },
+                )?;
+            } else {
+                conn.exec_drop(
+                    "DELETE FROM custom_field_values
+            WHERE job_application_id = :job_application_id AND custom_field_id = :custom_field_id",
+                    params! {
+                        "job_application_id" => job_application_id,
+                        "custom_field_id" => custom_field.id,
+                    },
+                )?;
+            }
+            // Custom fields are part of the job application, so changing one is a new version of it
+            Ok(conn.exec_drop(
+                "UPDATE job_applications SET version = version + 1, updated_at = UTC_TIMESTAMP() WHERE id = :id",
+                params! {"id" => job_application_id},
+            )?)
+        })
+    }
+
+    fn get_offer(&mut self, job_application_id: i32) -> Result<Option<Offer>, RepositoryError> {
+        Ok(self.exec_first(
+            "SELECT job_application_id, base_salary, pay_period, currency, bonus, equity_value, vesting_years, benefits, start_date, deadline
+        FROM offers
+        WHERE job_application_id = :job_application_id
+        AND job_application_id IN (SELECT id FROM job_applications WHERE deleted_at IS NULL)",
+            params! {"job_application_id" => job_application_id},
+        )?)
+    }
+
+    fn get_offers(&mut self) -> Result<Vec<Offer>, RepositoryError> {
+        Ok(self.query(
+            "SELECT job_application_id, base_salary, pay_period, currency, bonus, equity_value, vesting_years, benefits, start_date, deadline
+        FROM offers
+        WHERE job_application_id IN (SELECT id FROM job_applications WHERE deleted_at IS NULL)
+        ORDER BY job_application_id",
+        )?)
+    }
+
+    fn save_offer(&mut self, offer: &Offer) -> Result<(), RepositoryError> {
+        check_job_application_exists(self, offer.job_application_id)?;
+        Ok(self.exec_drop(
+            "INSERT INTO offers (job_application_id, base_salary, pay_period, currency, bonus, equity_value, vesting_years, benefits, start_date, deadline)
+        VALUES (:job_application_id, :base_salary, :pay_period, :currency, :bonus, :equity_value, :vesting_years, :benefits, :start_date, :deadline)
+        ON DUPLICATE KEY UPDATE
+        base_salary = VALUES(base_salary),
+        pay_period = VALUES(pay_period),
+        currency = VALUES(currency),
+        bonus = VALUES(bonus),
+        equity_value = VALUES(equity_value),
+        vesting_years = VALUES(vesting_years),
+        benefits = VALUES(benefits),
+        start_date = VALUES(start_date),
+        deadline = VALUES(deadline)",
+            offer,
+        )?)
+    }
+
+    fn delete_offer(&mut self, job_application_id: i32) -> Result<(), RepositoryError> {
+        check_job_application_exists(self, job_application_id)?;
+        Ok(self.exec_drop(
+            "DELETE FROM offers WHERE job_application_id = :job_application_id",
+            params! {"job_application_id" => job_application_id},
+        )?)
+    }
+
+    fn get_work_arrangement_stats(&mut self) -> Result<Vec<WorkArrangementStats>, RepositoryError> {
+        let mut stats = self.query(WORK_ARRANGEMENT_STATS_QUERY)?;
+        sort_work_arrangement_stats(&mut stats);
+        Ok(stats)
+    }
+
+    fn get_job_description(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Option<JobDescription>, RepositoryError> {
+        Ok(self.exec_first(
+            "SELECT job_application_id, description, captured_date
+        FROM job_descriptions
+        WHERE job_application_id = :job_application_id
+        AND job_application_id IN (SELECT id FROM job_applications WHERE deleted_at IS NULL)",
+            params! {"job_application_id" => job_application_id},
+        )?)
+    }
+
+    fn save_job_description(
+        &mut self,
+        job_description: &JobDescription,
+    ) -> Result<(), RepositoryError> {
+        check_job_application_exists(self, job_description.job_application_id)?;
+        Ok(self.exec_drop(
+            "INSERT INTO job_descriptions (job_application_id, description, captured_date)
+        VALUES (:job_application_id, :description, :captured_date)
+        ON DUPLICATE KEY UPDATE
+        description = VALUES(description),
+        captured_date = VALUES(captured_date)",
+            job_description,
+        )?)
+    }
+
+    fn delete_job_description(&mut self, job_application_id: i32) -> Result<(), RepositoryError> {
+        check_job_application_exists(self, job_application_id)?;
+        Ok(self.exec_drop(
+            "DELETE FROM job_descriptions WHERE job_application_id = :job_application_id",
+            params! {"job_application_id" => job_application_id},
+        )?)
+    }
+
+    fn get_attachments(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Vec<Attachment>, RepositoryError> {
+        Ok(self.exec(
+            format!(
+                "{ATTACHMENT_QUERY}
+        INNER JOIN job_applications ON job_applications.id = attachments.job_application_id
+        WHERE attachments.job_application_id = :job_application_id AND job_applications.deleted_at IS NULL
+        ORDER BY attachments.id"
+            ),
+            params! {"job_application_id" => job_application_id},
+        )?)
+    }
+
+    fn get_attachment(&mut self, id: i32) -> Result<Option<Attachment>, RepositoryError> {
+        Ok(self.exec_first(
+            format!("{ATTACHMENT_QUERY} WHERE attachments.id = :id"),
             params! {"id" => id},
-        )
+        )?)
+    }
+
+    fn add_attachment(
+        &mut self,
+        job_application_id: i32,
+        role: AttachmentRole,
+        file_name: &str,
+        contents: &[u8],
+        added_date: Date,
+    ) -> Result<Attachment, RepositoryError> {
+        let file_name = clean_file_name(file_name)
+            .ok_or_else(|| RepositoryError::Validation("File names can't be empty".to_owned()))?;
+        let file_hash = Attachment::hash_contents(contents);
+        check_job_application_exists(self, job_application_id)?;
+
+        // Files with the same contents are only stored once
+        self.exec_drop(
+            "INSERT IGNORE INTO attachment_files (hash, contents, size)
+        VALUES (:hash, :contents, :size)",
+            params! {"hash" => &file_hash, "contents" => contents, "size" => contents.len() as i64},
+        )?;
+        // `ON DUPLICATE KEY` is used instead of `INSERT IGNORE` so that a missing job application is still an error
+        self.exec_drop(
+            "INSERT INTO attachments (job_application_id, file_hash, file_name, role, added_date)
+        VALUES (:job_application_id, :file_hash, :file_name, :role, :added_date)
+        ON DUPLICATE KEY UPDATE id = id",
+            params! {
+                "job_application_id" => job_application_id,
+                "file_hash" => &file_hash,
+                "file_name" => file_name,
+                "role" => role,
+                "added_date" => added_date,
+            },
+        )?;
+
+        self.exec_first(
+            format!(
+                "{ATTACHMENT_QUERY}
+        WHERE attachments.job_application_id = :job_application_id
+        AND attachments.file_hash = :file_hash
+        AND attachments.role = :role"
+            ),
+            params! {"job_application_id" => job_application_id, "file_hash" => &file_hash, "role" => role},
+        )?
+        .ok_or_else(|| {
+            RepositoryError::NotFound("Unable to find the attachment after adding it".to_owned())
+        })
+    }
+
+    fn get_attachment_contents(&mut self, id: i32) -> Result<Option<Vec<u8>>, RepositoryError> {
+        Ok(self.exec_first(
+            "SELECT attachment_files.contents FROM attachment_files
+        INNER JOIN attachments ON attachments.file_hash = attachment_files.hash
+        WHERE attachments.id = :id",
+            params! {"id" => id},
+        )?)
+    }
+
+    fn delete_attachment(&mut self, id: i32) -> Result<(), RepositoryError> {
+        self.exec_drop(
+            "DELETE FROM attachments WHERE id = :id",
+            params! {"id" => id},
+        )?;
+        delete_unused_attachment_files(self)
+    }
+
+    fn get_resume_versions(&mut self) -> Result<Vec<ResumeVersion>, RepositoryError> {
+        Ok(self.query(format!("{RESUME_VERSION_QUERY} ORDER BY id"))?)
+    }
+
+    fn add_resume_version(
+        &mut self,
+        label: &str,
+        file_hash: Option<&str>,
+        created_date: Date,
+        notes: Option<&str>,
+    ) -> Result<ResumeVersion, RepositoryError> {
+        let label = label.trim();
+        if label.is_empty() {
+            return Err(RepositoryError::Validation(
+                "Resume version labels can't be empty".to_owned(),
+            ));
+        }
+        let notes = notes.map(str::trim).filter(|notes| !notes.is_empty());
+
+        let new_id: Option<i32> = self.exec_first(
+            "INSERT INTO resume_versions (label, file_hash, created_date, notes)
+        VALUES (:label, :file_hash, :created_date, :notes)
+        RETURNING id",
+            params! {
+                "label" => label,
+                "file_hash" => file_hash,
+                "created_date" => created_date,
+                "notes" => notes,
+            },
+        )?;
+
+        Ok(ResumeVersion {
+            id: new_id.unwrap_or_default(),
+            label: label.to_owned(),
+            file_hash: file_hash.map(str::to_owned),
+            created_date,
+            notes: notes.map(str::to_owned),
+        })
+    }
+
+    fn delete_resume_version(&mut self, id: i32) -> Result<(), RepositoryError> {
+        // Deleting the version cascades to the job applications it was sent with
+        Ok(self.exec_drop(
+            "DELETE FROM resume_versions WHERE id = :id",
+            params! {"id" => id},
+        )?)
+    }
+
+    fn get_sent_resume_version(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Option<ResumeVersion>, RepositoryError> {
+        Ok(self.exec_first(
+            format!(
+                "{RESUME_VERSION_QUERY}
+        WHERE id = (
+            SELECT resume_version_id FROM sent_resume_versions
+            WHERE job_application_id = :job_application_id
+            AND job_application_id IN (SELECT id FROM job_applications WHERE deleted_at IS NULL)
+        )"
+            ),
+            params! {"job_application_id" => job_application_id},
+        )?)
+    }
+
+    fn set_sent_resume_version(
+        &mut self,
+        job_application_id: i32,
+        resume_version_id: Option<i32>,
+    ) -> Result<(), RepositoryError> {
+        check_job_application_exists(self, job_application_id)?;
+        match resume_version_id {
+            Some(resume_version_id) => self.exec_drop(
+                "INSERT INTO sent_resume_versions (job_application_id, resume_version_id)
+        VALUES (:job_application_id, :resume_version_id)
+        ON DUPLICATE KEY UPDATE resume_version_id = VALUES(resume_version_id)",
+                params! {
+                    "job_application_id" => job_application_id,
+                    "resume_version_id" => resume_version_id,
+                },
+            ),
+            None => self.exec_drop(
+                "DELETE FROM sent_resume_versions WHERE job_application_id = :job_application_id",
+                params! {"job_application_id" => job_application_id},
+            ),
+        }?;
+        Ok(())
+    }
+
+    fn get_resume_version_stats(&mut self) -> Result<Vec<ResumeVersionStats>, RepositoryError> {
+        let mut stats = self.query(RESUME_VERSION_STATS_QUERY)?;
+        sort_resume_version_stats(&mut stats);
+        Ok(stats)
+    }
+}
+
+/// The function that takes the largest of its arguments, for [DateField::expression](crate::search_query::DateField::expression)
+const GREATEST: &str = "GREATEST";
+
+/// Select every column of [ResumeVersion], to be followed by a `WHERE` or `ORDER BY` clause
+const RESUME_VERSION_QUERY: &str =
+    "SELECT id, label, file_hash, created_date, notes FROM resume_versions";
+
+/// Select every column of [Attachment], to be followed by a `WHERE` clause
+const ATTACHMENT_QUERY: &str =
+    "SELECT attachments.id, attachments.job_application_id, attachments.role,
+        attachments.file_name, attachments.file_hash, attachment_files.size, attachments.added_date
+        FROM attachments
+        INNER JOIN attachment_files ON attachment_files.hash = attachments.file_hash";
+
+impl From<mysql::Error> for RepositoryError {
+    fn from(error: mysql::Error) -> Self {
+        match &error {
+            mysql::Error::MySqlError(mysql_error) => match mysql_error.code {
+                // ER_DUP_ENTRY, saving something that already exists
+                1062 => RepositoryError::Conflict(mysql_error.message.clone()),
+                // ER_ROW_IS_REFERENCED_2, ER_NO_REFERENCED_ROW_2, ER_BAD_NULL_ERROR, ER_CHECK_CONSTRAINT_VIOLATED
+                1451 | 1452 | 1048 | 3819 => {
+                    RepositoryError::Constraint(mysql_error.message.clone())
+                }
+                _ => RepositoryError::Backend(Box::new(error)),
+            },
+            _ => RepositoryError::Backend(Box::new(error)),
+        }
+    }
+}
+
+/// Move a job application to the trash, or take it back out, returning whether it was changed
+fn set_deleted<C: Queryable>(
+    conn: &mut C,
+    id: i32,
+    deleted: bool,
+) -> Result<bool, RepositoryError> {
+    let affected_rows = conn
+        .exec_iter(
+            if deleted {
+                "UPDATE job_applications SET deleted_at = UTC_TIMESTAMP()
+        WHERE id = :id AND deleted_at IS NULL"
+            } else {
+                "UPDATE job_applications SET deleted_at = NULL
+        WHERE id = :id AND deleted_at IS NOT NULL"
+            },
+            params! {"id" => id},
+        )?
+        .affected_rows();
+
+    if affected_rows > 0 {
+        let operation = if deleted {
+            AuditOperation::Delete
+        } else {
+            AuditOperation::Restore
+        };
+        add_audit_log_entry(conn, id, operation, &[])?;
+    }
+    Ok(affected_rows > 0)
+}
+
+/// Record a change to a job application in the journal, returning the id of the entry
+///
+/// Undone changes can't be redone once something else has changed, so they are cleared.
+/// Only the newest [JOURNAL_LENGTH] entries are kept.
+fn add_journal_entry<C: Queryable>(
+    conn: &mut C,
+    job_application_id: i32,
+    operation: JournalOperation,
+) -> Result<i32, RepositoryError> {
+    conn.query_drop("DELETE FROM journal_entries WHERE undone")?;
+
+    // Updates set the version after the change once it is made
+    let id: Option<i32> = conn.exec_first(
+        "INSERT INTO journal_entries (job_application_id, operation, version_before, version_after)
+        SELECT id, :operation, version, version FROM job_applications WHERE id = :job_application_id
+        RETURNING id",
+        params! {"job_application_id" => job_application_id, "operation" => operation},
+    )?;
+
+    // MySQL can't select from the table being deleted from, except through a derived table
+    conn.exec_drop(
+        "DELETE FROM journal_entries
+        WHERE id < (
+            SELECT id FROM (SELECT id FROM journal_entries ORDER BY id DESC LIMIT 1 OFFSET :offset) AS oldest_kept
+        )",
+        params! {"offset" => JOURNAL_LENGTH - 1},
+    )?;
+
+    Ok(id.unwrap_or_default())
+}
+
+/// Return [RepositoryError::job_application_not_found] unless job application `id` exists and isn't in the trash
+///
+/// Anything saved for a job application in the trash would be hidden until it is restored, so it is refused instead.
+fn check_job_application_exists<C: Queryable>(
+    conn: &mut C,
+    id: i32,
+) -> Result<(), RepositoryError> {
+    let exists: Option<bool> = conn.exec_first(
+        "SELECT EXISTS (SELECT 1 FROM job_applications WHERE id = :id AND deleted_at IS NULL)",
+        params! {"id" => id},
+    )?;
+    if exists.unwrap_or_default() {
+        Ok(())
+    } else {
+        Err(RepositoryError::job_application_not_found(id))
+    }
+}
+
+/// Select job application `id`, or return [RepositoryError::NotFound] if it doesn't exist or is in the trash
+///
+/// MySQL can't return rows from an `UPDATE`, so this is also how a job application is returned after changing it.
+fn existing_job_application<C: Queryable>(
+    conn: &mut C,
+    id: i32,
+) -> Result<JobApplication, RepositoryError> {
+    conn.get_job_application_by_id(id)?
+        .ok_or_else(|| RepositoryError::job_application_not_found(id))
+}
+
+/// The columns of `job_applications` that are set when inserting a job application, used by [JobApplicationRepository::insert_many]
+const INSERTED_COLUMNS: [&str; 21] = [
+    "source",
+    "company",
+    "job_title",
+    "application_date",
+    "time_investment",
+    "human_response",
+    "human_response_date",
+    "application_website",
+    "notes",
+    "salary_min",
+    "salary_max",
+    "salary_currency",
+    "pay_period",
+    "equity",
+    "bonus",
+    "city",
+    "region",
+    "country",
+    "work_arrangement",
+    "relocation",
+    "visa_sponsorship",
+];
+
+/// Select the version of a job application, locking it until the transaction ends, used by [update_row]
+const VERSION_FOR_UPDATE_QUERY: &str =
+    "SELECT version FROM job_applications WHERE id = :id FOR UPDATE";
+
+/// Update every column of a job application from the [Params] of a [JobApplication], used by [update_row]
+const UPDATE_JOB_APPLICATION_QUERY: &str = "UPDATE job_applications
+        SET source = :source,
+        company = :company,
+        job_title = :job_title,
+        application_date = :application_date,
+        time_investment = :time_investment,
+        human_response = :human_response,
+        human_response_date = :human_response_date,
+        application_website = :application_website,
+        notes = :notes,
+        salary_min = :salary_min,
+        salary_max = :salary_max,
+        salary_currency = :salary_currency,
+        pay_period = :pay_period,
+        equity = :equity,
+        bonus = :bonus,
+        city = :city,
+        region = :region,
+        country = :country,
+        work_arrangement = :work_arrangement,
+        relocation = :relocation,
+        visa_sponsorship = :visa_sponsorship,
+        version = version + 1,
+        updated_at = UTC_TIMESTAMP()
+        WHERE id = :id";
+
+/// Update a job application and its custom fields, with statements prepared from [VERSION_FOR_UPDATE_QUERY] and [UPDATE_JOB_APPLICATION_QUERY]
+///
+/// Nothing is updated if it was changed since `application` was loaded.
+fn update_row<C: Queryable>(
+    conn: &mut C,
+    version_stmt: &Statement,
+    update_stmt: &Statement,
+    application: &JobApplication,
+    custom_field_values: &[(i32, String)],
+) -> Result<(), RepositoryError> {
+    journaled_update(conn, application.id, |conn| {
+        // The row stays locked until the transaction ends, so it can't be changed between the check and the update.
+        let version: Option<i32> =
+            conn.exec_first(version_stmt, params! {"id" => application.id})?;
+        if version != Some(application.version) {
+            return Err(RepositoryError::job_application_changed(application.id));
+        }
+
+        conn.exec_drop(update_stmt, application)?;
+        write_custom_field_values(conn, application.id, custom_field_values)
+    })
+}
+
+/// Make an update to job application `id` that can be undone, by saving it to the journal before and after `update`
+///
+/// `update` is only run if there is a job application with this id outside the trash.
+fn journaled_update<C: Queryable, T>(
+    conn: &mut C,
+    id: i32,
+    update: impl FnOnce(&mut C) -> Result<T, RepositoryError>,
+) -> Result<T, RepositoryError> {
+    in_transaction(conn, |conn| {
+        let exists: Option<i32> = conn.exec_first(
+            "SELECT id FROM job_applications WHERE id = :id AND deleted_at IS NULL",
+            params! {"id" => id},
+        )?;
+        if exists.is_none() {
+            return Err(RepositoryError::job_application_not_found(id));
+        }
+
+        let journal_entry_id = add_journal_entry(conn, id, JournalOperation::Update)?;
+        save_journal_snapshot(conn, journal_entry_id, id, false)?;
+        let result = audited_update(conn, id, update)?;
+        save_journal_snapshot(conn, journal_entry_id, id, true)?;
+        conn.exec_drop(
+            "UPDATE journal_entries
+        SET version_after = (SELECT version FROM job_applications WHERE id = :job_application_id)
+        WHERE id = :id",
+            params! {"job_application_id" => id, "id" => journal_entry_id},
+        )?;
+
+        Ok(result)
+    })
+}
+
+/// Make an update to job application `id`, recording the fields it changed in the audit log
+///
+/// Nothing is recorded if nothing changed.
+fn audited_update<C: Queryable, T>(
+    conn: &mut C,
+    id: i32,
+    update: impl FnOnce(&mut C) -> Result<T, RepositoryError>,
+) -> Result<T, RepositoryError> {
+    let before = conn.get_job_application_by_id(id)?;
+    let result = update(conn)?;
+    let after = conn.get_job_application_by_id(id)?;
+
+    let changes = field_changes(before.as_ref(), after.as_ref());
+    if !changes.is_empty() {
+        add_audit_log_entry(conn, id, AuditOperation::Update, &changes)?;
+    }
+
+    Ok(result)
+}
+
+/// Run `transaction` in a transaction, so the change it makes and its audit log entries are saved together or not at all
+///
+/// This only works on a connection that isn't already in a transaction started some other way.
+/// `@ats_transaction_depth` counts how many calls are running, so nested calls use savepoints instead of committing early.
+fn in_transaction<C: Queryable, T, E: From<RepositoryError>>(
+    conn: &mut C,
+    transaction: impl FnOnce(&mut C) -> Result<T, E>,
+) -> Result<T, E> {
+    let depth = begin_transaction(conn)?;
+    let result = transaction(conn);
+    end_transaction(conn, depth, result.is_ok())?;
+    result
+}
+
+/// Start a transaction, or a savepoint if one is already running, returning how deep it is
+fn begin_transaction<C: Queryable>(conn: &mut C) -> Result<u32, RepositoryError> {
+    let depth: Option<Option<u32>> = conn.query_first("SELECT @ats_transaction_depth")?;
+    let depth = depth.flatten().unwrap_or_default();
+
+    if depth == 0 {
+        conn.query_drop("START TRANSACTION")?;
+    } else {
+        conn.query_drop(format!("SAVEPOINT ats_savepoint_{depth}"))?;
+    }
+    conn.exec_drop("SET @ats_transaction_depth = ?", (depth + 1,))?;
+    Ok(depth)
+}
+
+/// Commit or roll back what [begin_transaction] started at `depth`
+fn end_transaction<C: Queryable>(
+    conn: &mut C,
+    depth: u32,
+    commit: bool,
+) -> Result<(), RepositoryError> {
+    conn.exec_drop("SET @ats_transaction_depth = ?", (depth,))?;
+    match (commit, depth) {
+        (true, 0) => conn.query_drop("COMMIT")?,
+        (true, _) => conn.query_drop(format!("RELEASE SAVEPOINT ats_savepoint_{depth}"))?,
+        (false, 0) => conn.query_drop("ROLLBACK")?,
+        (false, _) => conn.query_drop(format!("ROLLBACK TO SAVEPOINT ats_savepoint_{depth}"))?,
+    }
+    Ok(())
+}
+
+/// Record a change to a job application in the audit log, along with who made it and when
+///
+/// The user comes from `AUDIT_USER` or `DB_USER`, or the MySQL user if neither is set.
+fn add_audit_log_entry<C: Queryable>(
+    conn: &mut C,
+    job_application_id: i32,
+    operation: AuditOperation,
+    changes: &[FieldChange],
+) -> Result<(), RepositoryError> {
+    let id: Option<i32> = conn.exec_first(
+        "INSERT INTO audit_log (job_application_id, operation, changed_at, changed_by)
+        VALUES (:job_application_id, :operation, UTC_TIMESTAMP(), COALESCE(:changed_by, SUBSTRING_INDEX(CURRENT_USER(), '@', 1)))
+        RETURNING id",
+        params! {
+            "job_application_id" => job_application_id,
+            "operation" => operation,
+            "changed_by" => audit_user(),
+        },
+    )?;
+    let id = id.unwrap_or_default();
+
+    Ok(conn.exec_batch(
+        "INSERT INTO audit_log_changes (audit_log_id, field, old_value, new_value)
+        VALUES (:audit_log_id, :field, :old_value, :new_value)",
+        changes.iter().map(|change| {
+            params! {
+                "audit_log_id" => id,
+                "field" => &change.field,
+                "old_value" => &change.old_value,
+                "new_value" => &change.new_value,
+            }
+        }),
+    )?)
+}
+
+/// Permanently delete the job applications with these ids, recording what each one had in the audit log
+///
+/// Returns how many were deleted.
+fn purge_job_applications<C: Queryable>(
+    conn: &mut C,
+    ids: &[i32],
+) -> Result<usize, RepositoryError> {
+    for deleted in conn
+        .get_deleted_job_applications()?
+        .into_iter()
+        .filter(|deleted| ids.contains(&deleted.job_application.id))
+    {
+        add_audit_log_entry(
+            conn,
+            deleted.job_application.id,
+            AuditOperation::Purge,
+            &field_changes(Some(&deleted.job_application), None),
+        )?;
     }
+
+    conn.exec_batch(
+        "DELETE FROM job_applications WHERE id = :id",
+        ids.iter().map(|id| params! {"id" => id}),
+    )?;
+    // Deleting the job applications deleted their attachments
+    delete_unused_attachment_files(conn)?;
+    Ok(ids.len())
+}
+
+/// Save a copy of a job application and its custom fields, from before or after the change in a journal entry
+fn save_journal_snapshot<C: Queryable>(
+    conn: &mut C,
+    journal_entry_id: i32,
+    job_application_id: i32,
+    is_after: bool,
+) -> Result<(), RepositoryError> {
+    let params = params! {
+        "journal_entry_id" => journal_entry_id,
+        "is_after" => is_after,
+        "job_application_id" => job_application_id,
+    };
+
+    conn.exec_drop(
+        "INSERT INTO journal_snapshots (journal_entry_id, is_after, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship)
+        SELECT :journal_entry_id, :is_after, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship
+        FROM job_applications
+        WHERE id = :job_application_id",
+        &params,
+    )?;
+
+    Ok(conn.exec_drop(
+        "INSERT INTO journal_custom_field_values (journal_entry_id, is_after, custom_field_id, value)
+        SELECT :journal_entry_id, :is_after, custom_field_id, value
+        FROM custom_field_values
+        WHERE job_application_id = :job_application_id",
+        params,
+    )?)
+}
+
+/// Undo or redo the change in a journal entry, and mark it as undone or not
+///
+/// A change can only be undone while its job application has the version from after the change, and redone while it has the version from before,
+/// so a change made some other way since isn't lost. Otherwise this returns [RepositoryError::Conflict].
+/// Undoing or redoing changes the version, so the entries next to this one for the same job application are given the new version.
+fn apply_journal_entry<C: Queryable>(
+    conn: &mut C,
+    entry: &JournalEntry,
+    undo: bool,
+) -> Result<(), RepositoryError> {
+    let versions: Option<(i32, i32)> = conn.exec_first(
+        "SELECT version_before, version_after FROM journal_entries WHERE id = :id",
+        params! {"id" => entry.id},
+    )?;
+    let (version_before, version_after) = versions.unwrap_or_default();
+    let (expected_version, replaced_version) = if undo {
+        (version_after, version_before)
+    } else {
+        (version_before, version_after)
+    };
+    if query_version(conn, entry.job_application_id)? != Some(expected_version) {
+        return Err(RepositoryError::journal_entry_changed(entry, undo));
+    }
+
+    match (entry.operation, undo) {
+        (JournalOperation::Insert | JournalOperation::Restore, true)
+        | (JournalOperation::Delete, false) => {
+            set_deleted(conn, entry.job_application_id, true)?;
+        }
+        (JournalOperation::Insert | JournalOperation::Restore, false)
+        | (JournalOperation::Delete, true) => {
+            set_deleted(conn, entry.job_application_id, false)?;
+        }
+        (JournalOperation::Update, _) => audited_update(conn, entry.job_application_id, |conn| {
+            load_journal_snapshot(conn, entry, !undo)
+        })?,
+    }
+
+    // The job application is now the way it was after the change before this one, or before the change after it
+    let params = params! {
+        "version" => query_version(conn, entry.job_application_id)?,
+        "job_application_id" => entry.job_application_id,
+        "replaced_version" => replaced_version,
+        "id" => entry.id,
+    };
+    if undo {
+        conn.exec_drop(
+            "UPDATE journal_entries SET version_after = :version
+        WHERE job_application_id = :job_application_id AND version_after = :replaced_version AND NOT undone AND id < :id",
+            &params,
+        )?;
+        Ok(conn.exec_drop(
+            "UPDATE journal_entries SET version_before = :version, undone = TRUE WHERE id = :id",
+            params,
+        )?)
+    } else {
+        conn.exec_drop(
+            "UPDATE journal_entries SET version_before = :version
+        WHERE job_application_id = :job_application_id AND version_before = :replaced_version AND undone AND id > :id",
+            &params,
+        )?;
+        Ok(conn.exec_drop(
+            "UPDATE journal_entries SET version_after = :version, undone = FALSE WHERE id = :id",
+            params,
+        )?)
+    }
+}
+
+/// Get the [JobApplication::version] of job application `id`, whether or not it is in the trash
+fn query_version<C: Queryable>(conn: &mut C, id: i32) -> Result<Option<i32>, RepositoryError> {
+    Ok(conn.exec_first(
+        "SELECT version FROM job_applications WHERE id = :id",
+        params! {"id" => id},
+    )?)
+}
+
+/// Put a job application and its custom fields back the way they were before or after the change in a journal entry
+fn load_journal_snapshot<C: Queryable>(
+    conn: &mut C,
+    entry: &JournalEntry,
+    is_after: bool,
+) -> Result<(), RepositoryError> {
+    let params = params! {
+        "journal_entry_id" => entry.id,
+        "is_after" => is_after,
+        "job_application_id" => entry.job_application_id,
+    };
+
+    conn.exec_drop(
+        "UPDATE job_applications
+        INNER JOIN journal_snapshots ON journal_snapshots.journal_entry_id = :journal_entry_id AND journal_snapshots.is_after = :is_after
+        SET job_applications.source = journal_snapshots.source,
+        job_applications.company = journal_snapshots.company,
+        job_applications.job_title = journal_snapshots.job_title,
+        job_applications.application_date = journal_snapshots.application_date,
+        job_applications.time_investment = journal_snapshots.time_investment,
+        job_applications.human_response = journal_snapshots.human_response,
+        job_applications.human_response_date = journal_snapshots.human_response_date,
+        job_applications.application_website = journal_snapshots.application_website,
+        job_applications.notes = journal_snapshots.notes,
+        job_applications.salary_min = journal_snapshots.salary_min,
+        job_applications.salary_max = journal_snapshots.salary_max,
+        job_applications.salary_currency = journal_snapshots.salary_currency,
+        job_applications.pay_period = journal_snapshots.pay_period,
+        job_applications.equity = journal_snapshots.equity,
+        job_applications.bonus = journal_snapshots.bonus,
+        job_applications.city = journal_snapshots.city,
+        job_applications.region = journal_snapshots.region,
+        job_applications.country = journal_snapshots.country,
+        job_applications.work_arrangement = journal_snapshots.work_arrangement,
+        job_applications.relocation = journal_snapshots.relocation,
+        job_applications.visa_sponsorship = journal_snapshots.visa_sponsorship,
+        job_applications.version = job_applications.version + 1,
+        job_applications.updated_at = UTC_TIMESTAMP()
+        WHERE job_applications.id = :job_application_id",
+        &params,
+    )?;
+
+    conn.exec_drop(
+        "DELETE FROM custom_field_values WHERE job_application_id = :job_application_id",
+        params! {"job_application_id" => entry.job_application_id},
+    )?;
+    Ok(conn.exec_drop(
+        "INSERT INTO custom_field_values (job_application_id, custom_field_id, value)
+        SELECT :job_application_id, custom_field_id, value
+        FROM journal_custom_field_values
+        WHERE journal_entry_id = :journal_entry_id AND is_after = :is_after",
+        params,
+    )?)
+}
+
+/// Delete stored files that nothing is attached to anymore
+///
+/// MySQL doesn't run triggers for foreign key cascades, so this is done after anything that deletes attachments.
+fn delete_unused_attachment_files<C: Queryable>(conn: &mut C) -> Result<(), RepositoryError> {
+    Ok(conn.query_drop(
+        "DELETE FROM attachment_files
+        WHERE hash NOT IN (SELECT file_hash FROM attachments)",
+    )?)
+}
+
+/// Fill in the custom field values of each job application from the database
+fn load_custom_fields<'a, C: Queryable>(
+    conn: &mut C,
+    job_applications: impl IntoIterator<Item = &'a mut JobApplication>,
+) -> Result<(), RepositoryError> {
+    let mut job_applications_by_id: HashMap<i32, &mut JobApplication> = job_applications
+        .into_iter()
+        .map(|job_application| (job_application.id, job_application))
+        .collect();
+    if job_applications_by_id.is_empty() {
+        return Ok(());
+    }
+
+    // Load the values for every job application at once
+    let ids: Vec<Value> = job_applications_by_id.keys().map(|id| id.into()).collect();
+    let placeholders = vec!["?"; ids.len()].join(", ");
+    let rows: Vec<(i32, String, String, String)> = conn.exec(
+        format!(
+            "SELECT custom_field_values.job_application_id, custom_fields.name, custom_fields.field_type, custom_field_values.value
+        FROM custom_field_values
+        INNER JOIN custom_fields ON custom_fields.id = custom_field_values.custom_field_id
+        WHERE custom_field_values.job_application_id IN ({placeholders})"
+        ),
+        ids,
+    )?;
+
+    for (job_application_id, name, field_type, value) in rows {
+        let value = field_type
+            .parse::<CustomFieldType>()
+            .and_then(|field_type| field_type.value_from_stored_string(&value))
+            .map_err(|error| RepositoryError::Backend(error.into()))?;
+        if let Some(job_application) = job_applications_by_id.get_mut(&job_application_id) {
+            job_application.custom_fields.insert(name, value);
+        }
+    }
+
+    Ok(())
+}
+
+/// Replace all custom field values of a job application with values from [prepare_custom_field_values]
+fn write_custom_field_values<C: Queryable>(
+    conn: &mut C,
+    job_application_id: i32,
+    custom_field_values: &[(i32, String)],
+) -> Result<(), RepositoryError> {
+    conn.exec_drop(
+        "DELETE FROM custom_field_values WHERE job_application_id = :job_application_id",
+        params! {"job_application_id" => job_application_id},
+    )?;
+    Ok(conn.exec_batch(
+        "INSERT INTO custom_field_values (job_application_id, custom_field_id, value)
+        VALUES (:job_application_id, :custom_field_id, :value)",
+        custom_field_values.iter().map(|(custom_field_id, value)| {
+            params! {
+                "job_application_id" => job_application_id,
+                "custom_field_id" => custom_field_id,
+                "value" => value,
+            }
+        }),
+    )?)
+}
+
+/// Trim a tag name, making sure there is something left
+fn validate_tag_name(name: &str) -> Result<&str, RepositoryError> {
+    let name = name.trim();
+    if name.is_empty() {
+        Err(RepositoryError::Validation(
+            "Tag names can't be empty".to_owned(),
+        ))
+    } else {
+        Ok(name)
+    }
+}
+
+/// Translate full-text terms into a MySQL boolean mode query string
+///
+/// Every term is required (`+`). Words and phrases are quoted so nothing the user types is interpreted as an operator.
+fn to_boolean_mode_query(terms: &[FullTextTerm]) -> String {
+    terms
+        .iter()
+        .filter_map(|term| match term {
+            FullTextTerm::Word(word) | FullTextTerm::Phrase(word) => {
+                Some(format!("+\"{}\"", word.replace('"', " ")))
+            }
+            // Prefixes can't be quoted, so strip anything that could be an operator
+            FullTextTerm::Prefix(prefix) => {
+                let prefix: String = prefix
+                    .chars()
+                    .filter(|c| c.is_alphanumeric() || *c == '_')
+                    .collect();
+                Some(format!("+{prefix}*")).filter(|_| !prefix.is_empty())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
@@ -1,20 +1,45 @@
-use rusqlite::{named_params, params_from_iter, Connection, OptionalExtension, Params, ToSql};
+use std::{collections::BTreeMap, path::Path};
+
+use rusqlite::{
+    ffi, named_params, params_from_iter, types::Type, Connection, ErrorCode, OptionalExtension,
+    Params, Statement, ToSql,
+};
 use time::{Date, Duration};
 
-use crate::job_application_model::{
-    HumanResponse, JobApplication, JobApplicationField, PartialJobApplication,
+use crate::{
+    backend_connection::db_file_path,
+    backup::{self, Backup, BackupRetention},
+    job_application_model::{
+        compress_text, Activity, Attachment, AttachmentRole, AuditLogEntry, CustomField,
+        CustomFieldType, CustomFieldValue, DeletedJobApplication, FullTextSearchResult,
+        HumanResponse, JobApplication, JobApplicationField, JobDescription, JournalEntry,
+        JournalOperation, NewJobApplication, Offer, PartialJobApplication, ResumeVersion,
+        ResumeVersionStats, Tag, WorkArrangementStats, SNIPPET_MATCH_END, SNIPPET_MATCH_START,
+    },
+    plain_text,
+    search_query::{LocationField, SearchCondition, SearchQuery},
+    sync::{self, SyncChoice, SyncConflict, SyncReport},
 };
 
-use super::JobApplicationRepository;
+use super::{
+    activity_query, clean_file_name,
+    custom_fields::{
+        check_custom_field_value, custom_field_filter, find_custom_field,
+        prepare_custom_field_values, CustomFieldFilter,
+    },
+    full_text::{contains_pattern, make_snippet, parse_full_text_query, FullTextTerm},
+    sort_resume_version_stats, sort_work_arrangement_stats, validate, validate_changed,
+    validate_new, JobApplicationRepository, RepositoryError, SqliteRepository, BATCH_SIZE,
+    JOURNAL_LENGTH, RESUME_VERSION_STATS_QUERY, WORK_ARRANGEMENT_STATS_QUERY,
+};
 
 impl JobApplicationRepository for Connection {
-    type Error = rusqlite::Error;
-
-    fn get_job_applications(&mut self) -> Result<Vec<JobApplication>, Self::Error> {
+    fn get_job_applications(&mut self) -> Result<Vec<JobApplication>, RepositoryError> {
         execute_query(
             self,
-            "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes \
-            FROM job_applications",
+            "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version \
+            FROM job_applications \
+            WHERE deleted_at IS NULL",
             ()
         )
     }
@@ -22,41 +47,157 @@ impl JobApplicationRepository for Connection {
     fn get_job_application_by_id(
         &mut self,
         id: i32,
-    ) -> Result<Option<JobApplication>, Self::Error> {
-        let mut stmt = self.prepare_cached("SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes \
+    ) -> Result<Option<JobApplication>, RepositoryError> {
+        let mut stmt = self.prepare_cached("SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version \
             FROM job_applications \
-            WHERE id = ?"
+            WHERE id = ? AND deleted_at IS NULL"
         )?;
 
         // Execute the statement
         // At most one row can be returned when querying by primary key
-        stmt.query_row((id,), |row| row.try_into()).optional()
+        let mut job_application: Option<JobApplication> =
+            stmt.query_row((id,), |row| row.try_into()).optional()?;
+        drop(stmt);
+
+        load_custom_fields(self, job_application.iter_mut())?;
+        Ok(job_application)
     }
 
-    fn search_job_applications(&mut self, query: &str) -> Result<Vec<JobApplication>, Self::Error> {
+    fn get_job_application_versions(&mut self) -> Result<BTreeMap<i32, i32>, RepositoryError> {
+        self.prepare_cached("SELECT id, version FROM job_applications WHERE deleted_at IS NULL")?
+            .query_map((), |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()
+            .map_err(RepositoryError::from)
+    }
+
+    fn data_version(&mut self) -> Result<Option<i64>, RepositoryError> {
+        Ok(Some(self.pragma_query_value(
+            None,
+            "data_version",
+            |row| row.get(0),
+        )?))
+    }
+
+    fn get_job_application_activity(&mut self) -> Result<BTreeMap<i32, Activity>, RepositoryError> {
+        self.prepare_cached(&activity_query(GREATEST))?
+            .query_map((), |row| {
+                Ok((
+                    row.get("id")?,
+                    Activity {
+                        created_at: row.get("created_at")?,
+                        updated_at: row.get("updated_at")?,
+                        last_activity: row.get("last_activity")?,
+                    },
+                ))
+            })?
+            .collect::<Result<_, _>>()
+            .map_err(RepositoryError::from)
+    }
+
+    fn search_job_applications(
+        &mut self,
+        query: &str,
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
         // Add wildcards to query
         let query_with_wildcards = "%".to_owned() + &query.to_lowercase() + "%";
 
         execute_query(
             self,
-            "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes \
+            "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version \
             FROM job_applications \
-            WHERE LOWER(source) LIKE ?1 \
-            OR LOWER(company) LIKE ?1 \
-            OR LOWER(job_title) LIKE ?1",
+            WHERE deleted_at IS NULL AND ( \
+                LOWER(source) LIKE ?1 \
+                OR LOWER(company) LIKE ?1 \
+                OR LOWER(job_title) LIKE ?1 \
+            )",
         (query_with_wildcards,)
         )
     }
 
+    fn full_text_search(
+        &mut self,
+        query: &str,
+    ) -> Result<Vec<FullTextSearchResult>, RepositoryError> {
+        let terms = parse_full_text_query(query);
+        let fts_query = to_fts5_query(&terms);
+
+        // An empty MATCH is a syntax error in FTS5, but no terms should just mean no results
+        if fts_query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut stmt = self.prepare_cached(
+            "SELECT job_applications.id, job_applications.source, job_applications.company, job_applications.job_title, job_applications.application_date, \
+            job_applications.time_investment, job_applications.human_response, job_applications.human_response_date, job_applications.application_website, job_applications.notes, \
+            job_applications.salary_min, job_applications.salary_max, job_applications.salary_currency, job_applications.pay_period, job_applications.equity, job_applications.bonus, \
+            job_applications.city, job_applications.region, job_applications.country, job_applications.work_arrangement, job_applications.relocation, job_applications.visa_sponsorship, job_applications.version, \
+            matches.relevance, matches.snippet \
+            FROM ( \
+                SELECT id, MAX(relevance) AS relevance, MAX(snippet) AS snippet \
+                FROM ( \
+                    SELECT rowid AS id, -bm25(job_applications_fts) AS relevance, snippet(job_applications_fts, -1, ?2, ?3, '...', 12) AS snippet \
+                    FROM job_applications_fts \
+                    WHERE job_applications_fts MATCH ?1 \
+                    UNION ALL \
+                    SELECT rowid AS id, -bm25(job_descriptions_fts) AS relevance, NULL AS snippet \
+                    FROM job_descriptions_fts \
+                    WHERE job_descriptions_fts MATCH ?1 \
+                ) \
+                GROUP BY id \
+            ) AS matches \
+            INNER JOIN job_applications ON job_applications.id = matches.id \
+            WHERE job_applications.deleted_at IS NULL \
+            ORDER BY matches.relevance DESC",
+        )?;
+
+        // The snippet is `None` when only the job description matched
+        let row_iter =
+            stmt.query_map((fts_query, SNIPPET_MATCH_START, SNIPPET_MATCH_END), |row| {
+                Ok((
+                    FullTextSearchResult {
+                        job_application: row.try_into()?,
+                        relevance: row.get("relevance")?,
+                        snippet: String::new(),
+                    },
+                    row.get::<&str, Option<String>>("snippet")?,
+                ))
+            })?;
+
+        let mut rows = Vec::new();
+        for row in row_iter {
+            rows.push(row?);
+        }
+        drop(stmt);
+
+        // The job descriptions are compressed, so `snippet()` can't read them. Make those snippets here instead.
+        let mut results = Vec::with_capacity(rows.len());
+        for (result, snippet) in rows {
+            let snippet = match snippet {
+                Some(snippet) => snippet,
+                None => self
+                    .get_job_description(result.job_application.id)?
+                    .and_then(|job_description| make_snippet(&job_description.description, &terms))
+                    .unwrap_or_default(),
+            };
+            results.push(FullTextSearchResult { snippet, ..result });
+        }
+
+        load_custom_fields(
+            self,
+            results.iter_mut().map(|result| &mut result.job_application),
+        )?;
+        Ok(results)
+    }
+
     fn search_by_human_response(
         &mut self,
         human_response: HumanResponse,
-    ) -> Result<Vec<JobApplication>, Self::Error> {
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
         execute_query(
             self,
-            "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes \
+            "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version \
             FROM job_applications \
-            WHERE human_response = ?",
+            WHERE human_response = ? AND deleted_at IS NULL",
             (human_response,)
         )
     }
@@ -65,30 +206,185 @@ impl JobApplicationRepository for Connection {
         &mut self,
         query: &str,
         human_response: HumanResponse,
-    ) -> Result<Vec<JobApplication>, Self::Error> {
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
         // Add wildcards to query
         let query_with_wildcards = "%".to_owned() + &query.to_lowercase() + "%";
 
         execute_query(
             self,
-            "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes \
+            "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version \
             FROM job_applications \
             WHERE ( \
                 LOWER(source) LIKE ?1 \
                 OR LOWER(company) LIKE ?1 \
                 OR LOWER(job_title) LIKE ?1 \
-            ) AND human_response = ?2",
+            ) AND human_response = ?2 AND deleted_at IS NULL",
         (query_with_wildcards, human_response)
         )
     }
 
+    fn query_job_applications(
+        &mut self,
+        query: &SearchQuery,
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
+        // Build the WHERE clause from the filters
+        // This is not a SQLi vulnerability because everything from the query is passed as a parameter.
+        // Only the column names and operators, which are defined statically in `search_query`, are put in the string.
+        let mut query_builder = "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version \
+            FROM job_applications \
+            WHERE deleted_at IS NULL".to_owned();
+        let mut params: Vec<Box<dyn ToSql>> = Vec::new();
+
+        // Custom field filters need to know the type of the field
+        let has_custom_field_filter = query
+            .filters
+            .iter()
+            .any(|filter| matches!(filter.condition, SearchCondition::CustomField(..)));
+        let custom_fields = if has_custom_field_filter {
+            query_custom_fields(self)?
+        } else {
+            Vec::new()
+        };
+
+        for filter in &query.filters {
+            let condition = match &filter.condition {
+                SearchCondition::FullText(text) => {
+                    let fts_query = to_fts5_query(&parse_full_text_query(text));
+                    if fts_query.is_empty() {
+                        "1".to_owned()
+                    } else {
+                        // Job descriptions are indexed separately, so either index may match
+                        params.push(Box::new(fts_query.clone()));
+                        params.push(Box::new(fts_query));
+                        "(id IN (SELECT rowid FROM job_applications_fts WHERE job_applications_fts MATCH ?) \
+                        OR id IN (SELECT rowid FROM job_descriptions_fts WHERE job_descriptions_fts MATCH ?))"
+                            .to_owned()
+                    }
+                }
+                SearchCondition::Field(field, text) => {
+                    // Match part of the field like MySQL does, rather than FTS5's whole tokens. LIKE ignores case for ASCII characters.
+                    let terms = parse_full_text_query(text);
+                    if terms.is_empty() {
+                        "1".to_owned()
+                    } else {
+                        terms
+                            .iter()
+                            .map(|term| {
+                                params.push(Box::new(contains_pattern(term)));
+                                format!("{} LIKE ? ESCAPE '\\'", field.column_name())
+                            })
+                            .collect::<Vec<_>>()
+                            .join(" AND ")
+                    }
+                }
+                SearchCondition::HumanResponse(human_response) => {
+                    params.push(Box::new(*human_response));
+                    "human_response = ?".to_owned()
+                }
+                SearchCondition::Date(field, comparison, date) => {
+                    params.push(Box::new(*date));
+                    format!("{} {} ?", field.expression(GREATEST), comparison.operator())
+                }
+                SearchCondition::Tag(name) => {
+                    params.push(Box::new(name.clone()));
+                    "id IN (SELECT job_application_id FROM job_application_tags \
+                        INNER JOIN tags ON tags.id = job_application_tags.tag_id \
+                        WHERE tags.name = ?)"
+                        .to_owned()
+                }
+                SearchCondition::CustomField(name, comparison, value) => {
+                    let custom_field = find_custom_field(&custom_fields, name)
+                        .map_err(RepositoryError::NotFound)?;
+                    params.push(Box::new(custom_field.id));
+                    let value_condition =
+                        match custom_field_filter(custom_field, *comparison, value)
+                            .map_err(RepositoryError::Validation)?
+                        {
+                            // LIKE ignores case for ASCII characters
+                            CustomFieldFilter::Contains(text) => {
+                                params.push(Box::new(format!("%{text}%")));
+                                "value LIKE ?".to_owned()
+                            }
+                            CustomFieldFilter::EqualsIgnoringCase(text) => {
+                                params.push(Box::new(text));
+                                "value = ? COLLATE NOCASE".to_owned()
+                            }
+                            CustomFieldFilter::Number(comparison, number) => {
+                                params.push(Box::new(number));
+                                format!("CAST(value AS REAL) {} ?", comparison.operator())
+                            }
+                            CustomFieldFilter::Text(comparison, text) => {
+                                params.push(Box::new(text));
+                                format!("value {} ?", comparison.operator())
+                            }
+                        };
+                    format!(
+                        "id IN (SELECT job_application_id FROM custom_field_values \
+                        WHERE custom_field_id = ? AND {value_condition})"
+                    )
+                }
+                // LIKE ignores case for ASCII characters
+                SearchCondition::Location(field, text) => {
+                    let fields = field.map_or(LocationField::ALL.to_vec(), |field| vec![field]);
+                    let conditions: Vec<String> = fields
+                        .iter()
+                        .map(|field| {
+                            params.push(Box::new(format!("%{text}%")));
+                            format!("{} LIKE ?", field.column_name())
+                        })
+                        .collect();
+                    format!("({})", conditions.join(" OR "))
+                }
+                SearchCondition::WorkArrangement(work_arrangement) => {
+                    params.push(Box::new(*work_arrangement));
+                    "work_arrangement = ?".to_owned()
+                }
+                SearchCondition::Bool(field, value) => {
+                    params.push(Box::new(*value));
+                    format!("{} = ?", field.column_name())
+                }
+            };
+
+            query_builder += " AND ";
+            if filter.negated {
+                // NULL (e.g. comparing a missing response date) counts as not matching, so the negation should match
+                query_builder += &format!("NOT IFNULL({condition}, 0)");
+            } else {
+                query_builder += &condition;
+            }
+        }
+
+        // We will not be caching this due to the variance in the number of ways to represent this query
+        let mut stmt = self.prepare(&query_builder)?;
+        let row_iter = stmt.query_map(params_from_iter(params), |row| row.try_into())?;
+
+        let mut row_vec: Vec<JobApplication> = Vec::new();
+        for row in row_iter {
+            row_vec.push(row?);
+        }
+        drop(stmt);
+
+        load_custom_fields(self, row_vec.iter_mut())?;
+        Ok(row_vec)
+    }
+
     fn insert_job_application(
         &mut self,
-        application: &JobApplication,
-    ) -> Result<JobApplication, Self::Error> {
-        self.prepare_cached(
-            "INSERT INTO job_applications (source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes) \
-                VALUES (:source, :company, :job_title, :application_date, :time_investment, :human_response, :human_response_date, :application_website, :notes)")?
+        application: &NewJobApplication,
+    ) -> Result<JobApplication, RepositoryError> {
+        validate_new(application)?;
+
+        // The job application and its custom fields should be saved together
+        let tx = self.savepoint()?;
+
+        // Check the custom fields before writing anything
+        let custom_field_values =
+            prepare_custom_field_values(&query_custom_fields(&tx)?, &application.custom_fields)
+                .map_err(RepositoryError::Validation)?;
+
+        let id = tx.prepare_cached(
+            "INSERT INTO job_applications (source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, created_at, updated_at) \
+                VALUES (:source, :company, :job_title, :application_date, :time_investment, :human_response, :human_response_date, :application_website, :notes, :salary_min, :salary_max, :salary_currency, :pay_period, :equity, :bonus, :city, :region, :country, :work_arrangement, :relocation, :visa_sponsorship, datetime('now'), datetime('now'))")?
             // If the preparation succeeded, insert the row
             .insert(
                 named_params! {
@@ -101,10 +397,27 @@ impl JobApplicationRepository for Connection {
                     ":human_response_date": application.human_response_date,
                     ":application_website": application.application_website,
                     ":notes": application.notes,
+                    ":salary_min": application.salary_min,
+                    ":salary_max": application.salary_max,
+                    ":salary_currency": application.salary_currency,
+                    ":pay_period": application.pay_period,
+                    ":equity": application.equity,
+                    ":bonus": application.bonus,
+                    ":city": application.city,
+                    ":region": application.region,
+                    ":country": application.country,
+                    ":work_arrangement": application.work_arrangement,
+                    ":relocation": application.relocation,
+                    ":visa_sponsorship": application.visa_sponsorship,
                 }
-            )
-            // If that succeeded, return the new job application
-            .map(|id| JobApplication {id: id as i32, ..application.clone()})
+            )? as i32;
+
+        write_custom_field_values(&tx, id, &custom_field_values)?;
+        add_journal_entry(&tx, id, JournalOperation::Insert)?;
+        tx.commit()?;
+
+        // If that succeeded, return the new job application
+        Ok(application.clone().with_id(id))
     }
 
     fn update_human_response(
@@ -112,55 +425,59 @@ impl JobApplicationRepository for Connection {
         id: i32,
         human_response: HumanResponse,
         human_response_date: Option<Date>,
-    ) -> Result<(), Self::Error> {
-        let mut stmt = self.prepare_cached(
-            "UPDATE job_applications \
-            SET human_response = :human_response, human_response_date = :human_response_date \
-            WHERE id = :id",
-        )?;
+    ) -> Result<JobApplication, RepositoryError> {
+        journaled_update(self, id, |tx| {
+            let mut stmt = tx.prepare_cached(&format!(
+                "UPDATE job_applications \
+                SET human_response = :human_response, human_response_date = :human_response_date, version = version + 1, updated_at = datetime('now') \
+                WHERE id = :id \
+                {RETURNING_JOB_APPLICATION}"
+            ))?;
+
+            let job_application = stmt.query_row(
+                named_params! {
+                    ":id": id,
+                    ":human_response": human_response,
+                    ":human_response_date": human_response_date,
+                },
+                |row| row.try_into(),
+            )?;
+            drop(stmt);
 
-        stmt.execute(named_params! {
-            ":id": id,
-            ":human_response": human_response,
-            ":human_response_date": human_response_date,
+            // The new response date has to make sense with the rest of the job application
+            validate_changed(
+                &job_application,
+                &[
+                    "human_response".to_owned(),
+                    "human_response_date".to_owned(),
+                ],
+            )?;
+            with_custom_fields(tx, job_application)
         })
-        .map(|_| ())
     }
 
-    fn update_job_application(&mut self, application: &JobApplication) -> Result<(), Self::Error> {
-        let mut stmt = self.prepare_cached(
-            "UPDATE job_applications \
-            SET source = :source, \
-            company = :company, \
-            job_title = :job_title, \
-            application_date = :application_date, \
-            time_investment = :time_investment, \
-            human_response = :human_response, \
-            human_response_date = :human_response_date, \
-            application_website = :application_website, \
-            notes = :notes \
-            WHERE id = :id",
-        )?;
+    fn update_job_application(
+        &mut self,
+        application: &JobApplication,
+    ) -> Result<JobApplication, RepositoryError> {
+        validate(application)?;
+
+        // Saving a job application without changing anything shouldn't need to be undone
+        if self.get_job_application_by_id(application.id)?.as_ref() == Some(application) {
+            return Ok(application.clone());
+        }
 
-        stmt.execute(named_params! {
-            ":id": application.id,
-            ":source": application.source,
-            ":company": application.company,
-            ":job_title": application.job_title,
-            ":application_date": application.application_date,
-            ":time_investment": application.time_investment.map(Duration::whole_seconds),
-            ":human_response": application.human_response,
-            ":human_response_date": application.human_response_date,
-            ":application_website": application.application_website,
-            ":notes": application.notes,
+        // The job application and its custom fields are saved together, in the same transaction as the journal entry
+        journaled_update(self, application.id, |tx| {
+            let mut stmt = tx.prepare_cached(&update_job_application_query())?;
+            update_row(tx, &mut stmt, &query_custom_fields(tx)?, application)
         })
-        .map(|_| ())
     }
 
     fn update_job_application_partial(
         &mut self,
         partial_application: PartialJobApplication,
-    ) -> Result<(), Self::Error> {
+    ) -> Result<JobApplication, RepositoryError> {
         // Build the query parameters in a string
         // This is necessary because we only want to modify the given columns
         // This is not a SQLi vulnerability because we will only be using this for the names, which are defined statically in `JobApplicationField::name()`
@@ -179,9 +496,10 @@ impl JobApplicationRepository for Connection {
                 if id_index.is_none() {
                     id_index = Some(index + 1);
                 } else {
-                    return Err(rusqlite::Error::ToSqlConversionFailure(Box::from(
-                        "Unable to generate SQL statement because there are multiple id fields",
-                    )));
+                    return Err(RepositoryError::Validation(
+                        "Unable to generate SQL statement because there are multiple id fields"
+                            .to_owned(),
+                    ));
                 }
             } else if is_first {
                 // The first non-id value is special because of where the SET and commas are
@@ -195,35 +513,1384 @@ impl JobApplicationRepository for Connection {
 
         // Assert there is at least one change
         if is_first {
-            return Err(rusqlite::Error::ToSqlConversionFailure(Box::from(
-                "Unable to generate SQL statement because there are no changes",
-            )));
+            return Err(RepositoryError::Validation(
+                "Unable to generate SQL statement because there are no changes".to_owned(),
+            ));
         }
 
         // End with the WHERE clause
         query_builder += &format!(
-            "\nWHERE id = ?{}",
-            id_index.ok_or(rusqlite::Error::ToSqlConversionFailure(Box::from(
-                "Unable to generate SQL statement because there is no id field"
-            )))?
+            ",\nversion = version + 1,\nupdated_at = datetime('now')\nWHERE id = ?{} {RETURNING_JOB_APPLICATION}",
+            id_index.ok_or_else(|| RepositoryError::Validation(
+                "Unable to generate SQL statement because there is no id field".to_owned()
+            ))?
         );
 
-        // Now that we have the statement, prepare it
-        // We will not be caching this due to the variance in the number of ways to represent this query
-        let mut stmt = self.prepare(&query_builder)?;
+        // The id was found above, so this is always a `JobApplicationField::Id`
+        let JobApplicationField::Id(id) = partial_application.0[id_index.unwrap_or_default() - 1]
+        else {
+            unreachable!("id_index should point to the id field");
+        };
+
+        let changed: Vec<String> = partial_application
+            .0
+            .iter()
+            .map(JobApplicationField::name)
+            .collect();
+        journaled_update(self, id, |tx| {
+            // Now that we have the statement, prepare it
+            // We will not be caching this due to the variance in the number of ways to represent this query
+            let mut stmt = tx.prepare(&query_builder)?;
+
+            // Finally, execute returning the updated job application
+            let job_application = stmt.query_row(
+                params_from_iter(Into::<Vec<Box<dyn ToSql>>>::into(partial_application)),
+                |row| row.try_into(),
+            )?;
+            drop(stmt);
+
+            // The changed fields have to make sense with the others, which may not have been checked when they were saved
+            validate_changed(&job_application, &changed)?;
+            with_custom_fields(tx, job_application)
+        })
+    }
+
+    fn delete_job_application(&mut self, id: i32) -> Result<JobApplication, RepositoryError> {
+        let tx = self.savepoint()?;
+        let job_application = tx
+            .prepare_cached(&format!(
+                "UPDATE job_applications SET deleted_at = datetime('now') \
+                WHERE id = ? AND deleted_at IS NULL \
+                {RETURNING_JOB_APPLICATION}"
+            ))?
+            .query_row((id,), |row| row.try_into())
+            .optional()?
+            .ok_or_else(|| RepositoryError::job_application_not_found(id))?;
+        add_journal_entry(&tx, id, JournalOperation::Delete)?;
+        let job_application = with_custom_fields(&tx, job_application)?;
+
+        tx.commit()?;
+        Ok(job_application)
+    }
+
+    fn insert_many(
+        &mut self,
+        applications: &[NewJobApplication],
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
+        for application in applications {
+            validate_new(application)?;
+        }
+
+        let tx = self.savepoint()?;
+
+        // Check the custom fields before writing anything
+        let custom_fields = query_custom_fields(&tx)?;
+        let custom_field_values = applications
+            .iter()
+            .map(|application| {
+                prepare_custom_field_values(&custom_fields, &application.custom_fields)
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(RepositoryError::Validation)?;
+
+        let mut ids = Vec::with_capacity(applications.len());
+        for batch in applications.chunks(BATCH_SIZE) {
+            let time_investments: Vec<Option<i64>> = batch
+                .iter()
+                .map(|application| application.time_investment.map(Duration::whole_seconds))
+                .collect();
+            let params = batch.iter().zip(&time_investments).flat_map(
+                |(application, time_investment)| -> [&dyn ToSql; 21] {
+                    [
+                        &application.source,
+                        &application.company,
+                        &application.job_title,
+                        &application.application_date,
+                        time_investment,
+                        &application.human_response,
+                        &application.human_response_date,
+                        &application.application_website,
+                        &application.notes,
+                        &application.salary_min,
+                        &application.salary_max,
+                        &application.salary_currency,
+                        &application.pay_period,
+                        &application.equity,
+                        &application.bonus,
+                        &application.city,
+                        &application.region,
+                        &application.country,
+                        &application.work_arrangement,
+                        &application.relocation,
+                        &application.visa_sponsorship,
+                    ]
+                },
+            );
+            let values = vec![
+                "(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))";
+                batch.len()
+            ]
+            .join(", ");
+
+            let mut batch_ids = tx
+                .prepare(&format!(
+                    "INSERT INTO job_applications (source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, created_at, updated_at) \
+                    VALUES {values} \
+                    RETURNING id"
+                ))?
+                .query_map(params_from_iter(params), |row| row.get::<usize, i32>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            // The rows are given ids in the order they are inserted, but RETURNING can give them back in any order
+            batch_ids.sort_unstable();
+            ids.extend(batch_ids);
+        }
+
+        for (id, custom_field_values) in ids.iter().zip(&custom_field_values) {
+            write_custom_field_values(&tx, *id, custom_field_values)?;
+            add_journal_entry(&tx, *id, JournalOperation::Insert)?;
+        }
+        tx.commit()?;
+
+        Ok(applications
+            .iter()
+            .zip(ids)
+            .map(|(application, id)| application.clone().with_id(id))
+            .collect())
+    }
+
+    fn update_many(
+        &mut self,
+        applications: &[JobApplication],
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
+        for application in applications {
+            validate(application)?;
+        }
+
+        self.with_transaction(|conn| {
+            // Saving a job application without changing anything shouldn't need to be undone
+            let mut unchanged = Vec::with_capacity(applications.len());
+            for application in applications {
+                unchanged.push(
+                    conn.get_job_application_by_id(application.id)?.as_ref() == Some(application),
+                );
+            }
+
+            let custom_fields = query_custom_fields(conn)?;
+            let mut stmt = conn.prepare(&update_job_application_query())?;
+            applications
+                .iter()
+                .zip(unchanged)
+                .map(|(application, unchanged)| {
+                    if unchanged {
+                        Ok(application.clone())
+                    } else {
+                        journal_update(conn, application.id, |tx| {
+                            update_row(tx, &mut stmt, &custom_fields, application)
+                        })
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn delete_many(&mut self, ids: &[i32]) -> Result<Vec<JobApplication>, RepositoryError> {
+        let tx = self.savepoint()?;
+
+        let mut deleted = BTreeMap::new();
+        for batch in ids.chunks(BATCH_SIZE) {
+            let placeholders = vec!["?"; batch.len()].join(", ");
+            let mut stmt = tx.prepare(&format!(
+                "UPDATE job_applications SET deleted_at = datetime('now') \
+                WHERE id IN ({placeholders}) AND deleted_at IS NULL \
+                {RETURNING_JOB_APPLICATION}"
+            ))?;
+            for job_application in
+                stmt.query_map(params_from_iter(batch), |row| JobApplication::try_from(row))?
+            {
+                let job_application = job_application?;
+                deleted.insert(job_application.id, job_application);
+            }
+        }
+
+        // Every id has to have been moved, and an id that is given twice was already moved the second time
+        let mut job_applications = ids
+            .iter()
+            .map(|id| {
+                deleted
+                    .remove(id)
+                    .ok_or_else(|| RepositoryError::job_application_not_found(*id))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for id in ids {
+            add_journal_entry(&tx, *id, JournalOperation::Delete)?;
+        }
+        load_custom_fields(&tx, job_applications.iter_mut())?;
+
+        tx.commit()?;
+        Ok(job_applications)
+    }
+
+    fn with_transaction<T, E, F>(&mut self, transaction: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut Self) -> Result<T, E>,
+        E: From<RepositoryError>,
+    {
+        // Savepoints can be nested, unlike transactions. A savepoint outside of a transaction starts one.
+        // This is also why the write methods use savepoints instead of transactions, so they can be run in here.
+        self.execute_batch("SAVEPOINT with_transaction")
+            .map_err(RepositoryError::from)?;
+        match transaction(self) {
+            Ok(result) => {
+                self.execute_batch("RELEASE with_transaction")
+                    .map_err(RepositoryError::from)?;
+                Ok(result)
+            }
+            Err(error) => {
+                self.execute_batch("ROLLBACK TO with_transaction; RELEASE with_transaction")
+                    .map_err(RepositoryError::from)?;
+                Err(error)
+            }
+        }
+    }
+
+    fn move_related_records(&mut self, from_id: i32, to_id: i32) -> Result<(), RepositoryError> {
+        self.with_transaction(|conn| {
+            // Rows that would conflict with ones `to_id` already has are skipped, leaving them with `from_id`
+            for table in [
+                "job_application_tags",
+                "offers",
+                "attachments",
+                "sent_resume_versions",
+            ] {
+                conn.prepare_cached(&format!(
+                    "UPDATE OR IGNORE {table} SET job_application_id = :to_id WHERE job_application_id = :from_id"
+                ))?
+                .execute(named_params! {":from_id": from_id, ":to_id": to_id})?;
+            }
+
+            // The job description index is keyed by job application id, so the description is saved again to move its index entry
+            if conn.get_job_description(to_id)?.is_none() {
+                if let Some(job_description) = conn.get_job_description(from_id)? {
+                    conn.delete_job_description(from_id)?;
+                    conn.save_job_description(&JobDescription {
+                        job_application_id: to_id,
+                        ..job_description
+                    })?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn get_deleted_job_applications(
+        &mut self,
+    ) -> Result<Vec<DeletedJobApplication>, RepositoryError> {
+        let mut stmt = self.prepare_cached(
+            "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version, deleted_at \
+            FROM job_applications \
+            WHERE deleted_at IS NOT NULL \
+            ORDER BY deleted_at DESC, id DESC",
+        )?;
+        let row_iter = stmt.query_map((), |row| row.try_into())?;
+
+        let mut row_vec: Vec<DeletedJobApplication> = Vec::new();
+        for row in row_iter {
+            row_vec.push(row?);
+        }
+        drop(stmt);
+
+        load_custom_fields(
+            self,
+            row_vec
+                .iter_mut()
+                .map(|deleted| &mut deleted.job_application),
+        )?;
+        Ok(row_vec)
+    }
+
+    fn restore_job_application(&mut self, id: i32) -> Result<(), RepositoryError> {
+        let tx = self.savepoint()?;
+        if !set_deleted(&tx, id, false)? {
+            return Err(RepositoryError::job_application_not_found(id));
+        }
+        add_journal_entry(&tx, id, JournalOperation::Restore)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn empty_trash(&mut self) -> Result<usize, RepositoryError> {
+        // Tags, offers, descriptions, and attachments are deleted by their foreign keys
+        Ok(self.execute(
+            "DELETE FROM job_applications WHERE deleted_at IS NOT NULL",
+            (),
+        )?)
+    }
+
+    fn purge_trash(&mut self, days: u32) -> Result<usize, RepositoryError> {
+        let mut stmt = self.prepare_cached(
+            "DELETE FROM job_applications \
+            WHERE deleted_at <= datetime('now', '-' || ? || ' days')",
+        )?;
+
+        Ok(stmt.execute((days,))?)
+    }
+
+    fn undo(&mut self) -> Result<Option<JournalEntry>, RepositoryError> {
+        let tx = self.savepoint()?;
+        let entry: Option<JournalEntry> = tx
+            .prepare_cached(
+                "SELECT id, job_application_id, operation FROM journal_entries \
+                WHERE NOT undone \
+                ORDER BY id DESC \
+                LIMIT 1",
+            )?
+            .query_row((), |row| row.try_into())
+            .optional()?;
+
+        if let Some(entry) = &entry {
+            apply_journal_entry(&tx, entry, true)?;
+        }
+
+        tx.commit()?;
+        Ok(entry)
+    }
+
+    fn redo(&mut self) -> Result<Option<JournalEntry>, RepositoryError> {
+        let tx = self.savepoint()?;
+        // Undone entries are always the newest, so the oldest of them was undone last
+        let entry: Option<JournalEntry> = tx
+            .prepare_cached(
+                "SELECT id, job_application_id, operation FROM journal_entries \
+                WHERE undone \
+                ORDER BY id \
+                LIMIT 1",
+            )?
+            .query_row((), |row| row.try_into())
+            .optional()?;
+
+        if let Some(entry) = &entry {
+            apply_journal_entry(&tx, entry, false)?;
+        }
+
+        tx.commit()?;
+        Ok(entry)
+    }
+
+    fn get_history(
+        &mut self,
+        _job_application_id: i32,
+    ) -> Result<Vec<AuditLogEntry>, RepositoryError> {
+        // A SQLite database belongs to one person, so there is no one else to tell apart
+        Ok(Vec::new())
+    }
+
+    fn get_tags(&mut self) -> Result<Vec<Tag>, RepositoryError> {
+        execute_tag_query(
+            self,
+            "SELECT id, name FROM tags \
+            WHERE id IN ( \
+                SELECT tag_id FROM job_application_tags \
+                INNER JOIN job_applications ON job_applications.id = job_application_tags.job_application_id \
+                WHERE job_applications.deleted_at IS NULL \
+            ) \
+            ORDER BY name",
+            (),
+        )
+    }
+
+    fn get_job_application_tags(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Vec<Tag>, RepositoryError> {
+        execute_tag_query(
+            self,
+            "SELECT tags.id, tags.name FROM tags \
+            INNER JOIN job_application_tags ON job_application_tags.tag_id = tags.id \
+            INNER JOIN job_applications ON job_applications.id = job_application_tags.job_application_id \
+            WHERE job_application_tags.job_application_id = ? AND job_applications.deleted_at IS NULL \
+            ORDER BY tags.name",
+            (job_application_id,),
+        )
+    }
+
+    fn add_tag(&mut self, job_application_id: i32, name: &str) -> Result<Tag, RepositoryError> {
+        let name = validate_tag_name(name)?;
+
+        // Creating the tag and adding it should succeed or fail together
+        let tx = self.savepoint()?;
+        check_job_application_exists(&tx, job_application_id)?;
+
+        // The unique constraint ignores case, so this reuses "Remote" when adding "remote"
+        tx.prepare_cached("INSERT INTO tags (name) VALUES (?) ON CONFLICT (name) DO NOTHING")?
+            .execute((name,))?;
+        let tag: Tag = tx
+            .prepare_cached("SELECT id, name FROM tags WHERE name = ?")?
+            .query_row((name,), |row| row.try_into())?;
+        tx.prepare_cached(
+            "INSERT INTO job_application_tags (job_application_id, tag_id) VALUES (?, ?) \
+            ON CONFLICT DO NOTHING",
+        )?
+        .execute((job_application_id, tag.id))?;
+
+        tx.commit()?;
+        Ok(tag)
+    }
+
+    fn remove_tag(&mut self, job_application_id: i32, name: &str) -> Result<(), RepositoryError> {
+        check_job_application_exists(self, job_application_id)?;
+        let mut stmt = self.prepare_cached(
+            "DELETE FROM job_application_tags \
+            WHERE job_application_id = ? \
+            AND tag_id = (SELECT id FROM tags WHERE name = ?)",
+        )?;
+
+        stmt.execute((job_application_id, name.trim()))?;
+        Ok(())
+    }
+
+    fn rename_tag(&mut self, name: &str, new_name: &str) -> Result<(), RepositoryError> {
+        let new_name = validate_tag_name(new_name)?;
+
+        let tx = self.savepoint()?;
+
+        let find_tag = "SELECT id FROM tags WHERE name = ?";
+        let Some(id) = tx
+            .prepare_cached(find_tag)?
+            .query_row((name.trim(),), |row| row.get::<usize, i32>(0))
+            .optional()?
+        else {
+            return Ok(());
+        };
+        let existing_id = tx
+            .prepare_cached(find_tag)?
+            .query_row((new_name,), |row| row.get::<usize, i32>(0))
+            .optional()?;
+
+        match existing_id {
+            // Merge into the existing tag by moving every job application over to it, then deleting this tag
+            Some(existing_id) if existing_id != id => {
+                tx.prepare_cached(
+                    "INSERT INTO job_application_tags (job_application_id, tag_id) \
+                    SELECT job_application_id, ?1 FROM job_application_tags WHERE tag_id = ?2 \
+                    ON CONFLICT DO NOTHING",
+                )?
+                .execute((existing_id, id))?;
+                tx.prepare_cached("DELETE FROM job_application_tags WHERE tag_id = ?")?
+                    .execute((id,))?;
+                tx.prepare_cached("DELETE FROM tags WHERE id = ?")?
+                    .execute((id,))?;
+            }
+            // Otherwise, this is a plain rename (possibly only changing case)
+            _ => {
+                tx.prepare_cached("UPDATE tags SET name = ? WHERE id = ?")?
+                    .execute((new_name, id))?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn get_custom_fields(&mut self) -> Result<Vec<CustomField>, RepositoryError> {
+        query_custom_fields(self)
+    }
+
+    fn add_custom_field(
+        &mut self,
+        name: &str,
+        field_type: &CustomFieldType,
+    ) -> Result<CustomField, RepositoryError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(RepositoryError::Validation(
+                "Custom field names can't be empty".to_owned(),
+            ));
+        }
+
+        let id = self
+            .prepare_cached("INSERT INTO custom_fields (name, field_type) VALUES (?, ?)")?
+            .insert((name, field_type))?;
+
+        Ok(CustomField {
+            id: id as i32,
+            name: name.to_owned(),
+            field_type: field_type.clone(),
+        })
+    }
+
+    fn delete_custom_field(&mut self, name: &str) -> Result<(), RepositoryError> {
+        // Deleting the field cascades to its values
+        self.prepare_cached("DELETE FROM custom_fields WHERE name = ?")?
+            .execute((name.trim(),))?;
+        Ok(())
+    }
+
+    fn set_custom_field_value(
+        &mut self,
+        job_application_id: i32,
+        name: &str,
+        value: Option<&CustomFieldValue>,
+    ) -> Result<(), RepositoryError> {
+        let custom_fields = query_custom_fields(self)?;
+        let custom_field =
+            find_custom_field(&custom_fields, name).map_err(RepositoryError::NotFound)?;
+
+        if let Some(value) = value {
+            check_custom_field_value(custom_field, value).map_err(RepositoryError::Validation)?;
+        }
+
+        journaled_update(self, job_application_id, |tx| {
+            if let Some(value) = value {
+                tx.prepare_cached(
+                    "INSERT INTO custom_field_values (job_application_id, custom_field_id, value) VALUES (?, ?, ?) \
+                    ON CONFLICT (job_application_id, custom_field_id) DO UPDATE SET value = excluded.value",
+                )?
+                .execute((job_application_id, custom_field.id, value.to_stored_string()))?;
+            } else {
+                tx.prepare_cached(
+                    "DELETE FROM custom_field_values WHERE job_application_id = ? AND custom_field_id = ?",
+                )?
+                .execute((job_application_id, custom_field.id))?;
+            }
+            // Custom fields are part of the job application, so changing one is a new version of it
+            tx.prepare_cached("UPDATE job_applications SET version = version + 1, updated_at = datetime('now') WHERE id = ?")?
+                .execute((job_application_id,))?;
+            Ok(())
+        })
+    }
+
+    fn get_offer(&mut self, job_application_id: i32) -> Result<Option<Offer>, RepositoryError> {
+        self.prepare_cached(
+            "SELECT job_application_id, base_salary, pay_period, currency, bonus, equity_value, vesting_years, benefits, start_date, deadline \
+            FROM offers \
+            WHERE job_application_id = ? \
+            AND job_application_id IN (SELECT id FROM job_applications WHERE deleted_at IS NULL)",
+        )?
+        .query_row((job_application_id,), |row| row.try_into())
+        .optional()
+        .map_err(RepositoryError::from)
+    }
+
+    fn get_offers(&mut self) -> Result<Vec<Offer>, RepositoryError> {
+        let mut stmt = self.prepare_cached(
+            "SELECT job_application_id, base_salary, pay_period, currency, bonus, equity_value, vesting_years, benefits, start_date, deadline \
+            FROM offers \
+            WHERE job_application_id IN (SELECT id FROM job_applications WHERE deleted_at IS NULL) \
+            ORDER BY job_application_id",
+        )?;
+        let row_iter = stmt.query_map((), |row| row.try_into())?;
+
+        let mut row_vec: Vec<Offer> = Vec::new();
+        for row in row_iter {
+            row_vec.push(row?);
+        }
+
+        Ok(row_vec)
+    }
+
+    fn save_offer(&mut self, offer: &Offer) -> Result<(), RepositoryError> {
+        check_job_application_exists(self, offer.job_application_id)?;
+        self.prepare_cached(
+            "INSERT INTO offers (job_application_id, base_salary, pay_period, currency, bonus, equity_value, vesting_years, benefits, start_date, deadline) \
+            VALUES (:job_application_id, :base_salary, :pay_period, :currency, :bonus, :equity_value, :vesting_years, :benefits, :start_date, :deadline) \
+            ON CONFLICT (job_application_id) DO UPDATE SET \
+            base_salary = excluded.base_salary, \
+            pay_period = excluded.pay_period, \
+            currency = excluded.currency, \
+            bonus = excluded.bonus, \
+            equity_value = excluded.equity_value, \
+            vesting_years = excluded.vesting_years, \
+            benefits = excluded.benefits, \
+            start_date = excluded.start_date, \
+            deadline = excluded.deadline",
+        )?
+        .execute(named_params! {
+            ":job_application_id": offer.job_application_id,
+            ":base_salary": offer.base_salary,
+            ":pay_period": offer.pay_period,
+            ":currency": offer.currency,
+            ":bonus": offer.bonus,
+            ":equity_value": offer.equity_value,
+            ":vesting_years": offer.vesting_years,
+            ":benefits": offer.benefits,
+            ":start_date": offer.start_date,
+            ":deadline": offer.deadline,
+        })?;
+        Ok(())
+    }
+
+    fn delete_offer(&mut self, job_application_id: i32) -> Result<(), RepositoryError> {
+        check_job_application_exists(self, job_application_id)?;
+        self.prepare_cached("DELETE FROM offers WHERE job_application_id = ?")?
+            .execute((job_application_id,))?;
+        Ok(())
+    }
+
+    fn get_work_arrangement_stats(&mut self) -> Result<Vec<WorkArrangementStats>, RepositoryError> {
+        let mut stmt = self.prepare_cached(WORK_ARRANGEMENT_STATS_QUERY)?;
+        let row_iter = stmt.query_map((), |row| row.try_into())?;
+
+        let mut row_vec: Vec<WorkArrangementStats> = Vec::new();
+        for row in row_iter {
+            row_vec.push(row?);
+        }
+
+        sort_work_arrangement_stats(&mut row_vec);
+        Ok(row_vec)
+    }
+
+    fn get_job_description(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Option<JobDescription>, RepositoryError> {
+        self.prepare_cached(
+            "SELECT job_application_id, description, captured_date \
+            FROM job_descriptions \
+            WHERE job_application_id = ? \
+            AND job_application_id IN (SELECT id FROM job_applications WHERE deleted_at IS NULL)",
+        )?
+        .query_row((job_application_id,), |row| row.try_into())
+        .optional()
+        .map_err(RepositoryError::from)
+    }
+
+    fn save_job_description(
+        &mut self,
+        job_description: &JobDescription,
+    ) -> Result<(), RepositoryError> {
+        // The description and its index entry should be saved together
+        let tx = self.savepoint()?;
+        check_job_application_exists(&tx, job_description.job_application_id)?;
+
+        tx.prepare_cached(
+            "INSERT INTO job_descriptions (job_application_id, description, captured_date) \
+            VALUES (:job_application_id, :description, :captured_date) \
+            ON CONFLICT (job_application_id) DO UPDATE SET \
+            description = excluded.description, \
+            captured_date = excluded.captured_date",
+        )?
+        .execute(named_params! {
+            ":job_application_id": job_description.job_application_id,
+            ":description": compress_text(&job_description.description),
+            ":captured_date": job_description.captured_date,
+        })?;
+
+        // Updates don't fire the delete trigger, so replace the index entry here
+        tx.prepare_cached("DELETE FROM job_descriptions_fts WHERE rowid = ?")?
+            .execute((job_description.job_application_id,))?;
+        tx.prepare_cached("INSERT INTO job_descriptions_fts (rowid, description) VALUES (?, ?)")?
+            .execute((
+                job_description.job_application_id,
+                &job_description.description,
+            ))?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn delete_job_description(&mut self, job_application_id: i32) -> Result<(), RepositoryError> {
+        check_job_application_exists(self, job_application_id)?;
+        // The index entry is deleted by a trigger
+        self.prepare_cached("DELETE FROM job_descriptions WHERE job_application_id = ?")?
+            .execute((job_application_id,))?;
+        Ok(())
+    }
+
+    fn get_attachments(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Vec<Attachment>, RepositoryError> {
+        let mut stmt = self.prepare_cached(&format!(
+            "{ATTACHMENT_QUERY} \
+            INNER JOIN job_applications ON job_applications.id = attachments.job_application_id \
+            WHERE attachments.job_application_id = ? AND job_applications.deleted_at IS NULL \
+            ORDER BY attachments.id"
+        ))?;
+        let row_iter = stmt.query_map((job_application_id,), |row| row.try_into())?;
+        Ok(row_iter.collect::<Result<_, _>>()?)
+    }
+
+    fn get_attachment(&mut self, id: i32) -> Result<Option<Attachment>, RepositoryError> {
+        self.prepare_cached(&format!("{ATTACHMENT_QUERY} WHERE attachments.id = ?"))?
+            .query_row((id,), |row| row.try_into())
+            .optional()
+            .map_err(RepositoryError::from)
+    }
+
+    fn add_attachment(
+        &mut self,
+        job_application_id: i32,
+        role: AttachmentRole,
+        file_name: &str,
+        contents: &[u8],
+        added_date: Date,
+    ) -> Result<Attachment, RepositoryError> {
+        let file_name = clean_file_name(file_name)
+            .ok_or_else(|| RepositoryError::Validation("File names can't be empty".to_owned()))?;
+        let file_hash = Attachment::hash_contents(contents);
+
+        // Storing the file and attaching it should succeed or fail together
+        let tx = self.savepoint()?;
+        check_job_application_exists(&tx, job_application_id)?;
+
+        // Files with the same contents are only stored once
+        tx.prepare_cached(
+            "INSERT INTO attachment_files (hash, contents, size) VALUES (?, ?, ?) \
+            ON CONFLICT (hash) DO NOTHING",
+        )?
+        .execute((&file_hash, contents, contents.len() as i64))?;
+        tx.prepare_cached(
+            "INSERT INTO attachments (job_application_id, file_hash, file_name, role, added_date) \
+            VALUES (?, ?, ?, ?, ?) \
+            ON CONFLICT (job_application_id, file_hash, role) DO NOTHING",
+        )?
+        .execute((job_application_id, &file_hash, file_name, role, added_date))?;
+        let attachment: Attachment = tx
+            .prepare_cached(&format!(
+                "{ATTACHMENT_QUERY} WHERE attachments.job_application_id = ? \
+                AND attachments.file_hash = ? AND attachments.role = ?"
+            ))?
+            .query_row((job_application_id, &file_hash, role), |row| row.try_into())?;
+
+        tx.commit()?;
+        Ok(attachment)
+    }
+
+    fn get_attachment_contents(&mut self, id: i32) -> Result<Option<Vec<u8>>, RepositoryError> {
+        self.prepare_cached(
+            "SELECT attachment_files.contents FROM attachment_files \
+            INNER JOIN attachments ON attachments.file_hash = attachment_files.hash \
+            WHERE attachments.id = ?",
+        )?
+        .query_row((id,), |row| row.get(0))
+        .optional()
+        .map_err(RepositoryError::from)
+    }
+
+    fn delete_attachment(&mut self, id: i32) -> Result<(), RepositoryError> {
+        // Files that nothing is attached to anymore are deleted by a trigger
+        self.prepare_cached("DELETE FROM attachments WHERE id = ?")?
+            .execute((id,))?;
+        Ok(())
+    }
+
+    fn get_resume_versions(&mut self) -> Result<Vec<ResumeVersion>, RepositoryError> {
+        let mut stmt = self.prepare_cached(&format!("{RESUME_VERSION_QUERY} ORDER BY id"))?;
+        let row_iter = stmt.query_map((), |row| row.try_into())?;
+        Ok(row_iter.collect::<Result<_, _>>()?)
+    }
+
+    fn add_resume_version(
+        &mut self,
+        label: &str,
+        file_hash: Option<&str>,
+        created_date: Date,
+        notes: Option<&str>,
+    ) -> Result<ResumeVersion, RepositoryError> {
+        let label = label.trim();
+        if label.is_empty() {
+            return Err(RepositoryError::Validation(
+                "Resume version labels can't be empty".to_owned(),
+            ));
+        }
+        let notes = notes.map(str::trim).filter(|notes| !notes.is_empty());
+
+        let id = self
+            .prepare_cached(
+                "INSERT INTO resume_versions (label, file_hash, created_date, notes) \
+                VALUES (?, ?, ?, ?)",
+            )?
+            .insert((label, file_hash, created_date, notes))?;
+
+        Ok(ResumeVersion {
+            id: id as i32,
+            label: label.to_owned(),
+            file_hash: file_hash.map(str::to_owned),
+            created_date,
+            notes: notes.map(str::to_owned),
+        })
+    }
+
+    fn delete_resume_version(&mut self, id: i32) -> Result<(), RepositoryError> {
+        // Deleting the version cascades to the job applications it was sent with
+        self.prepare_cached("DELETE FROM resume_versions WHERE id = ?")?
+            .execute((id,))?;
+        Ok(())
+    }
+
+    fn get_sent_resume_version(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Option<ResumeVersion>, RepositoryError> {
+        self.prepare_cached(&format!(
+            "{RESUME_VERSION_QUERY} \
+            WHERE id = (SELECT resume_version_id FROM sent_resume_versions WHERE job_application_id = ? \
+                AND job_application_id IN (SELECT id FROM job_applications WHERE deleted_at IS NULL))"
+        ))?
+        .query_row((job_application_id,), |row| row.try_into())
+        .optional()
+        .map_err(RepositoryError::from)
+    }
+
+    fn set_sent_resume_version(
+        &mut self,
+        job_application_id: i32,
+        resume_version_id: Option<i32>,
+    ) -> Result<(), RepositoryError> {
+        check_job_application_exists(self, job_application_id)?;
+        match resume_version_id {
+            Some(resume_version_id) => self
+                .prepare_cached(
+                    "INSERT INTO sent_resume_versions (job_application_id, resume_version_id) \
+                    VALUES (?, ?) \
+                    ON CONFLICT (job_application_id) DO UPDATE SET resume_version_id = excluded.resume_version_id",
+                )?
+                .execute((job_application_id, resume_version_id)),
+            None => self
+                .prepare_cached("DELETE FROM sent_resume_versions WHERE job_application_id = ?")?
+                .execute((job_application_id,)),
+        }?;
+        Ok(())
+    }
+
+    fn get_resume_version_stats(&mut self) -> Result<Vec<ResumeVersionStats>, RepositoryError> {
+        let mut stmt = self.prepare_cached(RESUME_VERSION_STATS_QUERY)?;
+        let row_iter = stmt.query_map((), |row| row.try_into())?;
+
+        let mut row_vec: Vec<ResumeVersionStats> = row_iter.collect::<Result<_, _>>()?;
+        sort_resume_version_stats(&mut row_vec);
+        Ok(row_vec)
+    }
+
+    fn sqlite(&mut self) -> Option<&mut dyn SqliteRepository> {
+        Some(self)
+    }
+}
+
+impl SqliteRepository for Connection {
+    fn is_encrypted(&mut self) -> Result<bool, RepositoryError> {
+        Ok(crate::is_encrypted(&db_file_path(self)?)?)
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    fn set_passphrase(
+        &mut self,
+        passphrase: Option<&str>,
+        current_passphrase: Option<&str>,
+    ) -> Result<(), RepositoryError> {
+        crate::encryption::set_passphrase(self, passphrase, current_passphrase)
+    }
+
+    #[cfg(not(feature = "sqlcipher"))]
+    fn set_passphrase(
+        &mut self,
+        _passphrase: Option<&str>,
+        _current_passphrase: Option<&str>,
+    ) -> Result<(), RepositoryError> {
+        Err(RepositoryError::Validation(
+            "Encrypting the database needs ats-tracking to be built with the sqlcipher feature"
+                .to_owned(),
+        ))
+    }
+
+    fn take_backup(&mut self, retention: BackupRetention) -> Result<Backup, RepositoryError> {
+        backup::take_backup(self, retention)
+    }
+
+    fn get_backups(&mut self) -> Result<Vec<Backup>, RepositoryError> {
+        backup::get_backups(self)
+    }
+
+    fn restore_backup(
+        &mut self,
+        file: &Path,
+        passphrase: Option<&str>,
+        current_passphrase: Option<&str>,
+    ) -> Result<Backup, RepositoryError> {
+        backup::restore_backup(self, file, passphrase, current_passphrase)
+    }
+
+    fn sync_with(
+        &mut self,
+        file: &Path,
+        passphrase: Option<&str>,
+        resolve: &mut dyn FnMut(&SyncConflict) -> SyncChoice,
+    ) -> Result<SyncReport, RepositoryError> {
+        sync::sync_with(self, file, passphrase, resolve)
+    }
+
+    fn export_sync_file(
+        &mut self,
+        file: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<usize, RepositoryError> {
+        sync::export_sync_file(self, file, passphrase)
+    }
+
+    fn export_plain_text(&mut self, dir: &Path) -> Result<usize, RepositoryError> {
+        plain_text::export_plain_text(self, dir)
+    }
+
+    fn import_plain_text(&mut self, dir: &Path) -> Result<usize, RepositoryError> {
+        plain_text::import_plain_text(self, dir)
+    }
+}
+
+/// The function that takes the largest of its arguments, for [DateField::expression](crate::search_query::DateField::expression)
+const GREATEST: &str = "MAX";
+
+/// Return every column of [JobApplication] from an `UPDATE`, in the order it is read from a row
+const RETURNING_JOB_APPLICATION: &str = "RETURNING id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version";
+
+/// Select every column of [ResumeVersion], to be followed by a `WHERE` or `ORDER BY` clause
+const RESUME_VERSION_QUERY: &str =
+    "SELECT id, label, file_hash, created_date, notes FROM resume_versions";
+
+/// Select every column of [Attachment], to be followed by a `WHERE` clause
+const ATTACHMENT_QUERY: &str =
+    "SELECT attachments.id, attachments.job_application_id, attachments.role, \
+    attachments.file_name, attachments.file_hash, attachment_files.size, attachments.added_date \
+    FROM attachments \
+    INNER JOIN attachment_files ON attachment_files.hash = attachments.file_hash";
+
+impl From<rusqlite::Error> for RepositoryError {
+    fn from(error: rusqlite::Error) -> Self {
+        match &error {
+            rusqlite::Error::SqliteFailure(failure, message)
+                if failure.code == ErrorCode::ConstraintViolation =>
+            {
+                let message = message.clone().unwrap_or_else(|| failure.to_string());
+                // Unique constraints are only broken by saving something that already exists
+                if matches!(
+                    failure.extended_code,
+                    ffi::SQLITE_CONSTRAINT_UNIQUE | ffi::SQLITE_CONSTRAINT_PRIMARYKEY
+                ) {
+                    RepositoryError::Conflict(message)
+                } else {
+                    RepositoryError::Constraint(message)
+                }
+            }
+            _ => RepositoryError::Backend(Box::new(error)),
+        }
+    }
+}
 
-        // Finally, execute returning Result<()>
-        stmt.execute(params_from_iter(Into::<Vec<Box<dyn ToSql>>>::into(
-            partial_application,
-        )))
-        .map(|_| ())
+/// Return [RepositoryError::job_application_not_found] unless job application `id` exists and isn't in the trash
+///
+/// Anything saved for a job application in the trash would be hidden until it is restored, so it is refused instead.
+fn check_job_application_exists(conn: &Connection, id: i32) -> Result<(), RepositoryError> {
+    let exists = conn
+        .prepare_cached(
+            "SELECT EXISTS (SELECT 1 FROM job_applications WHERE id = ? AND deleted_at IS NULL)",
+        )?
+        .query_row((id,), |row| row.get::<usize, bool>(0))?;
+    if exists {
+        Ok(())
+    } else {
+        Err(RepositoryError::job_application_not_found(id))
     }
+}
+
+/// Move a job application to the trash, or take it back out, returning whether it was changed
+fn set_deleted(conn: &Connection, id: i32, deleted: bool) -> Result<bool, RepositoryError> {
+    // Stored as UTC text, which is the format `datetime()` uses and sorts in order
+    let mut stmt = if deleted {
+        conn.prepare_cached(
+            "UPDATE job_applications SET deleted_at = datetime('now') \
+            WHERE id = ? AND deleted_at IS NULL",
+        )?
+    } else {
+        conn.prepare_cached(
+            "UPDATE job_applications SET deleted_at = NULL \
+            WHERE id = ? AND deleted_at IS NOT NULL",
+        )?
+    };
+
+    Ok(stmt.execute((id,))? > 0)
+}
+
+/// Record a change to a job application in the journal, returning the id of the entry
+///
+/// Undone changes can't be redone once something else has changed, so they are cleared.
+/// Only the newest [JOURNAL_LENGTH] entries are kept.
+fn add_journal_entry(
+    conn: &Connection,
+    job_application_id: i32,
+    operation: JournalOperation,
+) -> Result<i32, RepositoryError> {
+    conn.prepare_cached("DELETE FROM journal_entries WHERE undone")?
+        .execute(())?;
+
+    // Updates set the version after the change once it is made
+    let id = conn
+        .prepare_cached(
+            "INSERT INTO journal_entries (job_application_id, operation, version_before, version_after) \
+            SELECT ?1, ?2, version, version FROM job_applications WHERE id = ?1",
+        )?
+        .insert((job_application_id, operation))? as i32;
+
+    conn.prepare_cached(
+        "DELETE FROM journal_entries \
+        WHERE id < (SELECT id FROM journal_entries ORDER BY id DESC LIMIT 1 OFFSET ?)",
+    )?
+    .execute((JOURNAL_LENGTH - 1,))?;
+
+    Ok(id)
+}
+
+/// SQL that updates every column of a job application from named parameters, if it hasn't changed since it was loaded, used by [update_row]
+fn update_job_application_query() -> String {
+    format!(
+        "UPDATE job_applications \
+        SET source = :source, \
+        company = :company, \
+        job_title = :job_title, \
+        application_date = :application_date, \
+        time_investment = :time_investment, \
+        human_response = :human_response, \
+        human_response_date = :human_response_date, \
+        application_website = :application_website, \
+        notes = :notes, \
+        salary_min = :salary_min, \
+        salary_max = :salary_max, \
+        salary_currency = :salary_currency, \
+        pay_period = :pay_period, \
+        equity = :equity, \
+        bonus = :bonus, \
+        city = :city, \
+        region = :region, \
+        country = :country, \
+        work_arrangement = :work_arrangement, \
+        relocation = :relocation, \
+        visa_sponsorship = :visa_sponsorship, \
+        version = version + 1, \
+        updated_at = datetime('now') \
+        WHERE id = :id AND version = :version \
+        {RETURNING_JOB_APPLICATION}"
+    )
+}
 
-    fn delete_job_application(&mut self, id: i32) -> Result<(), Self::Error> {
-        let mut stmt = self.prepare_cached("DELETE FROM job_applications WHERE id = ?")?;
+/// Update a job application and its custom fields with `stmt`, prepared from [update_job_application_query]
+///
+/// This doesn't add a journal entry, so it should be run in [journaled_update] or [journal_update].
+fn update_row(
+    conn: &Connection,
+    stmt: &mut Statement,
+    custom_fields: &[CustomField],
+    application: &JobApplication,
+) -> Result<JobApplication, RepositoryError> {
+    // Check the custom fields before writing anything
+    let custom_field_values =
+        prepare_custom_field_values(custom_fields, &application.custom_fields)
+            .map_err(RepositoryError::Validation)?;
+
+    // Nothing is updated if it was changed since `application` was loaded
+    let job_application = stmt
+        .query_row(
+            named_params! {
+                ":id": application.id,
+                ":version": application.version,
+                ":source": application.source,
+                ":company": application.company,
+                ":job_title": application.job_title,
+                ":application_date": application.application_date,
+                ":time_investment": application.time_investment.map(Duration::whole_seconds),
+                ":human_response": application.human_response,
+                ":human_response_date": application.human_response_date,
+                ":application_website": application.application_website,
+                ":notes": application.notes,
+                ":salary_min": application.salary_min,
+                ":salary_max": application.salary_max,
+                ":salary_currency": application.salary_currency,
+                ":pay_period": application.pay_period,
+                ":equity": application.equity,
+                ":bonus": application.bonus,
+                ":city": application.city,
+                ":region": application.region,
+                ":country": application.country,
+                ":work_arrangement": application.work_arrangement,
+                ":relocation": application.relocation,
+                ":visa_sponsorship": application.visa_sponsorship,
+            },
+            |row| row.try_into(),
+        )
+        .optional()?
+        .ok_or_else(|| RepositoryError::job_application_changed(application.id))?;
+
+    write_custom_field_values(conn, application.id, &custom_field_values)?;
+    with_custom_fields(conn, job_application)
+}
+
+/// Make an update to job application `id` that can be undone, by saving it to the journal before and after `update`
+///
+/// `update` is run in the same transaction as the journal entry, and only if there is a job application with this id outside the trash.
+fn journaled_update<T>(
+    conn: &mut Connection,
+    id: i32,
+    update: impl FnOnce(&Connection) -> Result<T, RepositoryError>,
+) -> Result<T, RepositoryError> {
+    let tx = conn.savepoint()?;
+    let result = journal_update(&tx, id, update)?;
+
+    tx.commit()?;
+    Ok(result)
+}
+
+/// [journaled_update] for a connection that is already in a transaction, so several updates can be saved together
+fn journal_update<T>(
+    conn: &Connection,
+    id: i32,
+    update: impl FnOnce(&Connection) -> Result<T, RepositoryError>,
+) -> Result<T, RepositoryError> {
+    check_job_application_exists(conn, id)?;
+
+    let journal_entry_id = add_journal_entry(conn, id, JournalOperation::Update)?;
+    save_journal_snapshot(conn, journal_entry_id, id, false)?;
+    let result = update(conn)?;
+    save_journal_snapshot(conn, journal_entry_id, id, true)?;
+    conn.prepare_cached(
+        "UPDATE journal_entries \
+        SET version_after = (SELECT version FROM job_applications WHERE id = ?) \
+        WHERE id = ?",
+    )?
+    .execute((id, journal_entry_id))?;
+
+    Ok(result)
+}
+
+/// Save a copy of a job application and its custom fields, from before or after the change in a journal entry
+fn save_journal_snapshot(
+    conn: &Connection,
+    journal_entry_id: i32,
+    job_application_id: i32,
+    is_after: bool,
+) -> Result<(), RepositoryError> {
+    conn.prepare_cached(
+        "INSERT INTO journal_snapshots (journal_entry_id, is_after, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship) \
+        SELECT ?1, ?2, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship \
+        FROM job_applications \
+        WHERE id = ?3",
+    )?
+    .execute((journal_entry_id, is_after, job_application_id))?;
+
+    conn.prepare_cached(
+        "INSERT INTO journal_custom_field_values (journal_entry_id, is_after, custom_field_id, value) \
+        SELECT ?1, ?2, custom_field_id, value \
+        FROM custom_field_values \
+        WHERE job_application_id = ?3",
+    )?
+    .execute((journal_entry_id, is_after, job_application_id))?;
+
+    Ok(())
+}
+
+/// Undo or redo the change in a journal entry, and mark it as undone or not
+///
+/// A change can only be undone while its job application has the version from after the change, and redone while it has the version from before,
+/// so a change made some other way since, like by syncing, isn't lost. Otherwise this returns [RepositoryError::Conflict].
+/// Undoing or redoing changes the version, so the entries next to this one for the same job application are given the new version.
+fn apply_journal_entry(
+    conn: &Connection,
+    entry: &JournalEntry,
+    undo: bool,
+) -> Result<(), RepositoryError> {
+    let (version_before, version_after): (i32, i32) = conn
+        .prepare_cached("SELECT version_before, version_after FROM journal_entries WHERE id = ?")?
+        .query_row((entry.id,), |row| Ok((row.get(0)?, row.get(1)?)))?;
+    let (expected_version, replaced_version) = if undo {
+        (version_after, version_before)
+    } else {
+        (version_before, version_after)
+    };
+    if query_version(conn, entry.job_application_id)? != Some(expected_version) {
+        return Err(RepositoryError::journal_entry_changed(entry, undo));
+    }
+
+    match (entry.operation, undo) {
+        (JournalOperation::Insert | JournalOperation::Restore, true)
+        | (JournalOperation::Delete, false) => {
+            set_deleted(conn, entry.job_application_id, true)?;
+        }
+        (JournalOperation::Insert | JournalOperation::Restore, false)
+        | (JournalOperation::Delete, true) => {
+            set_deleted(conn, entry.job_application_id, false)?;
+        }
+        (JournalOperation::Update, _) => load_journal_snapshot(conn, entry, !undo)?,
+    }
 
-        stmt.execute((id,)).map(|_| ())
+    // The job application is now the way it was after the change before this one, or before the change after it
+    let version = query_version(conn, entry.job_application_id)?;
+    if undo {
+        conn.prepare_cached(
+            "UPDATE journal_entries SET version_after = ?1 \
+            WHERE job_application_id = ?2 AND version_after = ?3 AND NOT undone AND id < ?4",
+        )?
+        .execute((
+            version,
+            entry.job_application_id,
+            replaced_version,
+            entry.id,
+        ))?;
+        conn.prepare_cached(
+            "UPDATE journal_entries SET version_before = ?, undone = 1 WHERE id = ?",
+        )?
+        .execute((version, entry.id))?;
+    } else {
+        conn.prepare_cached(
+            "UPDATE journal_entries SET version_before = ?1 \
+            WHERE job_application_id = ?2 AND version_before = ?3 AND undone AND id > ?4",
+        )?
+        .execute((
+            version,
+            entry.job_application_id,
+            replaced_version,
+            entry.id,
+        ))?;
+        conn.prepare_cached(
+            "UPDATE journal_entries SET version_after = ?, undone = 0 WHERE id = ?",
+        )?
+        .execute((version, entry.id))?;
     }
+
+    Ok(())
+}
+
+/// Get the [JobApplication::version] of job application `id`, whether or not it is in the trash
+fn query_version(conn: &Connection, id: i32) -> Result<Option<i32>, RepositoryError> {
+    Ok(conn
+        .prepare_cached("SELECT version FROM job_applications WHERE id = ?")?
+        .query_row((id,), |row| row.get(0))
+        .optional()?)
+}
+
+/// Put a job application and its custom fields back the way they were before or after the change in a journal entry
+fn load_journal_snapshot(
+    conn: &Connection,
+    entry: &JournalEntry,
+    is_after: bool,
+) -> Result<(), RepositoryError> {
+    conn.prepare_cached(
+        "UPDATE job_applications \
+        SET (source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship) = ( \
+            SELECT source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship \
+            FROM journal_snapshots \
+            WHERE journal_entry_id = ?1 AND is_after = ?2 \
+        ), \
+        version = version + 1, \
+        updated_at = datetime('now') \
+        WHERE id = ?3",
+    )?
+    .execute((entry.id, is_after, entry.job_application_id))?;
+
+    conn.prepare_cached("DELETE FROM custom_field_values WHERE job_application_id = ?")?
+        .execute((entry.job_application_id,))?;
+    conn.prepare_cached(
+        "INSERT INTO custom_field_values (job_application_id, custom_field_id, value) \
+        SELECT ?3, custom_field_id, value \
+        FROM journal_custom_field_values \
+        WHERE journal_entry_id = ?1 AND is_after = ?2",
+    )?
+    .execute((entry.id, is_after, entry.job_application_id))?;
+
+    Ok(())
+}
+
+/// Get every custom field, sorted by name
+fn query_custom_fields(conn: &Connection) -> Result<Vec<CustomField>, RepositoryError> {
+    let mut stmt =
+        conn.prepare_cached("SELECT id, name, field_type FROM custom_fields ORDER BY name")?;
+    let row_iter = stmt.query_map((), |row| row.try_into())?;
+
+    let mut row_vec: Vec<CustomField> = Vec::new();
+    for row in row_iter {
+        row_vec.push(row?);
+    }
+
+    Ok(row_vec)
+}
+
+/// Fill in the custom field values of a job application that was just read from a `RETURNING` clause
+fn with_custom_fields(
+    conn: &Connection,
+    mut job_application: JobApplication,
+) -> Result<JobApplication, RepositoryError> {
+    load_custom_fields(conn, [&mut job_application])?;
+    Ok(job_application)
+}
+
+/// Fill in the custom field values of each job application from the database
+fn load_custom_fields<'a>(
+    conn: &Connection,
+    job_applications: impl IntoIterator<Item = &'a mut JobApplication>,
+) -> Result<(), RepositoryError> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT custom_fields.name, custom_fields.field_type, custom_field_values.value \
+        FROM custom_field_values \
+        INNER JOIN custom_fields ON custom_fields.id = custom_field_values.custom_field_id \
+        WHERE custom_field_values.job_application_id = ?",
+    )?;
+
+    for job_application in job_applications {
+        let mut rows = stmt.query((job_application.id,))?;
+        while let Some(row) = rows.next()? {
+            let field_type: CustomFieldType = row.get(1)?;
+            let value = field_type
+                .value_from_stored_string(&row.get::<usize, String>(2)?)
+                .map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(2, Type::Text, Box::from(e))
+                })?;
+            job_application.custom_fields.insert(row.get(0)?, value);
+        }
+    }
+
+    Ok(())
+}
+
+/// Replace all custom field values of a job application with values from [prepare_custom_field_values]
+fn write_custom_field_values(
+    conn: &Connection,
+    job_application_id: i32,
+    custom_field_values: &[(i32, String)],
+) -> Result<(), RepositoryError> {
+    conn.prepare_cached("DELETE FROM custom_field_values WHERE job_application_id = ?")?
+        .execute((job_application_id,))?;
+
+    let mut stmt = conn.prepare_cached(
+        "INSERT INTO custom_field_values (job_application_id, custom_field_id, value) VALUES (?, ?, ?)",
+    )?;
+    for (custom_field_id, value) in custom_field_values {
+        stmt.execute((job_application_id, custom_field_id, value))?;
+    }
+
+    Ok(())
+}
+
+/// Trim a tag name, making sure there is something left
+fn validate_tag_name(name: &str) -> Result<&str, RepositoryError> {
+    let name = name.trim();
+    if name.is_empty() {
+        Err(RepositoryError::Validation(
+            "Tag names can't be empty".to_owned(),
+        ))
+    } else {
+        Ok(name)
+    }
+}
+
+/// Translate full-text terms into an FTS5 query string
+///
+/// Every term is quoted so nothing the user types is interpreted as FTS5 syntax. Terms are implicitly ANDed.
+fn to_fts5_query(terms: &[FullTextTerm]) -> String {
+    terms
+        .iter()
+        .map(|term| match term {
+            FullTextTerm::Word(word) | FullTextTerm::Phrase(word) => {
+                format!("\"{}\"", word.replace('"', "\"\""))
+            }
+            FullTextTerm::Prefix(prefix) => format!("\"{}\" *", prefix.replace('"', "\"\"")),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 /// Internal method to make a query where multiple rows are returned easier
@@ -233,7 +1900,7 @@ fn execute_query<P: Params>(
     conn: &mut Connection,
     sql: &str,
     params: P,
-) -> Result<Vec<JobApplication>, rusqlite::Error> {
+) -> Result<Vec<JobApplication>, RepositoryError> {
     // Create a prepared statement object
     let mut stmt = conn.prepare_cached(sql)?;
 
@@ -247,7 +1914,28 @@ fn execute_query<P: Params>(
     for row in row_iter {
         row_vec.push(row?);
     }
+    drop(stmt);
+
+    // Custom fields are stored in another table
+    load_custom_fields(conn, row_vec.iter_mut())?;
 
     // Return the collected Vec
     Ok(row_vec)
 }
+
+/// Same as [execute_query], but for queries that return tags
+fn execute_tag_query<P: Params>(
+    conn: &mut Connection,
+    sql: &str,
+    params: P,
+) -> Result<Vec<Tag>, RepositoryError> {
+    let mut stmt = conn.prepare_cached(sql)?;
+    let row_iter = stmt.query_map(params, |row| row.try_into())?;
+
+    let mut row_vec: Vec<Tag> = Vec::new();
+    for row in row_iter {
+        row_vec.push(row?);
+    }
+
+    Ok(row_vec)
+}
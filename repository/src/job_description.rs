@@ -0,0 +1,224 @@
+//! Read job posting descriptions from saved text and HTML files
+//!
+//! Postings are usually saved from a browser, so HTML is turned into plain text that can be read in a terminal and searched.
+
+use std::{fs, io, path::Path};
+
+/// Elements whose contents are never shown on the page
+const HIDDEN_ELEMENTS: [&str; 5] = ["head", "script", "style", "template", "noscript"];
+
+/// Elements that start on a new line
+const BLOCK_ELEMENTS: [&str; 24] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "tr",
+    "ul",
+];
+
+/// Read a job description from a text or HTML file
+///
+/// Files ending in `.html` or `.htm`, or starting with `<`, are converted from HTML to plain text.
+/// Leading and trailing whitespace is removed either way.
+pub fn read_job_description_file(path: &Path) -> io::Result<String> {
+    let contents = fs::read_to_string(path)?;
+
+    let is_html = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            extension.eq_ignore_ascii_case("html") || extension.eq_ignore_ascii_case("htm")
+        })
+        || contents.trim_start().starts_with('<');
+
+    if is_html {
+        Ok(html_to_text(&contents))
+    } else {
+        Ok(contents.trim().to_owned())
+    }
+}
+
+/// Convert HTML to plain text
+///
+/// Tags are removed, block elements like paragraphs are put on their own lines, list items start with `- `,
+/// and character references like `&amp;` are decoded. Runs of whitespace become one space, and at most one blank line is kept between blocks.
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+
+    while let Some(tag_start) = rest.find('<') {
+        push_text(&mut text, &rest[..tag_start]);
+        rest = &rest[tag_start..];
+
+        // Comments may contain `>`, so they end at `-->` instead
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        // A `<` that never closes isn't a tag, so it's kept as text
+        let Some(tag_end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[1..tag_end];
+        rest = &rest[tag_end + 1..];
+
+        let is_closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if !is_closing && HIDDEN_ELEMENTS.contains(&name.as_str()) {
+            // Skip everything up to and including the closing tag. ASCII lowercase keeps byte offsets the same.
+            let closing_tag = format!("</{name}");
+            rest = rest
+                .to_ascii_lowercase()
+                .find(&closing_tag)
+                .and_then(|start| rest[start..].find('>').map(|end| &rest[start + end + 1..]))
+                .unwrap_or_default();
+        } else if name == "li" {
+            // The next item starts its own line, so closing one doesn't need to
+            if !is_closing {
+                text.push_str("\n- ");
+            }
+        } else if BLOCK_ELEMENTS.contains(&name.as_str()) {
+            text.push('\n');
+        }
+    }
+    push_text(&mut text, rest);
+
+    // Trim every line and collapse blank lines
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if !line.is_empty() || lines.last().is_some_and(|last| !last.is_empty()) {
+            lines.push(line);
+        }
+    }
+    lines.join("\n").trim().to_owned()
+}
+
+/// Append text from between tags, collapsing whitespace and decoding character references
+fn push_text(text: &mut String, html_text: &str) {
+    for word in decode_character_references(html_text).split_inclusive(char::is_whitespace) {
+        let trimmed = word.trim_end();
+        text.push_str(trimmed);
+        if trimmed.len() < word.len() && !text.ends_with([' ', '\n']) {
+            text.push(' ');
+        }
+    }
+}
+
+/// Decode character references like `&amp;`, `&#39;`, and `&#x2014;`
+///
+/// Only the most common named references are known. Unknown references are left as they are.
+fn decode_character_references(html_text: &str) -> String {
+    let mut decoded = String::with_capacity(html_text.len());
+    let mut rest = html_text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let character = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let name = &rest[1..end];
+            let character = match name {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                // Non-breaking spaces are only there for layout
+                "nbsp" => Some(' '),
+                "ndash" => Some('–'),
+                "mdash" => Some('—'),
+                "bull" => Some('•'),
+                "hellip" => Some('…'),
+                _ => name
+                    .strip_prefix("#x")
+                    .or_else(|| name.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| name.strip_prefix('#').map(str::parse))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32),
+            };
+            character.map(|character| (character, end))
+        });
+
+        match character {
+            Some((character, end)) => {
+                decoded.push(character);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Blocks go on their own lines, inline elements don't, and hidden elements are removed
+    #[test]
+    fn test_html_to_text() {
+        let html = r#"<!DOCTYPE html>
+<html>
+<head><title>Backend Engineer</title><style>p { color: red; }</style></head>
+<body>
+  <h1>Backend   Engineer</h1>
+  <!-- posted <b>today</b> -->
+  <p>Build <b>APIs</b> in
+     Rust.</p>
+  <script>alert("<p>hi</p>");</script>
+  <ul>
+    <li>5+ years</li>
+    <li>SQL<br>and more</li>
+  </ul>
+  <div><div><p>Apply now</p></div></div>
+</body>
+</html>"#;
+
+        assert_eq!(
+            html_to_text(html),
+            "Backend Engineer\n\nBuild APIs in Rust.\n\n- 5+ years\n- SQL\nand more\n\nApply now"
+        );
+    }
+
+    /// Named, decimal, and hexadecimal references are decoded, and anything else is left alone
+    #[test]
+    fn test_decode_character_references() {
+        assert_eq!(
+            decode_character_references("R&amp;D &lt;team&gt; &#39;quoted&#x27; &copy; & more"),
+            "R&D <team> 'quoted' &copy; & more"
+        );
+        assert_eq!(html_to_text("a&nbsp;b &lt;p&gt; 1 < 2"), "a b <p> 1 < 2");
+    }
+}
@@ -1,11 +1,107 @@
 //! Job application repository with a MySQL database
 
+/// Take rotating backups of the SQLite database, and restore them
+pub mod backup;
+/// Notice job applications that were changed by another connection
+pub mod change_watcher;
+/// Find job applications that were likely entered twice, and combine them
+pub mod duplicates;
+/// Encrypt the SQLite database with a passphrase
+mod encryption;
 /// Define `struct JobApplication` and some implement conversions between that and MySQL objects
 pub mod job_application_model;
 /// Define CRUD actions for `struct JobApplication` into the MySQL database
 pub mod job_application_repository;
+/// Read job posting descriptions from saved text and HTML files
+pub mod job_description;
+/// Score job offers against each other by weighted criteria
+pub mod offer_comparison;
+/// Keep job applications as plain-text files in a directory, so they can be versioned with git
+#[cfg(not(feature = "mysql"))]
+pub mod plain_text;
+/// Parse the search query language used by the frontends into filters the repository can use
+pub mod search_query;
+/// Combine the job applications in two SQLite databases, like ones on different machines
+pub mod sync;
+/// Check job applications for values that don't make sense before they are saved
+pub mod validation;
+
+#[cfg(all(feature = "mysql", feature = "sqlcipher"))]
+compile_error!("The sqlcipher feature encrypts the SQLite database, so it can't be used with the mysql feature");
+
+#[cfg(not(feature = "mysql"))]
+pub use backend_connection::ensure_schema;
+pub use backend_connection::{get_conn, get_conn_with_passphrase, get_plain_text_repository};
+pub use encryption::is_encrypted;
+
+/// Get how many days deleted job applications should stay in the trash before they are purged, from `TRASH_RETENTION_DAYS`
+///
+/// `None` means the trash is only emptied by hand.
+pub fn trash_retention_days() -> Option<u32> {
+    let days = std::env::var("TRASH_RETENTION_DAYS").ok()?;
+    match days.trim().parse::<u32>() {
+        Ok(days) => Some(days),
+        Err(_) => {
+            eprintln!("Warning: TRASH_RETENTION_DAYS is not a whole number of days, so the trash will not be purged");
+            None
+        }
+    }
+}
+
+/// Get how many days apart job applications to the same job can be sent to be likely duplicates, from `DUPLICATE_WINDOW_DAYS`
+///
+/// Defaults to [duplicates::DEFAULT_DUPLICATE_WINDOW_DAYS] if it isn't set.
+pub fn duplicate_window_days() -> u32 {
+    let Ok(days) = std::env::var("DUPLICATE_WINDOW_DAYS") else {
+        return duplicates::DEFAULT_DUPLICATE_WINDOW_DAYS;
+    };
+    match days.trim().parse::<u32>() {
+        Ok(days) => days,
+        Err(_) => {
+            eprintln!("Warning: DUPLICATE_WINDOW_DAYS is not a whole number of days, so the default of {} days will be used", duplicates::DEFAULT_DUPLICATE_WINDOW_DAYS);
+            duplicates::DEFAULT_DUPLICATE_WINDOW_DAYS
+        }
+    }
+}
+
+/// Get how often backups are taken automatically, from `BACKUP_SCHEDULE` (`startup`, `daily`, or `off`)
+///
+/// SQLite databases are backed up daily if it isn't set. `None` means backups are only taken by hand.
+/// Only SQLite databases have backups, so this is only used for them.
+pub fn backup_schedule() -> Option<backup::BackupSchedule> {
+    let Ok(schedule) = std::env::var("BACKUP_SCHEDULE") else {
+        return Some(backup::BackupSchedule::Daily);
+    };
+    match schedule.trim().to_lowercase().as_str() {
+        "startup" => Some(backup::BackupSchedule::Startup),
+        "daily" => Some(backup::BackupSchedule::Daily),
+        "off" => None,
+        _ => {
+            eprintln!("Warning: BACKUP_SCHEDULE should be startup, daily, or off, so backups will be taken daily");
+            Some(backup::BackupSchedule::Daily)
+        }
+    }
+}
 
-pub use backend_connection::get_conn;
+/// Get how many backups to keep, from `BACKUP_KEEP_DAILY` and `BACKUP_KEEP_WEEKLY`
+///
+/// Each defaults to [backup::BackupRetention::default] if it isn't set.
+pub fn backup_retention() -> backup::BackupRetention {
+    let default = backup::BackupRetention::default();
+    let keep = |name: &str, default: usize| {
+        let Ok(count) = std::env::var(name) else {
+            return default;
+        };
+        count.trim().parse::<usize>().unwrap_or_else(|_| {
+            eprintln!("Warning: {name} is not a whole number of backups, so the default of {default} will be used");
+            default
+        })
+    };
+    backup::BackupRetention {
+        daily: keep("BACKUP_KEEP_DAILY", default.daily),
+        weekly: keep("BACKUP_KEEP_WEEKLY", default.weekly),
+    }
+}
 
 #[cfg(feature = "mysql")]
 mod backend_connection {
@@ -13,6 +109,8 @@ mod backend_connection {
 
     use mysql::{OptsBuilder, Pool, PooledConn};
 
+    use crate::job_application_repository::RepositoryError;
+
     /// Get a connection object to be used by the rest of this crate
     ///
     /// This exists so that the main function can own the connection object instead of creating a new one for every call
@@ -49,20 +147,198 @@ mod backend_connection {
         let pool = Pool::new(sql_opts_builder)?;
         pool.get_conn()
     }
+
+    /// Get a connection the same way as [get_conn]
+    ///
+    /// MySQL databases are protected by the server's own passwords, so `ask_passphrase` is never used.
+    pub fn get_conn_with_passphrase(
+        _ask_passphrase: impl FnMut(bool) -> Option<String>,
+    ) -> Result<PooledConn, RepositoryError> {
+        Ok(get_conn()?)
+    }
+
+    /// Open plain-text storage instead of the database, if `PLAIN_TEXT_DIR` is set
+    ///
+    /// Plain-text storage is built on SQLite, so this is always `None` for MySQL.
+    /// It has the same type as [get_conn_with_passphrase] so the frontends can use either one.
+    pub fn get_plain_text_repository() -> Option<Result<PooledConn, RepositoryError>> {
+        if env::var_os("PLAIN_TEXT_DIR").is_some() {
+            eprintln!("Warning: PLAIN_TEXT_DIR is ignored, since plain-text storage can't be used with MySQL");
+        }
+        None
+    }
 }
 
 #[cfg(not(feature = "mysql"))]
 mod backend_connection {
-    use std::path::{Path, PathBuf};
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
 
     use rusqlite::Connection;
 
+    use crate::{
+        encryption, job_application_repository::RepositoryError, plain_text::PlainTextRepository,
+    };
+
     /// Get a connection object to be used by the rest of this crate
     ///
     /// This exists so that the main function can own the connection object instead of creating a new one for every call
     pub fn get_conn() -> Result<Connection, rusqlite::Error> {
-        // The ats-tracking.db3 file should be placed in the user's home directory
+        get_or_make_db(database_path())
+    }
+
+    /// Get a connection like [get_conn], using `ask_passphrase` to ask for the passphrase if the database is encrypted
+    ///
+    /// `ask_passphrase` is told whether the last passphrase it gave was wrong, and is asked again until it is right.
+    /// It can return `None` to give up on opening the database.
+    pub fn get_conn_with_passphrase(
+        ask_passphrase: impl FnMut(bool) -> Option<String>,
+    ) -> Result<Connection, RepositoryError> {
+        let path = database_path();
+        if encryption::is_encrypted(&path)? {
+            open_encrypted_db(&path, ask_passphrase)
+        } else {
+            Ok(get_or_make_db(path)?)
+        }
+    }
+
+    /// Open an encrypted database, asking for its passphrase until it is right
+    #[cfg(feature = "sqlcipher")]
+    fn open_encrypted_db(
+        path: &Path,
+        mut ask_passphrase: impl FnMut(bool) -> Option<String>,
+    ) -> Result<Connection, RepositoryError> {
+        let mut wrong_passphrase = false;
+        loop {
+            let passphrase = ask_passphrase(wrong_passphrase).ok_or_else(|| {
+                RepositoryError::Validation(
+                    "The database is encrypted, so it can't be opened without its passphrase"
+                        .to_owned(),
+                )
+            })?;
+            let mut conn = encryption::open_encrypted(path, &passphrase)?;
+            match ensure_schema(&mut conn) {
+                Ok(()) => return Ok(conn),
+                Err(error) if encryption::is_wrong_passphrase(&error) => wrong_passphrase = true,
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+
+    /// Fail to open an encrypted database, because this wasn't built with SQLCipher
+    #[cfg(not(feature = "sqlcipher"))]
+    fn open_encrypted_db(
+        path: &Path,
+        _ask_passphrase: impl FnMut(bool) -> Option<String>,
+    ) -> Result<Connection, RepositoryError> {
+        Err(RepositoryError::Validation(format!(
+            "{} is encrypted. Build ats-tracking with the sqlcipher feature to open it",
+            path.display()
+        )))
+    }
+
+    /// Open the database at `path` like [get_or_make_db], with `passphrase` if it is encrypted
+    pub(crate) fn open_db(
+        path: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<Connection, RepositoryError> {
+        if !encryption::is_encrypted(path)? {
+            return Ok(get_or_make_db(path)?);
+        }
+        // Only tried once, since it won't be any less wrong the second time
+        let mut passphrase = passphrase.map(str::to_owned);
+        let mut wrong_passphrase = false;
+        open_encrypted_db(path, |wrong| {
+            wrong_passphrase = wrong;
+            passphrase.take()
+        })
+        .map_err(|error| {
+            if wrong_passphrase {
+                RepositoryError::Validation(format!("Wrong passphrase for {}", path.display()))
+            } else {
+                error
+            }
+        })
+    }
+
+    /// Where the database `conn` has open is saved, or an error if it is only in memory
+    pub(crate) fn db_file_path(conn: &Connection) -> Result<PathBuf, RepositoryError> {
+        conn.path()
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .ok_or_else(|| {
+                RepositoryError::Validation(
+                    "This only works for a database saved in a file".to_owned(),
+                )
+            })
+    }
+
+    /// Close `conn`, move `replacement` over the database file it had open, and open that in its place
+    ///
+    /// `passphrase` is what `replacement` is encrypted with, if it is, and `current_passphrase` is what the database is encrypted with now.
+    /// If anything goes wrong, `replacement` is removed and `conn` is opened on the database it had, so nothing is written to an empty one instead.
+    pub(crate) fn replace_db_file(
+        conn: &mut Connection,
+        replacement: &Path,
+        passphrase: Option<&str>,
+        current_passphrase: Option<&str>,
+    ) -> Result<(), RepositoryError> {
+        let path = db_file_path(conn)?;
+        let mut set_aside = path.clone().into_os_string();
+        set_aside.push(".replaced");
+        let set_aside = PathBuf::from(set_aside);
+
+        // The database is opened again if it can't be replaced, so this makes sure it can be before closing it
+        if let Err(error) = open_db(&path, current_passphrase) {
+            _ = fs::remove_file(replacement);
+            return Err(error);
+        }
+
+        // The database has to be closed before it is replaced
+        let old_conn = std::mem::replace(conn, Connection::open_in_memory()?);
+        if let Err((old_conn, error)) = old_conn.close() {
+            *conn = old_conn;
+            _ = fs::remove_file(replacement);
+            return Err(error.into());
+        }
 
+        // The database is moved aside instead of overwritten, so it can be put back if the replacement can't be opened.
+        // Settings like enforcing foreign keys only last as long as the connection, so opening it sets them up again.
+        let replaced = fs::rename(&path, &set_aside)
+            .map_err(RepositoryError::from)
+            .and_then(|()| {
+                let opened = fs::rename(replacement, &path)
+                    .map_err(RepositoryError::from)
+                    .and_then(|()| open_db(&path, passphrase));
+                if opened.is_err() {
+                    fs::rename(&set_aside, &path)?;
+                }
+                opened
+            });
+        match replaced {
+            Ok(new_conn) => {
+                *conn = new_conn;
+                _ = fs::remove_file(&set_aside);
+                Ok(())
+            }
+            Err(error) => {
+                _ = fs::remove_file(replacement);
+                *conn = open_db(&path, current_passphrase)?;
+                Err(error)
+            }
+        }
+    }
+
+    /// Open plain-text storage in `PLAIN_TEXT_DIR` instead of the database, if it is set
+    pub fn get_plain_text_repository() -> Option<Result<PlainTextRepository, RepositoryError>> {
+        let dir = std::env::var_os("PLAIN_TEXT_DIR")?;
+        Some(PlainTextRepository::open(Path::new(&dir)))
+    }
+
+    /// Where the database is saved: ats-tracking.db3 in the user's home directory
+    fn database_path() -> PathBuf {
         // The reason this is deprecated is fixed in Rust 1.85 and the deprecation notice will be removed soon.
         #[allow(deprecated)]
         let home = std::env::home_dir().unwrap_or_else(|| {
@@ -73,21 +349,65 @@ mod backend_connection {
             PathBuf::from(".")
         });
 
-        get_or_make_db(home.join("ats-tracking.db3"))
+        home.join("ats-tracking.db3")
     }
 
+    /// Schema changes made after the original table definition, in the order they must be applied
+    ///
+    /// `PRAGMA user_version` holds the number of these that have already been applied to a database.
+    pub(crate) const MIGRATIONS: [&str; 14] = [
+        include_str!("resources/migrations/01_full_text_search.sql"),
+        include_str!("resources/migrations/02_tags.sql"),
+        include_str!("resources/migrations/03_custom_fields.sql"),
+        include_str!("resources/migrations/04_compensation.sql"),
+        include_str!("resources/migrations/05_location.sql"),
+        include_str!("resources/migrations/06_job_descriptions.sql"),
+        include_str!("resources/migrations/07_attachments.sql"),
+        include_str!("resources/migrations/08_resume_versions.sql"),
+        include_str!("resources/migrations/09_trash.sql"),
+        include_str!("resources/migrations/10_journal.sql"),
+        include_str!("resources/migrations/11_version.sql"),
+        include_str!("resources/migrations/12_timestamps.sql"),
+        include_str!("resources/migrations/13_sync.sql"),
+        include_str!("resources/migrations/14_journal_versions.sql"),
+    ];
+
     /// Get connection for a path and ensure the job_applications table exists
     fn get_or_make_db<P: AsRef<Path>>(path: P) -> Result<Connection, rusqlite::Error> {
         // Get connection
-        let conn = Connection::open(path)?;
+        let mut conn = Connection::open(path)?;
 
-        // Ensure the table exists
-        conn.execute(include_str!("resources/sqlite_table_definition.sql"), ())?;
+        // Ensure the tables exist
+        ensure_schema(&mut conn)?;
 
         // Return conn
         Ok(conn)
     }
 
+    /// Ensure the job_applications table exists and apply any migrations the database has not seen yet
+    ///
+    /// This is public so that other connections (e.g. in-memory databases for testing) can be given the same schema.
+    /// It also turns on foreign key enforcement, which SQLite only remembers per connection.
+    pub fn ensure_schema(conn: &mut Connection) -> Result<(), rusqlite::Error> {
+        // Needed for `ON DELETE CASCADE` to remove tags from deleted job applications
+        conn.pragma_update(None, "foreign_keys", true)?;
+
+        conn.execute(include_str!("resources/sqlite_table_definition.sql"), ())?;
+
+        let applied_migrations: usize =
+            conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        // Each migration is applied in its own transaction so a failure can't leave a half-migrated database
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(applied_migrations) {
+            let tx = conn.transaction()?;
+            tx.execute_batch(migration)?;
+            tx.pragma_update(None, "user_version", index + 1)?;
+            tx.commit()?;
+        }
+
+        Ok(())
+    }
+
     #[cfg(test)]
     mod tests {
         use tempfile::TempDir;
@@ -110,8 +430,7 @@ mod backend_connection {
             assert_eq!(
                 1,
                 conn.query_row(expected_tbl_sql, (expected_tbl_name,), |row| row
-                    .get::<usize, i32>(0)
-                    .map(Into::<i32>::into))?
+                    .get::<usize, i32>(0))?
             );
 
             Ok(())
@@ -172,5 +491,88 @@ mod backend_connection {
 
             Ok(())
         }
+
+        #[test]
+        fn test_migrate_existing_db() -> Result<(), Box<dyn std::error::Error>> {
+            // Create db path
+            let path = TempDir::new()?;
+            let db_path = path.path().join("test_db.db3");
+
+            // Create a database the way versions without migrations did, with one row
+            {
+                let conn = Connection::open(&db_path)?;
+                conn.execute(include_str!("resources/sqlite_table_definition.sql"), ())?;
+                conn.execute(
+                    "INSERT INTO job_applications (source, company, job_title, application_date) \
+                VALUES ('test source', 'test company', 'test job title', '2000-01-01')",
+                    (),
+                )?;
+            }
+
+            let conn = get_or_make_db(&db_path)?;
+
+            assert_eq!(
+                MIGRATIONS.len(),
+                conn.pragma_query_value(None, "user_version", |row| row.get::<usize, usize>(0))?,
+                "All migrations should be recorded as applied"
+            );
+
+            // Rows from before the full-text index existed should still be indexed
+            assert_eq!(
+                1,
+                conn.query_row(
+                    "SELECT COUNT(*) FROM job_applications_fts WHERE job_applications_fts MATCH 'company'",
+                    (),
+                    |row| row.get::<usize, i32>(0)
+                )?
+            );
+
+            // Opening the database again should not try to apply the migrations again
+            drop(conn);
+            get_or_make_db(&db_path)?;
+
+            Ok(())
+        }
+
+        #[test]
+        fn test_replace_db_file_failure() -> Result<(), Box<dyn std::error::Error>> {
+            let path = TempDir::new()?;
+            let db_path = path.path().join("test_db.db3");
+            let insert_row = |conn: &Connection| {
+                conn.execute(
+                    "INSERT INTO job_applications (source, company, job_title, application_date) \
+                    VALUES ('test source', 'test company', 'test job title', '2000-01-01')",
+                    (),
+                )
+            };
+            let count_rows = |conn: &Connection| {
+                conn.query_row("SELECT COUNT(*) FROM job_applications", (), |row| {
+                    row.get::<_, i32>(0)
+                })
+            };
+
+            let mut conn = get_or_make_db(&db_path)?;
+            insert_row(&conn)?;
+
+            // A replacement that can't be moved into place, since its directory doesn't exist
+            let missing = path.path().join("missing").join("replacement.db3");
+            assert!(replace_db_file(&mut conn, &missing, None, None).is_err());
+            assert_eq!(db_file_path(&conn)?, db_path);
+            assert_eq!(count_rows(&conn)?, 1);
+
+            // A replacement that is moved into place but can't be opened, so the database is put back
+            let damaged = path.path().join("damaged.db3");
+            fs::write(&damaged, "not a database")?;
+            assert!(replace_db_file(&mut conn, &damaged, None, None).is_err());
+            assert!(!damaged.exists());
+            assert_eq!(count_rows(&conn)?, 1);
+
+            // Changes are still saved in the database file
+            insert_row(&conn)?;
+            drop(conn);
+            assert_eq!(count_rows(&get_or_make_db(&db_path)?)?, 2);
+
+            Ok(())
+        }
     }
 }
@@ -0,0 +1,314 @@
+//! Score job offers against each other using criteria weighted by the user
+//!
+//! Every amount is converted to a yearly amount first, so hourly and yearly offers can be compared.
+//! Amounts are compared as-is, so offers in different currencies should be converted before comparing.
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::job_application_model::Offer;
+
+/// Something offers are compared by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Criterion {
+    /// Yearly base pay
+    Base,
+    /// Expected yearly bonus
+    Bonus,
+    /// The part of the equity grant that vests each year
+    Equity,
+    /// Base, bonus, and equity over a year
+    Total,
+}
+
+impl Criterion {
+    /// Every criterion, in the order they are shown
+    pub const ALL: [Criterion; 4] = [Self::Base, Self::Bonus, Self::Equity, Self::Total];
+
+    /// The yearly amount of this criterion for `offer`
+    pub fn annual_amount(self, offer: &Offer) -> f64 {
+        match self {
+            Self::Base => offer.annual_base(),
+            Self::Bonus => offer.annual_bonus(),
+            Self::Equity => offer.annual_equity(),
+            Self::Total => offer.annual_total(),
+        }
+    }
+}
+
+impl Display for Criterion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Base => "base",
+            Self::Bonus => "bonus",
+            Self::Equity => "equity",
+            Self::Total => "total",
+        })
+    }
+}
+
+impl FromStr for Criterion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|criterion| criterion.to_string() == s.trim().to_lowercase())
+            .ok_or_else(|| format!("Unknown criterion '{s}'. Use base, bonus, equity, or total"))
+    }
+}
+
+/// How much each [Criterion] counts towards an offer's score
+///
+/// Weights are relative to each other, so `base=2 total=1` is the same as `base=4 total=2`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CriteriaWeights {
+    pub base: f64,
+    pub bonus: f64,
+    pub equity: f64,
+    pub total: f64,
+}
+
+impl CriteriaWeights {
+    /// The weight of `criterion`
+    pub fn get(&self, criterion: Criterion) -> f64 {
+        match criterion {
+            Criterion::Base => self.base,
+            Criterion::Bonus => self.bonus,
+            Criterion::Equity => self.equity,
+            Criterion::Total => self.total,
+        }
+    }
+
+    /// Change the weight of `criterion`
+    pub fn set(&mut self, criterion: Criterion, weight: f64) {
+        match criterion {
+            Criterion::Base => self.base = weight,
+            Criterion::Bonus => self.bonus = weight,
+            Criterion::Equity => self.equity = weight,
+            Criterion::Total => self.total = weight,
+        }
+    }
+}
+
+impl Default for CriteriaWeights {
+    /// Only compare total compensation
+    fn default() -> Self {
+        Self {
+            base: 0.0,
+            bonus: 0.0,
+            equity: 0.0,
+            total: 1.0,
+        }
+    }
+}
+
+impl Display for CriteriaWeights {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let weights: Vec<String> = Criterion::ALL
+            .iter()
+            .map(|criterion| format!("{criterion}={}", self.get(*criterion)))
+            .collect();
+        f.write_str(&weights.join(" "))
+    }
+}
+
+impl FromStr for CriteriaWeights {
+    type Err = String;
+
+    /// Parse weights written like `base=2 equity=1`, separated by spaces or commas
+    ///
+    /// Criteria that are left out have a weight of 0. An empty string gives the default weights.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        let mut weights = Self {
+            base: 0.0,
+            bonus: 0.0,
+            equity: 0.0,
+            total: 0.0,
+        };
+        for pair in s.split([' ', ',']).filter(|pair| !pair.is_empty()) {
+            let (criterion, weight) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("Expected criterion=weight, got '{pair}'"))?;
+            let weight = weight
+                .parse::<f64>()
+                .ok()
+                .filter(|weight| weight.is_finite() && *weight >= 0.0)
+                .ok_or_else(|| format!("'{weight}' is not a weight. Use a number 0 or above"))?;
+            weights.set(criterion.parse()?, weight);
+        }
+
+        Ok(weights)
+    }
+}
+
+/// An offer's yearly amounts and how it scored against the other offers
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoredOffer {
+    pub offer: Offer,
+    pub annual_base: f64,
+    pub annual_bonus: f64,
+    pub annual_equity: f64,
+    pub annual_total: f64,
+    /// From 0 to 100, where 100 is the best of the offers in every weighted criterion
+    pub score: f64,
+}
+
+/// Score every offer, returning them from best to worst
+///
+/// For each criterion, the best offer gets full marks and the worst gets none, with the rest placed in between.
+/// The score is the weighted average of those marks. If every weight is 0, every offer scores 0.
+pub fn score_offers(offers: Vec<Offer>, weights: &CriteriaWeights) -> Vec<ScoredOffer> {
+    let total_weight: f64 = Criterion::ALL
+        .iter()
+        .map(|criterion| weights.get(*criterion))
+        .sum();
+
+    // Range of each criterion across all offers
+    let ranges: Vec<(f64, f64)> = Criterion::ALL
+        .iter()
+        .map(|criterion| {
+            offers
+                .iter()
+                .map(|offer| criterion.annual_amount(offer))
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), amount| {
+                    (min.min(amount), max.max(amount))
+                })
+        })
+        .collect();
+
+    let mut scored_offers: Vec<ScoredOffer> = offers
+        .into_iter()
+        .map(|offer| {
+            let weighted_marks: f64 = Criterion::ALL
+                .iter()
+                .zip(&ranges)
+                .map(|(criterion, (min, max))| {
+                    // If every offer is the same, they are all the best
+                    let marks = if max > min {
+                        (criterion.annual_amount(&offer) - min) / (max - min)
+                    } else {
+                        1.0
+                    };
+                    marks * weights.get(*criterion)
+                })
+                .sum();
+
+            ScoredOffer {
+                annual_base: offer.annual_base(),
+                annual_bonus: offer.annual_bonus(),
+                annual_equity: offer.annual_equity(),
+                annual_total: offer.annual_total(),
+                score: if total_weight > 0.0 {
+                    weighted_marks / total_weight * 100.0
+                } else {
+                    0.0
+                },
+                offer,
+            }
+        })
+        .collect();
+
+    scored_offers.sort_by(|a, b| b.score.total_cmp(&a.score));
+    scored_offers
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::job_application_model::PayPeriod;
+
+    use super::*;
+
+    fn offer(job_application_id: i32, base_salary: f64, pay_period: PayPeriod) -> Offer {
+        Offer {
+            job_application_id,
+            base_salary,
+            pay_period,
+            currency: None,
+            bonus: None,
+            equity_value: None,
+            vesting_years: None,
+            benefits: None,
+            start_date: None,
+            deadline: None,
+        }
+    }
+
+    /// Weights can be given in any order, and anything left out is 0
+    #[test]
+    fn test_parse_weights() {
+        assert_eq!("".parse(), Ok(CriteriaWeights::default()));
+        assert_eq!(
+            "equity=0.5, Base=2".parse(),
+            Ok(CriteriaWeights {
+                base: 2.0,
+                bonus: 0.0,
+                equity: 0.5,
+                total: 0.0,
+            })
+        );
+        assert!("base".parse::<CriteriaWeights>().is_err());
+        assert!("base=-1".parse::<CriteriaWeights>().is_err());
+        assert!("salary=1".parse::<CriteriaWeights>().is_err());
+    }
+
+    /// Pay periods and vesting are turned into yearly amounts
+    #[test]
+    fn test_annual_amounts() {
+        let hourly = Offer {
+            bonus: Some(5000.0),
+            equity_value: Some(40000.0),
+            ..offer(1, 50.0, PayPeriod::Hourly)
+        };
+        assert_eq!(hourly.annual_base(), 104000.0);
+        assert_eq!(
+            hourly.annual_equity(),
+            10000.0,
+            "Vesting defaults to four years"
+        );
+        assert_eq!(hourly.annual_total(), 119000.0);
+
+        let vested = Offer {
+            equity_value: Some(30000.0),
+            vesting_years: Some(3.0),
+            ..offer(2, 10000.0, PayPeriod::Monthly)
+        };
+        assert_eq!(vested.annual_base(), 120000.0);
+        assert_eq!(vested.annual_equity(), 10000.0);
+    }
+
+    /// The best offer in the weighted criteria scores 100, the worst scores 0
+    #[test]
+    fn test_score_offers() {
+        let low_base_high_equity = Offer {
+            equity_value: Some(200000.0),
+            ..offer(1, 100000.0, PayPeriod::Yearly)
+        };
+        let high_base = offer(2, 150000.0, PayPeriod::Yearly);
+        let middle = offer(3, 120000.0, PayPeriod::Yearly);
+        let offers = vec![
+            low_base_high_equity.clone(),
+            high_base.clone(),
+            middle.clone(),
+        ];
+
+        let by_base = score_offers(offers.clone(), &"base=1".parse().unwrap());
+        assert_eq!(
+            by_base
+                .iter()
+                .map(|scored| (scored.offer.job_application_id, scored.score))
+                .collect::<Vec<_>>(),
+            vec![(2, 100.0), (3, 40.0), (1, 0.0)]
+        );
+
+        let by_equity = score_offers(offers.clone(), &"base=1 equity=3".parse().unwrap());
+        assert_eq!(by_equity[0].offer, low_base_high_equity);
+        assert_eq!(by_equity[0].score, 75.0);
+
+        let unweighted = score_offers(offers, &"base=0".parse().unwrap());
+        assert!(unweighted.iter().all(|scored| scored.score == 0.0));
+    }
+}
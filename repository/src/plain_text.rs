@@ -0,0 +1,1498 @@
+//! Storing job applications as plain-text files in a directory, so a job search can be versioned with git
+//!
+//! Each job application is a Markdown file named after it, like `acme-software-engineer-1a2b3c4d.md`.
+//! Its fields are TOML front matter between `+++` lines, and its notes are the body, so a merge that touched the same job application can be fixed by hand.
+//! Custom fields are defined in `custom-fields.toml`, as the name and type of each field.
+//!
+//! The files are the only copy of the job applications. Everything is read from an SQLite index, `.index.db3`,
+//! which is updated from every file that changed since it was last read, like after a `git pull`, so it can be deleted at any time and is ignored by git.
+//! A file can be added by hand, without a `uuid`, and is given one the first time it is read.
+//!
+//! Offers, job descriptions, attachments, and resume versions can be large or binary, so they aren't kept here.
+//! Backups, encryption, and syncing are also left to git.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use rusqlite::{params_from_iter, types::Value, Connection, OptionalExtension, ToSql, Transaction};
+use time::{macros::format_description, Date, Duration, Month};
+use toml::{
+    value::{Date as TomlDate, Datetime},
+    Table, Value as TomlValue,
+};
+
+use crate::{
+    backend_connection::open_db,
+    job_application_model::{
+        Activity, Attachment, AttachmentRole, AuditLogEntry, CustomField, CustomFieldType,
+        CustomFieldValue, CustomFields, DeletedJobApplication, FullTextSearchResult, HumanResponse,
+        JobApplication, JobDescription, JournalEntry, NewJobApplication, Offer,
+        PartialJobApplication, ResumeVersion, ResumeVersionStats, Tag, WorkArrangementStats,
+    },
+    job_application_repository::{JobApplicationRepository, RepositoryError},
+    search_query::SearchQuery,
+    sync::{set_tags, NEW_UUID, SYNCED_COLUMNS},
+    validation::validate_new_job_application,
+};
+
+/// The SQLite index of the files, in the same directory
+const INDEX_FILE: &str = ".index.db3";
+
+/// The custom field definitions, in the same directory
+const CUSTOM_FIELDS_FILE: &str = "custom-fields.toml";
+
+/// The line before and after the fields of a job application
+const FRONT_MATTER_FENCE: &str = "+++";
+
+/// Keeps track of what each file had in it when it was last read or written
+///
+/// `rendered` is the file as it would be written from the index, so a file is only rewritten when the job application changes,
+/// not just because it was formatted differently by hand.
+const INDEX_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS plain_text_files (
+    file_name TEXT PRIMARY KEY,
+    uuid TEXT UNIQUE,
+    rendered TEXT NOT NULL,
+    modified INTEGER NOT NULL,
+    size INTEGER NOT NULL
+)";
+
+/// The name of each human response in a file, which is what it is called everywhere else without punctuation
+const HUMAN_RESPONSE_NAMES: [(HumanResponse, &str); 5] = [
+    (HumanResponse::None, "none"),
+    (HumanResponse::Rejection, "rejection"),
+    (HumanResponse::InterviewRequest, "interview request"),
+    (
+        HumanResponse::InterviewedThenRejected,
+        "interviewed then rejected",
+    ),
+    (HumanResponse::JobOffer, "job offer"),
+];
+
+/// Job applications stored as plain-text files in a directory
+pub struct PlainTextRepository {
+    dir: PathBuf,
+    index: Connection,
+    /// How many calls to [JobApplicationRepository::with_transaction] are running
+    ///
+    /// Files are only read and written outside of them, so a transaction that is rolled back never reaches the files.
+    transaction_depth: usize,
+}
+
+impl PlainTextRepository {
+    /// Open the job applications in `dir`, making it if it doesn't exist
+    ///
+    /// The index is brought up to date with the files, so this fails if any of them can't be read,
+    /// like when a merge left conflict markers in one.
+    pub fn open(dir: &Path) -> Result<Self, RepositoryError> {
+        fs::create_dir_all(dir)?;
+        let gitignore = dir.join(".gitignore");
+        if !gitignore.exists() {
+            fs::write(&gitignore, format!("/{INDEX_FILE}*\n"))?;
+        }
+
+        let index = open_db(&dir.join(INDEX_FILE), None)?;
+        index.execute_batch(INDEX_SCHEMA)?;
+        let mut repository = Self {
+            dir: dir.to_owned(),
+            index,
+            transaction_depth: 0,
+        };
+        repository.refresh()?;
+        Ok(repository)
+    }
+
+    /// The directory the files are in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Read every file that was added, changed, or removed since it was last read into the index
+    fn refresh(&mut self) -> Result<(), RepositoryError> {
+        if self.transaction_depth > 0 {
+            return Ok(());
+        }
+
+        let known: BTreeMap<String, (i64, i64)> = self
+            .index
+            .prepare("SELECT file_name, modified, size FROM plain_text_files")?
+            .query_map((), |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?
+            .collect::<Result<_, _>>()?;
+        let mut present = BTreeMap::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let Ok(file_name) = entry?.file_name().into_string() else {
+                continue;
+            };
+            if is_job_application_file(&file_name) || file_name == CUSTOM_FIELDS_FILE {
+                let stamp = file_stamp(&self.dir.join(&file_name))?;
+                present.insert(file_name, stamp);
+            }
+        }
+
+        let custom_fields_changed =
+            known.get(CUSTOM_FIELDS_FILE) != present.get(CUSTOM_FIELDS_FILE);
+        let changed: Vec<(&String, &(i64, i64))> = present
+            .iter()
+            .filter(|(file_name, stamp)| {
+                is_job_application_file(file_name)
+                    // Values are checked against the custom fields again when they change
+                    && (custom_fields_changed || known.get(*file_name) != Some(*stamp))
+            })
+            .collect();
+        let removed: Vec<&String> = known
+            .keys()
+            .filter(|file_name| !present.contains_key(*file_name))
+            .collect();
+        if !custom_fields_changed && changed.is_empty() && removed.is_empty() {
+            return Ok(());
+        }
+
+        let tx = self.index.transaction()?;
+        if custom_fields_changed {
+            match present.get(CUSTOM_FIELDS_FILE) {
+                Some(stamp) => {
+                    let contents = fs::read_to_string(self.dir.join(CUSTOM_FIELDS_FILE))?;
+                    let fields = parse_custom_fields(&contents)
+                        .map_err(|error| file_error(CUSTOM_FIELDS_FILE, error))?;
+                    import_custom_fields(&tx, &fields)?;
+                    let rendered = render_custom_fields(&tx)?;
+                    record_file(&tx, CUSTOM_FIELDS_FILE, None, &rendered, *stamp)?;
+                }
+                None => {
+                    import_custom_fields(&tx, &[])?;
+                    forget_file(&tx, CUSTOM_FIELDS_FILE)?;
+                }
+            }
+        }
+
+        let custom_fields = get_custom_fields(&tx)?;
+        for (file_name, stamp) in changed {
+            let contents = fs::read_to_string(self.dir.join(file_name))?;
+            // A file that was only touched, like by a checkout, doesn't change its job application's version
+            let recorded: Option<(Option<String>, String)> = tx
+                .query_row(
+                    "SELECT uuid, rendered FROM plain_text_files WHERE file_name = ?",
+                    (file_name,),
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+            if let Some((uuid, rendered)) = recorded {
+                if !custom_fields_changed && rendered == contents {
+                    record_file(&tx, file_name, uuid.as_deref(), &rendered, *stamp)?;
+                    continue;
+                }
+            }
+
+            let file = parse_job_application_file(&contents, &custom_fields)
+                .map_err(|error| file_error(file_name, error))?;
+            let uuid = match &file.uuid {
+                Some(uuid) => uuid.clone(),
+                None => tx.query_row(&format!("SELECT {NEW_UUID}"), (), |row| row.get(0))?,
+            };
+
+            // A file that was renamed is the same job application. Two files with the same UUID can't both be.
+            let other_file: Option<String> = tx
+                .query_row(
+                    "SELECT file_name FROM plain_text_files WHERE uuid = ? AND file_name != ?",
+                    (&uuid, file_name),
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if let Some(other_file) = other_file {
+                if present.contains_key(&other_file) {
+                    return Err(RepositoryError::Validation(format!(
+                        "{file_name} and {other_file} have the same uuid. Remove the uuid from one of them to make it a separate job application"
+                    )));
+                }
+                forget_file(&tx, &other_file)?;
+            }
+
+            import_job_application(&tx, &uuid, &file)?;
+            let rendered = render_job_application(&tx, &uuid)?;
+            if file.uuid.is_some() {
+                record_file(&tx, file_name, Some(&uuid), &rendered, *stamp)?;
+            } else {
+                // The UUID is written into the file, so it stays the same job application when it is read again
+                write_file(&self.dir, &tx, file_name, Some(&uuid), &rendered)?;
+            }
+        }
+
+        for file_name in removed {
+            let uuid: Option<Option<String>> = tx
+                .query_row(
+                    "SELECT uuid FROM plain_text_files WHERE file_name = ?",
+                    (file_name,),
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if let Some(Some(uuid)) = uuid {
+                tx.execute("DELETE FROM job_applications WHERE uuid = ?", (uuid,))?;
+            }
+            forget_file(&tx, file_name)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Write every job application and the custom fields that changed in the index since they were last read or written, and remove the files of job applications that are gone
+    fn write_files(&mut self) -> Result<(), RepositoryError> {
+        if self.transaction_depth > 0 {
+            return Ok(());
+        }
+
+        let recorded: BTreeMap<Option<String>, (String, String)> = self
+            .index
+            .prepare("SELECT uuid, file_name, rendered FROM plain_text_files")?
+            .query_map((), |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?
+            .collect::<Result<_, _>>()?;
+        let uuids: Vec<String> = self
+            .index
+            .prepare("SELECT uuid FROM job_applications WHERE uuid IS NOT NULL")?
+            .query_map((), |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+
+        for uuid in &uuids {
+            let rendered = render_job_application(&self.index, uuid)?;
+            let file_name = match recorded.get(&Some(uuid.clone())) {
+                Some((_, recorded)) if *recorded == rendered => continue,
+                Some((file_name, _)) => file_name.clone(),
+                None => self.new_file_name(uuid)?,
+            };
+            write_file(&self.dir, &self.index, &file_name, Some(uuid), &rendered)?;
+        }
+
+        let uuids: BTreeSet<&String> = uuids.iter().collect();
+        for (uuid, (file_name, _)) in &recorded {
+            if uuid.as_ref().is_some_and(|uuid| !uuids.contains(uuid)) {
+                match fs::remove_file(self.dir.join(file_name)) {
+                    Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                        return Err(error.into())
+                    }
+                    _ => {}
+                }
+                forget_file(&self.index, file_name)?;
+            }
+        }
+
+        let custom_fields = render_custom_fields(&self.index)?;
+        let recorded_custom_fields = recorded.get(&None).map_or("", |(_, rendered)| rendered);
+        if custom_fields != recorded_custom_fields {
+            write_file(
+                &self.dir,
+                &self.index,
+                CUSTOM_FIELDS_FILE,
+                None,
+                &custom_fields,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// A file name for a new job application, from its company and job title, that isn't used yet
+    fn new_file_name(&self, uuid: &str) -> Result<String, RepositoryError> {
+        let (company, job_title): (String, String) = self.index.query_row(
+            "SELECT company, job_title FROM job_applications WHERE uuid = ?",
+            (uuid,),
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let mut slug = String::new();
+        for c in format!("{company} {job_title}")
+            .chars()
+            .flat_map(char::to_lowercase)
+        {
+            if c.is_alphanumeric() {
+                slug.push(c);
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        let slug: String = slug.chars().take(60).collect();
+        let slug = slug.trim_end_matches('-');
+
+        // The start of the UUID is almost always enough to tell apart job applications at the same company
+        let short_uuid = uuid.split('-').next().unwrap_or(uuid);
+        let file_name = format!("{slug}-{short_uuid}.md");
+        if self.dir.join(&file_name).exists() {
+            Ok(format!("{slug}-{uuid}.md"))
+        } else {
+            Ok(file_name)
+        }
+    }
+
+    /// Read from the index, after bringing it up to date
+    fn read<T>(
+        &mut self,
+        read: impl FnOnce(&mut Connection) -> Result<T, RepositoryError>,
+    ) -> Result<T, RepositoryError> {
+        self.refresh()?;
+        read(&mut self.index)
+    }
+
+    /// Change the index, after bringing it up to date, then write the files that changed
+    fn write<T>(
+        &mut self,
+        write: impl FnOnce(&mut Connection) -> Result<T, RepositoryError>,
+    ) -> Result<T, RepositoryError> {
+        self.refresh()?;
+        let result = write(&mut self.index)?;
+        self.write_files()?;
+        Ok(result)
+    }
+}
+
+/// Save every job application, with its tags and custom fields, from the SQLite database `conn` has open as plain-text files in `dir`, returning how many there are
+///
+/// `dir` can't have job applications in it already.
+pub(crate) fn export_plain_text(
+    conn: &mut Connection,
+    dir: &Path,
+) -> Result<usize, RepositoryError> {
+    if has_plain_text_files(dir)? {
+        return Err(RepositoryError::Conflict(format!(
+            "{} already has job applications in it",
+            dir.display()
+        )));
+    }
+    let mut repository = PlainTextRepository::open(dir)?;
+    let tx = repository.index.transaction()?;
+    let count = copy_job_applications(conn, &tx)?;
+    tx.commit()?;
+    repository.write_files()?;
+    Ok(count)
+}
+
+/// Copy every job application, with its tags and custom fields, from the plain-text files in `dir` into the SQLite database `conn` has open, returning how many there are
+///
+/// The database has to be empty, so nothing in it is replaced or duplicated.
+pub(crate) fn import_plain_text(
+    conn: &mut Connection,
+    dir: &Path,
+) -> Result<usize, RepositoryError> {
+    if !has_plain_text_files(dir)? {
+        return Err(RepositoryError::NotFound(format!(
+            "No job applications in {}",
+            dir.display()
+        )));
+    }
+    let count: i64 = conn.query_row("SELECT COUNT(*) FROM job_applications", (), |row| {
+        row.get(0)
+    })?;
+    if count > 0 {
+        return Err(RepositoryError::Conflict(
+            "Job applications can only be imported into an empty database".to_owned(),
+        ));
+    }
+
+    let repository = PlainTextRepository::open(dir)?;
+    let tx = conn.transaction()?;
+    let count = copy_job_applications(&repository.index, &tx)?;
+    tx.commit()?;
+    Ok(count)
+}
+
+/// Whether `dir` has any job application or custom field files in it
+fn has_plain_text_files(dir: &Path) -> Result<bool, RepositoryError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(error) => return Err(error.into()),
+    };
+    for entry in entries {
+        if let Ok(file_name) = entry?.file_name().into_string() {
+            if is_job_application_file(&file_name) || file_name == CUSTOM_FIELDS_FILE {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Copy job applications, with their UUIDs, tags, and custom fields, from one SQLite database to another, returning how many were copied
+fn copy_job_applications(from: &Connection, to: &Connection) -> Result<usize, rusqlite::Error> {
+    let mut stmt = from.prepare("SELECT name, field_type FROM custom_fields")?;
+    let mut rows = stmt.query(())?;
+    while let Some(row) = rows.next()? {
+        to.execute(
+            "INSERT INTO custom_fields (name, field_type) VALUES (?, ?) ON CONFLICT (name) DO NOTHING",
+            (row.get::<_, String>(0)?, row.get::<_, String>(1)?),
+        )?;
+    }
+
+    let columns = SYNCED_COLUMNS.join(", ");
+    let mut ids = BTreeMap::new();
+    let mut stmt = from.prepare(&format!(
+        "SELECT id, uuid, created_at, updated_at, {columns} FROM job_applications"
+    ))?;
+    let mut rows = stmt.query(())?;
+    while let Some(row) = rows.next()? {
+        let values = (1..4 + SYNCED_COLUMNS.len())
+            .map(|index| row.get::<_, Value>(index))
+            .collect::<Result<Vec<_>, _>>()?;
+        to.execute(
+            &format!(
+                "INSERT INTO job_applications (uuid, created_at, updated_at, {columns}) VALUES ({})",
+                vec!["?"; values.len()].join(", ")
+            ),
+            params_from_iter(values),
+        )?;
+        ids.insert(row.get::<_, i64>(0)?, to.last_insert_rowid());
+    }
+
+    let mut tags: BTreeMap<i64, Vec<String>> = BTreeMap::new();
+    let mut stmt = from.prepare(
+        "SELECT job_application_tags.job_application_id, tags.name FROM job_application_tags \
+        JOIN tags ON tags.id = job_application_tags.tag_id",
+    )?;
+    let mut rows = stmt.query(())?;
+    while let Some(row) = rows.next()? {
+        tags.entry(row.get(0)?).or_default().push(row.get(1)?);
+    }
+    for (id, names) in &tags {
+        set_tags(to, ids[id], names)?;
+    }
+
+    let mut stmt = from.prepare(
+        "SELECT custom_field_values.job_application_id, custom_fields.name, custom_field_values.value \
+        FROM custom_field_values JOIN custom_fields ON custom_fields.id = custom_field_values.custom_field_id",
+    )?;
+    let mut rows = stmt.query(())?;
+    while let Some(row) = rows.next()? {
+        to.execute(
+            "INSERT INTO custom_field_values (job_application_id, custom_field_id, value) \
+            SELECT ?, id, ? FROM custom_fields WHERE name = ?",
+            (
+                ids[&row.get::<_, i64>(0)?],
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(1)?,
+            ),
+        )?;
+    }
+
+    Ok(ids.len())
+}
+
+/// Whether a file in the directory is a job application, leaving out hidden files like the ones being written
+fn is_job_application_file(file_name: &str) -> bool {
+    file_name.ends_with(".md") && !file_name.starts_with('.')
+}
+
+/// When a file was last modified, in nanoseconds, and how long it is, to notice when it changes
+fn file_stamp(path: &Path) -> io::Result<(i64, i64)> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_nanos() as i64);
+    Ok((modified, metadata.len() as i64))
+}
+
+/// An error in one of the files, which is named so it can be fixed
+fn file_error(file_name: &str, error: String) -> RepositoryError {
+    RepositoryError::Validation(format!("{file_name}: {error}"))
+}
+
+/// Remember what a file has in it and when it was last changed, so it is only read again once it changes
+fn record_file(
+    conn: &Connection,
+    file_name: &str,
+    uuid: Option<&str>,
+    rendered: &str,
+    (modified, size): (i64, i64),
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT OR REPLACE INTO plain_text_files (file_name, uuid, rendered, modified, size) VALUES (?, ?, ?, ?, ?)",
+        (file_name, uuid, rendered, modified, size),
+    )?;
+    Ok(())
+}
+
+/// Stop keeping track of a file
+fn forget_file(conn: &Connection, file_name: &str) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "DELETE FROM plain_text_files WHERE file_name = ?",
+        (file_name,),
+    )?;
+    Ok(())
+}
+
+/// Write a file in `dir`, replacing it all at once so it is never read half written, then remember what it has in it
+fn write_file(
+    dir: &Path,
+    conn: &Connection,
+    file_name: &str,
+    uuid: Option<&str>,
+    contents: &str,
+) -> Result<(), RepositoryError> {
+    let path = dir.join(file_name);
+    let partial_path = dir.join(format!(".{file_name}.partial"));
+    fs::write(&partial_path, contents)?;
+    fs::rename(&partial_path, &path)?;
+    record_file(conn, file_name, uuid, contents, file_stamp(&path)?)?;
+    Ok(())
+}
+
+/// Everything in a job application file
+struct JobApplicationFile {
+    /// `None` for a file that was just added by hand
+    uuid: Option<String>,
+    job_application: NewJobApplication,
+    tags: Vec<String>,
+    /// In the same format as the database, like `2025-01-31 18:30:00`
+    created_at: Option<String>,
+    /// In the same format as the database. `Some` if the job application is in the trash.
+    deleted_at: Option<String>,
+}
+
+/// The fields in the front matter of a file, taken out one at a time so any left over can be reported
+struct FrontMatter(Table);
+
+impl FrontMatter {
+    fn text(&mut self, key: &str) -> Result<Option<String>, String> {
+        match self.0.remove(key) {
+            None => Ok(None),
+            Some(TomlValue::String(text)) if text.trim().is_empty() => Ok(None),
+            Some(TomlValue::String(text)) => Ok(Some(text)),
+            Some(_) => Err(format!("{key} should be text, in quotes")),
+        }
+    }
+
+    fn required_text(&mut self, key: &str) -> Result<String, String> {
+        self.text(key)?.ok_or_else(|| format!("{key} is missing"))
+    }
+
+    fn date(&mut self, key: &str) -> Result<Option<Date>, String> {
+        let error = || format!("{key} should be a date, like 2025-01-31");
+        match self.0.remove(key) {
+            None => Ok(None),
+            Some(TomlValue::Datetime(Datetime {
+                date: Some(date),
+                time: None,
+                offset: None,
+            })) => Month::try_from(date.month)
+                .and_then(|month| Date::from_calendar_date(date.year.into(), month, date.day))
+                .map(Some)
+                .map_err(|_| error()),
+            Some(TomlValue::String(text)) => {
+                Date::parse(&text, format_description!("[year]-[month]-[day]"))
+                    .map(Some)
+                    .map_err(|_| error())
+            }
+            Some(_) => Err(error()),
+        }
+    }
+
+    /// A date and time, in the same format as the database
+    fn timestamp(&mut self, key: &str) -> Result<Option<String>, String> {
+        match self.0.remove(key) {
+            None => Ok(None),
+            Some(TomlValue::Datetime(Datetime {
+                date: Some(date),
+                time: Some(time),
+                offset: None,
+            })) => Ok(Some(format!("{date} {time}"))),
+            Some(_) => Err(format!(
+                "{key} should be a date and time, like 2025-01-31T18:30:00"
+            )),
+        }
+    }
+
+    fn number(&mut self, key: &str) -> Result<Option<f64>, String> {
+        match self.0.remove(key) {
+            None => Ok(None),
+            Some(TomlValue::Float(number)) if number.is_finite() => Ok(Some(number)),
+            Some(TomlValue::Integer(number)) => Ok(Some(number as f64)),
+            Some(_) => Err(format!("{key} should be a number")),
+        }
+    }
+
+    fn boolean(&mut self, key: &str) -> Result<Option<bool>, String> {
+        match self.0.remove(key) {
+            None => Ok(None),
+            Some(TomlValue::Boolean(value)) => Ok(Some(value)),
+            Some(_) => Err(format!("{key} should be true or false")),
+        }
+    }
+
+    /// Text that is parsed into something else, like a pay period
+    fn parsed<T: std::str::FromStr<Err = String>>(
+        &mut self,
+        key: &str,
+    ) -> Result<Option<T>, String> {
+        self.text(key)?
+            .map(|text| text.parse().map_err(|error| format!("{key}: {error}")))
+            .transpose()
+    }
+
+    fn time_investment(&mut self) -> Result<Option<Duration>, String> {
+        let Some(text) = self.text("time_investment")? else {
+            return Ok(None);
+        };
+        text.split_once(':')
+            .and_then(|(minutes, seconds)| {
+                Some(
+                    Duration::minutes(minutes.trim().parse().ok()?)
+                        + Duration::seconds(seconds.trim().parse().ok()?),
+                )
+            })
+            .map(Some)
+            .ok_or_else(|| {
+                "time_investment should be minutes and seconds, like \"12:30\"".to_owned()
+            })
+    }
+
+    fn human_response(&mut self) -> Result<HumanResponse, String> {
+        let Some(text) = self.text("human_response")? else {
+            return Ok(HumanResponse::None);
+        };
+        HUMAN_RESPONSE_NAMES
+            .iter()
+            .find(|(_, name)| text.trim().eq_ignore_ascii_case(name))
+            .map(|(human_response, _)| *human_response)
+            .ok_or_else(|| {
+                format!(
+                    "human_response should be one of: {}",
+                    HUMAN_RESPONSE_NAMES.map(|(_, name)| name).join(", ")
+                )
+            })
+    }
+
+    fn tags(&mut self) -> Result<Vec<String>, String> {
+        let error = || "tags should be a list of text, like [\"remote\", \"startup\"]".to_owned();
+        match self.0.remove("tags") {
+            None => Ok(Vec::new()),
+            Some(TomlValue::Array(tags)) => tags
+                .into_iter()
+                .map(|tag| match tag {
+                    TomlValue::String(tag) if !tag.trim().is_empty() => Ok(tag.trim().to_owned()),
+                    _ => Err(error()),
+                })
+                .collect(),
+            Some(_) => Err(error()),
+        }
+    }
+
+    fn custom_fields(&mut self, defined: &[CustomField]) -> Result<CustomFields, String> {
+        let values = match self.0.remove("custom_fields") {
+            None => return Ok(CustomFields::new()),
+            Some(TomlValue::Table(values)) => values,
+            Some(_) => return Err("custom_fields should be a [custom_fields] section".to_owned()),
+        };
+        let mut custom_fields = CustomFields::new();
+        for (name, value) in values {
+            let field = defined
+                .iter()
+                .find(|field| field.name.eq_ignore_ascii_case(&name))
+                .ok_or_else(|| {
+                    format!("There is no custom field named {name}. Add it to {CUSTOM_FIELDS_FILE} first")
+                })?;
+            let text = match value {
+                TomlValue::String(text) => text,
+                TomlValue::Integer(number) => number.to_string(),
+                TomlValue::Float(number) => number.to_string(),
+                TomlValue::Boolean(value) => value.to_string(),
+                TomlValue::Datetime(Datetime {
+                    date: Some(date),
+                    time: None,
+                    ..
+                }) => date.to_string(),
+                _ => return Err(format!("{name} should be a {}", field.field_type)),
+            };
+            let value = field
+                .field_type
+                .parse_value(&text)
+                .map_err(|error| format!("{name}: {error}"))?;
+            custom_fields.insert(field.name.clone(), value);
+        }
+        Ok(custom_fields)
+    }
+
+    /// Make sure every field was used, to catch misspelled ones
+    fn finish(self) -> Result<(), String> {
+        match self.0.keys().next() {
+            Some(key) => Err(format!("Unknown field {key}")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Read a job application file, checking its values the same way as when a job application is saved
+fn parse_job_application_file(
+    contents: &str,
+    custom_fields: &[CustomField],
+) -> Result<JobApplicationFile, String> {
+    if contents
+        .lines()
+        .any(|line| line.starts_with("<<<<<<<") || line.starts_with(">>>>>>>"))
+    {
+        return Err(
+            "This has merge conflict markers. Keep the lines that should stay, then remove the markers"
+                .to_owned(),
+        );
+    }
+
+    let mut lines = contents
+        .trim_start_matches('\u{feff}')
+        .split_inclusive('\n');
+    if lines.next().map(str::trim_end) != Some(FRONT_MATTER_FENCE) {
+        return Err(format!(
+            "This should start with a {FRONT_MATTER_FENCE} line, followed by the fields"
+        ));
+    }
+    let mut front_matter = String::new();
+    loop {
+        match lines.next() {
+            Some(line) if line.trim_end() == FRONT_MATTER_FENCE => break,
+            Some(line) => front_matter.push_str(line),
+            None => {
+                return Err(format!(
+                    "The fields should end with a {FRONT_MATTER_FENCE} line"
+                ))
+            }
+        }
+    }
+    let notes = lines.collect::<String>().trim().to_owned();
+
+    let mut fields = FrontMatter(
+        front_matter
+            .parse::<Table>()
+            .map_err(|error| error.to_string().trim_end().to_owned())?,
+    );
+    let job_application = NewJobApplication {
+        source: fields.required_text("source")?,
+        company: fields.required_text("company")?,
+        job_title: fields.required_text("job_title")?,
+        application_date: fields
+            .date("application_date")?
+            .ok_or("application_date is missing")?,
+        time_investment: fields.time_investment()?,
+        human_response: fields.human_response()?,
+        human_response_date: fields.date("human_response_date")?,
+        application_website: fields.text("application_website")?,
+        notes: Some(notes).filter(|notes| !notes.is_empty()),
+        salary_min: fields.number("salary_min")?,
+        salary_max: fields.number("salary_max")?,
+        salary_currency: fields.text("salary_currency")?,
+        pay_period: fields.parsed("pay_period")?,
+        equity: fields.text("equity")?,
+        bonus: fields.text("bonus")?,
+        city: fields.text("city")?,
+        region: fields.text("region")?,
+        country: fields.text("country")?,
+        work_arrangement: fields.parsed("work_arrangement")?,
+        relocation: fields.boolean("relocation")?,
+        visa_sponsorship: fields.boolean("visa_sponsorship")?,
+        custom_fields: fields.custom_fields(custom_fields)?,
+    };
+    let file = JobApplicationFile {
+        uuid: fields.text("uuid")?,
+        tags: fields.tags()?,
+        created_at: fields.timestamp("created_at")?,
+        deleted_at: fields.timestamp("deleted_at")?,
+        job_application,
+    };
+    fields.finish()?;
+
+    validate_new_job_application(&file.job_application).map_err(|errors| {
+        errors
+            .iter()
+            .map(|error| format!("{}: {error}", error.field))
+            .collect::<Vec<_>>()
+            .join("\n")
+    })?;
+    Ok(file)
+}
+
+/// Save a job application read from a file in the index, replacing the one with the same UUID if there is one
+fn import_job_application(
+    tx: &Transaction,
+    uuid: &str,
+    file: &JobApplicationFile,
+) -> Result<(), RepositoryError> {
+    let job_application = &file.job_application;
+    let time_investment = job_application
+        .time_investment
+        .map(|time_investment| time_investment.whole_seconds());
+    // In the same order as SYNCED_COLUMNS
+    let values: [&dyn ToSql; 22] = [
+        &job_application.source,
+        &job_application.company,
+        &job_application.job_title,
+        &job_application.application_date,
+        &time_investment,
+        &job_application.human_response,
+        &job_application.human_response_date,
+        &job_application.application_website,
+        &job_application.notes,
+        &job_application.salary_min,
+        &job_application.salary_max,
+        &job_application.salary_currency,
+        &job_application.pay_period,
+        &job_application.equity,
+        &job_application.bonus,
+        &job_application.city,
+        &job_application.region,
+        &job_application.country,
+        &job_application.work_arrangement,
+        &job_application.relocation,
+        &job_application.visa_sponsorship,
+        &file.deleted_at,
+    ];
+
+    let id: Option<i64> = tx
+        .query_row(
+            "SELECT id FROM job_applications WHERE uuid = ?",
+            (uuid,),
+            |row| row.get(0),
+        )
+        .optional()?;
+    let id = match id {
+        // Changing the version makes anything that was opened before the file changed refuse to save over it
+        Some(id) => {
+            tx.execute(
+                &format!(
+                    "UPDATE job_applications SET {}, created_at = coalesce(?, created_at), version = version + 1, updated_at = datetime('now') WHERE id = ?",
+                    SYNCED_COLUMNS.map(|column| format!("{column} = ?")).join(", ")
+                ),
+                params_from_iter(values.into_iter().chain([&file.created_at as &dyn ToSql, &id])),
+            )?;
+            id
+        }
+        None => {
+            tx.execute(
+                &format!(
+                    "INSERT INTO job_applications ({}, uuid, created_at, updated_at) VALUES ({}, ?, coalesce(?, datetime('now')), datetime('now'))",
+                    SYNCED_COLUMNS.join(", "),
+                    vec!["?"; SYNCED_COLUMNS.len()].join(", ")
+                ),
+                params_from_iter(values.into_iter().chain([&uuid as &dyn ToSql, &file.created_at])),
+            )?;
+            tx.last_insert_rowid()
+        }
+    };
+
+    set_tags(tx, id, &file.tags)?;
+    tx.execute(
+        "DELETE FROM custom_field_values WHERE job_application_id = ?",
+        (id,),
+    )?;
+    for (name, value) in &job_application.custom_fields {
+        tx.execute(
+            "INSERT INTO custom_field_values (job_application_id, custom_field_id, value) \
+            SELECT ?, id, ? FROM custom_fields WHERE name = ?",
+            (id, value.to_stored_string(), name),
+        )?;
+    }
+    Ok(())
+}
+
+/// Write a job application the way it is saved in its file
+fn render_job_application(conn: &Connection, uuid: &str) -> Result<String, RepositoryError> {
+    let (job_application, created_at, deleted_at): (JobApplication, Option<String>, Option<String>) = conn
+        .prepare_cached(
+            "SELECT id, source, company, job_title, application_date, time_investment, human_response, human_response_date, application_website, notes, salary_min, salary_max, salary_currency, pay_period, equity, bonus, city, region, country, work_arrangement, relocation, visa_sponsorship, version, created_at, deleted_at \
+            FROM job_applications WHERE uuid = ?",
+        )?
+        .query_row((uuid,), |row| {
+            Ok((row.try_into()?, row.get("created_at")?, row.get("deleted_at")?))
+        })?;
+    let tags: Vec<String> = conn
+        .prepare_cached(
+            "SELECT tags.name FROM job_application_tags JOIN tags ON tags.id = job_application_tags.tag_id \
+            WHERE job_application_tags.job_application_id = ? ORDER BY tags.name",
+        )?
+        .query_map((job_application.id,), |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    let mut custom_fields = Table::new();
+    let mut stmt = conn.prepare_cached(
+        "SELECT custom_fields.name, custom_fields.field_type, custom_field_values.value FROM custom_field_values \
+        JOIN custom_fields ON custom_fields.id = custom_field_values.custom_field_id \
+        WHERE custom_field_values.job_application_id = ? ORDER BY custom_fields.name",
+    )?;
+    let mut rows = stmt.query((job_application.id,))?;
+    while let Some(row) = rows.next()? {
+        let field_type: CustomFieldType = row.get(1)?;
+        let value = match field_type.value_from_stored_string(&row.get::<_, String>(2)?) {
+            Ok(CustomFieldValue::Text(text) | CustomFieldValue::SingleSelect(text)) => {
+                TomlValue::String(text)
+            }
+            Ok(CustomFieldValue::Number(number)) => TomlValue::Float(number),
+            Ok(CustomFieldValue::Date(date)) => toml_date(date),
+            Ok(CustomFieldValue::Bool(value)) => TomlValue::Boolean(value),
+            Err(_) => TomlValue::String(row.get(2)?),
+        };
+        custom_fields.insert(row.get(0)?, value);
+    }
+
+    let mut fields = Table::new();
+    let mut insert = |key: &str, value: Option<TomlValue>| {
+        if let Some(value) = value {
+            fields.insert(key.to_owned(), value);
+        }
+    };
+    let text = |text: &Option<String>| text.clone().map(TomlValue::String);
+    let timestamp = |timestamp: Option<String>| {
+        timestamp.map(|timestamp| {
+            timestamp
+                .parse::<Datetime>()
+                .map_or(TomlValue::String(timestamp), TomlValue::Datetime)
+        })
+    };
+    insert("company", Some(job_application.company.clone().into()));
+    insert("job_title", Some(job_application.job_title.clone().into()));
+    insert("source", Some(job_application.source.clone().into()));
+    insert(
+        "application_date",
+        Some(toml_date(job_application.application_date)),
+    );
+    insert(
+        "time_investment",
+        job_application.time_investment.map(|time_investment| {
+            format!(
+                "{}:{:02}",
+                time_investment.whole_minutes(),
+                time_investment.whole_seconds() % 60
+            )
+            .into()
+        }),
+    );
+    insert(
+        "human_response",
+        HUMAN_RESPONSE_NAMES
+            .iter()
+            .find(|(human_response, _)| {
+                *human_response == job_application.human_response
+                    && *human_response != HumanResponse::None
+            })
+            .map(|(_, name)| (*name).into()),
+    );
+    insert(
+        "human_response_date",
+        job_application.human_response_date.map(toml_date),
+    );
+    insert(
+        "application_website",
+        text(&job_application.application_website),
+    );
+    insert(
+        "salary_min",
+        job_application.salary_min.map(TomlValue::Float),
+    );
+    insert(
+        "salary_max",
+        job_application.salary_max.map(TomlValue::Float),
+    );
+    insert("salary_currency", text(&job_application.salary_currency));
+    insert(
+        "pay_period",
+        job_application
+            .pay_period
+            .map(|pay_period| pay_period.to_string().into()),
+    );
+    insert("equity", text(&job_application.equity));
+    insert("bonus", text(&job_application.bonus));
+    insert("city", text(&job_application.city));
+    insert("region", text(&job_application.region));
+    insert("country", text(&job_application.country));
+    insert(
+        "work_arrangement",
+        job_application
+            .work_arrangement
+            .map(|work_arrangement| work_arrangement.to_string().into()),
+    );
+    insert(
+        "relocation",
+        job_application.relocation.map(TomlValue::Boolean),
+    );
+    insert(
+        "visa_sponsorship",
+        job_application.visa_sponsorship.map(TomlValue::Boolean),
+    );
+    insert(
+        "tags",
+        Some(tags).filter(|tags| !tags.is_empty()).map(|tags| {
+            tags.into_iter()
+                .map(TomlValue::String)
+                .collect::<Vec<_>>()
+                .into()
+        }),
+    );
+    insert("deleted_at", timestamp(deleted_at));
+    insert("created_at", timestamp(created_at));
+    insert("uuid", Some(uuid.into()));
+    insert(
+        "custom_fields",
+        Some(custom_fields)
+            .filter(|custom_fields| !custom_fields.is_empty())
+            .map(TomlValue::Table),
+    );
+
+    let front_matter =
+        toml::to_string(&fields).map_err(|error| RepositoryError::Backend(Box::new(error)))?;
+    Ok(match &job_application.notes {
+        Some(notes) => {
+            format!("{FRONT_MATTER_FENCE}\n{front_matter}{FRONT_MATTER_FENCE}\n\n{notes}\n")
+        }
+        None => format!("{FRONT_MATTER_FENCE}\n{front_matter}{FRONT_MATTER_FENCE}\n"),
+    })
+}
+
+/// A date as a TOML local date, like `2025-01-31`
+fn toml_date(date: Date) -> TomlValue {
+    TomlValue::Datetime(Datetime {
+        date: Some(TomlDate {
+            year: date.year() as u16,
+            month: date.month() as u8,
+            day: date.day(),
+        }),
+        time: None,
+        offset: None,
+    })
+}
+
+/// Read the custom field definitions file, which has the type of each field by name, like `recruiter = "text"`
+fn parse_custom_fields(contents: &str) -> Result<Vec<(String, CustomFieldType)>, String> {
+    contents
+        .parse::<Table>()
+        .map_err(|error| error.to_string().trim_end().to_owned())?
+        .into_iter()
+        .map(|(name, field_type)| {
+            let field_type = field_type
+                .as_str()
+                .ok_or_else(|| {
+                    format!("The type of {name} should be text, like \"text\" or \"select:a,b,c\"")
+                })?
+                .parse()
+                .map_err(|error| format!("{name}: {error}"))?;
+            Ok((name, field_type))
+        })
+        .collect()
+}
+
+/// Replace the custom fields in the index with the ones defined in the file, keeping the values of fields that are still there
+fn import_custom_fields(
+    tx: &Transaction,
+    fields: &[(String, CustomFieldType)],
+) -> Result<(), rusqlite::Error> {
+    for field in get_custom_fields(tx)? {
+        if !fields
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case(&field.name))
+        {
+            tx.execute("DELETE FROM custom_fields WHERE id = ?", (field.id,))?;
+        }
+    }
+    for (name, field_type) in fields {
+        tx.execute(
+            "INSERT INTO custom_fields (name, field_type) VALUES (?1, ?2) \
+            ON CONFLICT (name) DO UPDATE SET name = ?1, field_type = ?2",
+            (name, field_type.to_string()),
+        )?;
+    }
+    Ok(())
+}
+
+/// Every custom field in the index, sorted by name
+fn get_custom_fields(conn: &Connection) -> Result<Vec<CustomField>, rusqlite::Error> {
+    conn.prepare_cached("SELECT id, name, field_type FROM custom_fields ORDER BY name")?
+        .query_map((), |row| row.try_into())?
+        .collect()
+}
+
+/// Write the custom field definitions file, which is empty if there aren't any
+fn render_custom_fields(conn: &Connection) -> Result<String, RepositoryError> {
+    let fields: Table = get_custom_fields(conn)?
+        .into_iter()
+        .map(|field| (field.name, field.field_type.to_string().into()))
+        .collect();
+    toml::to_string(&fields).map_err(|error| RepositoryError::Backend(Box::new(error)))
+}
+
+/// The error for saving something that isn't kept in the files
+fn not_in_plain_text(what: &str) -> RepositoryError {
+    RepositoryError::Validation(format!(
+        "{what} aren't kept in plain-text storage. Import the job applications into a database to use them"
+    ))
+}
+
+impl JobApplicationRepository for PlainTextRepository {
+    fn get_job_applications(&mut self) -> Result<Vec<JobApplication>, RepositoryError> {
+        self.read(|index| index.get_job_applications())
+    }
+
+    fn get_job_application_by_id(
+        &mut self,
+        id: i32,
+    ) -> Result<Option<JobApplication>, RepositoryError> {
+        self.read(|index| index.get_job_application_by_id(id))
+    }
+
+    fn get_job_application_versions(&mut self) -> Result<BTreeMap<i32, i32>, RepositoryError> {
+        self.read(|index| index.get_job_application_versions())
+    }
+
+    fn data_version(&mut self) -> Result<Option<i64>, RepositoryError> {
+        // Files changed by hand are read in here, which changes the versions of their job applications
+        self.refresh()?;
+        Ok(None)
+    }
+
+    fn get_job_application_activity(&mut self) -> Result<BTreeMap<i32, Activity>, RepositoryError> {
+        self.read(|index| index.get_job_application_activity())
+    }
+
+    fn search_job_applications(
+        &mut self,
+        query: &str,
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
+        self.read(|index| index.search_job_applications(query))
+    }
+
+    fn full_text_search(
+        &mut self,
+        query: &str,
+    ) -> Result<Vec<FullTextSearchResult>, RepositoryError> {
+        self.read(|index| index.full_text_search(query))
+    }
+
+    fn search_by_human_response(
+        &mut self,
+        human_response: HumanResponse,
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
+        self.read(|index| index.search_by_human_response(human_response))
+    }
+
+    fn search_by_query_and_human_response(
+        &mut self,
+        query: &str,
+        human_response: HumanResponse,
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
+        self.read(|index| index.search_by_query_and_human_response(query, human_response))
+    }
+
+    fn query_job_applications(
+        &mut self,
+        query: &SearchQuery,
+    ) -> Result<Vec<JobApplication>, RepositoryError> {
+        self.read(|index| index.query_job_applications(query))
+    }
+
+    fn insert_job_application(
+        &mut self,
+        application: &NewJobApplication,
+    ) -> Result<JobApplication, RepositoryError> {
+        self.write(|index| index.insert_job_application(application))
+    }
+
+    fn update_human_response(
+        &mut self,
+        id: i32,
+        human_response: HumanResponse,
+        human_response_date: Option<Date>,
+    ) -> Result<JobApplication, RepositoryError> {
+        self.write(|index| index.update_human_response(id, human_response, human_response_date))
+    }
+
+    fn update_job_application(
+        &mut self,
+        application: &JobApplication,
+    ) -> Result<JobApplication, RepositoryError> {
+        self.write(|index| index.update_job_application(application))
+    }
+
+    fn update_job_application_partial(
+        &mut self,
+        partial_application: PartialJobApplication,
+    ) -> Result<JobApplication, RepositoryError> {
+        self.write(|index| index.update_job_application_partial(partial_application))
+    }
+
+    fn delete_job_application(&mut self, id: i32) -> Result<JobApplication, RepositoryError> {
+        self.write(|index| index.delete_job_application(id))
+    }
+
+    fn move_related_records(&mut self, from_id: i32, to_id: i32) -> Result<(), RepositoryError> {
+        self.write(|index| index.move_related_records(from_id, to_id))
+    }
+
+    fn with_transaction<T, E, F>(&mut self, transaction: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut Self) -> Result<T, E>,
+        E: From<RepositoryError>,
+    {
+        self.refresh()?;
+        self.index
+            .execute_batch("SAVEPOINT plain_text_transaction")
+            .map_err(RepositoryError::from)?;
+        self.transaction_depth += 1;
+        let result = transaction(self);
+        self.transaction_depth -= 1;
+        match result {
+            Ok(result) => {
+                self.index
+                    .execute_batch("RELEASE plain_text_transaction")
+                    .map_err(RepositoryError::from)?;
+                self.write_files()?;
+                Ok(result)
+            }
+            Err(error) => {
+                self.index
+                    .execute_batch(
+                        "ROLLBACK TO plain_text_transaction; RELEASE plain_text_transaction",
+                    )
+                    .map_err(RepositoryError::from)?;
+                Err(error)
+            }
+        }
+    }
+
+    fn get_deleted_job_applications(
+        &mut self,
+    ) -> Result<Vec<DeletedJobApplication>, RepositoryError> {
+        self.read(|index| index.get_deleted_job_applications())
+    }
+
+    fn restore_job_application(&mut self, id: i32) -> Result<(), RepositoryError> {
+        self.write(|index| index.restore_job_application(id))
+    }
+
+    fn empty_trash(&mut self) -> Result<usize, RepositoryError> {
+        self.write(|index| index.empty_trash())
+    }
+
+    fn purge_trash(&mut self, days: u32) -> Result<usize, RepositoryError> {
+        self.write(|index| index.purge_trash(days))
+    }
+
+    fn undo(&mut self) -> Result<Option<JournalEntry>, RepositoryError> {
+        self.write(|index| index.undo())
+    }
+
+    fn redo(&mut self) -> Result<Option<JournalEntry>, RepositoryError> {
+        self.write(|index| index.redo())
+    }
+
+    fn get_history(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Vec<AuditLogEntry>, RepositoryError> {
+        self.read(|index| index.get_history(job_application_id))
+    }
+
+    fn get_tags(&mut self) -> Result<Vec<Tag>, RepositoryError> {
+        self.read(|index| index.get_tags())
+    }
+
+    fn get_job_application_tags(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Vec<Tag>, RepositoryError> {
+        self.read(|index| index.get_job_application_tags(job_application_id))
+    }
+
+    fn add_tag(&mut self, job_application_id: i32, name: &str) -> Result<Tag, RepositoryError> {
+        self.write(|index| index.add_tag(job_application_id, name))
+    }
+
+    fn remove_tag(&mut self, job_application_id: i32, name: &str) -> Result<(), RepositoryError> {
+        self.write(|index| index.remove_tag(job_application_id, name))
+    }
+
+    fn rename_tag(&mut self, name: &str, new_name: &str) -> Result<(), RepositoryError> {
+        self.write(|index| index.rename_tag(name, new_name))
+    }
+
+    fn get_custom_fields(&mut self) -> Result<Vec<CustomField>, RepositoryError> {
+        self.read(|index| index.get_custom_fields())
+    }
+
+    fn add_custom_field(
+        &mut self,
+        name: &str,
+        field_type: &CustomFieldType,
+    ) -> Result<CustomField, RepositoryError> {
+        self.write(|index| index.add_custom_field(name, field_type))
+    }
+
+    fn delete_custom_field(&mut self, name: &str) -> Result<(), RepositoryError> {
+        self.write(|index| index.delete_custom_field(name))
+    }
+
+    fn set_custom_field_value(
+        &mut self,
+        job_application_id: i32,
+        name: &str,
+        value: Option<&CustomFieldValue>,
+    ) -> Result<(), RepositoryError> {
+        self.write(|index| index.set_custom_field_value(job_application_id, name, value))
+    }
+
+    fn get_offer(&mut self, job_application_id: i32) -> Result<Option<Offer>, RepositoryError> {
+        self.read(|index| index.get_offer(job_application_id))
+    }
+
+    fn get_offers(&mut self) -> Result<Vec<Offer>, RepositoryError> {
+        self.read(|index| index.get_offers())
+    }
+
+    fn save_offer(&mut self, _offer: &Offer) -> Result<(), RepositoryError> {
+        Err(not_in_plain_text("Offers"))
+    }
+
+    fn delete_offer(&mut self, _job_application_id: i32) -> Result<(), RepositoryError> {
+        Err(not_in_plain_text("Offers"))
+    }
+
+    fn get_work_arrangement_stats(&mut self) -> Result<Vec<WorkArrangementStats>, RepositoryError> {
+        self.read(|index| index.get_work_arrangement_stats())
+    }
+
+    fn get_job_description(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Option<JobDescription>, RepositoryError> {
+        self.read(|index| index.get_job_description(job_application_id))
+    }
+
+    fn save_job_description(
+        &mut self,
+        _job_description: &JobDescription,
+    ) -> Result<(), RepositoryError> {
+        Err(not_in_plain_text("Job descriptions"))
+    }
+
+    fn delete_job_description(&mut self, _job_application_id: i32) -> Result<(), RepositoryError> {
+        Err(not_in_plain_text("Job descriptions"))
+    }
+
+    fn get_attachments(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Vec<Attachment>, RepositoryError> {
+        self.read(|index| index.get_attachments(job_application_id))
+    }
+
+    fn get_attachment(&mut self, id: i32) -> Result<Option<Attachment>, RepositoryError> {
+        self.read(|index| index.get_attachment(id))
+    }
+
+    fn add_attachment(
+        &mut self,
+        _job_application_id: i32,
+        _role: AttachmentRole,
+        _file_name: &str,
+        _contents: &[u8],
+        _added_date: Date,
+    ) -> Result<Attachment, RepositoryError> {
+        Err(not_in_plain_text("Attachments"))
+    }
+
+    fn get_attachment_contents(&mut self, id: i32) -> Result<Option<Vec<u8>>, RepositoryError> {
+        self.read(|index| index.get_attachment_contents(id))
+    }
+
+    fn delete_attachment(&mut self, _id: i32) -> Result<(), RepositoryError> {
+        Err(not_in_plain_text("Attachments"))
+    }
+
+    fn get_resume_versions(&mut self) -> Result<Vec<ResumeVersion>, RepositoryError> {
+        self.read(|index| index.get_resume_versions())
+    }
+
+    fn add_resume_version(
+        &mut self,
+        _label: &str,
+        _file_hash: Option<&str>,
+        _created_date: Date,
+        _notes: Option<&str>,
+    ) -> Result<ResumeVersion, RepositoryError> {
+        Err(not_in_plain_text("Resume versions"))
+    }
+
+    fn delete_resume_version(&mut self, _id: i32) -> Result<(), RepositoryError> {
+        Err(not_in_plain_text("Resume versions"))
+    }
+
+    fn get_sent_resume_version(
+        &mut self,
+        job_application_id: i32,
+    ) -> Result<Option<ResumeVersion>, RepositoryError> {
+        self.read(|index| index.get_sent_resume_version(job_application_id))
+    }
+
+    fn set_sent_resume_version(
+        &mut self,
+        _job_application_id: i32,
+        _resume_version_id: Option<i32>,
+    ) -> Result<(), RepositoryError> {
+        Err(not_in_plain_text("Resume versions"))
+    }
+
+    fn get_resume_version_stats(&mut self) -> Result<Vec<ResumeVersionStats>, RepositoryError> {
+        self.read(|index| index.get_resume_version_stats())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_job_application_file() {
+        let custom_fields = [CustomField {
+            id: 1,
+            name: "Req ID".to_owned(),
+            field_type: CustomFieldType::Number,
+        }];
+        let file = parse_job_application_file(
+            "+++\ncompany = \"Acme\"\njob_title = \"Engineer\"\nsource = \"LinkedIn\"\napplication_date = 2025-01-02\ntime_investment = \"12:05\"\nhuman_response = \"Interviewed then rejected\"\npay_period = \"y\"\n\n[custom_fields]\n\"req id\" = 42\n+++\n\nNotes\n",
+            &custom_fields,
+        )
+        .unwrap();
+        assert_eq!(file.uuid, None);
+        assert_eq!(file.job_application.application_date, date!(2025 - 01 - 02));
+        assert_eq!(
+            file.job_application.time_investment,
+            Some(Duration::seconds(725))
+        );
+        assert_eq!(
+            file.job_application.human_response,
+            HumanResponse::InterviewedThenRejected
+        );
+        assert_eq!(file.job_application.notes.as_deref(), Some("Notes"));
+        assert_eq!(
+            file.job_application.custom_fields,
+            CustomFields::from([("Req ID".to_owned(), CustomFieldValue::Number(42.0))])
+        );
+
+        for (contents, error) in [
+            ("company = \"Acme\"\n", "This should start with a +++ line"),
+            ("+++\ncompany = \"Acme\"\n", "The fields should end with a +++ line"),
+            ("+++\ncompany = \"Acme\"\n+++\n", "source is missing"),
+            (
+                "+++\ncompany = \"Acme\"\njob_title = \"Engineer\"\nsource = \"LinkedIn\"\napplication_date = 2025-01-02\ncolor = \"blue\"\n+++\n",
+                "Unknown field color",
+            ),
+            (
+                "+++\ncompany = \"Acme\"\njob_title = \"Engineer\"\nsource = \"LinkedIn\"\napplication_date = 2025-01-02\n[custom_fields]\nTeam = \"Platform\"\n+++\n",
+                "There is no custom field named Team",
+            ),
+            (
+                "+++\n<<<<<<< HEAD\ncompany = \"Acme\"\n=======\ncompany = \"Acme Corp\"\n>>>>>>> theirs\n+++\n",
+                "This has merge conflict markers",
+            ),
+        ] {
+            match parse_job_application_file(contents, &custom_fields) {
+                Err(message) => assert!(
+                    message.starts_with(error),
+                    "Expected an error starting with {error:?}, got {message:?}"
+                ),
+                Ok(_) => panic!("Expected {contents:?} to be an error"),
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+-- Full-text index over every text field of job_applications
+-- `remove_diacritics 2` and the unicode61 tokenizer's case folding make matching accent and case insensitive for all of Unicode, not just ASCII
+CREATE VIRTUAL TABLE job_applications_fts USING fts5(
+    source,
+    company,
+    job_title,
+    application_website,
+    notes,
+    content = 'job_applications',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- Triggers to keep the index in sync with job_applications
+CREATE TRIGGER job_applications_fts_insert AFTER INSERT ON job_applications BEGIN
+    INSERT INTO job_applications_fts (rowid, source, company, job_title, application_website, notes)
+    VALUES (new.id, new.source, new.company, new.job_title, new.application_website, new.notes);
+END;
+
+CREATE TRIGGER job_applications_fts_delete AFTER DELETE ON job_applications BEGIN
+    INSERT INTO job_applications_fts (job_applications_fts, rowid, source, company, job_title, application_website, notes)
+    VALUES ('delete', old.id, old.source, old.company, old.job_title, old.application_website, old.notes);
+END;
+
+CREATE TRIGGER job_applications_fts_update AFTER UPDATE ON job_applications BEGIN
+    INSERT INTO job_applications_fts (job_applications_fts, rowid, source, company, job_title, application_website, notes)
+    VALUES ('delete', old.id, old.source, old.company, old.job_title, old.application_website, old.notes);
+    INSERT INTO job_applications_fts (rowid, source, company, job_title, application_website, notes)
+    VALUES (new.id, new.source, new.company, new.job_title, new.application_website, new.notes);
+END;
+
+-- Index any rows that existed before this migration
+INSERT INTO job_applications_fts (job_applications_fts) VALUES ('rebuild');
@@ -0,0 +1,15 @@
+-- Tags that can be put on any number of job applications
+-- NOCASE makes "Remote" and "remote" the same tag
+CREATE TABLE tags (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE
+);
+
+-- Join table between job_applications and tags
+CREATE TABLE job_application_tags (
+    job_application_id INTEGER NOT NULL REFERENCES job_applications (id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES tags (id) ON DELETE CASCADE,
+    PRIMARY KEY (job_application_id, tag_id)
+);
+
+CREATE INDEX job_application_tags_tag_id ON job_application_tags (tag_id);
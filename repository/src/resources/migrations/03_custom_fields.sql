@@ -0,0 +1,17 @@
+-- Fields defined by the user
+-- field_type is written the same way `CustomFieldType` is displayed, e.g. `number` or `select:Recruiter,Direct`
+CREATE TABLE custom_fields (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    field_type TEXT NOT NULL
+);
+
+-- The value of each custom field on each job application, written as text
+CREATE TABLE custom_field_values (
+    job_application_id INTEGER NOT NULL REFERENCES job_applications (id) ON DELETE CASCADE,
+    custom_field_id INTEGER NOT NULL REFERENCES custom_fields (id) ON DELETE CASCADE,
+    value TEXT NOT NULL,
+    PRIMARY KEY (job_application_id, custom_field_id)
+);
+
+CREATE INDEX custom_field_values_custom_field_id ON custom_field_values (custom_field_id);
@@ -0,0 +1,21 @@
+-- Posted compensation. pay_period uses the first letter of hourly, daily, weekly, monthly, or yearly.
+ALTER TABLE job_applications ADD COLUMN salary_min REAL;
+ALTER TABLE job_applications ADD COLUMN salary_max REAL;
+ALTER TABLE job_applications ADD COLUMN salary_currency TEXT;
+ALTER TABLE job_applications ADD COLUMN pay_period TEXT CHECK(pay_period IN ('H','D','W','M','Y'));
+ALTER TABLE job_applications ADD COLUMN equity TEXT;
+ALTER TABLE job_applications ADD COLUMN bonus TEXT;
+
+-- Job offers, at most one per job application
+CREATE TABLE offers (
+    job_application_id INTEGER PRIMARY KEY REFERENCES job_applications (id) ON DELETE CASCADE,
+    base_salary REAL NOT NULL,
+    pay_period TEXT CHECK(pay_period IN ('H','D','W','M','Y')) NOT NULL DEFAULT 'Y',
+    currency TEXT,
+    bonus REAL,
+    equity_value REAL,
+    vesting_years REAL,
+    benefits TEXT,
+    start_date TEXT,
+    deadline TEXT
+);
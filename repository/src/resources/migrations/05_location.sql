@@ -0,0 +1,8 @@
+-- Where the job is. work_arrangement uses the first letter of remote, hybrid, or onsite.
+ALTER TABLE job_applications ADD COLUMN city TEXT;
+ALTER TABLE job_applications ADD COLUMN region TEXT;
+ALTER TABLE job_applications ADD COLUMN country TEXT;
+ALTER TABLE job_applications ADD COLUMN work_arrangement TEXT CHECK(work_arrangement IN ('R','H','O'));
+-- Whether the company helps with relocation or sponsors visas. NULL means unknown.
+ALTER TABLE job_applications ADD COLUMN relocation INTEGER CHECK(relocation IN (0, 1));
+ALTER TABLE job_applications ADD COLUMN visa_sponsorship INTEGER CHECK(visa_sponsorship IN (0, 1));
@@ -0,0 +1,21 @@
+-- Descriptions saved from job postings, at most one per job application
+-- description is zlib compressed UTF-8, since postings can be long
+CREATE TABLE job_descriptions (
+    job_application_id INTEGER PRIMARY KEY REFERENCES job_applications (id) ON DELETE CASCADE,
+    description BLOB NOT NULL,
+    captured_date TEXT NOT NULL
+);
+
+-- Full-text index of the descriptions, keyed by job application id
+-- The descriptions are compressed, so the repository writes the uncompressed text here itself instead of using triggers like job_applications_fts
+CREATE VIRTUAL TABLE job_descriptions_fts USING fts5(
+    description,
+    content = '',
+    contentless_delete = 1,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- Deleting a job application deletes its description, so this also keeps the index in sync with deletes
+CREATE TRIGGER job_descriptions_fts_delete AFTER DELETE ON job_descriptions BEGIN
+    DELETE FROM job_descriptions_fts WHERE rowid = old.job_application_id;
+END;
@@ -0,0 +1,25 @@
+-- Files attached to job applications, like resumes and cover letters
+-- Files are stored once, keyed by the SHA-256 hash of their contents, so attaching the same resume to many job applications only stores it once
+CREATE TABLE attachment_files (
+    hash TEXT PRIMARY KEY,
+    contents BLOB NOT NULL,
+    size INTEGER NOT NULL
+);
+
+-- role is R (resume), C (cover letter), L (offer letter), T (take-home), or O (other)
+CREATE TABLE attachments (
+    id INTEGER PRIMARY KEY,
+    job_application_id INTEGER NOT NULL REFERENCES job_applications (id) ON DELETE CASCADE,
+    file_hash TEXT NOT NULL REFERENCES attachment_files (hash),
+    file_name TEXT NOT NULL,
+    role TEXT NOT NULL CHECK(role IN ('R','C','L','T','O')),
+    added_date TEXT NOT NULL,
+    UNIQUE (job_application_id, file_hash, role)
+);
+
+-- Delete a file once nothing is attached to it. This also runs when deleting a job application deletes its attachments.
+CREATE TRIGGER attachments_delete_unused_files AFTER DELETE ON attachments
+WHEN NOT EXISTS (SELECT 1 FROM attachments WHERE file_hash = old.file_hash)
+BEGIN
+    DELETE FROM attachment_files WHERE hash = old.file_hash;
+END;
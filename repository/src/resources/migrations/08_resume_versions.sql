@@ -0,0 +1,17 @@
+-- Versions of the user's resume. NOCASE makes "V2" and "v2" the same label.
+-- file_hash is the SHA-256 hash of the resume file, hashed the same way as attachment_files
+CREATE TABLE resume_versions (
+    id INTEGER PRIMARY KEY,
+    label TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    file_hash TEXT,
+    created_date TEXT NOT NULL,
+    notes TEXT
+);
+
+-- Which resume version was sent with each job application, at most one per job application
+CREATE TABLE sent_resume_versions (
+    job_application_id INTEGER PRIMARY KEY REFERENCES job_applications (id) ON DELETE CASCADE,
+    resume_version_id INTEGER NOT NULL REFERENCES resume_versions (id) ON DELETE CASCADE
+);
+
+CREATE INDEX sent_resume_versions_resume_version_id ON sent_resume_versions (resume_version_id);
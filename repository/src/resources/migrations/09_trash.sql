@@ -0,0 +1,4 @@
+-- When each job application was moved to the trash, in UTC. NULL for job applications that are not in the trash.
+ALTER TABLE job_applications ADD COLUMN deleted_at TEXT;
+
+CREATE INDEX job_applications_deleted_at ON job_applications (deleted_at);
@@ -0,0 +1,48 @@
+-- Changes to job applications, newest last, so they can be undone and redone
+-- operation is I (insert), U (update), D (delete), or R (restore). Undone entries are the ones that can be redone.
+CREATE TABLE journal_entries (
+    id INTEGER PRIMARY KEY,
+    job_application_id INTEGER NOT NULL REFERENCES job_applications (id) ON DELETE CASCADE,
+    operation TEXT NOT NULL CHECK(operation IN ('I','U','D','R')),
+    undone INTEGER NOT NULL DEFAULT 0 CHECK(undone IN (0, 1))
+);
+
+-- The job application before (is_after = 0) and after (is_after = 1) each update, with the same columns as job_applications
+CREATE TABLE journal_snapshots (
+    journal_entry_id INTEGER NOT NULL REFERENCES journal_entries (id) ON DELETE CASCADE,
+    is_after INTEGER NOT NULL CHECK(is_after IN (0, 1)),
+    source TEXT NOT NULL,
+    company TEXT NOT NULL,
+    job_title TEXT NOT NULL,
+    application_date TEXT NOT NULL,
+    time_investment INTEGER,
+    human_response TEXT NOT NULL,
+    human_response_date TEXT,
+    application_website TEXT,
+    notes TEXT,
+    salary_min REAL,
+    salary_max REAL,
+    salary_currency TEXT,
+    pay_period TEXT,
+    equity TEXT,
+    bonus TEXT,
+    city TEXT,
+    region TEXT,
+    country TEXT,
+    work_arrangement TEXT,
+    relocation INTEGER,
+    visa_sponsorship INTEGER,
+    PRIMARY KEY (journal_entry_id, is_after)
+);
+
+-- The custom field values that go with each snapshot
+CREATE TABLE journal_custom_field_values (
+    journal_entry_id INTEGER NOT NULL REFERENCES journal_entries (id) ON DELETE CASCADE,
+    is_after INTEGER NOT NULL CHECK(is_after IN (0, 1)),
+    custom_field_id INTEGER NOT NULL REFERENCES custom_fields (id) ON DELETE CASCADE,
+    value TEXT NOT NULL,
+    PRIMARY KEY (journal_entry_id, is_after, custom_field_id)
+);
+
+CREATE INDEX journal_entries_job_application_id ON journal_entries (job_application_id);
+CREATE INDEX journal_custom_field_values_custom_field_id ON journal_custom_field_values (custom_field_id);
@@ -0,0 +1,2 @@
+-- How many times each job application has been changed, so saving over a newer change can be refused
+ALTER TABLE job_applications ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,7 @@
+-- When each job application was first saved and last changed, in UTC
+-- The repository sets these, since SQLite can't add a column that defaults to the current time.
+-- Job applications saved before these were added use their application date.
+ALTER TABLE job_applications ADD COLUMN created_at TEXT;
+ALTER TABLE job_applications ADD COLUMN updated_at TEXT;
+
+UPDATE job_applications SET created_at = application_date || ' 00:00:00', updated_at = application_date || ' 00:00:00';
@@ -0,0 +1,109 @@
+-- A random UUID for each job application, which stays the same in every database it is synced to, unlike its id
+ALTER TABLE job_applications ADD COLUMN uuid TEXT;
+UPDATE job_applications SET uuid = lower(hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-' || substr('89ab', abs(random()) % 4 + 1, 1) || substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6)));
+CREATE UNIQUE INDEX job_applications_uuid ON job_applications (uuid);
+
+-- The full-text index only needs updating when an indexed column changes.
+-- Setting the UUID below happens before the new row is indexed, so updating the index then would corrupt it.
+DROP TRIGGER job_applications_fts_update;
+CREATE TRIGGER job_applications_fts_update AFTER UPDATE OF source, company, job_title, application_website, notes ON job_applications BEGIN
+    INSERT INTO job_applications_fts (job_applications_fts, rowid, source, company, job_title, application_website, notes)
+    VALUES ('delete', old.id, old.source, old.company, old.job_title, old.application_website, old.notes);
+    INSERT INTO job_applications_fts (rowid, source, company, job_title, application_website, notes)
+    VALUES (new.id, new.source, new.company, new.job_title, new.application_website, new.notes);
+END;
+
+-- New job applications get a UUID as soon as they are saved, unless they already have one from being synced
+CREATE TRIGGER job_applications_uuid AFTER INSERT ON job_applications WHEN NEW.uuid IS NULL BEGIN
+    UPDATE job_applications SET uuid = lower(hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-' || substr('89ab', abs(random()) % 4 + 1, 1) || substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6))) WHERE id = NEW.id;
+END;
+
+-- This database's own UUID, so the databases it syncs with can tell it apart from each other
+CREATE TABLE sync_identity (
+    uuid TEXT NOT NULL
+);
+INSERT INTO sync_identity (uuid) VALUES (lower(hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-' || substr('89ab', abs(random()) % 4 + 1, 1) || substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6))));
+
+-- When this database last synced with each other database, by their UUID, in UTC
+CREATE TABLE sync_peers (
+    uuid TEXT PRIMARY KEY,
+    last_synced_at TEXT NOT NULL
+);
+
+-- When each field of a job application was last changed, in UTC with milliseconds, so syncing can keep the newest value of each field
+-- Fields that haven't changed since the job application was created don't have a row
+CREATE TABLE sync_field_clocks (
+    job_application_uuid TEXT NOT NULL,
+    field TEXT NOT NULL,
+    changed_at TEXT NOT NULL,
+    PRIMARY KEY (job_application_uuid, field)
+);
+
+-- Job applications that were permanently deleted, so syncing deletes them everywhere instead of bringing them back
+CREATE TABLE sync_tombstones (
+    job_application_uuid TEXT PRIMARY KEY,
+    deleted_at TEXT NOT NULL
+);
+
+CREATE TRIGGER job_applications_field_clocks AFTER UPDATE ON job_applications BEGIN
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'source', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.source IS NOT NEW.source;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'company', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.company IS NOT NEW.company;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'job_title', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.job_title IS NOT NEW.job_title;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'application_date', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.application_date IS NOT NEW.application_date;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'time_investment', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.time_investment IS NOT NEW.time_investment;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'human_response', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.human_response IS NOT NEW.human_response;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'human_response_date', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.human_response_date IS NOT NEW.human_response_date;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'application_website', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.application_website IS NOT NEW.application_website;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'notes', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.notes IS NOT NEW.notes;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'salary_min', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.salary_min IS NOT NEW.salary_min;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'salary_max', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.salary_max IS NOT NEW.salary_max;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'salary_currency', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.salary_currency IS NOT NEW.salary_currency;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'pay_period', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.pay_period IS NOT NEW.pay_period;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'equity', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.equity IS NOT NEW.equity;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'bonus', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.bonus IS NOT NEW.bonus;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'city', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.city IS NOT NEW.city;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'region', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.region IS NOT NEW.region;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'country', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.country IS NOT NEW.country;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'work_arrangement', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.work_arrangement IS NOT NEW.work_arrangement;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'relocation', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.relocation IS NOT NEW.relocation;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'visa_sponsorship', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.visa_sponsorship IS NOT NEW.visa_sponsorship;
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT NEW.uuid, 'deleted_at', strftime('%Y-%m-%d %H:%M:%f', 'now') WHERE OLD.deleted_at IS NOT NEW.deleted_at;
+END;
+
+-- Tags are synced as one field, the whole set of tags on a job application
+CREATE TRIGGER job_application_tags_insert_clock AFTER INSERT ON job_application_tags BEGIN
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT uuid, 'tags', strftime('%Y-%m-%d %H:%M:%f', 'now') FROM job_applications WHERE id = NEW.job_application_id;
+END;
+
+CREATE TRIGGER job_application_tags_delete_clock AFTER DELETE ON job_application_tags BEGIN
+    INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at)
+    SELECT uuid, 'tags', strftime('%Y-%m-%d %H:%M:%f', 'now') FROM job_applications WHERE id = OLD.job_application_id;
+END;
+
+CREATE TRIGGER job_applications_tombstone AFTER DELETE ON job_applications WHEN OLD.uuid IS NOT NULL BEGIN
+    INSERT OR REPLACE INTO sync_tombstones (job_application_uuid, deleted_at) VALUES (OLD.uuid, strftime('%Y-%m-%d %H:%M:%f', 'now'));
+    DELETE FROM sync_field_clocks WHERE job_application_uuid = OLD.uuid;
+END;
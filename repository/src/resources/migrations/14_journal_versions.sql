@@ -0,0 +1,8 @@
+-- The version of the job application before and after each change in the journal, kept up to date as changes are undone and redone,
+-- so a change can't be undone or redone once the job application was changed in a way that isn't in the journal, like by syncing
+ALTER TABLE journal_entries ADD COLUMN version_before INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE journal_entries ADD COLUMN version_after INTEGER NOT NULL DEFAULT 1;
+-- Changes from before these were added can be undone as long as the job application isn't changed again some other way
+UPDATE journal_entries SET (version_before, version_after) = (
+    SELECT version, version FROM job_applications WHERE id = journal_entries.job_application_id
+);
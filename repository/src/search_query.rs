@@ -0,0 +1,960 @@
+use std::{error::Error, fmt::Display, str::FromStr};
+
+use time::{macros::format_description, Date};
+
+use crate::{
+    job_application_model::{HumanResponse, WorkArrangement},
+    job_application_repository::last_activity,
+};
+
+/// A parsed search query, such as `company:acme status:rejected applied:>=2025-01-01 "backend engineer" -notes:contract`
+///
+/// A job application matches the query if it matches every filter. An empty query matches everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    pub filters: Vec<SearchFilter>,
+}
+
+/// One space-separated part of a [SearchQuery]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchFilter {
+    /// `true` if the filter was prefixed with `-`, meaning job applications matching `condition` should be excluded
+    pub negated: bool,
+    pub condition: SearchCondition,
+}
+
+/// What a [SearchFilter] checks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchCondition {
+    /// A full-text query (a word, `prefix*`, or `"quoted phrase"`) that must match any text field
+    FullText(String),
+    /// Words or `"quoted phrases"` that a specific text field must each contain (ignoring case), e.g. `company:acme`
+    Field(TextField, String),
+    /// The human response must be this, e.g. `status:rejected`
+    HumanResponse(HumanResponse),
+    /// A date must compare to the given date, e.g. `applied:>=2025-01-01`
+    ///
+    /// Job applications where the date is not set never match.
+    Date(DateField, Comparison, Date),
+    /// The job application must have a tag with this name (ignoring case), e.g. `tag:remote` or `tag:"dream job"`
+    Tag(String),
+    /// A user-defined custom field must compare to a value, e.g. `field.agency:hays` or `field."req id":>=1000`
+    ///
+    /// The field's name and value are checked against its type when the query is run, since that needs the database.
+    CustomField(String, Comparison, String),
+    /// A location field must contain this text (ignoring case), e.g. `city:berlin` or `location:"new york"`
+    ///
+    /// `None` means any of the city, region, or country may contain it.
+    Location(Option<LocationField>, String),
+    /// The work arrangement must be this, e.g. `arrangement:remote`
+    WorkArrangement(WorkArrangement),
+    /// A yes/no field must be set to this, e.g. `visa:yes`
+    ///
+    /// Job applications where the field is not set never match.
+    Bool(BoolField, bool),
+}
+
+/// Text fields that can be searched individually
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextField {
+    Source,
+    Company,
+    JobTitle,
+    ApplicationWebsite,
+    Notes,
+}
+
+impl TextField {
+    /// The name of the column that holds this field
+    pub(crate) fn column_name(self) -> &'static str {
+        match self {
+            TextField::Source => "source",
+            TextField::Company => "company",
+            TextField::JobTitle => "job_title",
+            TextField::ApplicationWebsite => "application_website",
+            TextField::Notes => "notes",
+        }
+    }
+}
+
+/// Date fields that can be compared
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateField {
+    /// `application_date`
+    Applied,
+    /// `human_response_date`
+    Responded,
+    /// The date the job application was first saved
+    Created,
+    /// The date the job application was last changed
+    Updated,
+    /// The latest date anything happened with the job application, as in [Activity::last_activity](crate::job_application_model::Activity::last_activity)
+    LastActivity,
+}
+
+impl DateField {
+    /// SQL for this field, given the function that takes the largest of its arguments (`MAX` in SQLite and `GREATEST` in MySQL)
+    pub(crate) fn expression(self, greatest: &str) -> String {
+        match self {
+            DateField::Applied => "application_date".to_owned(),
+            DateField::Responded => "human_response_date".to_owned(),
+            // These are stored as times, so only compare the date
+            DateField::Created => "DATE(created_at)".to_owned(),
+            DateField::Updated => "DATE(updated_at)".to_owned(),
+            DateField::LastActivity => last_activity(greatest),
+        }
+    }
+}
+
+/// Location fields that can be searched individually
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocationField {
+    City,
+    Region,
+    Country,
+}
+
+impl LocationField {
+    /// Every location field, in the order they are written
+    pub const ALL: [LocationField; 3] = [Self::City, Self::Region, Self::Country];
+
+    /// The name of the column that holds this field
+    pub(crate) fn column_name(self) -> &'static str {
+        match self {
+            LocationField::City => "city",
+            LocationField::Region => "region",
+            LocationField::Country => "country",
+        }
+    }
+}
+
+/// Yes/no fields that can be filtered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoolField {
+    /// `relocation`
+    Relocation,
+    /// `visa_sponsorship`
+    VisaSponsorship,
+}
+
+impl BoolField {
+    /// The name of the column that holds this field
+    pub(crate) fn column_name(self) -> &'static str {
+        match self {
+            BoolField::Relocation => "relocation",
+            BoolField::VisaSponsorship => "visa_sponsorship",
+        }
+    }
+}
+
+/// How to compare a date in a [SearchCondition::Date] or a value in a [SearchCondition::CustomField]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    /// The SQL operator for this comparison
+    pub(crate) fn operator(self) -> &'static str {
+        match self {
+            Comparison::Equal => "=",
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+        }
+    }
+}
+
+/// Filter names recognized before a `:`, listed in error messages
+const FILTER_NAMES: &str =
+    "source, company, title, website, notes, status, applied, responded, created, updated, active, tag, location, city, region, country, arrangement, relocation, visa, field.<custom field name>";
+
+/// Written before the name of a custom field to filter by it, as in `field.agency:hays`
+const CUSTOM_FIELD_PREFIX: &str = "field.";
+
+/// An error produced when a search query is malformed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSearchQueryError {
+    /// The position of the problem in the query, counted in characters from 1
+    pub position: usize,
+    pub kind: ParseSearchQueryErrorKind,
+}
+
+/// The specific problem with a malformed search query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseSearchQueryErrorKind {
+    /// A `"` was never closed
+    UnterminatedQuote,
+    /// A `-` was not followed by anything to exclude
+    NothingToNegate,
+    /// The name before a `:` is not a known filter
+    UnknownFilter(String),
+    /// Nothing came after a `filter:`
+    MissingValue(String),
+    /// Nothing came after a `field.`
+    MissingCustomFieldName,
+    /// The value of `status:` is not a human response
+    InvalidHumanResponse(String),
+    /// The value of a date filter is not a valid date
+    InvalidDate(String),
+    /// The value of `arrangement:` is not a work arrangement
+    InvalidWorkArrangement(String),
+    /// The value of `relocation:` or `visa:` is not yes or no
+    InvalidYesNo(String),
+}
+
+impl Display for ParseSearchQueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let position = self.position;
+        match &self.kind {
+            ParseSearchQueryErrorKind::UnterminatedQuote => write!(
+                f,
+                "The quote at position {position} is never closed. Add a \" after the quoted text"
+            ),
+            ParseSearchQueryErrorKind::NothingToNegate => write!(
+                f,
+                "The - at position {position} must be directly followed by the text or filter to exclude (e.g. -notes:contract)"
+            ),
+            ParseSearchQueryErrorKind::UnknownFilter(name) => write!(
+                f,
+                "Unknown filter '{name}' at position {position}. Valid filters are: {FILTER_NAMES}. \
+                To search for text containing a colon, put it in double quotes"
+            ),
+            ParseSearchQueryErrorKind::MissingValue(name) => write!(
+                f,
+                "The filter '{name}' at position {position} has no value. Put the value directly after the colon (e.g. {name}:value)"
+            ),
+            ParseSearchQueryErrorKind::MissingCustomFieldName => write!(
+                f,
+                "The custom field filter at position {position} has no field name. \
+                Put the name directly after the dot, in double quotes if it has spaces (e.g. field.\"req id\":1234)"
+            ),
+            ParseSearchQueryErrorKind::InvalidHumanResponse(value) => write!(
+                f,
+                "Unknown status '{value}' at position {position}. \
+                Use one of: none, rejected, interview, interviewed-then-rejected, offer"
+            ),
+            ParseSearchQueryErrorKind::InvalidDate(value) => write!(
+                f,
+                "Invalid date '{value}' at position {position}. \
+                Use yyyy-mm-dd or mm/dd/yyyy, optionally after one of =, <, <=, >, >= (e.g. applied:>=2025-01-01)"
+            ),
+            ParseSearchQueryErrorKind::InvalidWorkArrangement(value) => write!(
+                f,
+                "Unknown work arrangement '{value}' at position {position}. Use one of: remote, hybrid, onsite"
+            ),
+            ParseSearchQueryErrorKind::InvalidYesNo(value) => write!(
+                f,
+                "Expected yes or no but got '{value}' at position {position}"
+            ),
+        }
+    }
+}
+
+impl Error for ParseSearchQueryError {}
+
+impl FromStr for SearchQuery {
+    type Err = ParseSearchQueryError;
+
+    /// Parse a search query
+    ///
+    /// The query is made of space-separated filters, each of which may be prefixed by `-` to exclude matches:
+    /// - `word`, `prefix*`, or `"a phrase"`: full-text search of all text fields
+    /// - `source:`, `company:`, `title:`, `website:`, or `notes:` followed by a word or phrase the field must contain
+    /// - `status:` followed by `none`, `rejected`, `interview`, `interviewed-then-rejected`, or `offer`
+    /// - `applied:`, `responded:`, `created:`, `updated:`, or `active:` (the last activity) followed by an optional comparison
+    ///   (`=`, `<`, `<=`, `>`, `>=`) and a date
+    /// - `tag:` followed by a tag name, quoted if it contains spaces
+    /// - `city:`, `region:`, `country:`, or `location:` (any of the three) followed by text the field must contain
+    /// - `arrangement:` followed by `remote`, `hybrid`, or `onsite`
+    /// - `relocation:` or `visa:` followed by `yes` or `no`
+    /// - `field.` followed by a custom field name (quoted if it contains spaces), `:`, an optional comparison, and a value
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            query: s,
+            position: 0,
+        };
+        let mut filters = Vec::new();
+
+        while let Some(filter) = parser.next_filter()? {
+            filters.push(filter);
+        }
+
+        Ok(SearchQuery { filters })
+    }
+}
+
+impl SearchQuery {
+    /// Returns `true` if there are no filters, meaning every job application matches
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+}
+
+/// Cursor over the query being parsed
+struct Parser<'a> {
+    query: &'a str,
+    /// Byte offset of the next unparsed character
+    position: usize,
+}
+
+impl Parser<'_> {
+    /// The part of the query that hasn't been parsed yet
+    fn rest(&self) -> &str {
+        &self.query[self.position..]
+    }
+
+    /// Build an error at a byte offset, converting the offset to a character position for users
+    fn error_at(
+        &self,
+        byte_offset: usize,
+        kind: ParseSearchQueryErrorKind,
+    ) -> ParseSearchQueryError {
+        ParseSearchQueryError {
+            position: self.query[..byte_offset].chars().count() + 1,
+            kind,
+        }
+    }
+
+    /// Parse the next filter, or return `None` at the end of the query
+    fn next_filter(&mut self) -> Result<Option<SearchFilter>, ParseSearchQueryError> {
+        // Skip whitespace between filters
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+        if self.rest().is_empty() {
+            return Ok(None);
+        }
+
+        let start = self.position;
+        let negated = self.rest().starts_with('-');
+        if negated {
+            self.position += 1;
+            if self.rest().is_empty() || self.rest().starts_with(char::is_whitespace) {
+                return Err(self.error_at(start, ParseSearchQueryErrorKind::NothingToNegate));
+            }
+        }
+
+        let condition = if self.rest().starts_with('"') {
+            // A phrase on its own is always full text
+            SearchCondition::FullText(self.take_value()?.to_owned())
+        } else if self
+            .rest()
+            .get(..CUSTOM_FIELD_PREFIX.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(CUSTOM_FIELD_PREFIX))
+        {
+            // Custom field names may be quoted, so they can't be split off at the first quote like other filter names
+            let filter_start = self.position;
+            self.position += CUSTOM_FIELD_PREFIX.len();
+            self.parse_custom_field_filter(filter_start)?
+        } else {
+            let filter_start = self.position;
+            let word_end = self
+                .rest()
+                .find(|c: char| c.is_whitespace() || c == '"')
+                .unwrap_or(self.rest().len());
+            match self.rest()[..word_end].split_once(':') {
+                Some((name, _)) if !name.is_empty() => {
+                    let name = name.to_owned();
+                    self.position += name.len() + 1;
+                    self.parse_named_filter(&name, filter_start)?
+                }
+                _ => SearchCondition::FullText(self.take_value()?.to_owned()),
+            }
+        };
+
+        Ok(Some(SearchFilter { negated, condition }))
+    }
+
+    /// Take a value: either a quoted phrase (including the quotes) or everything up to the next whitespace
+    fn take_value(&mut self) -> Result<&str, ParseSearchQueryError> {
+        let start = self.position;
+        let rest = self.rest();
+        let length = if let Some(after_quote) = rest.strip_prefix('"') {
+            match after_quote.find('"') {
+                // Include both quotes, and a `*` directly after the closing quote
+                Some(closing_quote) => {
+                    let length = closing_quote + 2;
+                    if rest[length..].starts_with('*') {
+                        length + 1
+                    } else {
+                        length
+                    }
+                }
+                None => {
+                    return Err(self.error_at(start, ParseSearchQueryErrorKind::UnterminatedQuote))
+                }
+            }
+        } else {
+            rest.find(char::is_whitespace).unwrap_or(rest.len())
+        };
+
+        self.position += length;
+        Ok(&self.query[start..start + length])
+    }
+
+    /// Parse the rest of a `field.<name>:<value>` filter, where `filter_start` is the offset of `field.`
+    fn parse_custom_field_filter(
+        &mut self,
+        filter_start: usize,
+    ) -> Result<SearchCondition, ParseSearchQueryError> {
+        let name = if self.rest().starts_with('"') {
+            self.take_value()?.trim_matches('"').to_owned()
+        } else {
+            let end = self
+                .rest()
+                .find(|c: char| c == ':' || c.is_whitespace())
+                .unwrap_or(self.rest().len());
+            let name = self.rest()[..end].to_owned();
+            self.position += end;
+            name
+        };
+        if name.trim().is_empty() {
+            return Err(self.error_at(
+                filter_start,
+                ParseSearchQueryErrorKind::MissingCustomFieldName,
+            ));
+        }
+
+        let full_name = format!("{CUSTOM_FIELD_PREFIX}{name}");
+        if !self.rest().starts_with(':') {
+            return Err(self.error_at(
+                filter_start,
+                ParseSearchQueryErrorKind::MissingValue(full_name),
+            ));
+        }
+        self.position += 1;
+
+        let value = self.take_value()?.to_owned();
+        let (comparison, value) = split_comparison(&value);
+        let value = value.trim_matches('"');
+        if value.is_empty() {
+            return Err(self.error_at(
+                filter_start,
+                ParseSearchQueryErrorKind::MissingValue(full_name),
+            ));
+        }
+
+        Ok(SearchCondition::CustomField(
+            name.trim().to_owned(),
+            comparison,
+            value.to_owned(),
+        ))
+    }
+
+    /// Parse the value after `name:`, where `filter_start` is the offset of the start of `name`
+    fn parse_named_filter(
+        &mut self,
+        name: &str,
+        filter_start: usize,
+    ) -> Result<SearchCondition, ParseSearchQueryError> {
+        let lowercase_name = name.to_lowercase();
+        let text_field = match lowercase_name.as_str() {
+            "source" => Some(TextField::Source),
+            "company" => Some(TextField::Company),
+            "title" | "job_title" => Some(TextField::JobTitle),
+            "website" | "application_website" => Some(TextField::ApplicationWebsite),
+            "notes" => Some(TextField::Notes),
+            "status" | "response" | "applied" | "responded" | "created" | "updated" | "active"
+            | "tag" | "location" | "city" | "region" | "state" | "country" | "arrangement"
+            | "work" | "relocation" | "visa" => None,
+            _ => {
+                return Err(self.error_at(
+                    filter_start,
+                    ParseSearchQueryErrorKind::UnknownFilter(name.to_owned()),
+                ))
+            }
+        };
+
+        let value_start = self.position;
+        let value = self.take_value()?.to_owned();
+        if value.is_empty() {
+            return Err(self.error_at(
+                filter_start,
+                ParseSearchQueryErrorKind::MissingValue(name.to_owned()),
+            ));
+        }
+
+        if let Some(text_field) = text_field {
+            return Ok(SearchCondition::Field(text_field, value));
+        }
+
+        match lowercase_name.as_str() {
+            // Tags are matched by their whole name, so quotes are only needed to include spaces
+            "tag" => match value.trim_matches('"').trim() {
+                "" => Err(self.error_at(
+                    filter_start,
+                    ParseSearchQueryErrorKind::MissingValue(name.to_owned()),
+                )),
+                tag => Ok(SearchCondition::Tag(tag.to_owned())),
+            },
+            "location" | "city" | "region" | "state" | "country" => {
+                let field = match lowercase_name.as_str() {
+                    "city" => Some(LocationField::City),
+                    "region" | "state" => Some(LocationField::Region),
+                    "country" => Some(LocationField::Country),
+                    _ => None,
+                };
+                match value.trim_matches('"').trim() {
+                    "" => Err(self.error_at(
+                        filter_start,
+                        ParseSearchQueryErrorKind::MissingValue(name.to_owned()),
+                    )),
+                    text => Ok(SearchCondition::Location(field, text.to_owned())),
+                }
+            }
+            "arrangement" | "work" => value
+                .trim_matches('"')
+                .parse()
+                .map(SearchCondition::WorkArrangement)
+                .map_err(|_| {
+                    self.error_at(
+                        value_start,
+                        ParseSearchQueryErrorKind::InvalidWorkArrangement(value),
+                    )
+                }),
+            "relocation" | "visa" => {
+                let field = if lowercase_name == "relocation" {
+                    BoolField::Relocation
+                } else {
+                    BoolField::VisaSponsorship
+                };
+                parse_yes_no(&value)
+                    .map(|yes| SearchCondition::Bool(field, yes))
+                    .ok_or_else(|| {
+                        self.error_at(value_start, ParseSearchQueryErrorKind::InvalidYesNo(value))
+                    })
+            }
+            "status" | "response" => parse_human_response(&value)
+                .map(SearchCondition::HumanResponse)
+                .ok_or_else(|| {
+                    self.error_at(
+                        value_start,
+                        ParseSearchQueryErrorKind::InvalidHumanResponse(value),
+                    )
+                }),
+            date_name => {
+                let field = match date_name {
+                    "applied" => DateField::Applied,
+                    "created" => DateField::Created,
+                    "updated" => DateField::Updated,
+                    "active" => DateField::LastActivity,
+                    _ => DateField::Responded,
+                };
+                parse_date_comparison(&value)
+                    .map(|(comparison, date)| SearchCondition::Date(field, comparison, date))
+                    .ok_or_else(|| {
+                        self.error_at(value_start, ParseSearchQueryErrorKind::InvalidDate(value))
+                    })
+            }
+        }
+    }
+}
+
+/// Parse the value of a `status:` filter, allowing a few more natural spellings than [HumanResponse]'s `TryFrom<&str>`
+fn parse_human_response(value: &str) -> Option<HumanResponse> {
+    let normalized = value
+        .trim_matches('"')
+        .to_lowercase()
+        .replace(['-', '_'], " ");
+    match normalized.as_str() {
+        "none" | "pending" | "no response" => Some(HumanResponse::None),
+        "rejected" => Some(HumanResponse::Rejection),
+        "interview" | "interviewing" => Some(HumanResponse::InterviewRequest),
+        "interviewed" | "interviewed rejected" => Some(HumanResponse::InterviewedThenRejected),
+        "offer" => Some(HumanResponse::JobOffer),
+        // Empty strings are `None` in `TryFrom<&str>`, but an empty status is an error here
+        "" => None,
+        _ => HumanResponse::try_from(normalized.as_str()).ok(),
+    }
+}
+
+/// Parse the value of a yes/no filter
+fn parse_yes_no(value: &str) -> Option<bool> {
+    match value.trim_matches('"').to_lowercase().as_str() {
+        "yes" | "y" | "true" => Some(true),
+        "no" | "n" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse the value of a date filter, like `>=2025-01-01` or `01/31/2025`
+fn parse_date_comparison(value: &str) -> Option<(Comparison, Date)> {
+    let (comparison, date_str) = split_comparison(value);
+
+    Date::parse(date_str, format_description!("[year]-[month]-[day]"))
+        .or_else(|_| {
+            Date::parse(
+                date_str,
+                format_description!("[month padding:none]/[day padding:none]/[year]"),
+            )
+        })
+        .ok()
+        .map(|date| (comparison, date))
+}
+
+/// Split an optional comparison operator off of the start of a value. No operator means [Comparison::Equal].
+fn split_comparison(value: &str) -> (Comparison, &str) {
+    [
+        (">=", Comparison::GreaterOrEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">", Comparison::Greater),
+        ("<", Comparison::Less),
+        ("=", Comparison::Equal),
+    ]
+    .into_iter()
+    .find_map(|(operator, comparison)| value.strip_prefix(operator).map(|rest| (comparison, rest)))
+    .unwrap_or((Comparison::Equal, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::Month;
+
+    use super::*;
+
+    /// Shorthand to build a filter that is not negated
+    fn filter(condition: SearchCondition) -> SearchFilter {
+        SearchFilter {
+            negated: false,
+            condition,
+        }
+    }
+
+    /// Shorthand to build a negated filter
+    fn negated(condition: SearchCondition) -> SearchFilter {
+        SearchFilter {
+            negated: true,
+            condition,
+        }
+    }
+
+    /// Parse the example from the documentation
+    #[test]
+    fn test_parse_full_example() {
+        assert_eq!(
+            r#"company:acme status:rejected applied:>=2025-01-01 source:linkedin "backend engineer" -notes:contract"#
+                .parse::<SearchQuery>(),
+            Ok(SearchQuery {
+                filters: vec![
+                    filter(SearchCondition::Field(TextField::Company, "acme".to_owned())),
+                    filter(SearchCondition::HumanResponse(HumanResponse::Rejection)),
+                    filter(SearchCondition::Date(
+                        DateField::Applied,
+                        Comparison::GreaterOrEqual,
+                        Date::from_calendar_date(2025, Month::January, 1).unwrap()
+                    )),
+                    filter(SearchCondition::Field(TextField::Source, "linkedin".to_owned())),
+                    filter(SearchCondition::FullText("\"backend engineer\"".to_owned())),
+                    negated(SearchCondition::Field(TextField::Notes, "contract".to_owned())),
+                ]
+            })
+        );
+    }
+
+    /// Blank queries have no filters
+    #[test]
+    fn test_parse_empty() {
+        assert_eq!("".parse(), Ok(SearchQuery::default()));
+        assert_eq!("  \t ".parse(), Ok(SearchQuery::default()));
+    }
+
+    /// Words, prefixes, and phrases without a filter name are full text, and may be negated
+    #[test]
+    fn test_parse_full_text() {
+        assert_eq!(
+            r#"rust  -java eng* -"on site" "remote work"*"#.parse(),
+            Ok(SearchQuery {
+                filters: vec![
+                    filter(SearchCondition::FullText("rust".to_owned())),
+                    negated(SearchCondition::FullText("java".to_owned())),
+                    filter(SearchCondition::FullText("eng*".to_owned())),
+                    negated(SearchCondition::FullText("\"on site\"".to_owned())),
+                    filter(SearchCondition::FullText("\"remote work\"*".to_owned())),
+                ]
+            })
+        );
+    }
+
+    /// Field values may be quoted, and filter names are case insensitive and have aliases
+    #[test]
+    fn test_parse_fields() {
+        assert_eq!(
+            r#"Company:"Acme Corp" title:eng* job_title:senior website:example.com url_is_text"#
+                .parse(),
+            Ok(SearchQuery {
+                filters: vec![
+                    filter(SearchCondition::Field(
+                        TextField::Company,
+                        "\"Acme Corp\"".to_owned()
+                    )),
+                    filter(SearchCondition::Field(
+                        TextField::JobTitle,
+                        "eng*".to_owned()
+                    )),
+                    filter(SearchCondition::Field(
+                        TextField::JobTitle,
+                        "senior".to_owned()
+                    )),
+                    filter(SearchCondition::Field(
+                        TextField::ApplicationWebsite,
+                        "example.com".to_owned()
+                    )),
+                    filter(SearchCondition::FullText("url_is_text".to_owned())),
+                ]
+            })
+        );
+    }
+
+    /// Tags may be quoted to include spaces
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(
+            r#"tag:remote -Tag:"dream job""#.parse(),
+            Ok(SearchQuery {
+                filters: vec![
+                    filter(SearchCondition::Tag("remote".to_owned())),
+                    negated(SearchCondition::Tag("dream job".to_owned())),
+                ]
+            })
+        );
+        assert_eq!(
+            r#"tag:"""#.parse::<SearchQuery>(),
+            Err(ParseSearchQueryError {
+                position: 1,
+                kind: ParseSearchQueryErrorKind::MissingValue("tag".to_owned())
+            })
+        );
+    }
+
+    /// Location, work arrangement, and yes/no filters
+    #[test]
+    fn test_parse_location_filters() {
+        assert_eq!(
+            r#"city:berlin State:"new york" -country:us location:land arrangement:on-site work:R relocation:yes -visa:No"#
+                .parse(),
+            Ok(SearchQuery {
+                filters: vec![
+                    filter(SearchCondition::Location(
+                        Some(LocationField::City),
+                        "berlin".to_owned()
+                    )),
+                    filter(SearchCondition::Location(
+                        Some(LocationField::Region),
+                        "new york".to_owned()
+                    )),
+                    negated(SearchCondition::Location(
+                        Some(LocationField::Country),
+                        "us".to_owned()
+                    )),
+                    filter(SearchCondition::Location(None, "land".to_owned())),
+                    filter(SearchCondition::WorkArrangement(WorkArrangement::Onsite)),
+                    filter(SearchCondition::WorkArrangement(WorkArrangement::Remote)),
+                    filter(SearchCondition::Bool(BoolField::Relocation, true)),
+                    negated(SearchCondition::Bool(BoolField::VisaSponsorship, false)),
+                ]
+            })
+        );
+    }
+
+    /// Custom field names may be quoted, and values may have comparisons
+    #[test]
+    fn test_parse_custom_fields() {
+        assert_eq!(
+            r#"field.agency:hays -Field."req id":>=1000 field.remote:"yes""#.parse(),
+            Ok(SearchQuery {
+                filters: vec![
+                    filter(SearchCondition::CustomField(
+                        "agency".to_owned(),
+                        Comparison::Equal,
+                        "hays".to_owned()
+                    )),
+                    negated(SearchCondition::CustomField(
+                        "req id".to_owned(),
+                        Comparison::GreaterOrEqual,
+                        "1000".to_owned()
+                    )),
+                    filter(SearchCondition::CustomField(
+                        "remote".to_owned(),
+                        Comparison::Equal,
+                        "yes".to_owned()
+                    )),
+                ]
+            })
+        );
+
+        for (query, kind) in [
+            (
+                "field.:hays",
+                ParseSearchQueryErrorKind::MissingCustomFieldName,
+            ),
+            (
+                "field.agency",
+                ParseSearchQueryErrorKind::MissingValue("field.agency".to_owned()),
+            ),
+            (
+                "field.agency:>=",
+                ParseSearchQueryErrorKind::MissingValue("field.agency".to_owned()),
+            ),
+        ] {
+            assert_eq!(
+                query.parse::<SearchQuery>(),
+                Err(ParseSearchQueryError { position: 1, kind }),
+                "{query}"
+            );
+        }
+    }
+
+    /// All status spellings
+    #[test]
+    fn test_parse_status() {
+        for (value, expected) in [
+            ("none", HumanResponse::None),
+            ("pending", HumanResponse::None),
+            ("rejected", HumanResponse::Rejection),
+            ("rejection", HumanResponse::Rejection),
+            ("interview", HumanResponse::InterviewRequest),
+            ("interview-request", HumanResponse::InterviewRequest),
+            (
+                "interviewed-then-rejected",
+                HumanResponse::InterviewedThenRejected,
+            ),
+            ("ir", HumanResponse::InterviewedThenRejected),
+            ("offer", HumanResponse::JobOffer),
+            ("\"Job offer\"", HumanResponse::JobOffer),
+        ] {
+            assert_eq!(
+                format!("status:{value}").parse(),
+                Ok(SearchQuery {
+                    filters: vec![filter(SearchCondition::HumanResponse(expected))]
+                }),
+                "status:{value}"
+            );
+        }
+    }
+
+    /// All date comparisons and formats
+    #[test]
+    fn test_parse_dates() {
+        let date = Date::from_calendar_date(2025, Month::March, 4).unwrap();
+        for (value, field, comparison) in [
+            ("applied:2025-03-04", DateField::Applied, Comparison::Equal),
+            ("applied:=03/04/2025", DateField::Applied, Comparison::Equal),
+            ("applied:<2025-03-04", DateField::Applied, Comparison::Less),
+            (
+                "responded:<=2025-03-04",
+                DateField::Responded,
+                Comparison::LessOrEqual,
+            ),
+            (
+                "responded:>3/4/2025",
+                DateField::Responded,
+                Comparison::Greater,
+            ),
+            (
+                "responded:>=2025-03-04",
+                DateField::Responded,
+                Comparison::GreaterOrEqual,
+            ),
+            ("created:2025-03-04", DateField::Created, Comparison::Equal),
+            ("updated:<2025-03-04", DateField::Updated, Comparison::Less),
+            (
+                "active:>=2025-03-04",
+                DateField::LastActivity,
+                Comparison::GreaterOrEqual,
+            ),
+        ] {
+            assert_eq!(
+                value.parse(),
+                Ok(SearchQuery {
+                    filters: vec![filter(SearchCondition::Date(field, comparison, date))]
+                }),
+                "{value}"
+            );
+        }
+    }
+
+    /// Malformed queries report the problem and where it is
+    #[test]
+    fn test_parse_errors() {
+        for (query, position, kind) in [
+            (
+                "backend \"senior engineer",
+                9,
+                ParseSearchQueryErrorKind::UnterminatedQuote,
+            ),
+            (
+                "company:\"acme",
+                9,
+                ParseSearchQueryErrorKind::UnterminatedQuote,
+            ),
+            ("rust - java", 6, ParseSearchQueryErrorKind::NothingToNegate),
+            ("rust -", 6, ParseSearchQueryErrorKind::NothingToNegate),
+            (
+                "über https://example.com",
+                6,
+                ParseSearchQueryErrorKind::UnknownFilter("https".to_owned()),
+            ),
+            (
+                "company: acme",
+                1,
+                ParseSearchQueryErrorKind::MissingValue("company".to_owned()),
+            ),
+            (
+                "status:maybe",
+                8,
+                ParseSearchQueryErrorKind::InvalidHumanResponse("maybe".to_owned()),
+            ),
+            (
+                "-applied:>=2025-13-01",
+                10,
+                ParseSearchQueryErrorKind::InvalidDate(">=2025-13-01".to_owned()),
+            ),
+            (
+                "arrangement:office-ish",
+                13,
+                ParseSearchQueryErrorKind::InvalidWorkArrangement("office-ish".to_owned()),
+            ),
+            (
+                "visa:maybe",
+                6,
+                ParseSearchQueryErrorKind::InvalidYesNo("maybe".to_owned()),
+            ),
+            (
+                "city:\"\"",
+                1,
+                ParseSearchQueryErrorKind::MissingValue("city".to_owned()),
+            ),
+            (
+                "applied:yesterday",
+                9,
+                ParseSearchQueryErrorKind::InvalidDate("yesterday".to_owned()),
+            ),
+        ] {
+            assert_eq!(
+                query.parse::<SearchQuery>(),
+                Err(ParseSearchQueryError { position, kind }),
+                "{query}"
+            );
+        }
+    }
+
+    /// Error messages should name the problem and how to fix it
+    #[test]
+    fn test_error_message() {
+        assert_eq!(
+            "salary:100000"
+                .parse::<SearchQuery>()
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "Unknown filter 'salary' at position 1. Valid filters are: {FILTER_NAMES}. \
+                To search for text containing a colon, put it in double quotes"
+            )
+        );
+    }
+}
@@ -0,0 +1,647 @@
+//! Syncing combines the job applications in two SQLite databases, like one on a laptop and one on a desktop,
+//! so changes made to either one while they were apart end up in both.
+//!
+//! Job applications are matched by a UUID that every database keeps for them, since ids are only unique within one database.
+//! Each field of a job application remembers when it was last changed, and the newest value of each field is kept.
+//! If a field was changed in both databases since they last synced, the two values are a [SyncConflict] to be resolved instead.
+//! Job applications that were permanently deleted in one database are deleted from the other, unless they were changed there afterwards.
+//!
+//! The fields of a job application, its tags, and whether it is in the trash are synced.
+//! Custom fields, offers, job descriptions, attachments, and resume versions stay in the database they were saved in.
+//! MySQL databases are already shared by everyone using them, so only SQLite databases are synced.
+
+/// A field of a job application that was changed in both databases since they last synced, to different values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncConflict {
+    /// The company of the job application, as it is in this database
+    pub company: String,
+    /// The job title of the job application, as it is in this database
+    pub job_title: String,
+    /// The name of the field, which is its column name, or `tags`
+    pub field: String,
+    /// The value in this database, or `None` if it is empty
+    pub local_value: Option<String>,
+    /// The value in the other database, or `None` if it is empty
+    pub remote_value: Option<String>,
+}
+
+/// Which value of a [SyncConflict] to keep in both databases
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncChoice {
+    /// The value in this database
+    Local,
+    /// The value in the other database
+    Remote,
+}
+
+/// How many job applications a sync changed in one of the databases
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncChanges {
+    /// Job applications that were only in the other database
+    pub added: usize,
+    /// Job applications that had fields changed to the other database's values
+    pub updated: usize,
+    /// Job applications that were permanently deleted because they were deleted in the other database
+    pub deleted: usize,
+}
+
+/// What a sync changed in each database
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Changes to this database
+    pub local: SyncChanges,
+    /// Changes to the other database
+    pub remote: SyncChanges,
+    /// How many conflicts were resolved
+    pub conflicts: usize,
+}
+
+#[cfg(not(feature = "mysql"))]
+pub(crate) use sqlite::{export_sync_file, set_tags, sync_with, NEW_UUID, SYNCED_COLUMNS};
+
+#[cfg(not(feature = "mysql"))]
+mod sqlite {
+    use std::{cmp::Ordering, collections::BTreeMap, fs, path::Path};
+
+    use rusqlite::{params_from_iter, types::Value, Connection, OptionalExtension};
+
+    use super::{SyncChanges, SyncChoice, SyncConflict, SyncReport};
+    use crate::{
+        backend_connection::{db_file_path, open_db},
+        job_application_model::{HumanResponse, PayPeriod, WorkArrangement},
+        job_application_repository::RepositoryError,
+    };
+
+    /// Every column of `job_applications` that is synced, each as its own field
+    ///
+    /// `id` and `resume_version_id` only mean something in one database, and `version`, `created_at`, and `updated_at` are kept by each database.
+    pub(crate) const SYNCED_COLUMNS: [&str; 22] = [
+        "source",
+        "company",
+        "job_title",
+        "application_date",
+        "time_investment",
+        "human_response",
+        "human_response_date",
+        "application_website",
+        "notes",
+        "salary_min",
+        "salary_max",
+        "salary_currency",
+        "pay_period",
+        "equity",
+        "bonus",
+        "city",
+        "region",
+        "country",
+        "work_arrangement",
+        "relocation",
+        "visa_sponsorship",
+        "deleted_at",
+    ];
+
+    /// The field that stands for every tag on a job application
+    const TAGS_FIELD: &str = "tags";
+
+    /// A new random UUID, the same way the migration that added them makes them
+    pub(crate) const NEW_UUID: &str = "lower(hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-' || substr('89ab', abs(random()) % 4 + 1, 1) || substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6)))";
+
+    /// The current time in UTC, in the same format as when fields were changed
+    const NOW: &str = "SELECT strftime('%Y-%m-%d %H:%M:%f', 'now')";
+
+    /// Everything that is synced about one job application in one database
+    struct SyncedJobApplication {
+        id: i32,
+        /// In the same order as [SYNCED_COLUMNS]
+        values: Vec<Value>,
+        /// Tag names, sorted
+        tags: Vec<String>,
+        created_at: String,
+        updated_at: String,
+        /// When each field was last changed, for fields that have changed since the job application was created
+        clocks: BTreeMap<String, String>,
+    }
+
+    impl SyncedJobApplication {
+        /// The value of a field, which is every tag name for [TAGS_FIELD]
+        fn value(&self, field: &str) -> FieldValue {
+            match SYNCED_COLUMNS.iter().position(|column| *column == field) {
+                Some(index) => FieldValue::Column(self.values[index].clone()),
+                None => FieldValue::Tags(self.tags.clone()),
+            }
+        }
+
+        /// When a field was last changed
+        fn clock(&self, field: &str) -> &str {
+            self.clocks.get(field).unwrap_or(&self.created_at)
+        }
+
+        /// When anything that is synced about the job application was last changed
+        ///
+        /// This isn't `updated_at`, which also changes for things that aren't synced, and whenever a sync changes the job application.
+        fn last_changed(&self) -> &str {
+            self.clocks
+                .values()
+                .map(String::as_str)
+                .chain([self.created_at.as_str()])
+                .max()
+                .unwrap_or_default()
+        }
+    }
+
+    /// The value of one synced field
+    #[derive(Clone, PartialEq)]
+    enum FieldValue {
+        /// The value of one of [SYNCED_COLUMNS]
+        Column(Value),
+        /// Tag names for [TAGS_FIELD], sorted. They are kept apart since a name may contain a comma.
+        Tags(Vec<String>),
+    }
+
+    impl FieldValue {
+        /// How the value is shown in a conflict, or `None` if it isn't set
+        fn format(&self, field: &str) -> Option<String> {
+            match self {
+                FieldValue::Column(value) => format_value(field, value),
+                FieldValue::Tags(tags) if tags.is_empty() => None,
+                FieldValue::Tags(tags) => Some(tags.join(", ")),
+            }
+        }
+
+        /// Order values that were changed at the same time, so both databases pick the same one
+        fn cmp_tie(&self, other: &FieldValue, field: &str) -> Ordering {
+            match (self, other) {
+                (FieldValue::Tags(tags), FieldValue::Tags(other_tags)) => tags.cmp(other_tags),
+                _ => self.format(field).cmp(&other.format(field)),
+            }
+        }
+    }
+
+    /// Everything that is synced in one database
+    struct SyncState {
+        /// Job applications by UUID
+        job_applications: BTreeMap<String, SyncedJobApplication>,
+        /// When each permanently deleted job application was deleted, by UUID
+        tombstones: BTreeMap<String, String>,
+    }
+
+    /// Sync the database `conn` has open with the database or sync file at `file`, in both directions
+    ///
+    /// `passphrase` is for `file`, if it is encrypted. Nothing is saved in either database unless the whole sync succeeds.
+    pub fn sync_with(
+        conn: &mut Connection,
+        file: &Path,
+        passphrase: Option<&str>,
+        resolve: &mut dyn FnMut(&SyncConflict) -> SyncChoice,
+    ) -> Result<SyncReport, RepositoryError> {
+        if !file.exists() {
+            return Err(RepositoryError::NotFound(format!(
+                "No database named {}",
+                file.display()
+            )));
+        }
+        if file.canonicalize()? == db_file_path(conn)?.canonicalize()? {
+            return Err(RepositoryError::Validation(
+                "A database can't be synced with itself".to_owned(),
+            ));
+        }
+        // This also upgrades the other database, if it was made by an older version
+        let mut other = open_db(file, passphrase)?;
+
+        // A database that was copied to another machine starts out with the same UUID, which the copy can't keep
+        if identity(conn)? == identity(&other)? {
+            conn.execute(&format!("UPDATE sync_identity SET uuid = {NEW_UUID}"), ())?;
+        }
+
+        let local_tx = conn.transaction()?;
+        let remote_tx = other.transaction()?;
+        let local_identity = identity(&local_tx)?;
+        let remote_identity = identity(&remote_tx)?;
+        let last_synced: Option<String> = local_tx
+            .query_row(
+                "SELECT last_synced_at FROM sync_peers WHERE uuid = ?",
+                (&remote_identity,),
+                |row| row.get(0),
+            )
+            .optional()?;
+        let now: String = local_tx.query_row(NOW, (), |row| row.get(0))?;
+
+        let mut report = SyncReport::default();
+        // Conflicts are resolved while bringing the other database's changes here.
+        // The resolved values are newer than anything in the other database, so they simply win on the way back.
+        let remote = read_state(&remote_tx)?;
+        report.local = merge_into(
+            &local_tx,
+            &remote,
+            Some((last_synced.as_deref(), &now, resolve, &mut report.conflicts)),
+        )?;
+        let local = read_state(&local_tx)?;
+        report.remote = merge_into(&remote_tx, &local, None)?;
+
+        record_sync(&local_tx, &remote_identity, &now)?;
+        record_sync(&remote_tx, &local_identity, &now)?;
+        remote_tx.commit()?;
+        local_tx.commit()?;
+        Ok(report)
+    }
+
+    /// Save everything that is synced from the database `conn` has open into a new sync file at `file`, returning how many job applications it has
+    ///
+    /// The sync file is encrypted with `passphrase` if one is given.
+    /// Syncing with it brings its job applications into another database, and that database's changes into the file, to bring back.
+    pub fn export_sync_file(
+        conn: &mut Connection,
+        file: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<usize, RepositoryError> {
+        if file.exists() {
+            return Err(RepositoryError::Conflict(format!(
+                "{} already exists",
+                file.display()
+            )));
+        }
+        if passphrase.is_some() && !cfg!(feature = "sqlcipher") {
+            return Err(RepositoryError::Validation(
+                "Sync files can only be encrypted when built with the sqlcipher feature".to_owned(),
+            ));
+        }
+
+        let exported = open_db(file, passphrase).and_then(|mut export| {
+            let tx = conn.transaction()?;
+            let export_tx = export.transaction()?;
+            let now: String = tx.query_row(NOW, (), |row| row.get(0))?;
+            let added = merge_into(&export_tx, &read_state(&tx)?, None)?.added;
+            record_sync(&tx, &identity(&export_tx)?, &now)?;
+            record_sync(&export_tx, &identity(&tx)?, &now)?;
+            export_tx.commit()?;
+            tx.commit()?;
+            Ok(added)
+        });
+        // A sync file that wasn't finished would only be confusing to sync with later
+        if exported.is_err() {
+            _ = fs::remove_file(file);
+        }
+        exported
+    }
+
+    /// The UUID of a database
+    fn identity(conn: &Connection) -> Result<String, rusqlite::Error> {
+        conn.query_row("SELECT uuid FROM sync_identity", (), |row| row.get(0))
+    }
+
+    /// Remember that `conn` synced with the database `peer` at `synced_at`
+    fn record_sync(conn: &Connection, peer: &str, synced_at: &str) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO sync_peers (uuid, last_synced_at) VALUES (?1, ?2) \
+            ON CONFLICT (uuid) DO UPDATE SET last_synced_at = ?2",
+            (peer, synced_at),
+        )?;
+        Ok(())
+    }
+
+    /// Read everything that is synced from a database
+    fn read_state(conn: &Connection) -> Result<SyncState, rusqlite::Error> {
+        let mut job_applications = BTreeMap::new();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT uuid, id, coalesce(created_at, ''), coalesce(updated_at, ''), {} FROM job_applications",
+            SYNCED_COLUMNS.join(", ")
+        ))?;
+        let mut rows = stmt.query(())?;
+        while let Some(row) = rows.next()? {
+            let values = (0..SYNCED_COLUMNS.len())
+                .map(|index| row.get(4 + index))
+                .collect::<Result<_, _>>()?;
+            job_applications.insert(
+                row.get(0)?,
+                SyncedJobApplication {
+                    id: row.get(1)?,
+                    values,
+                    tags: Vec::new(),
+                    created_at: row.get(2)?,
+                    updated_at: row.get(3)?,
+                    clocks: BTreeMap::new(),
+                },
+            );
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT job_applications.uuid, tags.name FROM job_application_tags \
+            JOIN job_applications ON job_applications.id = job_application_tags.job_application_id \
+            JOIN tags ON tags.id = job_application_tags.tag_id \
+            ORDER BY tags.name",
+        )?;
+        let mut rows = stmt.query(())?;
+        while let Some(row) = rows.next()? {
+            if let Some(job_application) = job_applications.get_mut(&row.get::<_, String>(0)?) {
+                job_application.tags.push(row.get(1)?);
+            }
+        }
+
+        let mut stmt =
+            conn.prepare("SELECT job_application_uuid, field, changed_at FROM sync_field_clocks")?;
+        let mut rows = stmt.query(())?;
+        while let Some(row) = rows.next()? {
+            if let Some(job_application) = job_applications.get_mut(&row.get::<_, String>(0)?) {
+                job_application.clocks.insert(row.get(1)?, row.get(2)?);
+            }
+        }
+
+        let tombstones = conn
+            .prepare("SELECT job_application_uuid, deleted_at FROM sync_tombstones")?
+            .query_map((), |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(SyncState {
+            job_applications,
+            tombstones,
+        })
+    }
+
+    /// How to resolve conflicts while merging: when the databases last synced, the time to give resolved values,
+    /// what to resolve them with, and a count of conflicts resolved
+    type Resolver<'a, 'b> = (
+        Option<&'a str>,
+        &'a str,
+        &'b mut dyn FnMut(&SyncConflict) -> SyncChoice,
+        &'a mut usize,
+    );
+
+    /// Bring everything in `source` that is newer than what `target` has into `target`
+    ///
+    /// Without `resolver`, every field that differs is resolved by keeping the newest value.
+    fn merge_into(
+        target: &Connection,
+        source: &SyncState,
+        mut resolver: Option<Resolver>,
+    ) -> Result<SyncChanges, RepositoryError> {
+        let target_state = read_state(target)?;
+        let mut changes = SyncChanges::default();
+
+        for (uuid, theirs) in &source.job_applications {
+            let Some(ours) = target_state.job_applications.get(uuid) else {
+                // A job application deleted here stays deleted, unless it was changed elsewhere after that
+                let deleted_here = target_state
+                    .tombstones
+                    .get(uuid)
+                    .is_some_and(|deleted_at| deleted_at.as_str() >= theirs.last_changed());
+                if !deleted_here {
+                    insert_job_application(target, uuid, theirs)?;
+                    changes.added += 1;
+                }
+                continue;
+            };
+
+            // Each field that should take their value, and when that value was changed
+            let mut updates: Vec<(&str, FieldValue, String)> = Vec::new();
+            for field in SYNCED_COLUMNS.into_iter().chain([TAGS_FIELD]) {
+                let (our_value, their_value) = (ours.value(field), theirs.value(field));
+                if our_value == their_value {
+                    continue;
+                }
+                let (our_clock, their_clock) = (ours.clock(field), theirs.clock(field));
+
+                match &mut resolver {
+                    Some((Some(last_synced), now, resolve, conflicts))
+                        if our_clock > *last_synced && their_clock > *last_synced =>
+                    {
+                        **conflicts += 1;
+                        let conflict = SyncConflict {
+                            company: ours.value("company").format("company").unwrap_or_default(),
+                            job_title: ours
+                                .value("job_title")
+                                .format("job_title")
+                                .unwrap_or_default(),
+                            field: field.to_owned(),
+                            local_value: our_value.format(field),
+                            remote_value: their_value.format(field),
+                        };
+                        // Whichever is chosen counts as changed now, so it wins in the other database too
+                        let value = match resolve(&conflict) {
+                            SyncChoice::Local => our_value,
+                            SyncChoice::Remote => their_value,
+                        };
+                        updates.push((field, value, now.to_string()));
+                    }
+                    _ => {
+                        // Ties go to the larger value, so both databases pick the same one
+                        let newest = their_clock
+                            .cmp(our_clock)
+                            .then_with(|| their_value.cmp_tie(&our_value, field));
+                        if newest == Ordering::Greater {
+                            updates.push((field, their_value, their_clock.to_owned()));
+                        }
+                    }
+                }
+            }
+
+            if !updates.is_empty() {
+                update_job_application(target, ours, &updates)?;
+                // Choosing our own value doesn't change anything but when it was changed
+                if updates
+                    .iter()
+                    .any(|(field, value, _)| ours.value(field) != *value)
+                {
+                    changes.updated += 1;
+                }
+            }
+        }
+
+        for (uuid, deleted_at) in &source.tombstones {
+            match target_state.job_applications.get(uuid) {
+                // Changes made after it was deleted elsewhere keep it
+                Some(ours) if ours.last_changed() > deleted_at.as_str() => {}
+                Some(ours) => {
+                    target.execute("DELETE FROM job_applications WHERE id = ?", (ours.id,))?;
+                    changes.deleted += 1;
+                }
+                None if target_state.tombstones.contains_key(uuid) => continue,
+                None => {}
+            }
+            target.execute(
+                "INSERT OR REPLACE INTO sync_tombstones (job_application_uuid, deleted_at) VALUES (?, ?)",
+                (uuid, deleted_at),
+            )?;
+        }
+
+        Ok(changes)
+    }
+
+    /// Insert a job application from another database, with the same UUID, tags, and clocks
+    fn insert_job_application(
+        conn: &Connection,
+        uuid: &str,
+        job_application: &SyncedJobApplication,
+    ) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            &format!(
+                "INSERT INTO job_applications (uuid, created_at, updated_at, {}) VALUES (?, ?, ?, {})",
+                SYNCED_COLUMNS.join(", "),
+                vec!["?"; SYNCED_COLUMNS.len()].join(", ")
+            ),
+            params_from_iter(
+                [
+                    Value::Text(uuid.to_owned()),
+                    Value::Text(job_application.created_at.clone()),
+                    Value::Text(job_application.updated_at.clone()),
+                ]
+                .into_iter()
+                .chain(job_application.values.iter().cloned()),
+            ),
+        )?;
+        let id = conn.last_insert_rowid();
+        set_tags(conn, id, &job_application.tags)?;
+
+        // Adding the tags changed the clocks, so they are replaced with the other database's
+        conn.execute(
+            "DELETE FROM sync_field_clocks WHERE job_application_uuid = ?",
+            (uuid,),
+        )?;
+        for (field, changed_at) in &job_application.clocks {
+            set_clock(conn, uuid, field, changed_at)?;
+        }
+        conn.execute(
+            "DELETE FROM sync_tombstones WHERE job_application_uuid = ?",
+            (uuid,),
+        )?;
+        Ok(())
+    }
+
+    /// Change the fields of a job application to values from another database, along with when they were changed there
+    fn update_job_application(
+        conn: &Connection,
+        job_application: &SyncedJobApplication,
+        updates: &[(&str, FieldValue, String)],
+    ) -> Result<(), rusqlite::Error> {
+        let columns: Vec<(&str, &Value)> = updates
+            .iter()
+            .filter_map(|(field, value, _)| match value {
+                FieldValue::Column(value) => Some((*field, value)),
+                FieldValue::Tags(_) => None,
+            })
+            .collect();
+        // Saving over a job application that was open before the sync is refused, like any other change
+        conn.execute(
+            &format!(
+                "UPDATE job_applications SET {} version = version + 1, updated_at = datetime('now') WHERE id = ?",
+                columns
+                    .iter()
+                    .map(|(field, _)| format!("{field} = ?,"))
+                    .collect::<String>()
+            ),
+            params_from_iter(
+                columns
+                    .iter()
+                    .map(|(_, value)| (*value).clone())
+                    .chain([Value::Integer(job_application.id.into())]),
+            ),
+        )?;
+
+        let uuid: String = conn.query_row(
+            "SELECT uuid FROM job_applications WHERE id = ?",
+            (job_application.id,),
+            |row| row.get(0),
+        )?;
+        for (field, value, changed_at) in updates {
+            if let FieldValue::Tags(tags) = value {
+                set_tags(conn, job_application.id.into(), tags)?;
+            }
+            set_clock(conn, &uuid, field, changed_at)?;
+        }
+        Ok(())
+    }
+
+    /// Replace every tag on a job application
+    pub(crate) fn set_tags(
+        conn: &Connection,
+        id: i64,
+        tags: &[String],
+    ) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "DELETE FROM job_application_tags WHERE job_application_id = ?",
+            (id,),
+        )?;
+        for name in tags {
+            conn.prepare_cached(
+                "INSERT INTO tags (name) VALUES (?) ON CONFLICT (name) DO NOTHING",
+            )?
+            .execute((name,))?;
+            conn.prepare_cached(
+                "INSERT INTO job_application_tags (job_application_id, tag_id) \
+                SELECT ?, id FROM tags WHERE name = ? ON CONFLICT DO NOTHING",
+            )?
+            .execute((id, name))?;
+        }
+        Ok(())
+    }
+
+    /// Record when a field was changed
+    fn set_clock(
+        conn: &Connection,
+        uuid: &str,
+        field: &str,
+        changed_at: &str,
+    ) -> Result<(), rusqlite::Error> {
+        conn.prepare_cached(
+            "INSERT OR REPLACE INTO sync_field_clocks (job_application_uuid, field, changed_at) VALUES (?, ?, ?)",
+        )?
+        .execute((uuid, field, changed_at))?;
+        Ok(())
+    }
+
+    /// Write a value the way the frontends show it, or `None` if it is empty
+    fn format_value(field: &str, value: &Value) -> Option<String> {
+        let text = match value {
+            Value::Null => return None,
+            Value::Integer(number) => number.to_string(),
+            Value::Real(number) => number.to_string(),
+            Value::Text(text) => text.clone(),
+            Value::Blob(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+        };
+        Some(match (field, value) {
+            ("human_response", _) => HumanResponse::try_from(text.as_str())
+                .map_or(text, |human_response| human_response.to_string()),
+            ("pay_period", _) => text
+                .parse::<PayPeriod>()
+                .map_or(text, |pay_period| pay_period.to_string()),
+            ("work_arrangement", _) => text
+                .parse::<WorkArrangement>()
+                .map_or(text, |work_arrangement| work_arrangement.to_string()),
+            ("relocation" | "visa_sponsorship", Value::Integer(0)) => "no".to_owned(),
+            ("relocation" | "visa_sponsorship", Value::Integer(_)) => "yes".to_owned(),
+            _ => text,
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// Conflicting values are shown the way the frontends show them, not the way they are stored
+        #[test]
+        fn test_format_value() {
+            let text = |text: &str| Value::Text(text.to_owned());
+
+            assert_eq!(
+                format_value("human_response", &text("IR")),
+                Some("Interviewed, then rejected".to_owned())
+            );
+            assert_eq!(
+                format_value("pay_period", &text("yearly")),
+                Some("yearly".to_owned())
+            );
+            assert_eq!(
+                format_value("relocation", &Value::Integer(0)),
+                Some("no".to_owned())
+            );
+            assert_eq!(
+                format_value("visa_sponsorship", &Value::Integer(1)),
+                Some("yes".to_owned())
+            );
+            assert_eq!(
+                format_value("salary_min", &Value::Real(85000.5)),
+                Some("85000.5".to_owned())
+            );
+            assert_eq!(format_value("notes", &Value::Null), None);
+        }
+    }
+}
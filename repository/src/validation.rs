@@ -0,0 +1,322 @@
+//! Check that a job application makes sense before it is saved
+//!
+//! Both backends run [validate_job_application] before writing, so a job application is rejected the same way no matter
+//! where it is stored, even when only one database would have refused it (like a company too long for MySQL).
+//! The frontends can use the checks for single fields to point out a problem as soon as it is entered.
+
+use std::fmt::Display;
+
+use time::Date;
+
+use crate::job_application_model::{HumanResponse, JobApplication, NewJobApplication};
+
+/// A problem with one field of a job application
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// The column name of the field, the same as [JobApplicationField::name](crate::job_application_model::JobApplicationField::name)
+    pub field: &'static str,
+    /// What is wrong, written to be shown to the user
+    pub message: String,
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// The label, whether it is required, and the most characters each text column can hold
+///
+/// The lengths are the sizes of the `VARCHAR` columns in the MySQL schema.
+const TEXT_FIELDS: [(&str, &str, bool, usize); 10] = [
+    ("source", "Source", false, 60),
+    ("company", "Company", true, 60),
+    ("job_title", "Job title", true, 255),
+    ("application_website", "Website", false, 255),
+    ("salary_currency", "Currency", false, 3),
+    ("equity", "Equity", false, 255),
+    ("bonus", "Bonus", false, 255),
+    ("city", "City", false, 60),
+    ("region", "Region", false, 60),
+    ("country", "Country", false, 60),
+];
+
+/// Check the value of a text field, using its column name
+///
+/// `company` and `job_title` can't be empty, and nothing can be longer than the database can store.
+/// Fields that aren't limited, like `notes`, are always valid.
+pub fn check_text_field(field: &str, value: &str) -> Result<(), String> {
+    let Some((_, label, required, max_length)) =
+        TEXT_FIELDS.iter().find(|(name, ..)| *name == field)
+    else {
+        return Ok(());
+    };
+
+    if *required && value.trim().is_empty() {
+        Err(format!("{label} can't be empty"))
+    } else if value.chars().count() > *max_length {
+        Err(format!(
+            "{label} can't be longer than {max_length} characters"
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that a website looks like a web address, like `https://example.com/jobs/1` or `example.com/jobs`
+pub fn check_website(website: &str) -> Result<(), String> {
+    let invalid = || Err(format!("{website} is not a web address"));
+
+    if website.chars().any(char::is_whitespace) {
+        return invalid();
+    }
+    // Only web addresses make sense here, but the scheme can be left out
+    let address = match website.split_once("://") {
+        Some((scheme, address))
+            if scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https") =>
+        {
+            address
+        }
+        Some(_) => return invalid(),
+        None => website,
+    };
+
+    let host = address
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .rsplit('@')
+        .next()
+        .unwrap_or_default();
+    let (host, port) = host.split_once(':').unwrap_or((host, ""));
+    let is_valid_label = |label: &str| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+    };
+
+    if (host.eq_ignore_ascii_case("localhost") || host.contains('.'))
+        && host.split('.').all(is_valid_label)
+        && port.chars().all(|c| c.is_ascii_digit())
+    {
+        Ok(())
+    } else {
+        invalid()
+    }
+}
+
+/// Check that a human response date goes with the response, and isn't before the job application was sent
+pub fn check_human_response_date(
+    application_date: Date,
+    human_response: HumanResponse,
+    human_response_date: Option<Date>,
+) -> Result<(), String> {
+    match human_response_date {
+        Some(_) if human_response == HumanResponse::None => {
+            Err("There can't be a response date without a response".to_owned())
+        }
+        Some(human_response_date) if human_response_date < application_date => {
+            Err("The response date can't be before the application date".to_owned())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The text fields of a [JobApplication] or [NewJobApplication] that can be invalid, keyed by column name
+///
+/// Both have the same fields, so this works for either.
+macro_rules! text_fields {
+    ($job_application:expr) => {
+        [
+            ("source", Some(&$job_application.source)),
+            ("company", Some(&$job_application.company)),
+            ("job_title", Some(&$job_application.job_title)),
+            (
+                "application_website",
+                $job_application.application_website.as_ref(),
+            ),
+            ("salary_currency", $job_application.salary_currency.as_ref()),
+            ("equity", $job_application.equity.as_ref()),
+            ("bonus", $job_application.bonus.as_ref()),
+            ("city", $job_application.city.as_ref()),
+            ("region", $job_application.region.as_ref()),
+            ("country", $job_application.country.as_ref()),
+        ]
+    };
+}
+
+/// Check every field of a job application, returning every problem found
+///
+/// Custom fields are checked against their definitions by the backends instead, since those are stored in the database.
+pub fn validate_job_application(job_application: &JobApplication) -> Result<(), Vec<FieldError>> {
+    validate_fields(
+        text_fields!(job_application),
+        job_application.application_date,
+        job_application.human_response,
+        job_application.human_response_date,
+    )
+}
+
+/// Check every field of a job application that hasn't been saved yet, like [validate_job_application]
+pub fn validate_new_job_application(
+    job_application: &NewJobApplication,
+) -> Result<(), Vec<FieldError>> {
+    validate_fields(
+        text_fields!(job_application),
+        job_application.application_date,
+        job_application.human_response,
+        job_application.human_response_date,
+    )
+}
+
+fn validate_fields(
+    text_fields: [(&'static str, Option<&String>); 10],
+    application_date: Date,
+    human_response: HumanResponse,
+    human_response_date: Option<Date>,
+) -> Result<(), Vec<FieldError>> {
+    let website = text_fields
+        .iter()
+        .find(|(field, _)| *field == "application_website")
+        .and_then(|(_, website)| *website);
+
+    let mut errors: Vec<FieldError> = text_fields
+        .into_iter()
+        .filter_map(|(field, value)| {
+            let message = check_text_field(field, value?).err()?;
+            Some(FieldError { field, message })
+        })
+        .collect();
+
+    // A website that is too long is already reported
+    if let Some(website) = website {
+        if !errors
+            .iter()
+            .any(|error| error.field == "application_website")
+        {
+            if let Err(message) = check_website(website) {
+                errors.push(FieldError {
+                    field: "application_website",
+                    message,
+                });
+            }
+        }
+    }
+    if let Err(message) =
+        check_human_response_date(application_date, human_response, human_response_date)
+    {
+        errors.push(FieldError {
+            field: "human_response_date",
+            message,
+        });
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::Month;
+
+    use super::*;
+    use crate::job_application_model::CustomFields;
+
+    fn job_application() -> JobApplication {
+        JobApplication {
+            id: 0,
+            version: 1,
+            source: "LinkedIn".to_owned(),
+            company: "Acme".to_owned(),
+            job_title: "Engineer".to_owned(),
+            application_date: Date::from_calendar_date(2025, Month::January, 10).unwrap(),
+            time_investment: None,
+            human_response: HumanResponse::None,
+            human_response_date: None,
+            application_website: Some("https://acme.example.com/careers?id=1".to_owned()),
+            notes: None,
+            salary_min: None,
+            salary_max: None,
+            salary_currency: Some("USD".to_owned()),
+            pay_period: None,
+            equity: None,
+            bonus: None,
+            city: None,
+            region: None,
+            country: None,
+            work_arrangement: None,
+            relocation: None,
+            visa_sponsorship: None,
+            custom_fields: CustomFields::new(),
+        }
+    }
+
+    #[test]
+    fn test_validate_job_application() {
+        assert_eq!(validate_job_application(&job_application()), Ok(()));
+
+        let invalid = JobApplication {
+            company: "  ".to_owned(),
+            job_title: "x".repeat(256),
+            human_response: HumanResponse::Rejection,
+            human_response_date: Some(Date::from_calendar_date(2025, Month::January, 9).unwrap()),
+            application_website: Some("not a website".to_owned()),
+            ..job_application()
+        };
+        assert_eq!(
+            validate_job_application(&invalid)
+                .unwrap_err()
+                .into_iter()
+                .map(|error| error.field)
+                .collect::<Vec<_>>(),
+            vec![
+                "company",
+                "job_title",
+                "application_website",
+                "human_response_date"
+            ],
+            "Every problem is found at once"
+        );
+
+        let without_response = JobApplication {
+            human_response_date: Some(Date::from_calendar_date(2025, Month::January, 11).unwrap()),
+            ..job_application()
+        };
+        assert!(validate_job_application(&without_response).is_err());
+    }
+
+    #[test]
+    fn test_check_text_field() {
+        assert!(check_text_field("company", &"é".repeat(60)).is_ok());
+        assert!(check_text_field("company", &"é".repeat(61)).is_err());
+        assert!(check_text_field("source", "").is_ok());
+        assert!(check_text_field("notes", &"x".repeat(10_000)).is_ok());
+    }
+
+    #[test]
+    fn test_check_website() {
+        for website in [
+            "https://example.com",
+            "HTTP://jobs.example.co.uk/apply/1?source=board#top",
+            "example.com/jobs",
+            "localhost:8080/jobs",
+        ] {
+            assert_eq!(check_website(website), Ok(()), "{website}");
+        }
+        for website in [
+            "example",
+            "ftp://example.com",
+            "https://",
+            "https://exa mple.com",
+            "https://-example.com",
+            "example..com",
+            "example.com:port",
+        ] {
+            assert!(check_website(website).is_err(), "{website}");
+        }
+    }
+}
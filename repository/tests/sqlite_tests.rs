@@ -2,14 +2,30 @@
 
 #![cfg(not(feature = "mysql"))]
 
+use std::path::Path;
+
 use rusqlite::{named_params, Connection};
-use time::{ext::NumericalDuration as _, Date, Month};
+use time::{
+    ext::NumericalDuration as _,
+    macros::{date, datetime},
+    Date, Month, OffsetDateTime,
+};
 
 use repository::{
+    backup::BackupRetention,
+    change_watcher::{ChangeWatcher, Changes},
     job_application_model::{
-        HumanResponse, JobApplication, JobApplicationField, PartialJobApplication,
+        Attachment, AttachmentRole, CustomField, CustomFieldType, CustomFieldValue, CustomFields,
+        HumanResponse, JobApplication, JobApplicationField, JobDescription, JournalOperation,
+        NewJobApplication, Offer, PartialJobApplication, PayPeriod, ResumeVersion,
+        ResumeVersionStats, Tag, WorkArrangement, WorkArrangementStats,
     },
-    job_application_repository::JobApplicationRepository,
+    job_application_repository::{
+        JobApplicationRepository, RepositoryError, SqliteRepository, JOURNAL_LENGTH,
+    },
+    plain_text::PlainTextRepository,
+    search_query::SearchQuery,
+    sync::{SyncChanges, SyncChoice, SyncConflict},
 };
 
 // I attempted to make the tests only test one function, but manually operating on the DB got annoying.
@@ -69,6 +85,7 @@ fn test_get_job_applications_one() -> Result<(), Box<dyn std::error::Error>> {
         conn.get_job_applications()?,
         vec![JobApplication {
             id,
+            version: 1,
             source: source.to_string(),
             company: company.to_string(),
             job_title: job_title.to_string(),
@@ -78,6 +95,19 @@ fn test_get_job_applications_one() -> Result<(), Box<dyn std::error::Error>> {
             human_response_date: Some(Date::from_calendar_date(2000, Month::January, 2).unwrap()),
             application_website: Some(application_website.to_string()),
             notes: Some(notes.to_string()),
+            salary_min: None,
+            salary_max: None,
+            salary_currency: None,
+            pay_period: None,
+            equity: None,
+            bonus: None,
+            city: None,
+            region: None,
+            country: None,
+            work_arrangement: None,
+            relocation: None,
+            visa_sponsorship: None,
+            custom_fields: CustomFields::new(),
         }],
         "Incorrect job application vec returned"
     );
@@ -130,6 +160,7 @@ fn test_get_job_applications_many() -> Result<(), Box<dyn std::error::Error>> {
         vec![
             JobApplication {
                 id: 1,
+                version: 1,
                 source: source.to_string(),
                 company: company.to_string(),
                 job_title: job_title.to_string(),
@@ -141,9 +172,23 @@ fn test_get_job_applications_many() -> Result<(), Box<dyn std::error::Error>> {
                 ),
                 application_website: Some(application_website.to_string()),
                 notes: Some(notes.to_string()),
+                salary_min: None,
+                salary_max: None,
+                salary_currency: None,
+                pay_period: None,
+                equity: None,
+                bonus: None,
+                city: None,
+                region: None,
+                country: None,
+                work_arrangement: None,
+                relocation: None,
+                visa_sponsorship: None,
+                custom_fields: CustomFields::new(),
             },
             JobApplication {
                 id: 2,
+                version: 1,
                 source: source.to_string(),
                 company: company.to_string(),
                 job_title: job_title.to_string(),
@@ -155,9 +200,23 @@ fn test_get_job_applications_many() -> Result<(), Box<dyn std::error::Error>> {
                 ),
                 application_website: Some(application_website.to_string()),
                 notes: Some(notes.to_string()),
+                salary_min: None,
+                salary_max: None,
+                salary_currency: None,
+                pay_period: None,
+                equity: None,
+                bonus: None,
+                city: None,
+                region: None,
+                country: None,
+                work_arrangement: None,
+                relocation: None,
+                visa_sponsorship: None,
+                custom_fields: CustomFields::new(),
             },
             JobApplication {
                 id: 3,
+                version: 1,
                 source: source.to_string(),
                 company: company.to_string(),
                 job_title: job_title.to_string(),
@@ -169,6 +228,19 @@ fn test_get_job_applications_many() -> Result<(), Box<dyn std::error::Error>> {
                 ),
                 application_website: Some(application_website.to_string()),
                 notes: Some(notes.to_string()),
+                salary_min: None,
+                salary_max: None,
+                salary_currency: None,
+                pay_period: None,
+                equity: None,
+                bonus: None,
+                city: None,
+                region: None,
+                country: None,
+                work_arrangement: None,
+                relocation: None,
+                visa_sponsorship: None,
+                custom_fields: CustomFields::new(),
             },
         ]
     );
@@ -187,6 +259,7 @@ fn test_get_pending_job_applications() -> Result<(), Box<dyn std::error::Error>>
     // We will only be checking id and human_response
     let job_application = JobApplication {
         id: 0,
+        version: 1,
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -196,6 +269,19 @@ fn test_get_pending_job_applications() -> Result<(), Box<dyn std::error::Error>>
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     conn.execute(
@@ -268,6 +354,7 @@ fn test_get_job_application_by_id() -> Result<(), Box<dyn std::error::Error>> {
         conn.get_job_application_by_id(2)?,
         Some(JobApplication {
             id: 2,
+            version: 1,
             source: source.to_string(),
             company: company.to_string(),
             job_title: job_title.to_string(),
@@ -277,6 +364,19 @@ fn test_get_job_application_by_id() -> Result<(), Box<dyn std::error::Error>> {
             human_response_date: Some(Date::from_calendar_date(2000, Month::January, 2).unwrap()),
             application_website: Some(application_website.to_string()),
             notes: Some(notes.to_string()),
+            salary_min: None,
+            salary_max: None,
+            salary_currency: None,
+            pay_period: None,
+            equity: None,
+            bonus: None,
+            city: None,
+            region: None,
+            country: None,
+            work_arrangement: None,
+            relocation: None,
+            visa_sponsorship: None,
+            custom_fields: CustomFields::new(),
         }),
         "Job application ID 2 should find a job application"
     );
@@ -303,8 +403,7 @@ fn test_search_job_applications() -> Result<(), Box<dyn std::error::Error>> {
     let search_string_invert_case = "sEaRcH sTrInG";
 
     // Base job application without search string
-    let job_application_base = JobApplication {
-        id: 1,
+    let job_application_base = NewJobApplication {
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -314,32 +413,46 @@ fn test_search_job_applications() -> Result<(), Box<dyn std::error::Error>> {
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     // Id for the base job application, which should not match
     let JobApplication { id: id_base, .. } = conn.insert_job_application(&job_application_base)?;
 
     // Id for job application where source matches
-    let JobApplication { id: id_source, .. } = conn.insert_job_application(&JobApplication {
+    let JobApplication { id: id_source, .. } = conn.insert_job_application(&NewJobApplication {
         source: "aaa".to_string() + search_string + "bbb",
         ..job_application_base.clone()
     })?;
 
     // Id for job application where company matches
-    let JobApplication { id: id_company, .. } = conn.insert_job_application(&JobApplication {
-        company: "aaa".to_string() + search_string,
-        ..job_application_base.clone()
-    })?;
+    let JobApplication { id: id_company, .. } =
+        conn.insert_job_application(&NewJobApplication {
+            company: "aaa".to_string() + search_string,
+            ..job_application_base.clone()
+        })?;
 
     // Id for job application where job_title matches
     let JobApplication {
         id: id_job_title, ..
-    } = conn.insert_job_application(&JobApplication {
+    } = conn.insert_job_application(&NewJobApplication {
         job_title: search_string.to_string(),
         ..job_application_base
     })?;
 
-    let job_applications = conn.search_job_applications(&search_string)?;
+    let job_applications = conn.search_job_applications(search_string)?;
 
     // Assert all job applications that should match do match
     for (name, id) in [
@@ -365,7 +478,7 @@ fn test_search_job_applications() -> Result<(), Box<dyn std::error::Error>> {
 
     // Do the same for when the query would not match if it was case sensitive
     let job_applications_inverted_query =
-        conn.search_job_applications(&search_string_invert_case)?;
+        conn.search_job_applications(search_string_invert_case)?;
 
     for (name, id) in [
         ("source", id_source),
@@ -399,6 +512,7 @@ fn test_search_by_human_response() -> Result<(), Box<dyn std::error::Error>> {
     // We will only be checking id and human_response
     let job_application = JobApplication {
         id: 0,
+        version: 1,
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -408,6 +522,19 @@ fn test_search_by_human_response() -> Result<(), Box<dyn std::error::Error>> {
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     conn.execute(
@@ -488,8 +615,7 @@ fn test_search_by_query_and_human_response() -> Result<(), Box<dyn std::error::E
     let search_string_invert_case = "sEaRcH sTrInG";
 
     // Base job application without search string
-    let job_application_base = JobApplication {
-        id: 1,
+    let job_application_base = NewJobApplication {
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -499,27 +625,41 @@ fn test_search_by_query_and_human_response() -> Result<(), Box<dyn std::error::E
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     // Id for the base job application, which should not match
     let JobApplication { id: id_base, .. } = conn.insert_job_application(&job_application_base)?;
 
     // Id for job application where source matches
-    let JobApplication { id: id_source, .. } = conn.insert_job_application(&JobApplication {
+    let JobApplication { id: id_source, .. } = conn.insert_job_application(&NewJobApplication {
         source: "aaa".to_string() + search_string + "bbb",
         ..job_application_base.clone()
     })?;
 
     // Id for job application where company matches
-    let JobApplication { id: id_company, .. } = conn.insert_job_application(&JobApplication {
-        company: "aaa".to_string() + search_string,
-        ..job_application_base.clone()
-    })?;
+    let JobApplication { id: id_company, .. } =
+        conn.insert_job_application(&NewJobApplication {
+            company: "aaa".to_string() + search_string,
+            ..job_application_base.clone()
+        })?;
 
     // Id for job application where job_title matches
     let JobApplication {
         id: id_job_title, ..
-    } = conn.insert_job_application(&JobApplication {
+    } = conn.insert_job_application(&NewJobApplication {
         job_title: search_string.to_string(),
         ..job_application_base.clone()
     })?;
@@ -528,14 +668,14 @@ fn test_search_by_query_and_human_response() -> Result<(), Box<dyn std::error::E
     let JobApplication {
         id: id_query_match_human_response_mismatch,
         ..
-    } = conn.insert_job_application(&JobApplication {
+    } = conn.insert_job_application(&NewJobApplication {
         source: "aaa".to_string() + search_string,
         human_response: HumanResponse::Rejection,
         ..job_application_base.clone()
     })?;
 
     let job_applications =
-        conn.search_by_query_and_human_response(&search_string, HumanResponse::None)?;
+        conn.search_by_query_and_human_response(search_string, HumanResponse::None)?;
 
     // Assert all job applications that should match do match
     for (name, id) in [
@@ -569,7 +709,7 @@ fn test_search_by_query_and_human_response() -> Result<(), Box<dyn std::error::E
 
     // Do the same for when the query would not match if it was case sensitive
     let job_applications_inverted_query =
-        conn.search_by_query_and_human_response(&search_string_invert_case, HumanResponse::None)?;
+        conn.search_by_query_and_human_response(search_string_invert_case, HumanResponse::None)?;
 
     for (name, id) in [
         ("source", id_source),
@@ -607,8 +747,7 @@ fn test_search_by_query_and_human_response() -> Result<(), Box<dyn std::error::E
 fn test_insert_job_application() -> Result<(), Box<dyn std::error::Error>> {
     let mut conn = get_memory_connection()?;
 
-    let job_application = JobApplication {
-        id: 0,
+    let job_application = NewJobApplication {
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -618,6 +757,19 @@ fn test_insert_job_application() -> Result<(), Box<dyn std::error::Error>> {
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     let JobApplication { id: id_1, .. } = conn.insert_job_application(&job_application)?;
@@ -626,14 +778,11 @@ fn test_insert_job_application() -> Result<(), Box<dyn std::error::Error>> {
 
     assert_eq!(
         conn.get_job_application_by_id(id_1)?,
-        Some(JobApplication {
-            id: id_1,
-            ..job_application.clone()
-        }),
+        Some(job_application.clone().with_id(id_1)),
         "The job application should be able to be retrieved after being inserted"
     );
 
-    let job_application_2 = JobApplication {
+    let job_application_2 = NewJobApplication {
         time_investment: Some(90.seconds()),
         human_response: HumanResponse::Rejection,
         human_response_date: Some(Date::from_calendar_date(2000, Month::February, 2).unwrap()),
@@ -648,23 +797,62 @@ fn test_insert_job_application() -> Result<(), Box<dyn std::error::Error>> {
 
     assert_eq!(
         conn.get_job_application_by_id(id_2)?,
-        Some(JobApplication {
-            id: id_2,
-            ..job_application_2
-        }),
+        Some(job_application_2.clone().with_id(id_2)),
         "The second job application should be able to be retrieved after being inserted"
     );
 
     Ok(())
 }
 
+/// Test [NewJobApplication::builder] defaults and saving what it builds
+#[test]
+fn test_new_job_application_builder() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let today = OffsetDateTime::now_local()
+        .unwrap_or_else(|_| OffsetDateTime::now_utc())
+        .date();
+    let minimal = NewJobApplication::builder("LinkedIn", "Acme", "Engineer").build();
+    assert_eq!(minimal.application_date, today, "It should be sent today");
+    assert_eq!(minimal.human_response, HumanResponse::None);
+    assert_eq!(minimal.human_response_date, None);
+    assert_eq!(minimal.custom_fields, CustomFields::new());
+
+    let application_date = Date::from_calendar_date(2025, Month::March, 3).unwrap();
+    let response_date = Date::from_calendar_date(2025, Month::March, 10).unwrap();
+    let new_job_application = NewJobApplication::builder("Referral", "Acme", "Engineer")
+        .application_date(application_date)
+        .human_response(HumanResponse::InterviewRequest, response_date)
+        .application_website("https://acme.example.com/jobs/1")
+        .salary(100_000.0, 120_000.0, "USD", PayPeriod::Yearly)
+        .city("Austin")
+        .work_arrangement(WorkArrangement::Hybrid)
+        .relocation(false)
+        .build();
+    assert_eq!(new_job_application.salary_min, Some(100_000.0));
+    assert_eq!(new_job_application.pay_period, Some(PayPeriod::Yearly));
+    assert_eq!(new_job_application.region, None);
+
+    let job_application = conn.insert_job_application(&new_job_application)?;
+    assert_eq!(job_application, new_job_application.clone().with_id(1));
+    assert_eq!(
+        conn.get_job_application_by_id(1)?,
+        Some(job_application.clone())
+    );
+    assert_eq!(
+        NewJobApplication::from(job_application),
+        new_job_application
+    );
+
+    Ok(())
+}
+
 /// Test [JobApplicationRepository::update_human_response] with all three human response variants
 #[test]
 fn test_update_human_response() -> Result<(), Box<dyn std::error::Error>> {
     let mut conn = get_memory_connection()?;
 
-    let job_application = JobApplication {
-        id: 0,
+    let job_application = NewJobApplication {
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -674,6 +862,19 @@ fn test_update_human_response() -> Result<(), Box<dyn std::error::Error>> {
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     let inserted = conn.insert_job_application(&job_application)?;
@@ -692,6 +893,7 @@ fn test_update_human_response() -> Result<(), Box<dyn std::error::Error>> {
     assert_eq!(
         conn.get_job_application_by_id(inserted.id)?,
         Some(JobApplication {
+            version: 2,
             human_response: HumanResponse::Rejection,
             human_response_date: Some(Date::from_calendar_date(2000, Month::February, 2).unwrap()),
             ..inserted.clone()
@@ -710,6 +912,7 @@ fn test_update_human_response() -> Result<(), Box<dyn std::error::Error>> {
     assert_eq!(
         conn.get_job_application_by_id(inserted.id)?,
         Some(JobApplication {
+            version: 3,
             human_response: HumanResponse::InterviewRequest,
             human_response_date: Some(Date::from_calendar_date(2000, Month::February, 3).unwrap()),
             ..inserted.clone()
@@ -723,6 +926,7 @@ fn test_update_human_response() -> Result<(), Box<dyn std::error::Error>> {
     assert_eq!(
         conn.get_job_application_by_id(inserted.id)?,
         Some(JobApplication {
+            version: 4,
             human_response: HumanResponse::None,
             human_response_date: None,
             ..inserted.clone()
@@ -740,8 +944,7 @@ fn test_update_human_response() -> Result<(), Box<dyn std::error::Error>> {
 fn test_update_job_application() -> Result<(), Box<dyn std::error::Error>> {
     let mut conn = get_memory_connection()?;
 
-    let job_application = JobApplication {
-        id: 0,
+    let job_application = NewJobApplication {
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -751,6 +954,19 @@ fn test_update_job_application() -> Result<(), Box<dyn std::error::Error>> {
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     // Insert the job application twice
@@ -760,6 +976,7 @@ fn test_update_job_application() -> Result<(), Box<dyn std::error::Error>> {
     // Generated an updated job application from the second id
     let updated_job_application = JobApplication {
         id: job_application_2.id,
+        version: job_application_2.version,
         source: "Updated source".to_string(),
         company: "Updated company".to_string(),
         job_title: "Updated job title".to_string(),
@@ -769,6 +986,19 @@ fn test_update_job_application() -> Result<(), Box<dyn std::error::Error>> {
         human_response_date: Some(Date::from_calendar_date(2001, Month::February, 1).unwrap()),
         application_website: Some("http://example.com".to_string()),
         notes: Some("Updated notes".to_string()),
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     // Perform the update
@@ -782,7 +1012,10 @@ fn test_update_job_application() -> Result<(), Box<dyn std::error::Error>> {
 
     assert_eq!(
         conn.get_job_application_by_id(job_application_2.id)?,
-        Some(updated_job_application),
+        Some(JobApplication {
+            version: job_application_2.version + 1,
+            ..updated_job_application
+        }),
         "The matching job application should be updated"
     );
 
@@ -793,8 +1026,7 @@ fn test_update_job_application() -> Result<(), Box<dyn std::error::Error>> {
 fn test_update_job_application_invalid_id() -> Result<(), Box<dyn std::error::Error>> {
     let mut conn = get_memory_connection()?;
 
-    let job_application = JobApplication {
-        id: 0,
+    let job_application = NewJobApplication {
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -804,17 +1036,30 @@ fn test_update_job_application_invalid_id() -> Result<(), Box<dyn std::error::Er
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     // Insert
     let inserted = conn.insert_job_application(&job_application)?;
 
-    // Try to update without an ID
-    // In order to prevent changes that would affect the MySQL backend as well, errors for invalid ids may not exist
-    _ = conn.update_job_application(&JobApplication {
+    // Try to update with an id that doesn't exist
+    let result = conn.update_job_application(&JobApplication {
         id: 3,
         ..inserted.clone()
     });
+    assert!(matches!(result, Err(RepositoryError::NotFound(_))));
 
     // Assert no change was made
     assert_eq!(
@@ -826,13 +1071,67 @@ fn test_update_job_application_invalid_id() -> Result<(), Box<dyn std::error::Er
     Ok(())
 }
 
+/// Test that [JobApplicationRepository::update_job_application] refuses to save over a newer change
+#[test]
+fn test_update_job_application_conflict() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let id = insert_tag_test_job_application(&mut conn, "Test company")?;
+    let loaded = conn.get_job_application_by_id(id)?.unwrap();
+    assert_eq!(loaded.version, JobApplication::FIRST_VERSION);
+
+    // Someone else changes the job application after it was loaded
+    let theirs = conn.update_job_application(&JobApplication {
+        notes: Some("Their notes".to_owned()),
+        ..loaded.clone()
+    })?;
+    assert_eq!(theirs.version, loaded.version + 1);
+
+    // Saving the copy that was loaded first would lose their change
+    let mine = JobApplication {
+        company: "My company".to_owned(),
+        ..loaded.clone()
+    };
+    assert!(matches!(
+        conn.update_job_application(&mine),
+        Err(RepositoryError::Conflict(_))
+    ));
+    assert_eq!(conn.get_job_application_by_id(id)?, Some(theirs.clone()));
+
+    // Saving on top of the newer version works
+    let saved = conn.update_job_application(&JobApplication {
+        version: theirs.version,
+        notes: theirs.notes.clone(),
+        ..mine
+    })?;
+    assert_eq!(saved.version, theirs.version + 1);
+    assert_eq!(saved.company, "My company");
+    assert_eq!(saved.notes.as_deref(), Some("Their notes"));
+
+    // Changing only some fields also makes a new version
+    conn.update_human_response(id, HumanResponse::Rejection, None)?;
+    conn.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(id),
+        JobApplicationField::Notes(None),
+    ]))?;
+    assert_eq!(
+        conn.get_job_application_by_id(id)?.unwrap().version,
+        saved.version + 2
+    );
+    assert!(matches!(
+        conn.update_job_application(&saved),
+        Err(RepositoryError::Conflict(_))
+    ));
+
+    Ok(())
+}
+
 /// Test [JobApplicationRepository::update_job_application_partial] to make sure that unreferenced fields are not affected
 #[test]
 fn test_update_job_application_partial_partial() -> Result<(), Box<dyn std::error::Error>> {
     let mut conn = get_memory_connection()?;
 
-    let job_application = JobApplication {
-        id: 0,
+    let job_application = NewJobApplication {
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -842,6 +1141,19 @@ fn test_update_job_application_partial_partial() -> Result<(), Box<dyn std::erro
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     // Insert the job application twice so we can ensure the non-matching job application is not modified
@@ -868,6 +1180,7 @@ fn test_update_job_application_partial_partial() -> Result<(), Box<dyn std::erro
     assert_eq!(
         conn.get_job_application_by_id(job_application_2.id)?,
         Some(JobApplication {
+            version: job_application_2.version + 1,
             company: "Updated company".to_string(),
             ..job_application_2
         }),
@@ -883,8 +1196,7 @@ fn test_update_job_application_partial_full() -> Result<(), Box<dyn std::error::
     let mut conn = get_memory_connection()?;
 
     // Most of this code is copied from `test_update_job_application()`
-    let job_application = JobApplication {
-        id: 0,
+    let job_application = NewJobApplication {
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -894,6 +1206,19 @@ fn test_update_job_application_partial_full() -> Result<(), Box<dyn std::error::
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     // Insert the job application twice
@@ -903,6 +1228,7 @@ fn test_update_job_application_partial_full() -> Result<(), Box<dyn std::error::
     // Generated an updated job application from the second id
     let updated_job_application = JobApplication {
         id: job_application_2.id,
+        version: job_application_2.version + 1,
         source: "Updated source".to_string(),
         company: "Updated company".to_string(),
         job_title: "Updated job title".to_string(),
@@ -912,6 +1238,19 @@ fn test_update_job_application_partial_full() -> Result<(), Box<dyn std::error::
         human_response_date: Some(Date::from_calendar_date(2001, Month::February, 1).unwrap()),
         application_website: Some("http://example.com".to_string()),
         notes: Some("Updated notes".to_string()),
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     // Get the updated job application as a partial job application
@@ -955,8 +1294,7 @@ fn test_update_job_application_partial_missing_id() -> Result<(), Box<dyn std::e
 
     let expected_error_message = "Unable to generate SQL statement because there is no id field";
 
-    let job_application = JobApplication {
-        id: 0,
+    let job_application = NewJobApplication {
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -966,6 +1304,19 @@ fn test_update_job_application_partial_missing_id() -> Result<(), Box<dyn std::e
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     // Insert
@@ -1008,8 +1359,7 @@ fn test_update_job_application_partial_too_many_ids() -> Result<(), Box<dyn std:
     let expected_error_message =
         "Unable to generate SQL statement because there are multiple id fields";
 
-    let job_application = JobApplication {
-        id: 0,
+    let job_application = NewJobApplication {
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -1019,6 +1369,19 @@ fn test_update_job_application_partial_too_many_ids() -> Result<(), Box<dyn std:
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     // Insert
@@ -1060,8 +1423,7 @@ fn test_update_job_application_partial_too_many_ids() -> Result<(), Box<dyn std:
 fn test_update_job_application_partial_invalid_id() -> Result<(), Box<dyn std::error::Error>> {
     let mut conn = get_memory_connection()?;
 
-    let job_application = JobApplication {
-        id: 0,
+    let job_application = NewJobApplication {
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -1071,6 +1433,19 @@ fn test_update_job_application_partial_invalid_id() -> Result<(), Box<dyn std::e
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     // Insert
@@ -1082,9 +1457,9 @@ fn test_update_job_application_partial_invalid_id() -> Result<(), Box<dyn std::e
         JobApplicationField::Company("Updated company".to_string()),
     ]);
 
-    // Try to update without an ID
-    // In order to prevent changes that would affect the MySQL backend as well, errors for invalid ids may not exist
-    _ = conn.update_job_application_partial(update);
+    // Try to update with an id that doesn't exist
+    let result = conn.update_job_application_partial(update);
+    assert!(matches!(result, Err(RepositoryError::NotFound(_))));
 
     // Assert no change was made
     assert_eq!(
@@ -1103,8 +1478,7 @@ fn test_update_job_application_partial_no_change() -> Result<(), Box<dyn std::er
 
     let expected_error_message = "Unable to generate SQL statement because there are no changes";
 
-    let job_application = JobApplication {
-        id: 0,
+    let job_application = NewJobApplication {
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -1114,6 +1488,19 @@ fn test_update_job_application_partial_no_change() -> Result<(), Box<dyn std::er
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     // Insert
@@ -1149,8 +1536,7 @@ fn test_update_job_application_partial_no_change() -> Result<(), Box<dyn std::er
 fn test_delete_job_application() -> Result<(), Box<dyn std::error::Error>> {
     let mut conn = get_memory_connection()?;
 
-    let job_application = JobApplication {
-        id: 0,
+    let job_application = NewJobApplication {
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -1160,6 +1546,19 @@ fn test_delete_job_application() -> Result<(), Box<dyn std::error::Error>> {
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     // Insert the job application twice
@@ -1183,8 +1582,7 @@ fn test_delete_job_application() -> Result<(), Box<dyn std::error::Error>> {
 fn test_delete_job_application_invalid_id() -> Result<(), Box<dyn std::error::Error>> {
     let mut conn = get_memory_connection()?;
 
-    let job_application = JobApplication {
-        id: 0,
+    let job_application = NewJobApplication {
         source: "Test source".to_string(),
         company: "Test company".to_string(),
         job_title: "Test job title".to_string(),
@@ -1194,14 +1592,27 @@ fn test_delete_job_application_invalid_id() -> Result<(), Box<dyn std::error::Er
         human_response_date: None,
         application_website: None,
         notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
     };
 
     // Insert the job application twice
     let job_application_1 = conn.insert_job_application(&job_application)?;
 
     // Delete by an invalid id
-    // In order to prevent changes that would affect the MySQL backend as well, errors for invalid ids may not exist
-    _ = conn.delete_job_application(3);
+    let result = conn.delete_job_application(3);
+    assert!(matches!(result, Err(RepositoryError::NotFound(_))));
 
     assert_eq!(
         conn.get_job_applications()?,
@@ -1212,14 +1623,2644 @@ fn test_delete_job_application_invalid_id() -> Result<(), Box<dyn std::error::Er
     Ok(())
 }
 
-/// Not a test. Just a helper function to generate empty memory connections.
-fn get_memory_connection() -> Result<Connection, rusqlite::Error> {
-    let conn = Connection::open_in_memory()?;
+/// Test [JobApplicationRepository::full_text_search] to ensure all text fields are searched and results are ranked
+#[test]
+fn test_full_text_search() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
 
-    conn.execute(
-        include_str!("../src/resources/sqlite_table_definition.sql"),
-        (),
-    )?;
+    let job_application_base = NewJobApplication {
+        source: "Test source".to_string(),
+        company: "Test company".to_string(),
+        job_title: "Test job title".to_string(),
+        application_date: Date::from_calendar_date(2000, Month::January, 1).unwrap(),
+        time_investment: None,
+        human_response: HumanResponse::None,
+        human_response_date: None,
+        application_website: None,
+        notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
+    };
+
+    // Does not match
+    conn.insert_job_application(&job_application_base)?;
+
+    // Matches once, in the notes
+    let JobApplication { id: id_notes, .. } = conn.insert_job_application(&NewJobApplication {
+        notes: Some("Recruiter mentioned Kubernetes experience".to_string()),
+        ..job_application_base.clone()
+    })?;
+
+    // Matches in the website and more than once, so it should be more relevant
+    let JobApplication { id: id_website, .. } =
+        conn.insert_job_application(&NewJobApplication {
+            job_title: "Kubernetes engineer".to_string(),
+            application_website: Some("https://example.com/careers/kubernetes".to_string()),
+            ..job_application_base.clone()
+        })?;
+
+    let results = conn.full_text_search("kubernetes")?;
+
+    assert_eq!(
+        results
+            .iter()
+            .map(|result| result.job_application.id)
+            .collect::<Vec<_>>(),
+        vec![id_website, id_notes],
+        "Both matches should be returned, most relevant first"
+    );
+    assert!(
+        results[0].relevance > results[1].relevance,
+        "Relevance should be descending"
+    );
+    assert_eq!(
+        results[1].snippet, "Recruiter mentioned [Kubernetes] experience",
+        "Snippet should highlight the matching term"
+    );
+
+    Ok(())
+}
+
+/// Test [JobApplicationRepository::full_text_search] with prefixes, phrases, accents, and non-ASCII case
+#[test]
+fn test_full_text_search_query_syntax() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let job_application_base = NewJobApplication {
+        source: "Test source".to_string(),
+        company: "Test company".to_string(),
+        job_title: "Test job title".to_string(),
+        application_date: Date::from_calendar_date(2000, Month::January, 1).unwrap(),
+        time_investment: None,
+        human_response: HumanResponse::None,
+        human_response_date: None,
+        application_website: None,
+        notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
+    };
+
+    let JobApplication { id: id_accent, .. } = conn.insert_job_application(&NewJobApplication {
+        company: "ÉCOLE Société Générale".to_string(),
+        ..job_application_base.clone()
+    })?;
+
+    let JobApplication { id: id_phrase, .. } = conn.insert_job_application(&NewJobApplication {
+        job_title: "Senior backend engineer".to_string(),
+        ..job_application_base.clone()
+    })?;
+
+    let JobApplication {
+        id: id_reversed, ..
+    } = conn.insert_job_application(&NewJobApplication {
+        job_title: "Engineer, backend".to_string(),
+        ..job_application_base
+    })?;
+
+    let ids = |results: Vec<repository::job_application_model::FullTextSearchResult>| {
+        let mut ids = results
+            .into_iter()
+            .map(|result| result.job_application.id)
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids
+    };
+
+    assert_eq!(
+        ids(conn.full_text_search("societe école")?),
+        vec![id_accent],
+        "Accents and case should be ignored"
+    );
+    assert_eq!(
+        ids(conn.full_text_search("gén*")?),
+        vec![id_accent],
+        "Prefixes should match"
+    );
+    assert_eq!(
+        ids(conn.full_text_search("backend engineer")?),
+        vec![id_phrase, id_reversed],
+        "Words should match in any order"
+    );
+    assert_eq!(
+        ids(conn.full_text_search(r#""backend engineer""#)?),
+        vec![id_phrase],
+        "Phrases should only match in order"
+    );
+    assert_eq!(
+        ids(conn.full_text_search(r#"backend AND OR NOT ( " "#)?),
+        vec![],
+        "FTS5 syntax should be treated as plain words"
+    );
+    assert_eq!(
+        ids(conn.full_text_search("  ")?),
+        vec![],
+        "A blank query should not match anything"
+    );
+
+    Ok(())
+}
+
+/// Test [JobApplicationRepository::full_text_search] to ensure the index follows updates and deletes
+#[test]
+fn test_full_text_search_after_changes() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let job_application = conn.insert_job_application(&NewJobApplication {
+        source: "Test source".to_string(),
+        company: "Old company".to_string(),
+        job_title: "Test job title".to_string(),
+        application_date: Date::from_calendar_date(2000, Month::January, 1).unwrap(),
+        time_investment: None,
+        human_response: HumanResponse::None,
+        human_response_date: None,
+        application_website: None,
+        notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
+    })?;
+
+    conn.update_job_application(&JobApplication {
+        company: "New company".to_string(),
+        ..job_application.clone()
+    })?;
+
+    assert!(
+        conn.full_text_search("old")?.is_empty(),
+        "Old values should be removed from the index"
+    );
+    assert_eq!(
+        conn.full_text_search("new")?.len(),
+        1,
+        "New values should be added to the index"
+    );
+
+    conn.delete_job_application(job_application.id)?;
+
+    assert!(
+        conn.full_text_search("new")?.is_empty(),
+        "Deleted job applications should be removed from the index"
+    );
+
+    Ok(())
+}
+
+/// Test [JobApplicationRepository::query_job_applications] with each kind of filter, and with negation
+#[test]
+fn test_query_job_applications() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let job_application_base = NewJobApplication {
+        source: "LinkedIn".to_string(),
+        company: "Acme Corp".to_string(),
+        job_title: "Backend engineer".to_string(),
+        application_date: Date::from_calendar_date(2025, Month::January, 1).unwrap(),
+        time_investment: None,
+        human_response: HumanResponse::Rejection,
+        human_response_date: Some(Date::from_calendar_date(2025, Month::January, 10).unwrap()),
+        application_website: None,
+        notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::new(),
+    };
+
+    // Matches everything in the example query
+    let JobApplication { id: id_match, .. } = conn.insert_job_application(&job_application_base)?;
+    // Each of these fails exactly one filter in the example query
+    let JobApplication { id: id_company, .. } =
+        conn.insert_job_application(&NewJobApplication {
+            company: "Globex".to_string(),
+            ..job_application_base.clone()
+        })?;
+    let JobApplication { id: id_status, .. } = conn.insert_job_application(&NewJobApplication {
+        human_response: HumanResponse::None,
+        human_response_date: None,
+        ..job_application_base.clone()
+    })?;
+    let JobApplication { id: id_applied, .. } =
+        conn.insert_job_application(&NewJobApplication {
+            application_date: Date::from_calendar_date(2024, Month::December, 31).unwrap(),
+            ..job_application_base.clone()
+        })?;
+    let JobApplication { id: id_source, .. } = conn.insert_job_application(&NewJobApplication {
+        source: "Indeed".to_string(),
+        ..job_application_base.clone()
+    })?;
+    let JobApplication { id: id_phrase, .. } = conn.insert_job_application(&NewJobApplication {
+        job_title: "Engineer, backend".to_string(),
+        ..job_application_base.clone()
+    })?;
+    let JobApplication { id: id_notes, .. } = conn.insert_job_application(&NewJobApplication {
+        notes: Some("Contract position".to_string()),
+        ..job_application_base.clone()
+    })?;
+
+    // Helper to get the sorted ids returned by a query
+    let mut query_ids = |query: &str| -> Result<Vec<i32>, Box<dyn std::error::Error>> {
+        let mut ids = conn
+            .query_job_applications(&query.parse::<SearchQuery>()?)?
+            .into_iter()
+            .map(|job_application| job_application.id)
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        Ok(ids)
+    };
+
+    assert_eq!(
+        query_ids(
+            r#"company:acme status:rejected applied:>=2025-01-01 source:linkedin "backend engineer" -notes:contract"#
+        )?,
+        vec![id_match],
+        "Only the job application matching every filter should be returned"
+    );
+    assert_eq!(
+        query_ids("")?,
+        vec![id_match, id_company, id_status, id_applied, id_source, id_phrase, id_notes],
+        "An empty query should return everything"
+    );
+    assert_eq!(
+        query_ids("-company:acme")?,
+        vec![id_company],
+        "Negated field filter"
+    );
+    assert_eq!(
+        query_ids("status:none")?,
+        vec![id_status],
+        "Human response filter"
+    );
+    assert_eq!(
+        query_ids("applied:<2025-01-01")?,
+        vec![id_applied],
+        "Date filter"
+    );
+    assert_eq!(
+        query_ids("responded:>2025-01-09 -responded:2025-01-10")?,
+        vec![],
+        "Date comparisons"
+    );
+    assert_eq!(
+        query_ids("-responded:>=2025-01-01")?,
+        vec![id_status],
+        "A negated date filter should match job applications where the date is not set"
+    );
+    assert_eq!(
+        query_ids("-contract indeed")?,
+        vec![id_source],
+        "Negated and regular full text"
+    );
+    assert_eq!(
+        query_ids("title:engin* -title:\"backend engineer\"")?,
+        vec![id_phrase],
+        "Field prefixes and phrases"
+    );
+    assert_eq!(
+        query_ids("company:linkedin")?,
+        vec![],
+        "Field filters should only match their own field"
+    );
+    assert_eq!(
+        query_ids("company:acm")?,
+        vec![id_match, id_status, id_applied, id_source, id_phrase, id_notes],
+        "Field filters should match part of the field"
+    );
+    assert_eq!(
+        query_ids("company:acme_corp")?,
+        vec![],
+        "Wildcards in field filters should be matched literally"
+    );
+
+    Ok(())
+}
+
+/// Test [JobApplicationRepository::add_tag], [JobApplicationRepository::remove_tag], and listing tags
+#[test]
+fn test_tags() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let first_id = insert_tag_test_job_application(&mut conn, "First company")?;
+    let second_id = insert_tag_test_job_application(&mut conn, "Second company")?;
+
+    let remote = conn.add_tag(first_id, "remote")?;
+    let dream_job = conn.add_tag(first_id, "  Dream job ")?;
+    assert_eq!(dream_job.name, "Dream job", "Names should be trimmed");
+    assert_eq!(
+        conn.add_tag(second_id, "REMOTE")?,
+        remote,
+        "Tag names should ignore case, reusing the existing tag"
+    );
+    conn.add_tag(first_id, "remote")?;
+
+    assert_eq!(
+        conn.get_job_application_tags(first_id)?,
+        vec![dream_job.clone(), remote.clone()],
+        "Adding a tag twice should not duplicate it"
+    );
+    assert_eq!(
+        conn.get_job_application_tags(second_id)?,
+        vec![remote.clone()]
+    );
+    assert_eq!(conn.get_tags()?, vec![dream_job.clone(), remote.clone()]);
+
+    conn.remove_tag(first_id, "Remote")?;
+    // Removing a tag that isn't there should do nothing
+    conn.remove_tag(first_id, "contract")?;
+    assert_eq!(
+        conn.get_job_application_tags(first_id)?,
+        vec![dream_job.clone()]
+    );
+    assert_eq!(
+        conn.get_job_application_tags(second_id)?,
+        vec![remote.clone()],
+        "Removing a tag from one job application should not affect others"
+    );
+
+    // Tags only on job applications in the trash shouldn't be listed, and emptying the trash should remove them
+    conn.delete_job_application(first_id)?;
+    assert_eq!(conn.get_tags()?, vec![remote.clone()]);
+    conn.empty_trash()?;
+    assert_eq!(conn.get_tags()?, vec![remote]);
+
+    assert!(conn.add_tag(second_id, "   ").is_err(), "Empty tag name");
+    assert!(
+        conn.add_tag(first_id, "contract").is_err(),
+        "Tagging a job application that doesn't exist"
+    );
+
+    Ok(())
+}
+
+/// Test [JobApplicationRepository::rename_tag], including merging into an existing tag
+#[test]
+fn test_rename_tag() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let first_id = insert_tag_test_job_application(&mut conn, "First company")?;
+    let second_id = insert_tag_test_job_application(&mut conn, "Second company")?;
+
+    let Tag { id: remote_id, .. } = conn.add_tag(first_id, "remote")?;
+    conn.add_tag(first_id, "wfh")?;
+    conn.add_tag(second_id, "wfh")?;
+
+    conn.rename_tag("remote", "Remote")?;
+    assert_eq!(
+        conn.get_job_application_tags(first_id)?[0],
+        Tag {
+            id: remote_id,
+            name: "Remote".to_owned()
+        },
+        "Changing only the case should rename the tag"
+    );
+
+    // Merge "wfh" into "Remote". The first job application has both.
+    conn.rename_tag("WFH", "remote")?;
+    let expected_tags = vec![Tag {
+        id: remote_id,
+        name: "Remote".to_owned(),
+    }];
+    assert_eq!(conn.get_job_application_tags(first_id)?, expected_tags);
+    assert_eq!(conn.get_job_application_tags(second_id)?, expected_tags);
+    assert_eq!(conn.get_tags()?, expected_tags);
+
+    // Renaming a tag that doesn't exist should do nothing
+    conn.rename_tag("contract", "temporary")?;
+    assert_eq!(conn.get_tags()?, expected_tags);
+
+    Ok(())
+}
+
+/// Test the `tag:` filter of [JobApplicationRepository::query_job_applications]
+#[test]
+fn test_query_job_applications_by_tag() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let first_id = insert_tag_test_job_application(&mut conn, "First company")?;
+    let second_id = insert_tag_test_job_application(&mut conn, "Second company")?;
+    let untagged_id = insert_tag_test_job_application(&mut conn, "Untagged company")?;
+
+    conn.add_tag(first_id, "remote")?;
+    conn.add_tag(first_id, "dream job")?;
+    conn.add_tag(second_id, "remote")?;
+
+    let mut query_ids = |query: &str| -> Result<Vec<i32>, Box<dyn std::error::Error>> {
+        let mut ids = conn
+            .query_job_applications(&query.parse::<SearchQuery>()?)?
+            .into_iter()
+            .map(|job_application| job_application.id)
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        Ok(ids)
+    };
+
+    assert_eq!(query_ids("tag:Remote")?, vec![first_id, second_id]);
+    assert_eq!(query_ids("tag:\"dream job\"")?, vec![first_id]);
+    assert_eq!(
+        query_ids("-tag:\"dream job\"")?,
+        vec![second_id, untagged_id]
+    );
+    assert_eq!(query_ids("tag:remote -tag:\"dream job\"")?, vec![second_id]);
+    assert_eq!(query_ids("tag:dream")?, vec![], "Tags match by whole name");
+
+    Ok(())
+}
+
+/// Test defining custom fields, and saving job applications with custom field values
+#[test]
+fn test_custom_fields() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let agency = conn.add_custom_field("Agency", &CustomFieldType::Text)?;
+    let req_id = conn.add_custom_field(" Req ID ", &CustomFieldType::Number)?;
+    assert_eq!(req_id.name, "Req ID", "Names are trimmed");
+    assert!(
+        conn.add_custom_field("agency", &CustomFieldType::Bool)
+            .is_err(),
+        "Names are unique ignoring case"
+    );
+    assert!(conn.add_custom_field("  ", &CustomFieldType::Text).is_err());
+    assert_eq!(conn.get_custom_fields()?, vec![agency, req_id]);
+
+    // Insert with values, and read them back
+    let new_job_application = NewJobApplication {
+        source: "Test source".to_owned(),
+        company: "Test company".to_owned(),
+        job_title: "Test job title".to_owned(),
+        application_date: Date::from_calendar_date(2025, Month::January, 1).unwrap(),
+        time_investment: None,
+        human_response: HumanResponse::None,
+        human_response_date: None,
+        application_website: None,
+        notes: None,
+        salary_min: None,
+        salary_max: None,
+        salary_currency: None,
+        pay_period: None,
+        equity: None,
+        bonus: None,
+        city: None,
+        region: None,
+        country: None,
+        work_arrangement: None,
+        relocation: None,
+        visa_sponsorship: None,
+        custom_fields: CustomFields::from([
+            (
+                "Agency".to_owned(),
+                CustomFieldValue::Text("Hays".to_owned()),
+            ),
+            ("Req ID".to_owned(), CustomFieldValue::Number(1234.0)),
+        ]),
+    };
+    let mut job_application = conn.insert_job_application(&new_job_application)?;
+    assert_eq!(
+        conn.get_job_application_by_id(job_application.id)?,
+        Some(job_application.clone())
+    );
+    assert_eq!(conn.get_job_applications()?, vec![job_application.clone()]);
+
+    // Updating replaces every value
+    job_application.custom_fields =
+        CustomFields::from([("Req ID".to_owned(), CustomFieldValue::Number(99.5))]);
+    let job_application = conn.update_job_application(&job_application)?;
+    assert_eq!(
+        conn.get_job_application_by_id(job_application.id)?,
+        Some(job_application.clone())
+    );
+
+    // Values of the wrong type or for unknown fields are rejected without saving anything
+    let mut invalid = job_application.clone();
+    invalid.custom_fields =
+        CustomFields::from([("Req ID".to_owned(), CustomFieldValue::Bool(true))]);
+    assert!(conn.update_job_application(&invalid).is_err());
+    invalid.custom_fields = CustomFields::from([("Team".to_owned(), CustomFieldValue::Bool(true))]);
+    assert!(conn.insert_job_application(&invalid.into()).is_err());
+    assert_eq!(conn.get_job_applications()?, vec![job_application]);
+
+    Ok(())
+}
+
+/// Test [JobApplicationRepository::set_custom_field_value] and [JobApplicationRepository::delete_custom_field]
+#[test]
+fn test_set_custom_field_value() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    conn.add_custom_field(
+        "Channel",
+        &CustomFieldType::SingleSelect(vec!["Recruiter".to_owned(), "Referral".to_owned()]),
+    )?;
+    let id = insert_tag_test_job_application(&mut conn, "Test company")?;
+    let get_custom_fields = |conn: &mut Connection| -> Result<CustomFields, RepositoryError> {
+        Ok(conn
+            .get_job_application_by_id(id)?
+            .map(|job_application| job_application.custom_fields)
+            .unwrap_or_default())
+    };
+
+    // Names are matched ignoring case, but the value is stored under the field's name
+    let recruiter = CustomFieldValue::SingleSelect("Recruiter".to_owned());
+    conn.set_custom_field_value(id, "channel", Some(&recruiter))?;
+    assert_eq!(
+        get_custom_fields(&mut conn)?,
+        CustomFields::from([("Channel".to_owned(), recruiter)])
+    );
+
+    // Setting again overwrites the value
+    let referral = CustomFieldValue::SingleSelect("Referral".to_owned());
+    conn.set_custom_field_value(id, "Channel", Some(&referral))?;
+    assert_eq!(
+        get_custom_fields(&mut conn)?,
+        CustomFields::from([("Channel".to_owned(), referral.clone())])
+    );
+
+    assert!(conn
+        .set_custom_field_value(id, "Channel", Some(&CustomFieldValue::Number(1.0)))
+        .is_err());
+    assert!(conn
+        .set_custom_field_value(id, "Missing", Some(&referral))
+        .is_err());
+
+    // Clearing removes the value
+    conn.set_custom_field_value(id, "Channel", None)?;
+    assert_eq!(get_custom_fields(&mut conn)?, CustomFields::new());
+
+    // Deleting the field removes its values
+    conn.set_custom_field_value(id, "Channel", Some(&referral))?;
+    conn.delete_custom_field("CHANNEL")?;
+    assert_eq!(conn.get_custom_fields()?, Vec::<CustomField>::new());
+    assert_eq!(get_custom_fields(&mut conn)?, CustomFields::new());
+
+    Ok(())
+}
+
+/// Test the `field.` filters of [JobApplicationRepository::query_job_applications]
+#[test]
+fn test_query_job_applications_by_custom_field() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    conn.add_custom_field("Agency", &CustomFieldType::Text)?;
+    conn.add_custom_field("Req ID", &CustomFieldType::Number)?;
+    conn.add_custom_field("Follow up", &CustomFieldType::Date)?;
+    conn.add_custom_field("Visa", &CustomFieldType::Bool)?;
+
+    let first_id = insert_tag_test_job_application(&mut conn, "First company")?;
+    let second_id = insert_tag_test_job_application(&mut conn, "Second company")?;
+    let empty_id = insert_tag_test_job_application(&mut conn, "Empty company")?;
+
+    for (id, agency, req_id, follow_up, visa) in [
+        (first_id, "Hays", 900.0, Month::March, true),
+        (second_id, "Robert Half", 1000.0, Month::May, false),
+    ] {
+        conn.set_custom_field_value(
+            id,
+            "Agency",
+            Some(&CustomFieldValue::Text(agency.to_owned())),
+        )?;
+        conn.set_custom_field_value(id, "Req ID", Some(&CustomFieldValue::Number(req_id)))?;
+        conn.set_custom_field_value(
+            id,
+            "Follow up",
+            Some(&CustomFieldValue::Date(
+                Date::from_calendar_date(2025, follow_up, 1).unwrap(),
+            )),
+        )?;
+        conn.set_custom_field_value(id, "Visa", Some(&CustomFieldValue::Bool(visa)))?;
+    }
+
+    let mut query_ids = |query: &str| -> Result<Vec<i32>, Box<dyn std::error::Error>> {
+        let mut ids = conn
+            .query_job_applications(&query.parse::<SearchQuery>()?)?
+            .into_iter()
+            .map(|job_application| job_application.id)
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        Ok(ids)
+    };
+
+    assert_eq!(query_ids("field.agency:hays")?, vec![first_id]);
+    assert_eq!(query_ids("field.Agency:half")?, vec![second_id]);
+    // Numbers are compared as numbers, not text ("900" > "1000" as text)
+    assert_eq!(query_ids(r#"field."req id":>=1000"#)?, vec![second_id]);
+    assert_eq!(query_ids(r#"field."req id":<1000"#)?, vec![first_id]);
+    assert_eq!(query_ids(r#"field."follow up":<4/1/2025"#)?, vec![first_id]);
+    assert_eq!(query_ids("field.visa:no")?, vec![second_id]);
+    assert_eq!(
+        query_ids("-field.visa:yes")?,
+        vec![second_id, empty_id],
+        "Job applications without a value don't match, so the negation does"
+    );
+
+    assert!(query_ids("field.team:a").is_err(), "Unknown field");
+    assert!(
+        query_ids("field.agency:>a").is_err(),
+        "Text can't be ordered"
+    );
+    assert!(query_ids("field.visa:maybe").is_err(), "Not a bool");
+
+    Ok(())
+}
+
+/// Test that posted compensation is saved and can be updated like any other field
+#[test]
+fn test_compensation_fields() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let id = insert_tag_test_job_application(&mut conn, "Test company")?;
+    conn.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(id),
+        JobApplicationField::SalaryMin(Some(45.0)),
+        JobApplicationField::SalaryMax(Some(55.5)),
+        JobApplicationField::SalaryCurrency(Some("EUR".to_owned())),
+        JobApplicationField::PayPeriod(Some(PayPeriod::Hourly)),
+        JobApplicationField::Bonus(Some("10% target".to_owned())),
+    ]))?;
+
+    let job_application = conn.get_job_application_by_id(id)?.unwrap();
+    assert_eq!(job_application.salary_min, Some(45.0));
+    assert_eq!(job_application.salary_max, Some(55.5));
+    assert_eq!(job_application.salary_currency.as_deref(), Some("EUR"));
+    assert_eq!(job_application.pay_period, Some(PayPeriod::Hourly));
+    assert_eq!(job_application.equity, None);
+    assert_eq!(job_application.bonus.as_deref(), Some("10% target"));
+
+    let updated = JobApplication {
+        pay_period: Some(PayPeriod::Monthly),
+        equity: Some("0.1% over 4 years".to_owned()),
+        ..job_application
+    };
+    conn.update_job_application(&updated)?;
+    assert_eq!(
+        conn.get_job_application_by_id(id)?,
+        Some(JobApplication {
+            version: updated.version + 1,
+            ..updated
+        })
+    );
+
+    Ok(())
+}
+
+/// Test saving, replacing, and deleting offers, and that deleting a job application deletes its offer
+#[test]
+fn test_offers() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let first_id = insert_tag_test_job_application(&mut conn, "First company")?;
+    let second_id = insert_tag_test_job_application(&mut conn, "Second company")?;
+    assert_eq!(conn.get_offer(first_id)?, None);
+
+    let second_offer = Offer {
+        job_application_id: second_id,
+        base_salary: 9000.0,
+        pay_period: PayPeriod::Monthly,
+        currency: Some("USD".to_owned()),
+        bonus: None,
+        equity_value: Some(100000.0),
+        vesting_years: Some(4.0),
+        benefits: Some("Health, dental".to_owned()),
+        start_date: Some(Date::from_calendar_date(2025, Month::March, 3)?),
+        deadline: Some(Date::from_calendar_date(2025, Month::February, 14)?),
+    };
+    let first_offer = Offer {
+        job_application_id: first_id,
+        base_salary: 120000.0,
+        pay_period: PayPeriod::Yearly,
+        currency: Some("USD".to_owned()),
+        bonus: Some(10000.0),
+        equity_value: None,
+        vesting_years: None,
+        benefits: None,
+        start_date: None,
+        deadline: None,
+    };
+    conn.save_offer(&second_offer)?;
+    conn.save_offer(&first_offer)?;
+    assert_eq!(conn.get_offer(second_id)?, Some(second_offer.clone()));
+    assert_eq!(
+        conn.get_offers()?,
+        vec![first_offer.clone(), second_offer.clone()]
+    );
+
+    // Saving again replaces the offer
+    let raised_offer = Offer {
+        base_salary: 130000.0,
+        ..first_offer
+    };
+    conn.save_offer(&raised_offer)?;
+    assert_eq!(conn.get_offer(first_id)?, Some(raised_offer.clone()));
+
+    conn.delete_offer(first_id)?;
+    assert_eq!(conn.get_offers()?, vec![second_offer]);
+
+    // An offer can't exist without its job application
+    conn.delete_job_application(second_id)?;
+    assert_eq!(conn.get_offers()?, Vec::<Offer>::new());
+    conn.empty_trash()?;
+    assert!(conn.save_offer(&raised_offer).is_ok());
+    assert!(conn
+        .save_offer(&Offer {
+            job_application_id: second_id,
+            ..raised_offer
+        })
+        .is_err());
+
+    Ok(())
+}
+
+/// Test reading and writing location, work arrangement, relocation, and visa sponsorship
+#[test]
+fn test_location_fields() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let id = insert_tag_test_job_application(&mut conn, "Test company")?;
+    conn.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(id),
+        JobApplicationField::City(Some("Toronto".to_owned())),
+        JobApplicationField::Country(Some("Canada".to_owned())),
+        JobApplicationField::WorkArrangement(Some(WorkArrangement::Hybrid)),
+        JobApplicationField::VisaSponsorship(Some(false)),
+    ]))?;
+
+    let job_application = conn.get_job_application_by_id(id)?.unwrap();
+    assert_eq!(job_application.city.as_deref(), Some("Toronto"));
+    assert_eq!(job_application.region, None);
+    assert_eq!(job_application.country.as_deref(), Some("Canada"));
+    assert_eq!(job_application.location(), "Toronto, Canada");
+    assert_eq!(
+        job_application.work_arrangement,
+        Some(WorkArrangement::Hybrid)
+    );
+    assert_eq!(job_application.relocation, None);
+    assert_eq!(job_application.visa_sponsorship, Some(false));
+
+    let updated = JobApplication {
+        region: Some("Ontario".to_owned()),
+        work_arrangement: Some(WorkArrangement::Remote),
+        relocation: Some(true),
+        ..job_application
+    };
+    conn.update_job_application(&updated)?;
+    assert_eq!(
+        conn.get_job_application_by_id(id)?,
+        Some(JobApplication {
+            version: updated.version + 1,
+            ..updated
+        })
+    );
+
+    Ok(())
+}
+
+/// Test [JobApplicationRepository::query_job_applications] with location, work arrangement, and yes/no filters
+#[test]
+fn test_query_job_applications_by_location() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let berlin_id = insert_tag_test_job_application(&mut conn, "Berlin company")?;
+    let york_id = insert_tag_test_job_application(&mut conn, "New York company")?;
+    let unknown_id = insert_tag_test_job_application(&mut conn, "Unknown company")?;
+    conn.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(berlin_id),
+        JobApplicationField::City(Some("Berlin".to_owned())),
+        JobApplicationField::Country(Some("Germany".to_owned())),
+        JobApplicationField::WorkArrangement(Some(WorkArrangement::Onsite)),
+        JobApplicationField::Relocation(Some(true)),
+        JobApplicationField::VisaSponsorship(Some(true)),
+    ]))?;
+    conn.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(york_id),
+        JobApplicationField::City(Some("New York".to_owned())),
+        JobApplicationField::Region(Some("New York".to_owned())),
+        JobApplicationField::Country(Some("United States".to_owned())),
+        JobApplicationField::WorkArrangement(Some(WorkArrangement::Remote)),
+        JobApplicationField::VisaSponsorship(Some(false)),
+    ]))?;
+
+    let mut query_ids = |query: &str| -> Result<Vec<i32>, Box<dyn std::error::Error>> {
+        let mut ids = conn
+            .query_job_applications(&query.parse::<SearchQuery>()?)?
+            .into_iter()
+            .map(|job_application| job_application.id)
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        Ok(ids)
+    };
+
+    assert_eq!(query_ids("city:berlin")?, vec![berlin_id]);
+    assert_eq!(query_ids("region:\"new york\"")?, vec![york_id]);
+    assert_eq!(query_ids("location:ER")?, vec![berlin_id]);
+    assert_eq!(query_ids("location:united")?, vec![york_id]);
+    assert_eq!(
+        query_ids("-country:germany")?,
+        vec![york_id, unknown_id],
+        "Unknown locations count as not matching"
+    );
+    assert_eq!(query_ids("arrangement:remote")?, vec![york_id]);
+    assert_eq!(
+        query_ids("-arrangement:remote")?,
+        vec![berlin_id, unknown_id]
+    );
+    assert_eq!(query_ids("relocation:yes")?, vec![berlin_id]);
+    assert_eq!(query_ids("relocation:no")?, vec![]);
+    assert_eq!(query_ids("visa:no")?, vec![york_id]);
+    assert_eq!(query_ids("-visa:yes")?, vec![york_id, unknown_id]);
+
+    Ok(())
+}
+
+/// Test [JobApplicationRepository::get_work_arrangement_stats]
+#[test]
+fn test_work_arrangement_stats() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    assert_eq!(conn.get_work_arrangement_stats()?, vec![]);
+
+    for (work_arrangement, human_response) in [
+        (Some(WorkArrangement::Onsite), HumanResponse::None),
+        (Some(WorkArrangement::Remote), HumanResponse::Rejection),
+        (
+            Some(WorkArrangement::Remote),
+            HumanResponse::InterviewedThenRejected,
+        ),
+        (Some(WorkArrangement::Remote), HumanResponse::JobOffer),
+        (None, HumanResponse::InterviewRequest),
+    ] {
+        let id = insert_tag_test_job_application(&mut conn, "Test company")?;
+        conn.update_job_application_partial(PartialJobApplication(vec![
+            JobApplicationField::Id(id),
+            JobApplicationField::WorkArrangement(work_arrangement),
+            JobApplicationField::HumanResponse(human_response),
+        ]))?;
+    }
+
+    assert_eq!(
+        conn.get_work_arrangement_stats()?,
+        vec![
+            WorkArrangementStats {
+                work_arrangement: Some(WorkArrangement::Remote),
+                applications: 3,
+                pending: 0,
+                rejections: 2,
+                interviews: 2,
+                offers: 1,
+            },
+            WorkArrangementStats {
+                work_arrangement: Some(WorkArrangement::Onsite),
+                applications: 1,
+                pending: 1,
+                rejections: 0,
+                interviews: 0,
+                offers: 0,
+            },
+            WorkArrangementStats {
+                work_arrangement: None,
+                applications: 1,
+                pending: 0,
+                rejections: 0,
+                interviews: 1,
+                offers: 0,
+            },
+        ]
+    );
+
+    Ok(())
+}
+
+/// Test saving, replacing, and deleting job descriptions, and that deleting a job application deletes its description
+#[test]
+fn test_job_descriptions() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let id = insert_tag_test_job_application(&mut conn, "Test company")?;
+    assert_eq!(conn.get_job_description(id)?, None);
+
+    // Long enough that compression matters
+    let job_description = JobDescription {
+        job_application_id: id,
+        description: "We are hiring a backend engineer.\n\n- Rust\n- SQL\n".repeat(1000),
+        captured_date: Date::from_calendar_date(2025, Month::January, 2)?,
+    };
+    conn.save_job_description(&job_description)?;
+    assert_eq!(conn.get_job_description(id)?, Some(job_description.clone()));
+
+    // Saving again replaces the description
+    let updated_job_description = JobDescription {
+        description: "Remote-first, benefits include a learning budget".to_owned(),
+        captured_date: Date::from_calendar_date(2025, Month::January, 3)?,
+        ..job_description
+    };
+    conn.save_job_description(&updated_job_description)?;
+    assert_eq!(
+        conn.get_job_description(id)?,
+        Some(updated_job_description.clone())
+    );
+
+    conn.delete_job_description(id)?;
+    assert_eq!(conn.get_job_description(id)?, None);
+
+    // A description can't exist without its job application
+    conn.save_job_description(&updated_job_description)?;
+    conn.delete_job_application(id)?;
+    conn.empty_trash()?;
+    assert_eq!(conn.get_job_description(id)?, None);
+    assert!(conn.save_job_description(&updated_job_description).is_err());
+
+    Ok(())
+}
+
+/// Test that full-text search and search queries look in job descriptions, and stop once a description is replaced
+#[test]
+fn test_search_job_descriptions() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let id = insert_tag_test_job_application(&mut conn, "Description company")?;
+    insert_tag_test_job_application(&mut conn, "Other company")?;
+    conn.save_job_description(&JobDescription {
+        job_application_id: id,
+        description: "Our team maintains the Kubernetes platform".to_owned(),
+        captured_date: Date::from_calendar_date(2025, Month::January, 2)?,
+    })?;
+
+    let results = conn.full_text_search("kubernetes")?;
+    assert_eq!(
+        results
+            .iter()
+            .map(|result| result.job_application.id)
+            .collect::<Vec<_>>(),
+        vec![id]
+    );
+    assert_eq!(
+        results[0].snippet, "Our team maintains the [Kubernetes] platform",
+        "Snippet should come from the description"
+    );
+
+    // Matching the company and the description is more relevant than only the description
+    let company_id = insert_tag_test_job_application(&mut conn, "Kubernetes company")?;
+    conn.save_job_description(&JobDescription {
+        job_application_id: company_id,
+        description: "Kubernetes all day".to_owned(),
+        captured_date: Date::from_calendar_date(2025, Month::January, 2)?,
+    })?;
+    let results = conn.full_text_search("kubernetes")?;
+    assert_eq!(
+        results
+            .iter()
+            .map(|result| result.job_application.id)
+            .collect::<Vec<_>>(),
+        vec![company_id, id]
+    );
+
+    assert_eq!(
+        conn.query_job_applications(&"platform".parse::<SearchQuery>()?)?
+            .iter()
+            .map(|job_application| job_application.id)
+            .collect::<Vec<_>>(),
+        vec![id]
+    );
+
+    // Replacing the description removes the old words from the index
+    conn.save_job_description(&JobDescription {
+        job_application_id: id,
+        description: "Now hiring for Terraform".to_owned(),
+        captured_date: Date::from_calendar_date(2025, Month::January, 3)?,
+    })?;
+    assert_eq!(
+        conn.query_job_applications(&"platform".parse::<SearchQuery>()?)?,
+        vec![]
+    );
+    assert_eq!(conn.full_text_search("terraform")?.len(), 1);
+
+    Ok(())
+}
+
+/// Test attaching, reading, and detaching files, and that files with the same contents are stored once
+#[test]
+fn test_attachments() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let first_id = insert_tag_test_job_application(&mut conn, "First company")?;
+    let second_id = insert_tag_test_job_application(&mut conn, "Second company")?;
+    let added_date = Date::from_calendar_date(2025, Month::January, 2)?;
+    let resume = b"%PDF-1.7 resume".as_slice();
+    let count_files = |conn: &Connection| {
+        conn.query_row("SELECT COUNT(*) FROM attachment_files", (), |row| {
+            row.get::<usize, i64>(0)
+        })
+    };
+
+    let first_resume = conn.add_attachment(
+        first_id,
+        AttachmentRole::Resume,
+        "/home/me/resumes/resume.pdf",
+        resume,
+        added_date,
+    )?;
+    assert_eq!(
+        first_resume,
+        Attachment {
+            id: first_resume.id,
+            job_application_id: first_id,
+            role: AttachmentRole::Resume,
+            file_name: "resume.pdf".to_owned(),
+            file_hash: Attachment::hash_contents(resume),
+            size: resume.len() as i64,
+            added_date,
+        },
+        "Directories should be removed from the file name"
+    );
+
+    // Attaching the same file again returns the same attachment
+    assert_eq!(
+        conn.add_attachment(
+            first_id,
+            AttachmentRole::Resume,
+            "resume.pdf",
+            resume,
+            added_date
+        )?,
+        first_resume
+    );
+
+    let cover_letter = conn.add_attachment(
+        first_id,
+        AttachmentRole::CoverLetter,
+        "C:\\Users\\me\\cover letter.docx",
+        b"cover letter",
+        added_date,
+    )?;
+    assert_eq!(cover_letter.file_name, "cover letter.docx");
+    let second_resume = conn.add_attachment(
+        second_id,
+        AttachmentRole::Resume,
+        "resume-copy.pdf",
+        resume,
+        added_date,
+    )?;
+    assert_eq!(
+        count_files(&conn)?,
+        2,
+        "The same resume attached twice should be stored once"
+    );
+    assert_eq!(
+        conn.get_attachments(first_id)?,
+        vec![first_resume.clone(), cover_letter.clone()]
+    );
+    assert_eq!(conn.get_attachment(second_resume.id)?, Some(second_resume));
+    assert_eq!(
+        conn.get_attachment_contents(cover_letter.id)?,
+        Some(b"cover letter".to_vec())
+    );
+
+    // A file is only deleted once nothing is attached to it, including when its job application is deleted for good
+    conn.delete_attachment(first_resume.id)?;
+    assert_eq!(conn.get_attachment(first_resume.id)?, None);
+    assert_eq!(count_files(&conn)?, 2);
+    conn.delete_job_application(second_id)?;
+    assert_eq!(count_files(&conn)?, 2);
+    conn.empty_trash()?;
+    assert_eq!(count_files(&conn)?, 1);
+    conn.delete_attachment(cover_letter.id)?;
+    assert_eq!(count_files(&conn)?, 0);
+    assert_eq!(conn.get_attachment_contents(cover_letter.id)?, None);
+
+    assert!(conn
+        .add_attachment(first_id, AttachmentRole::Other, "dir/", resume, added_date)
+        .is_err());
+    assert!(conn
+        .add_attachment(
+            second_id,
+            AttachmentRole::Other,
+            "a.pdf",
+            resume,
+            added_date
+        )
+        .is_err());
+
+    Ok(())
+}
+
+/// Test adding resume versions, recording which was sent, and the stats for each version
+#[test]
+fn test_resume_versions() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    assert_eq!(conn.get_resume_versions()?, vec![]);
+    assert_eq!(conn.get_resume_version_stats()?, vec![]);
+
+    let created_date = Date::from_calendar_date(2025, Month::January, 2)?;
+    let file_hash = Attachment::hash_contents(b"%PDF-1.7 resume");
+    let first = conn.add_resume_version(" v1 ", Some(&file_hash), created_date, Some(" "))?;
+    assert_eq!(
+        first,
+        ResumeVersion {
+            id: first.id,
+            label: "v1".to_owned(),
+            file_hash: Some(file_hash),
+            created_date,
+            notes: None,
+        }
+    );
+    let second = conn.add_resume_version("v2", None, created_date, Some("Backend focus"))?;
+    let unused = conn.add_resume_version("v3", None, created_date, None)?;
+    assert_eq!(
+        conn.get_resume_versions()?,
+        vec![first.clone(), second.clone(), unused.clone()]
+    );
+
+    // Labels are unique, ignoring case, and can't be empty
+    assert!(conn
+        .add_resume_version("V1", None, created_date, None)
+        .is_err());
+    assert!(conn
+        .add_resume_version(" ", None, created_date, None)
+        .is_err());
+
+    for (resume_version_id, human_response) in [
+        (Some(first.id), HumanResponse::None),
+        (Some(first.id), HumanResponse::Rejection),
+        (Some(first.id), HumanResponse::InterviewedThenRejected),
+        (Some(first.id), HumanResponse::JobOffer),
+        (Some(second.id), HumanResponse::None),
+        (None, HumanResponse::InterviewRequest),
+    ] {
+        let id = insert_tag_test_job_application(&mut conn, "Test company")?;
+        conn.update_human_response(id, human_response, None)?;
+        conn.set_sent_resume_version(id, resume_version_id)?;
+        assert_eq!(
+            conn.get_sent_resume_version(id)?.map(|version| version.id),
+            resume_version_id
+        );
+    }
+
+    let first_stats = ResumeVersionStats {
+        resume_version_id: Some(first.id),
+        label: Some("v1".to_owned()),
+        applications: 4,
+        responses: 3,
+        interviews: 2,
+        offers: 1,
+    };
+    let second_stats = ResumeVersionStats {
+        resume_version_id: Some(second.id),
+        label: Some("v2".to_owned()),
+        applications: 1,
+        responses: 0,
+        interviews: 0,
+        offers: 0,
+    };
+    let unused_stats = ResumeVersionStats {
+        resume_version_id: Some(unused.id),
+        label: Some("v3".to_owned()),
+        applications: 0,
+        responses: 0,
+        interviews: 0,
+        offers: 0,
+    };
+    let no_version_stats = ResumeVersionStats {
+        resume_version_id: None,
+        label: None,
+        applications: 1,
+        responses: 1,
+        interviews: 1,
+        offers: 0,
+    };
+    assert_eq!(
+        conn.get_resume_version_stats()?,
+        vec![
+            first_stats.clone(),
+            second_stats.clone(),
+            unused_stats.clone(),
+            no_version_stats.clone(),
+        ]
+    );
+
+    // Recording another version replaces the first, and clearing it moves the job application to no version
+    let changed_id = insert_tag_test_job_application(&mut conn, "Changed company")?;
+    conn.set_sent_resume_version(changed_id, Some(first.id))?;
+    conn.set_sent_resume_version(changed_id, Some(second.id))?;
+    assert_eq!(
+        conn.get_sent_resume_version(changed_id)?,
+        Some(second.clone())
+    );
+    conn.set_sent_resume_version(changed_id, None)?;
+    assert_eq!(conn.get_sent_resume_version(changed_id)?, None);
+    assert!(conn.set_sent_resume_version(changed_id, Some(-1)).is_err());
+    conn.delete_job_application(changed_id)?;
+
+    // Deleting a version leaves its job applications without one
+    conn.delete_resume_version(second.id)?;
+    assert_eq!(
+        conn.get_resume_version_stats()?,
+        vec![
+            first_stats,
+            unused_stats,
+            ResumeVersionStats {
+                applications: 2,
+                ..no_version_stats
+            },
+        ]
+    );
+
+    Ok(())
+}
+
+/// Test that deleted job applications go to the trash, where they can be listed, restored, emptied, and purged
+#[test]
+fn test_trash() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let kept_id = insert_tag_test_job_application(&mut conn, "Kept company")?;
+    let first_id = insert_tag_test_job_application(&mut conn, "First company")?;
+    let second_id = insert_tag_test_job_application(&mut conn, "Second company")?;
+    conn.add_tag(first_id, "remote")?;
+    let kept = conn.get_job_application_by_id(kept_id)?.unwrap();
+    let first = conn.get_job_application_by_id(first_id)?.unwrap();
+    let second = conn.get_job_application_by_id(second_id)?.unwrap();
+    assert_eq!(conn.get_deleted_job_applications()?, vec![]);
+
+    // Job applications in the trash are left out of everything else
+    conn.delete_job_application(first_id)?;
+    conn.delete_job_application(second_id)?;
+    assert_eq!(conn.get_job_applications()?, vec![kept.clone()]);
+    assert_eq!(conn.get_job_application_by_id(first_id)?, None);
+    assert_eq!(conn.search_job_applications("company")?, vec![kept.clone()]);
+    assert_eq!(
+        conn.query_job_applications(&"company:first".parse::<SearchQuery>()?)?,
+        vec![]
+    );
+    assert_eq!(conn.full_text_search("first")?, vec![]);
+    assert_eq!(conn.get_tags()?, vec![]);
+
+    // Deleting a job application that is already in the trash can't find it, and changes nothing
+    conn.execute(
+        "UPDATE job_applications SET deleted_at = '2025-01-01 12:00:00' WHERE id = ?",
+        (first_id,),
+    )?;
+    assert!(matches!(
+        conn.delete_job_application(first_id),
+        Err(RepositoryError::NotFound(_))
+    ));
+    let deleted = conn.get_deleted_job_applications()?;
+    assert_eq!(
+        deleted
+            .iter()
+            .map(|deleted| &deleted.job_application)
+            .collect::<Vec<_>>(),
+        vec![&second, &first],
+        "Most recently deleted first"
+    );
+    assert_eq!(
+        deleted[1].deleted_at,
+        Date::from_calendar_date(2025, Month::January, 1)?.with_hms(12, 0, 0)?
+    );
+
+    // Restoring keeps everything the job application had
+    conn.restore_job_application(first_id)?;
+    assert!(
+        matches!(
+            conn.restore_job_application(kept_id),
+            Err(RepositoryError::NotFound(_))
+        ),
+        "Restoring a job application that isn't in the trash"
+    );
+    assert_eq!(
+        conn.get_job_applications()?,
+        vec![kept.clone(), first.clone()]
+    );
+    assert_eq!(conn.get_tags()?.len(), 1);
+
+    // Purging only deletes job applications that have been in the trash long enough
+    conn.delete_job_application(first_id)?;
+    conn.execute(
+        "UPDATE job_applications SET deleted_at = datetime('now', '-31 days') WHERE id = ?",
+        (first_id,),
+    )?;
+    assert_eq!(conn.purge_trash(30)?, 1);
+    assert_eq!(conn.get_deleted_job_applications()?.len(), 1);
+    assert!(matches!(
+        conn.restore_job_application(first_id),
+        Err(RepositoryError::NotFound(_))
+    ));
+    assert_eq!(conn.get_job_application_by_id(first_id)?, None);
+
+    assert_eq!(conn.empty_trash()?, 1);
+    assert_eq!(conn.get_deleted_job_applications()?, vec![]);
+    assert_eq!(conn.get_job_applications()?, vec![kept]);
+
+    Ok(())
+}
+
+/// Test that what belongs to a job application in the trash can't be read or changed by its id until it is restored
+#[test]
+fn test_trashed_job_application_details() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+    let id = insert_tag_test_job_application(&mut conn, "Test company")?;
+    let offer = Offer {
+        job_application_id: id,
+        base_salary: 120000.0,
+        pay_period: PayPeriod::Yearly,
+        currency: None,
+        bonus: None,
+        equity_value: None,
+        vesting_years: None,
+        benefits: None,
+        start_date: None,
+        deadline: None,
+    };
+    let job_description = JobDescription {
+        job_application_id: id,
+        description: "We are hiring a backend engineer.".to_owned(),
+        captured_date: Date::from_calendar_date(2025, Month::January, 2)?,
+    };
+    let added_date = Date::from_calendar_date(2025, Month::January, 3)?;
+    let tag = conn.add_tag(id, "remote")?;
+    conn.save_offer(&offer)?;
+    conn.save_job_description(&job_description)?;
+    let attachment = conn.add_attachment(
+        id,
+        AttachmentRole::Resume,
+        "resume.pdf",
+        b"resume",
+        added_date,
+    )?;
+
+    conn.delete_job_application(id)?;
+    assert_eq!(conn.get_job_application_tags(id)?, vec![]);
+    assert_eq!(conn.get_offer(id)?, None);
+    assert_eq!(conn.get_job_description(id)?, None);
+    assert_eq!(conn.get_attachments(id)?, vec![]);
+    assert!(matches!(
+        conn.add_tag(id, "onsite"),
+        Err(RepositoryError::NotFound(_))
+    ));
+    assert!(matches!(
+        conn.save_offer(&Offer {
+            base_salary: 130000.0,
+            ..offer.clone()
+        }),
+        Err(RepositoryError::NotFound(_))
+    ));
+    assert!(matches!(
+        conn.save_job_description(&job_description),
+        Err(RepositoryError::NotFound(_))
+    ));
+    assert!(matches!(
+        conn.add_attachment(
+            id,
+            AttachmentRole::CoverLetter,
+            "letter.pdf",
+            b"letter",
+            added_date
+        ),
+        Err(RepositoryError::NotFound(_))
+    ));
+
+    // Restoring it brings back everything it had, unchanged
+    conn.restore_job_application(id)?;
+    assert_eq!(conn.get_job_application_tags(id)?, vec![tag]);
+    assert_eq!(conn.get_offer(id)?, Some(offer));
+    assert_eq!(conn.get_job_description(id)?, Some(job_description));
+    assert_eq!(conn.get_attachments(id)?, vec![attachment]);
+
+    Ok(())
+}
+
+/// Test [JobApplicationRepository::undo] and [JobApplicationRepository::redo]
+#[test]
+fn test_undo_redo() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    assert_eq!(conn.undo()?, None);
+    assert_eq!(conn.redo()?, None);
+
+    conn.add_custom_field("Referrer", &CustomFieldType::Text)?;
+    let id = insert_tag_test_job_application(&mut conn, "Test company")?;
+    let original = conn.get_job_application_by_id(id)?.unwrap();
+
+    // Saving without changing anything isn't recorded
+    conn.update_job_application(&original)?;
+
+    // Undoing an edit puts back the fields and custom fields from before it
+    let edited = JobApplication {
+        company: "Edited company".to_owned(),
+        notes: Some("Edited notes".to_owned()),
+        custom_fields: CustomFields::from([(
+            "Referrer".to_owned(),
+            CustomFieldValue::Text("Someone".to_owned()),
+        )]),
+        ..original.clone()
+    };
+    conn.update_job_application(&edited)?;
+    let entry = conn.undo()?.unwrap();
+    assert_eq!(entry.job_application_id, id);
+    assert_eq!(entry.operation, JournalOperation::Update);
+    // Undoing and redoing are changes too, so each one is a new version
+    assert_eq!(
+        conn.get_job_application_by_id(id)?,
+        Some(JobApplication {
+            version: 3,
+            ..original.clone()
+        })
+    );
+    assert_eq!(
+        conn.redo()?.map(|entry| entry.operation),
+        Some(JournalOperation::Update)
+    );
+    assert_eq!(
+        conn.get_job_application_by_id(id)?,
+        Some(JobApplication {
+            version: 4,
+            ..edited.clone()
+        })
+    );
+    assert_eq!(conn.redo()?, None);
+
+    // Changes are undone newest first
+    conn.update_human_response(
+        id,
+        HumanResponse::Rejection,
+        Some(original.application_date),
+    )?;
+    conn.set_custom_field_value(id, "Referrer", None)?;
+    conn.undo()?;
+    assert_eq!(
+        conn.get_job_application_by_id(id)?.unwrap().custom_fields,
+        edited.custom_fields
+    );
+    conn.undo()?;
+    assert_eq!(
+        conn.get_job_application_by_id(id)?,
+        Some(JobApplication {
+            version: 8,
+            ..edited.clone()
+        })
+    );
+
+    // A new change can't be followed by redoing older undone changes
+    conn.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(id),
+        JobApplicationField::JobTitle("Partial job title".to_owned()),
+    ]))?;
+    assert_eq!(conn.redo()?, None);
+    conn.undo()?;
+    let edited = JobApplication {
+        version: 10,
+        ..edited
+    };
+    assert_eq!(conn.get_job_application_by_id(id)?, Some(edited.clone()));
+
+    // Undoing a delete takes the job application out of the trash
+    conn.delete_job_application(id)?;
+    assert_eq!(
+        conn.undo()?.map(|entry| entry.operation),
+        Some(JournalOperation::Delete)
+    );
+    assert_eq!(conn.get_job_application_by_id(id)?, Some(edited.clone()));
+
+    // Undoing an insert moves the job application to the trash
+    let inserted_id = insert_tag_test_job_application(&mut conn, "Inserted company")?;
+    assert_eq!(
+        conn.undo()?.map(|entry| entry.operation),
+        Some(JournalOperation::Insert)
+    );
+    assert_eq!(conn.get_job_application_by_id(inserted_id)?, None);
+    assert_eq!(conn.get_deleted_job_applications()?.len(), 1);
+    conn.redo()?;
+    assert!(conn.get_job_application_by_id(inserted_id)?.is_some());
+
+    // Only the newest changes are kept, and permanently deleted job applications can't be undone
+    for i in 0..JOURNAL_LENGTH {
+        conn.update_job_application_partial(PartialJobApplication(vec![
+            JobApplicationField::Id(inserted_id),
+            JobApplicationField::Notes(Some(i.to_string())),
+        ]))?;
+    }
+    let journal_length: u32 =
+        conn.query_row("SELECT COUNT(*) FROM journal_entries", (), |row| row.get(0))?;
+    assert_eq!(journal_length, JOURNAL_LENGTH);
+    conn.delete_job_application(inserted_id)?;
+    conn.empty_trash()?;
+    let journal_length: u32 =
+        conn.query_row("SELECT COUNT(*) FROM journal_entries", (), |row| row.get(0))?;
+    assert_eq!(journal_length, 0);
+    assert_eq!(conn.undo()?, None);
+
+    Ok(())
+}
+
+/// Test [JobApplicationRepository::with_transaction]
+#[test]
+fn test_with_transaction() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    // Everything is undone when the transaction fails, including nested transactions that succeeded
+    let result: Result<(), Box<dyn std::error::Error>> = conn.with_transaction(|tx| {
+        insert_tag_test_job_application(tx, "Rolled back company")?;
+        tx.with_transaction(|tx| insert_tag_test_job_application(tx, "Nested company"))?;
+        Err("Something went wrong".into())
+    });
+    assert!(result.is_err());
+    assert_eq!(conn.get_job_applications()?, vec![]);
+    assert_eq!(conn.undo()?, None, "The journal is rolled back too");
+
+    // A failed nested transaction only undoes its own changes
+    let kept_id = conn.with_transaction(|tx| {
+        let id = insert_tag_test_job_application(tx, "Kept company")?;
+        let nested: Result<(), RepositoryError> = tx.with_transaction(|tx| {
+            tx.delete_job_application(id)?;
+            Err(RepositoryError::Validation(
+                "Something went wrong".to_owned(),
+            ))
+        });
+        assert!(nested.is_err());
+        Ok::<_, RepositoryError>(id)
+    })?;
+    assert_eq!(
+        conn.get_job_applications()?
+            .iter()
+            .map(|job_application| job_application.id)
+            .collect::<Vec<_>>(),
+        vec![kept_id]
+    );
+
+    Ok(())
+}
+
+/// Test that failures are reported as the right kind of [RepositoryError]
+#[test]
+fn test_repository_errors() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+    let id = insert_tag_test_job_application(&mut conn, "Test company")?;
+    conn.add_custom_field("Referrer", &CustomFieldType::Text)?;
+
+    assert!(matches!(
+        conn.add_tag(id, "  "),
+        Err(RepositoryError::Validation(_))
+    ));
+    assert!(matches!(
+        conn.add_tag(id + 1, "remote"),
+        Err(RepositoryError::NotFound(_))
+    ));
+    assert!(matches!(
+        conn.set_sent_resume_version(id, Some(1)),
+        Err(RepositoryError::Constraint(_))
+    ));
+    assert!(matches!(
+        conn.add_custom_field("referrer", &CustomFieldType::Text),
+        Err(RepositoryError::Conflict(_))
+    ));
+    assert!(matches!(
+        conn.set_custom_field_value(id, "Team", None),
+        Err(RepositoryError::NotFound(_))
+    ));
+    assert!(matches!(
+        conn.update_job_application_partial(PartialJobApplication(vec![])),
+        Err(RepositoryError::Validation(_))
+    ));
+
+    Ok(())
+}
+
+/// Test that job applications are validated when inserting and updating
+#[test]
+fn test_validation() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+    let id = insert_tag_test_job_application(&mut conn, "Test company")?;
+    let job_application = conn.get_job_application_by_id(id)?.unwrap();
+
+    // Every problem is reported at once, and nothing is saved
+    let invalid = JobApplication {
+        company: "x".repeat(61),
+        application_website: Some("not a website".to_owned()),
+        ..job_application.clone()
+    };
+    let Err(RepositoryError::InvalidFields(errors)) =
+        conn.insert_job_application(&invalid.clone().into())
+    else {
+        panic!("Expected invalid fields");
+    };
+    assert_eq!(
+        errors.iter().map(|error| error.field).collect::<Vec<_>>(),
+        vec!["company", "application_website"]
+    );
+    assert!(matches!(
+        conn.update_job_application(&invalid),
+        Err(RepositoryError::InvalidFields(_))
+    ));
+    assert_eq!(conn.get_job_applications()?, vec![job_application.clone()]);
+
+    // Partial updates are checked along with the fields they don't change
+    let before_application = job_application.application_date.previous_day().unwrap();
+    assert!(matches!(
+        conn.update_human_response(id, HumanResponse::Rejection, Some(before_application)),
+        Err(RepositoryError::InvalidFields(_))
+    ));
+    assert!(matches!(
+        conn.update_job_application_partial(PartialJobApplication(vec![
+            JobApplicationField::Id(id),
+            JobApplicationField::HumanResponseDate(Some(job_application.application_date)),
+        ])),
+        Err(RepositoryError::InvalidFields(_))
+    ));
+    assert_eq!(conn.get_job_applications()?, vec![job_application]);
+    assert_eq!(
+        conn.undo()?.map(|entry| entry.operation),
+        Some(JournalOperation::Insert)
+    );
+
+    Ok(())
+}
+
+/// Test that job applications saved before validation was added can still be updated, as long as the changed fields are valid
+#[test]
+fn test_validation_legacy_rows() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+    let id = insert_tag_test_job_application(&mut conn, "Test company")?;
+    // Written directly, like an older version would have saved it
+    conn.execute(
+        "UPDATE job_applications SET application_website = 'LinkedIn', company = ? WHERE id = ?",
+        ("x".repeat(61), id),
+    )?;
+    let job_application = conn.get_job_application_by_id(id)?.unwrap();
+    let response_date = job_application.application_date.next_day().unwrap();
+
+    let updated = conn.update_human_response(id, HumanResponse::Rejection, Some(response_date))?;
+    assert_eq!(updated.human_response, HumanResponse::Rejection);
+    assert_eq!(updated.application_website.as_deref(), Some("LinkedIn"));
+    let updated = conn.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(id),
+        JobApplicationField::City(Some("Springfield".to_owned())),
+    ]))?;
+    assert_eq!(updated.city.as_deref(), Some("Springfield"));
+
+    // The fields that are changed are still checked, including the response date when the response changes
+    let Err(RepositoryError::InvalidFields(errors)) =
+        conn.update_job_application_partial(PartialJobApplication(vec![
+            JobApplicationField::Id(id),
+            JobApplicationField::ApplicationWebsite(Some("company portal".to_owned())),
+        ]))
+    else {
+        panic!("Expected invalid fields");
+    };
+    assert_eq!(
+        errors.iter().map(|error| error.field).collect::<Vec<_>>(),
+        vec!["application_website"]
+    );
+    assert!(matches!(
+        conn.update_human_response(id, HumanResponse::None, Some(response_date)),
+        Err(RepositoryError::InvalidFields(_))
+    ));
+    assert!(matches!(
+        conn.update_job_application_partial(PartialJobApplication(vec![
+            JobApplicationField::Id(id),
+            JobApplicationField::HumanResponse(HumanResponse::None),
+        ])),
+        Err(RepositoryError::InvalidFields(_))
+    ));
+
+    Ok(())
+}
+
+/// Test that the write methods return the changed job application, or [RepositoryError::NotFound] if there isn't one
+#[test]
+fn test_write_methods_return_job_application() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+    conn.add_custom_field("Referrer", &CustomFieldType::Text)?;
+    let id = insert_tag_test_job_application(&mut conn, "Test company")?;
+    conn.set_custom_field_value(
+        id,
+        "Referrer",
+        Some(&CustomFieldValue::Text("Sam".to_owned())),
+    )?;
+    let date = Date::from_calendar_date(2025, Month::March, 4)?;
+
+    let updated = conn.update_human_response(id, HumanResponse::InterviewRequest, Some(date))?;
+    assert_eq!(updated.human_response, HumanResponse::InterviewRequest);
+    assert_eq!(updated.human_response_date, Some(date));
+    assert_eq!(
+        updated.custom_fields.len(),
+        1,
+        "Custom fields are returned too"
+    );
+    assert_eq!(Some(&updated), conn.get_job_application_by_id(id)?.as_ref());
+
+    let updated = conn.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(id),
+        JobApplicationField::Company("Renamed company".to_owned()),
+    ]))?;
+    assert_eq!(updated.company, "Renamed company");
+    assert_eq!(updated.human_response, HumanResponse::InterviewRequest);
+    assert_eq!(Some(&updated), conn.get_job_application_by_id(id)?.as_ref());
+
+    let updated = conn.update_job_application(&JobApplication {
+        notes: Some("Follow up next week".to_owned()),
+        custom_fields: CustomFields::new(),
+        ..updated
+    })?;
+    assert_eq!(updated.notes.as_deref(), Some("Follow up next week"));
+    assert_eq!(updated.custom_fields, CustomFields::new());
+    assert_eq!(Some(&updated), conn.get_job_application_by_id(id)?.as_ref());
+
+    assert_eq!(conn.delete_job_application(id)?, updated);
+
+    // Job applications that don't exist, or are in the trash, aren't found
+    assert!(matches!(
+        conn.update_human_response(id, HumanResponse::Rejection, None),
+        Err(RepositoryError::NotFound(_))
+    ));
+    assert!(matches!(
+        conn.update_job_application_partial(PartialJobApplication(vec![
+            JobApplicationField::Id(id + 1),
+            JobApplicationField::Company("Missing company".to_owned()),
+        ])),
+        Err(RepositoryError::NotFound(_))
+    ));
+    assert!(matches!(
+        conn.update_job_application(&JobApplication {
+            id: id + 1,
+            ..updated
+        }),
+        Err(RepositoryError::NotFound(_))
+    ));
+    assert!(matches!(
+        conn.delete_job_application(id),
+        Err(RepositoryError::NotFound(_))
+    ));
+
+    Ok(())
+}
+
+/// Test [JobApplicationRepository::insert_many], [JobApplicationRepository::update_many], and [JobApplicationRepository::delete_many]
+#[test]
+fn test_bulk_operations() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let kept_id = insert_tag_test_job_application(&mut conn, "Kept company")?;
+    let kept = conn.get_job_application_by_id(kept_id)?.unwrap();
+
+    let inserted = conn.insert_many(&[
+        NewJobApplication {
+            company: "First company".to_owned(),
+            ..kept.clone().into()
+        },
+        NewJobApplication {
+            company: "Second company".to_owned(),
+            ..kept.clone().into()
+        },
+    ])?;
+    assert_eq!(
+        inserted
+            .iter()
+            .map(|job_application| job_application.company.as_str())
+            .collect::<Vec<_>>(),
+        vec!["First company", "Second company"]
+    );
+    assert_eq!(conn.get_job_applications()?.len(), 3);
+    for job_application in &inserted {
+        assert_eq!(
+            conn.get_job_application_by_id(job_application.id)?.as_ref(),
+            Some(job_application)
+        );
+    }
+
+    // Nothing is saved if any of them fails
+    let invalid = JobApplication {
+        custom_fields: CustomFields::from([(
+            "Missing".to_owned(),
+            CustomFieldValue::Text("Value".to_owned()),
+        )]),
+        ..kept.clone()
+    };
+    assert!(conn
+        .insert_many(&[kept.clone().into(), invalid.clone().into()])
+        .is_err());
+    assert_eq!(conn.get_job_applications()?.len(), 3);
+
+    let updated = inserted
+        .iter()
+        .map(|job_application| JobApplication {
+            notes: Some("Updated".to_owned()),
+            ..job_application.clone()
+        })
+        .collect::<Vec<_>>();
+    let updated = conn.update_many(&updated)?;
+    assert!(conn
+        .update_many(&[
+            JobApplication {
+                notes: Some("Not saved".to_owned()),
+                ..updated[0].clone()
+            },
+            JobApplication {
+                id: updated[1].id,
+                ..invalid
+            },
+        ])
+        .is_err());
+    assert_eq!(
+        conn.get_job_applications()?,
+        vec![kept.clone(), updated[0].clone(), updated[1].clone()]
+    );
+
+    // An id that is given twice can only be moved once
+    assert!(matches!(
+        conn.delete_many(&[updated[0].id, updated[0].id]),
+        Err(RepositoryError::NotFound(_))
+    ));
+    assert_eq!(conn.get_deleted_job_applications()?.len(), 0);
+
+    let deleted = conn.delete_many(&[updated[1].id, updated[0].id])?;
+    assert_eq!(deleted, vec![updated[1].clone(), updated[0].clone()]);
+    assert_eq!(conn.get_job_applications()?, vec![kept.clone()]);
+    assert_eq!(conn.get_deleted_job_applications()?.len(), 2);
+
+    // Each one is in the journal, so they can be restored one at a time
+    conn.undo()?;
+    assert_eq!(conn.get_job_applications()?, vec![kept, updated[0].clone()]);
+
+    Ok(())
+}
+
+/// Test [JobApplicationRepository::find_duplicates] and [JobApplicationRepository::merge_job_applications]
+#[test]
+fn test_duplicates_and_merge() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let keep = conn.insert_job_application(
+        &NewJobApplication::builder("LinkedIn", "Acme, Inc.", "Senior Software Engineer")
+            .application_date(Date::from_calendar_date(2025, Month::January, 10).unwrap())
+            .notes("Applied online")
+            .build(),
+    )?;
+    let merged = conn.insert_job_application(
+        &NewJobApplication::builder("Referral", "ACME", "Sr. Software Engineer")
+            .application_date(Date::from_calendar_date(2025, Month::January, 5).unwrap())
+            .human_response(
+                HumanResponse::InterviewRequest,
+                Date::from_calendar_date(2025, Month::January, 20).unwrap(),
+            )
+            .notes("Referred by a friend")
+            .build(),
+    )?;
+    insert_tag_test_job_application(&mut conn, "Someone else")?;
+
+    let duplicate = NewJobApplication::builder("Indeed", "acme", "Software Engineer, Senior")
+        .application_date(Date::from_calendar_date(2025, Month::January, 12).unwrap())
+        .build();
+    assert_eq!(
+        conn.find_duplicates(&duplicate, 30)?,
+        vec![keep.clone(), merged.clone()]
+    );
+    assert_eq!(conn.find_duplicates(&duplicate, 3)?, vec![keep.clone()]);
+
+    // Give the merged job application things to move, and `keep` a tag they share
+    conn.add_tag(keep.id, "Remote")?;
+    conn.add_tag(merged.id, "remote")?;
+    conn.add_tag(merged.id, "Referral")?;
+    conn.add_attachment(
+        merged.id,
+        AttachmentRole::Resume,
+        "resume.pdf",
+        b"resume",
+        merged.application_date,
+    )?;
+    conn.save_job_description(&JobDescription {
+        job_application_id: merged.id,
+        description: "Build distributed systems".to_owned(),
+        captured_date: merged.application_date,
+    })?;
+
+    assert!(matches!(
+        conn.merge_job_applications(keep.id, keep.id),
+        Err(RepositoryError::Validation(_))
+    ));
+    assert!(matches!(
+        conn.merge_job_applications(keep.id, 100),
+        Err(RepositoryError::NotFound(_))
+    ));
+
+    let combined = conn.merge_job_applications(keep.id, merged.id)?;
+    assert_eq!(combined.id, keep.id);
+    assert_eq!(combined.company, "Acme, Inc.");
+    assert_eq!(combined.application_date, merged.application_date);
+    assert_eq!(combined.human_response, HumanResponse::InterviewRequest);
+    assert_eq!(
+        combined.notes.as_deref(),
+        Some("Applied online\n\nReferred by a friend")
+    );
+    assert_eq!(conn.get_job_application_by_id(keep.id)?, Some(combined));
+    assert_eq!(conn.get_job_application_by_id(merged.id)?, None);
+    assert_eq!(conn.get_deleted_job_applications()?.len(), 1);
+
+    assert_eq!(
+        conn.get_job_application_tags(keep.id)?
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect::<Vec<_>>(),
+        vec!["Referral", "Remote"]
+    );
+    assert_eq!(conn.get_attachments(keep.id)?.len(), 1);
+    assert_eq!(
+        conn.get_job_description(keep.id)?
+            .map(|job_description| job_description.description),
+        Some("Build distributed systems".to_owned())
+    );
+    assert_eq!(conn.get_job_description(merged.id)?, None);
+    assert_eq!(
+        conn.full_text_search("distributed")?
+            .into_iter()
+            .map(|result| result.job_application.id)
+            .collect::<Vec<_>>(),
+        vec![keep.id],
+        "The description index should follow the description"
+    );
+
+    Ok(())
+}
+
+/// Test [JobApplicationRepository::get_job_application_activity] and the date filters that use it
+#[test]
+fn test_job_application_activity() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = get_memory_connection()?;
+
+    let application_date = date!(2025 - 01 - 01);
+    let JobApplication { id, .. } = conn.insert_job_application(
+        &NewJobApplication::builder("LinkedIn", "Acme", "Engineer")
+            .application_date(application_date)
+            .build(),
+    )?;
+
+    // A new job application was just created, and hasn't been changed since
+    let activity = conn.get_job_application_activity()?[&id];
+    assert_eq!(activity.created_at, activity.updated_at);
+    assert!((OffsetDateTime::now_utc() - activity.created_at.assume_utc()).abs() < 1.minutes());
+    assert_eq!(activity.last_activity, application_date);
+
+    // Pretend it was saved a while ago, so the change is noticeable
+    let saved_at = datetime!(2025-01-01 09:00);
+    conn.execute(
+        "UPDATE job_applications SET created_at = '2025-01-01 09:00:00', updated_at = '2025-01-01 09:00:00' WHERE id = ?",
+        (id,),
+    )?;
+    conn.update_human_response(id, HumanResponse::Rejection, Some(date!(2025 - 01 - 10)))?;
+    let activity = conn.get_job_application_activity()?[&id];
+    assert_eq!(activity.created_at, saved_at);
+    assert!(activity.updated_at > saved_at);
+    assert_eq!(activity.last_activity, date!(2025 - 01 - 10));
+
+    // Saving a job description and attaching files count as activity, even though they don't change the job application
+    conn.save_job_description(&JobDescription {
+        job_application_id: id,
+        description: "We are hiring an engineer".to_owned(),
+        captured_date: date!(2025 - 01 - 15),
+    })?;
+    assert_eq!(
+        conn.get_job_application_activity()?[&id].last_activity,
+        date!(2025 - 01 - 15)
+    );
+    conn.add_attachment(
+        id,
+        AttachmentRole::Resume,
+        "resume.pdf",
+        b"resume",
+        date!(2025 - 01 - 20),
+    )?;
+    assert_eq!(
+        conn.get_job_application_activity()?[&id].last_activity,
+        date!(2025 - 01 - 20)
+    );
+
+    let JobApplication { id: other_id, .. } = conn.insert_job_application(
+        &NewJobApplication::builder("Indeed", "Globex", "Engineer")
+            .application_date(date!(2025 - 01 - 05))
+            .build(),
+    )?;
+
+    // Helper to get the sorted ids returned by a query
+    let mut query_ids = |query: &str| -> Result<Vec<i32>, Box<dyn std::error::Error>> {
+        let mut ids = conn
+            .query_job_applications(&query.parse::<SearchQuery>()?)?
+            .into_iter()
+            .map(|job_application| job_application.id)
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        Ok(ids)
+    };
+    assert_eq!(query_ids("active:>2025-01-15")?, vec![id]);
+    assert_eq!(query_ids("active:<=2025-01-15")?, vec![other_id]);
+    assert_eq!(query_ids("created:2025-01-01")?, vec![id]);
+    assert_eq!(query_ids("created:<2025-01-01")?, vec![]);
+    let today = OffsetDateTime::now_utc().date();
+    assert_eq!(query_ids(&format!("updated:{today}"))?, vec![id, other_id]);
+
+    // Job applications in the trash are left out
+    conn.delete_job_application(other_id)?;
+    assert_eq!(
+        conn.get_job_application_activity()?
+            .into_keys()
+            .collect::<Vec<_>>(),
+        vec![id]
+    );
+
+    Ok(())
+}
+
+/// Test [ChangeWatcher] with a second connection to the same database, like the CLI while the GUI is open
+#[test]
+fn test_change_watcher() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::TempDir::new()?;
+    let path = dir.path().join("test_db.db3");
+    let mut gui_conn = Connection::open(&path)?;
+    repository::ensure_schema(&mut gui_conn)?;
+    let mut cli_conn = Connection::open(&path)?;
+    repository::ensure_schema(&mut cli_conn)?;
+
+    let kept_id = insert_tag_test_job_application(&mut gui_conn, "Kept company")?;
+    let deleted_id = insert_tag_test_job_application(&mut gui_conn, "Deleted company")?;
+    let mut watcher = ChangeWatcher::new(&mut gui_conn)?;
+    assert!(watcher.check(&mut gui_conn)?.is_empty());
+
+    // Changes from the other connection are found once, with the job applications as they are saved now
+    let inserted_id = insert_tag_test_job_application(&mut cli_conn, "Inserted company")?;
+    let edited = cli_conn.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(kept_id),
+        JobApplicationField::Notes(Some("Edited in the CLI".to_owned())),
+    ]))?;
+    cli_conn.delete_job_application(deleted_id)?;
+    let inserted = cli_conn.get_job_application_by_id(inserted_id)?.unwrap();
+    assert_eq!(
+        watcher.check(&mut gui_conn)?,
+        Changes {
+            added: vec![inserted],
+            changed: vec![edited],
+            removed: vec![deleted_id],
+        }
+    );
+    assert!(watcher.check(&mut gui_conn)?.is_empty());
+
+    // Restoring from the trash adds it back
+    cli_conn.restore_job_application(deleted_id)?;
+    let changes = watcher.check(&mut gui_conn)?;
+    assert_eq!(
+        changes
+            .added
+            .iter()
+            .map(|job_application| job_application.id)
+            .collect::<Vec<_>>(),
+        vec![deleted_id]
+    );
+    assert!(changes.changed.is_empty() && changes.removed.is_empty());
+
+    Ok(())
+}
+
+/// Test [SqliteRepository::take_backup], [SqliteRepository::get_backups], and [SqliteRepository::restore_backup]
+#[test]
+fn test_backups() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::TempDir::new()?;
+    let path = dir.path().join("test_db.db3");
+    let mut conn = Connection::open(&path)?;
+    repository::ensure_schema(&mut conn)?;
+    let retention = BackupRetention::default();
+
+    assert_eq!(conn.get_backups()?, vec![]);
+    let kept_id = insert_tag_test_job_application(&mut conn, "Kept company")?;
+    let backup = conn.take_backup(retention)?;
+    assert_eq!(
+        backup.path.parent(),
+        Some(dir.path().join("test_db-backups").as_path())
+    );
+    assert!(!backup.encrypted);
+    assert_eq!(conn.get_backups()?, vec![backup.clone()]);
+
+    // Restoring brings back the database as it was, after backing up the database as it is now
+    let later_id = insert_tag_test_job_application(&mut conn, "Later company")?;
+    conn.delete_job_application(kept_id)?;
+    let before_restore = conn.restore_backup(Path::new(&backup.file_name()), None, None)?;
+    assert_eq!(
+        conn.get_job_applications()?
+            .into_iter()
+            .map(|job_application| job_application.id)
+            .collect::<Vec<_>>(),
+        vec![kept_id]
+    );
+    assert_eq!(conn.get_backups()?, vec![before_restore.clone(), backup]);
+
+    // Which can be undone, by path as well as by name
+    conn.restore_backup(&before_restore.path, None, None)?;
+    assert!(conn.get_job_application_by_id(later_id).is_ok());
+
+    // Nothing is replaced by a backup that doesn't exist or is damaged
+    assert!(matches!(
+        conn.restore_backup(Path::new("missing.db3"), None, None),
+        Err(RepositoryError::NotFound(_))
+    ));
+    let damaged = dir.path().join("damaged.db3");
+    let mut contents = std::fs::read(&before_restore.path)?;
+    contents.truncate(contents.len() / 2);
+    std::fs::write(&damaged, contents)?;
+    assert!(matches!(
+        conn.restore_backup(&damaged, None, None),
+        Err(RepositoryError::Validation(_))
+    ));
+    let not_ats_tracking = dir.path().join("other.db3");
+    Connection::open(&not_ats_tracking)?.execute("CREATE TABLE other (id INTEGER)", ())?;
+    assert!(matches!(
+        conn.restore_backup(&not_ats_tracking, None, None),
+        Err(RepositoryError::Validation(_))
+    ));
+    assert!(conn.get_job_application_by_id(later_id).is_ok());
+
+    // A database only in memory has nowhere to keep backups
+    assert!(matches!(
+        get_memory_connection()?.take_backup(retention),
+        Err(RepositoryError::Validation(_))
+    ));
+
+    Ok(())
+}
+
+/// Test [SqliteRepository::sync_with] and [SqliteRepository::export_sync_file] with two databases, like on a laptop and a desktop
+#[test]
+fn test_sync() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::TempDir::new()?;
+    let laptop_path = dir.path().join("laptop.db3");
+    let desktop_path = dir.path().join("desktop.db3");
+    let mut laptop = Connection::open(&laptop_path)?;
+    repository::ensure_schema(&mut laptop)?;
+    let mut desktop = Connection::open(&desktop_path)?;
+    repository::ensure_schema(&mut desktop)?;
+    let mut no_conflicts = |conflict: &SyncConflict| panic!("Unexpected conflict {conflict:?}");
+    // Ids are different in each database, so job applications are found by company
+    let find = |conn: &mut Connection, company: &str| -> Result<JobApplication, RepositoryError> {
+        Ok(conn
+            .get_job_applications()?
+            .into_iter()
+            .find(|job_application| job_application.company == company)
+            .expect("Job application should be synced"))
+    };
+    let tag_names = |conn: &mut Connection, id: i32| -> Result<Vec<String>, RepositoryError> {
+        Ok(conn
+            .get_job_application_tags(id)?
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect())
+    };
+    // Changes are timed to the millisecond, so a change right after a sync could count as before it
+    let wait = || std::thread::sleep(std::time::Duration::from_millis(5));
+
+    // Each database gets what was only added to the other, with its tags
+    let laptop_id = insert_tag_test_job_application(&mut laptop, "Laptop company")?;
+    laptop.add_tag(laptop_id, "remote")?;
+    insert_tag_test_job_application(&mut desktop, "Desktop company")?;
+    insert_tag_test_job_application(&mut desktop, "Shared company")?;
+    let report = laptop.sync_with(&desktop_path, None, &mut no_conflicts)?;
+    assert_eq!(report.local.added, 2);
+    assert_eq!(report.remote.added, 1);
+    assert_eq!(report.conflicts, 0);
+    assert_eq!(laptop.get_job_applications()?.len(), 3);
+    assert_eq!(desktop.get_job_applications()?.len(), 3);
+    let desktop_id = find(&mut desktop, "Laptop company")?.id;
+    assert_eq!(tag_names(&mut desktop, desktop_id)?, vec!["remote"]);
+
+    // Syncing again without changes changes nothing
+    let report = desktop.sync_with(&laptop_path, None, &mut no_conflicts)?;
+    assert_eq!(report.local, SyncChanges::default());
+    assert_eq!(report.remote, SyncChanges::default());
+    wait();
+
+    // Different fields changed on each side are combined
+    let shared = find(&mut laptop, "Shared company")?;
+    laptop.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(shared.id),
+        JobApplicationField::Notes(Some("Laptop notes".to_owned())),
+    ]))?;
+    let desktop_shared = find(&mut desktop, "Shared company")?;
+    desktop.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(desktop_shared.id),
+        JobApplicationField::City(Some("Desktop city".to_owned())),
+    ]))?;
+    desktop.add_tag(desktop_shared.id, "onsite")?;
+    // A comma in a tag name doesn't make it two tags
+    desktop.add_tag(desktop_shared.id, "C, C++")?;
+    let report = laptop.sync_with(&desktop_path, None, &mut no_conflicts)?;
+    assert_eq!(report.local.updated, 1);
+    assert_eq!(report.remote.updated, 1);
+    for conn in [&mut laptop, &mut desktop] {
+        let synced = find(conn, "Shared company")?;
+        assert_eq!(synced.notes.as_deref(), Some("Laptop notes"));
+        assert_eq!(synced.city.as_deref(), Some("Desktop city"));
+        assert_eq!(tag_names(conn, synced.id)?, vec!["C, C++", "onsite"]);
+    }
+    wait();
+
+    // The same field changed on both sides is a conflict, and the chosen value ends up in both
+    laptop.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(shared.id),
+        JobApplicationField::Notes(Some("Newer laptop notes".to_owned())),
+    ]))?;
+    desktop.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(desktop_shared.id),
+        JobApplicationField::Notes(Some("Desktop notes".to_owned())),
+    ]))?;
+    let mut conflicts = Vec::new();
+    let report = laptop.sync_with(&desktop_path, None, &mut |conflict: &SyncConflict| {
+        conflicts.push(conflict.clone());
+        SyncChoice::Local
+    })?;
+    assert_eq!(report.conflicts, 1);
+    assert_eq!(
+        conflicts,
+        vec![SyncConflict {
+            company: "Shared company".to_owned(),
+            job_title: "Test job title".to_owned(),
+            field: "notes".to_owned(),
+            local_value: Some("Newer laptop notes".to_owned()),
+            remote_value: Some("Desktop notes".to_owned()),
+        }]
+    );
+    assert_eq!(
+        find(&mut desktop, "Shared company")?.notes.as_deref(),
+        Some("Newer laptop notes")
+    );
+    assert_eq!(
+        find(&mut laptop, "Shared company")?.notes.as_deref(),
+        Some("Newer laptop notes")
+    );
+    wait();
+
+    // Moving to the trash is synced, and so is deleting permanently
+    let trashed_id = find(&mut laptop, "Desktop company")?.id;
+    laptop.delete_job_application(trashed_id)?;
+    desktop.delete_job_application(desktop_id)?;
+    desktop.empty_trash()?;
+    let report = laptop.sync_with(&desktop_path, None, &mut no_conflicts)?;
+    assert_eq!(report.local.deleted, 1);
+    assert_eq!(report.remote.updated, 1);
+    for conn in [&mut laptop, &mut desktop] {
+        assert_eq!(
+            conn.get_job_applications()?
+                .into_iter()
+                .map(|job_application| job_application.company)
+                .collect::<Vec<_>>(),
+            vec!["Shared company"]
+        );
+        assert_eq!(conn.get_deleted_job_applications()?.len(), 1);
+    }
+    wait();
+
+    // Changes can also go through a sync file, which is synced with each database in turn
+    let sync_file = dir.path().join("changes.db3");
+    assert_eq!(desktop.export_sync_file(&sync_file, None)?, 2);
+    assert!(matches!(
+        desktop.export_sync_file(&sync_file, None),
+        Err(RepositoryError::Conflict(_))
+    ));
+    insert_tag_test_job_application(&mut laptop, "Offline company")?;
+    let report = laptop.sync_with(&sync_file, None, &mut no_conflicts)?;
+    assert_eq!(report.remote.added, 1);
+    let report = desktop.sync_with(&sync_file, None, &mut no_conflicts)?;
+    assert_eq!(report.local.added, 1);
+    find(&mut desktop, "Offline company")?;
+
+    // A database that was copied gets its own identity, so both can keep syncing
+    let copy_path = dir.path().join("copy.db3");
+    std::fs::copy(&desktop_path, &copy_path)?;
+    let mut copy = Connection::open(&copy_path)?;
+    let report = copy.sync_with(&desktop_path, None, &mut no_conflicts)?;
+    assert_eq!(report.local, SyncChanges::default());
+    assert_eq!(report.remote, SyncChanges::default());
+
+    assert!(matches!(
+        laptop.sync_with(&laptop_path, None, &mut no_conflicts),
+        Err(RepositoryError::Validation(_))
+    ));
+    assert!(matches!(
+        laptop.sync_with(&dir.path().join("missing.db3"), None, &mut no_conflicts),
+        Err(RepositoryError::NotFound(_))
+    ));
+
+    Ok(())
+}
+
+/// Test that [JobApplicationRepository::undo] doesn't lose changes made by [SqliteRepository::sync_with], which aren't in the journal
+#[test]
+fn test_undo_after_sync() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::TempDir::new()?;
+    let desktop_path = dir.path().join("desktop.db3");
+    let mut laptop = Connection::open(dir.path().join("laptop.db3"))?;
+    repository::ensure_schema(&mut laptop)?;
+    let mut desktop = Connection::open(&desktop_path)?;
+    repository::ensure_schema(&mut desktop)?;
+    let mut no_conflicts = |conflict: &SyncConflict| panic!("Unexpected conflict {conflict:?}");
+
+    let id = insert_tag_test_job_application(&mut laptop, "Shared company")?;
+    laptop.sync_with(&desktop_path, None, &mut no_conflicts)?;
+    let desktop_id = desktop.get_job_applications()?[0].id;
+    std::thread::sleep(std::time::Duration::from_millis(5));
+
+    laptop.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(id),
+        JobApplicationField::Notes(Some("Laptop notes".to_owned())),
+    ]))?;
+    desktop.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(desktop_id),
+        JobApplicationField::City(Some("Desktop city".to_owned())),
+    ]))?;
+    laptop.sync_with(&desktop_path, None, &mut no_conflicts)?;
+    let synced = laptop.get_job_application_by_id(id)?.unwrap();
+    assert_eq!(synced.notes.as_deref(), Some("Laptop notes"));
+    assert_eq!(synced.city.as_deref(), Some("Desktop city"));
+
+    // Undoing the change from before the sync would put the city back too
+    assert!(matches!(laptop.undo(), Err(RepositoryError::Conflict(_))));
+    assert_eq!(laptop.get_job_application_by_id(id)?, Some(synced.clone()));
+
+    // Changes made after the sync can still be undone and redone, but not past it
+    let updated = laptop.update_job_application(&JobApplication {
+        region: Some("Laptop region".to_owned()),
+        ..synced.clone()
+    })?;
+    laptop.undo()?;
+    assert_eq!(
+        laptop.get_job_application_by_id(id)?.unwrap().region,
+        synced.region
+    );
+    assert!(matches!(laptop.undo(), Err(RepositoryError::Conflict(_))));
+    laptop.redo()?;
+    assert_eq!(
+        laptop.get_job_application_by_id(id)?.unwrap().region,
+        updated.region
+    );
+
+    // The same goes for redoing a change that was undone before a sync
+    laptop.undo()?;
+    desktop.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(desktop_id),
+        JobApplicationField::Country(Some("Desktop country".to_owned())),
+    ]))?;
+    laptop.sync_with(&desktop_path, None, &mut no_conflicts)?;
+    assert!(matches!(laptop.redo(), Err(RepositoryError::Conflict(_))));
+    let synced = laptop.get_job_application_by_id(id)?.unwrap();
+    assert_eq!(synced.country.as_deref(), Some("Desktop country"));
+    assert_eq!(synced.region, None);
+
+    Ok(())
+}
+
+/// Test keeping job applications as plain-text files, including reading files that were changed by hand
+#[test]
+fn test_plain_text_storage() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::TempDir::new()?;
+    let mut repository = PlainTextRepository::open(dir.path())?;
+    let job_application_files = || -> std::io::Result<Vec<String>> {
+        let mut file_names = std::fs::read_dir(dir.path())?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+            .filter(|file_name| {
+                file_name
+                    .as_ref()
+                    .map_or(true, |file_name| file_name.ends_with(".md"))
+            })
+            .collect::<std::io::Result<Vec<_>>>()?;
+        file_names.sort();
+        Ok(file_names)
+    };
+    assert_eq!(job_application_files()?, Vec::<String>::new());
+    assert!(dir.path().join(".gitignore").exists());
+
+    // Each job application is written to its own file, with its tags and custom fields
+    repository.add_custom_field("Recruiter", &CustomFieldType::Text)?;
+    let acme = repository.insert_job_application(
+        &NewJobApplication::builder("LinkedIn", "Acme Corp.", "Software Engineer")
+            .application_date(date!(2025 - 01 - 02))
+            .time_investment(90.seconds())
+            .notes("Sent a follow-up email")
+            .build(),
+    )?;
+    repository.add_tag(acme.id, "remote")?;
+    repository.set_custom_field_value(
+        acme.id,
+        "Recruiter",
+        Some(&CustomFieldValue::Text("Jane".to_owned())),
+    )?;
+    let file_names = job_application_files()?;
+    assert_eq!(file_names.len(), 1);
+    assert!(file_names[0].starts_with("acme-corp-software-engineer-"));
+    let acme_path = dir.path().join(&file_names[0]);
+    let contents = std::fs::read_to_string(&acme_path)?;
+    assert!(contents.starts_with("+++\ncompany = \"Acme Corp.\"\n"));
+    assert!(contents.contains("time_investment = \"1:30\"\n"));
+    assert!(contents.contains("tags = [\"remote\"]\n"));
+    assert!(contents.contains("[custom_fields]\nRecruiter = \"Jane\"\n"));
+    assert!(contents.ends_with("+++\n\nSent a follow-up email\n"));
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("custom-fields.toml"))?,
+        "Recruiter = \"text\"\n"
+    );
+
+    // Changes made by hand, like in a merge, are read the next time anything is read
+    std::fs::write(
+        &acme_path,
+        contents
+            .replace("Sent a follow-up email", "Heard back from the recruiter")
+            .replace(
+                "[custom_fields]",
+                "human_response = \"interview request\"\n\n[custom_fields]",
+            ),
+    )?;
+    let edited = repository
+        .get_job_application_by_id(acme.id)?
+        .expect("Edited job application should still be there");
+    assert_eq!(
+        edited.notes.as_deref(),
+        Some("Heard back from the recruiter")
+    );
+    assert_eq!(edited.human_response, HumanResponse::InterviewRequest);
+    assert!(edited.version > acme.version);
+
+    // A file added by hand is given a UUID, so it stays the same job application
+    std::fs::write(
+        dir.path().join("globex.md"),
+        "+++\ncompany = \"Globex\"\njob_title = \"Developer\"\nsource = \"Referral\"\napplication_date = 2025-01-03\ntags = [\"startup\"]\n+++\n",
+    )?;
+    assert_eq!(repository.search_job_applications("Globex")?.len(), 1);
+    assert!(std::fs::read_to_string(dir.path().join("globex.md"))?.contains("\nuuid = \""));
+    assert_eq!(repository.get_job_applications()?.len(), 2);
+
+    // A file that can't be read is named in the error, until it is fixed
+    std::fs::write(
+        dir.path().join("globex.md"),
+        "+++\n<<<<<<< HEAD\ncompany = \"Globex\"\n=======\ncompany = \"Globex Corp\"\n>>>>>>> theirs\n+++\n",
+    )?;
+    match repository.get_job_applications() {
+        Err(RepositoryError::Validation(message)) => assert!(message.starts_with("globex.md: ")),
+        result => panic!("Expected a validation error, got {result:?}"),
+    }
+    std::fs::remove_file(dir.path().join("globex.md"))?;
+    assert_eq!(repository.get_job_applications()?.len(), 1);
+
+    // Deleting a job application for good removes its file
+    repository.delete_job_application(acme.id)?;
+    assert!(std::fs::read_to_string(&acme_path)?.contains("\ndeleted_at = "));
+    repository.empty_trash()?;
+    assert_eq!(job_application_files()?, Vec::<String>::new());
+
+    // The index is rebuilt from the files if it is deleted
+    repository.insert_job_application(
+        &NewJobApplication::builder("Indeed", "Initech", "Engineer")
+            .application_date(date!(2025 - 01 - 04))
+            .build(),
+    )?;
+    drop(repository);
+    std::fs::remove_file(dir.path().join(".index.db3"))?;
+    let mut repository = PlainTextRepository::open(dir.path())?;
+    assert_eq!(repository.get_job_applications()?[0].company, "Initech");
+
+    // Offers, job descriptions, attachments, and resume versions aren't kept in the files
+    assert!(matches!(
+        repository.add_resume_version("v1", None, date!(2025 - 01 - 01), None),
+        Err(RepositoryError::Validation(_))
+    ));
+
+    Ok(())
+}
+
+/// Test converting a database to plain-text files and back
+#[test]
+fn test_plain_text_conversion() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::TempDir::new()?;
+    let mut conn = get_memory_connection()?;
+    conn.add_custom_field(
+        "Stage",
+        &CustomFieldType::SingleSelect(vec!["phone".to_owned(), "onsite".to_owned()]),
+    )?;
+    let id = insert_tag_test_job_application(&mut conn, "First company")?;
+    conn.add_tag(id, "remote")?;
+    conn.set_custom_field_value(
+        id,
+        "Stage",
+        Some(&CustomFieldValue::SingleSelect("onsite".to_owned())),
+    )?;
+    conn.update_job_application_partial(PartialJobApplication(vec![
+        JobApplicationField::Id(id),
+        JobApplicationField::SalaryMin(Some(100000.0)),
+        JobApplicationField::PayPeriod(Some(PayPeriod::Yearly)),
+        JobApplicationField::WorkArrangement(Some(WorkArrangement::Hybrid)),
+    ]))?;
+    insert_tag_test_job_application(&mut conn, "Second company")?;
+
+    // Versions only count the changes made in each database, so they aren't copied
+    let without_versions = |job_applications: Vec<JobApplication>| -> Vec<JobApplication> {
+        job_applications
+            .into_iter()
+            .map(|job_application| JobApplication {
+                version: 1,
+                ..job_application
+            })
+            .collect()
+    };
+    let expected = without_versions(conn.get_job_applications()?);
+
+    let text_dir = dir.path().join("job-search");
+    assert_eq!(conn.export_plain_text(&text_dir)?, 2);
+    assert!(matches!(
+        conn.export_plain_text(&text_dir),
+        Err(RepositoryError::Conflict(_))
+    ));
+
+    let mut repository = PlainTextRepository::open(&text_dir)?;
+    assert_eq!(
+        without_versions(repository.get_job_applications()?),
+        expected
+    );
+
+    // Importing into a database that already has job applications would duplicate them
+    assert!(matches!(
+        conn.import_plain_text(&text_dir),
+        Err(RepositoryError::Conflict(_))
+    ));
+    let mut copy = get_memory_connection()?;
+    assert_eq!(copy.import_plain_text(&text_dir)?, 2);
+    let job_applications = copy.get_job_applications()?;
+    assert_eq!(without_versions(job_applications.clone()), expected);
+    assert_eq!(
+        copy.get_job_application_tags(job_applications[0].id)?[0].name,
+        "remote"
+    );
+    assert_eq!(
+        copy.get_job_application_by_id(job_applications[0].id)?
+            .map(|job_application| job_application.custom_fields),
+        Some(CustomFields::from([(
+            "Stage".to_owned(),
+            CustomFieldValue::SingleSelect("onsite".to_owned())
+        )]))
+    );
+    assert!(matches!(
+        copy.import_plain_text(&dir.path().join("missing")),
+        Err(RepositoryError::NotFound(_))
+    ));
+
+    Ok(())
+}
+
+/// Not a test. Just a helper function to insert a job application for the tag, custom field, offer, job description, attachment, and resume version tests, returning its id.
+fn insert_tag_test_job_application(
+    conn: &mut Connection,
+    company: &str,
+) -> Result<i32, RepositoryError> {
+    conn.insert_job_application(
+        &NewJobApplication::builder("Test source", company, "Test job title")
+            .application_date(Date::from_calendar_date(2025, Month::January, 1).unwrap())
+            .build(),
+    )
+    .map(|job_application| job_application.id)
+}
+
+/// Not a test. Just a helper function to generate empty memory connections.
+fn get_memory_connection() -> Result<Connection, rusqlite::Error> {
+    let mut conn = Connection::open_in_memory()?;
+
+    repository::ensure_schema(&mut conn)?;
 
     Ok(conn)
 }
@@ -15,5 +15,181 @@ CREATE TABLE job_applications (
     human_response ENUM('N','R','I','IR','J') NOT NULL DEFAULT 'N',
     human_response_date DATE,
     application_website VARCHAR(255),
+    notes TEXT,
+    -- Posted compensation. pay_period uses the first letter of hourly, daily, weekly, monthly, or yearly.
+    salary_min DOUBLE,
+    salary_max DOUBLE,
+    salary_currency VARCHAR(3),
+    pay_period ENUM('H','D','W','M','Y'),
+    equity VARCHAR(255),
+    bonus VARCHAR(255),
+    -- Where the job is. work_arrangement uses the first letter of remote, hybrid, or onsite.
+    city VARCHAR(60),
+    region VARCHAR(60),
+    country VARCHAR(60),
+    work_arrangement ENUM('R','H','O'),
+    -- Whether the company helps with relocation or sponsors visas. NULL means unknown.
+    relocation BOOLEAN,
+    visa_sponsorship BOOLEAN,
+    -- When the job application was moved to the trash, in UTC. NULL means it is not in the trash.
+    deleted_at DATETIME,
+    -- How many times the job application has been changed, so saving over a newer change can be refused
+    version INT NOT NULL DEFAULT 1,
+    -- When the job application was first saved and last changed, in UTC
+    created_at DATETIME NOT NULL DEFAULT (UTC_TIMESTAMP()),
+    updated_at DATETIME NOT NULL DEFAULT (UTC_TIMESTAMP()),
+    INDEX job_applications_deleted_at (deleted_at),
+    -- Used by full-text search. The accent and case insensitive collation applies to these matches as well.
+    FULLTEXT INDEX job_applications_fulltext (source, company, job_title, application_website, notes)
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+-- Tags that can be put on any number of job applications. The collation makes names case insensitive.
+CREATE TABLE tags (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(60) NOT NULL UNIQUE
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+-- Join table between job_applications and tags
+CREATE TABLE job_application_tags (
+    job_application_id INT NOT NULL,
+    tag_id INT NOT NULL,
+    PRIMARY KEY (job_application_id, tag_id),
+    FOREIGN KEY (job_application_id) REFERENCES job_applications (id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags (id) ON DELETE CASCADE
+);
+-- Fields defined by the user. field_type is written like `number` or `select:Recruiter,Direct`.
+CREATE TABLE custom_fields (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(60) NOT NULL UNIQUE,
+    field_type TEXT NOT NULL
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+-- The value of each custom field on each job application, written as text
+CREATE TABLE custom_field_values (
+    job_application_id INT NOT NULL,
+    custom_field_id INT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (job_application_id, custom_field_id),
+    FOREIGN KEY (job_application_id) REFERENCES job_applications (id) ON DELETE CASCADE,
+    FOREIGN KEY (custom_field_id) REFERENCES custom_fields (id) ON DELETE CASCADE
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+-- Job offers, at most one per job application
+CREATE TABLE offers (
+    job_application_id INT PRIMARY KEY,
+    base_salary DOUBLE NOT NULL,
+    pay_period ENUM('H','D','W','M','Y') NOT NULL DEFAULT 'Y',
+    currency VARCHAR(3),
+    bonus DOUBLE,
+    equity_value DOUBLE,
+    vesting_years DOUBLE,
+    benefits TEXT,
+    start_date DATE,
+    deadline DATE,
+    FOREIGN KEY (job_application_id) REFERENCES job_applications (id) ON DELETE CASCADE
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+-- Descriptions saved from job postings, at most one per job application. Searched along with job_applications by full-text search.
+CREATE TABLE job_descriptions (
+    job_application_id INT PRIMARY KEY,
+    description LONGTEXT NOT NULL,
+    captured_date DATE NOT NULL,
+    FULLTEXT INDEX job_descriptions_fulltext (description),
+    FOREIGN KEY (job_application_id) REFERENCES job_applications (id) ON DELETE CASCADE
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+-- Files attached to job applications, stored once by the SHA-256 hash of their contents
+CREATE TABLE attachment_files (
+    hash CHAR(64) PRIMARY KEY,
+    contents LONGBLOB NOT NULL,
+    size BIGINT NOT NULL
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+-- Which files are attached to which job applications. role is R (resume), C (cover letter), L (offer letter), T (take-home), or O (other).
+CREATE TABLE attachments (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    job_application_id INT NOT NULL,
+    file_hash CHAR(64) NOT NULL,
+    file_name VARCHAR(255) NOT NULL,
+    role ENUM('R','C','L','T','O') NOT NULL,
+    added_date DATE NOT NULL,
+    UNIQUE (job_application_id, file_hash, role),
+    FOREIGN KEY (job_application_id) REFERENCES job_applications (id) ON DELETE CASCADE,
+    FOREIGN KEY (file_hash) REFERENCES attachment_files (hash)
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+-- Versions of the user's resume. The collation makes labels case insensitive. file_hash is hashed the same way as attachment_files.
+CREATE TABLE resume_versions (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    label VARCHAR(60) NOT NULL UNIQUE,
+    file_hash CHAR(64),
+    created_date DATE NOT NULL,
     notes TEXT
-);
\ No newline at end of file
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+-- Which resume version was sent with each job application, at most one per job application
+CREATE TABLE sent_resume_versions (
+    job_application_id INT PRIMARY KEY,
+    resume_version_id INT NOT NULL,
+    FOREIGN KEY (job_application_id) REFERENCES job_applications (id) ON DELETE CASCADE,
+    FOREIGN KEY (resume_version_id) REFERENCES resume_versions (id) ON DELETE CASCADE
+);
+-- Changes to job applications, newest last, so they can be undone and redone
+-- operation is I (insert), U (update), D (delete), or R (restore). Undone entries are the ones that can be redone.
+-- version_before and version_after are the versions of the job application before and after the change, kept up to date as it is undone and redone.
+CREATE TABLE journal_entries (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    job_application_id INT NOT NULL,
+    operation ENUM('I','U','D','R') NOT NULL,
+    undone BOOLEAN NOT NULL DEFAULT FALSE,
+    version_before INT NOT NULL DEFAULT 1,
+    version_after INT NOT NULL DEFAULT 1,
+    FOREIGN KEY (job_application_id) REFERENCES job_applications (id) ON DELETE CASCADE
+);
+-- The job application before (is_after = FALSE) and after (is_after = TRUE) each update, with the same columns as job_applications
+CREATE TABLE journal_snapshots (
+    journal_entry_id INT NOT NULL,
+    is_after BOOLEAN NOT NULL,
+    source VARCHAR(60) NOT NULL,
+    company VARCHAR(60) NOT NULL,
+    job_title VARCHAR(255) NOT NULL,
+    application_date DATE NOT NULL,
+    time_investment TIME,
+    human_response ENUM('N','R','I','IR','J') NOT NULL,
+    human_response_date DATE,
+    application_website VARCHAR(255),
+    notes TEXT,
+    salary_min DOUBLE,
+    salary_max DOUBLE,
+    salary_currency VARCHAR(3),
+    pay_period ENUM('H','D','W','M','Y'),
+    equity VARCHAR(255),
+    bonus VARCHAR(255),
+    city VARCHAR(60),
+    region VARCHAR(60),
+    country VARCHAR(60),
+    work_arrangement ENUM('R','H','O'),
+    relocation BOOLEAN,
+    visa_sponsorship BOOLEAN,
+    PRIMARY KEY (journal_entry_id, is_after),
+    FOREIGN KEY (journal_entry_id) REFERENCES journal_entries (id) ON DELETE CASCADE
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+-- The custom field values that go with each snapshot
+CREATE TABLE journal_custom_field_values (
+    journal_entry_id INT NOT NULL,
+    is_after BOOLEAN NOT NULL,
+    custom_field_id INT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (journal_entry_id, is_after, custom_field_id),
+    FOREIGN KEY (journal_entry_id) REFERENCES journal_entries (id) ON DELETE CASCADE,
+    FOREIGN KEY (custom_field_id) REFERENCES custom_fields (id) ON DELETE CASCADE
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+-- Who changed what in each job application, kept after the job application is permanently deleted
+CREATE TABLE audit_log (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    job_application_id INT NOT NULL,
+    operation ENUM('I','U','D','R','P') NOT NULL,
+    changed_at DATETIME NOT NULL,
+    changed_by VARCHAR(255) NOT NULL,
+    INDEX audit_log_job_application_id (job_application_id)
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+-- The old and new value of each field changed by an audit log entry. NULL means the field was empty.
+CREATE TABLE audit_log_changes (
+    audit_log_id INT NOT NULL,
+    field VARCHAR(255) NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    PRIMARY KEY (audit_log_id, field),
+    FOREIGN KEY (audit_log_id) REFERENCES audit_log (id) ON DELETE CASCADE
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
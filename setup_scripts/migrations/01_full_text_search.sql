@@ -0,0 +1,5 @@
+-- Upgrade a job_applications table created before full-text search was added
+-- Converting the table makes matches accent and case insensitive, the same as a new table
+ALTER TABLE job_applications CONVERT TO CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci;
+ALTER TABLE job_applications
+ADD FULLTEXT INDEX job_applications_fulltext (source, company, job_title, application_website, notes);
@@ -0,0 +1,12 @@
+-- Add tags to a database created before tags were added
+CREATE TABLE tags (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(60) NOT NULL UNIQUE
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+CREATE TABLE job_application_tags (
+    job_application_id INT NOT NULL,
+    tag_id INT NOT NULL,
+    PRIMARY KEY (job_application_id, tag_id),
+    FOREIGN KEY (job_application_id) REFERENCES job_applications (id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags (id) ON DELETE CASCADE
+);
@@ -0,0 +1,14 @@
+-- Add custom fields to a database created before custom fields were added
+CREATE TABLE custom_fields (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(60) NOT NULL UNIQUE,
+    field_type TEXT NOT NULL
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+CREATE TABLE custom_field_values (
+    job_application_id INT NOT NULL,
+    custom_field_id INT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (job_application_id, custom_field_id),
+    FOREIGN KEY (job_application_id) REFERENCES job_applications (id) ON DELETE CASCADE,
+    FOREIGN KEY (custom_field_id) REFERENCES custom_fields (id) ON DELETE CASCADE
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
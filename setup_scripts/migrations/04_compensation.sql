@@ -0,0 +1,21 @@
+-- Add compensation and offers to a database created before they were added
+ALTER TABLE job_applications
+ADD COLUMN salary_min DOUBLE,
+ADD COLUMN salary_max DOUBLE,
+ADD COLUMN salary_currency VARCHAR(3),
+ADD COLUMN pay_period ENUM('H','D','W','M','Y'),
+ADD COLUMN equity VARCHAR(255),
+ADD COLUMN bonus VARCHAR(255);
+CREATE TABLE offers (
+    job_application_id INT PRIMARY KEY,
+    base_salary DOUBLE NOT NULL,
+    pay_period ENUM('H','D','W','M','Y') NOT NULL DEFAULT 'Y',
+    currency VARCHAR(3),
+    bonus DOUBLE,
+    equity_value DOUBLE,
+    vesting_years DOUBLE,
+    benefits TEXT,
+    start_date DATE,
+    deadline DATE,
+    FOREIGN KEY (job_application_id) REFERENCES job_applications (id) ON DELETE CASCADE
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
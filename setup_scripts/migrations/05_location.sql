@@ -0,0 +1,8 @@
+-- Add location and work arrangement to a database created before they were added
+ALTER TABLE job_applications
+ADD COLUMN city VARCHAR(60),
+ADD COLUMN region VARCHAR(60),
+ADD COLUMN country VARCHAR(60),
+ADD COLUMN work_arrangement ENUM('R','H','O'),
+ADD COLUMN relocation BOOLEAN,
+ADD COLUMN visa_sponsorship BOOLEAN;
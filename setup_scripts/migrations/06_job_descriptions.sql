@@ -0,0 +1,8 @@
+-- Add job descriptions to a database created before they were added
+CREATE TABLE job_descriptions (
+    job_application_id INT PRIMARY KEY,
+    description LONGTEXT NOT NULL,
+    captured_date DATE NOT NULL,
+    FULLTEXT INDEX job_descriptions_fulltext (description),
+    FOREIGN KEY (job_application_id) REFERENCES job_applications (id) ON DELETE CASCADE
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
@@ -0,0 +1,19 @@
+-- Add attachments to a database created before they were added
+-- Files attached to job applications, stored once by the SHA-256 hash of their contents
+CREATE TABLE attachment_files (
+    hash CHAR(64) PRIMARY KEY,
+    contents LONGBLOB NOT NULL,
+    size BIGINT NOT NULL
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+-- Which files are attached to which job applications. role is R (resume), C (cover letter), L (offer letter), T (take-home), or O (other).
+CREATE TABLE attachments (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    job_application_id INT NOT NULL,
+    file_hash CHAR(64) NOT NULL,
+    file_name VARCHAR(255) NOT NULL,
+    role ENUM('R','C','L','T','O') NOT NULL,
+    added_date DATE NOT NULL,
+    UNIQUE (job_application_id, file_hash, role),
+    FOREIGN KEY (job_application_id) REFERENCES job_applications (id) ON DELETE CASCADE,
+    FOREIGN KEY (file_hash) REFERENCES attachment_files (hash)
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
@@ -0,0 +1,14 @@
+-- Add resume versions to a database created before they were added
+CREATE TABLE resume_versions (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    label VARCHAR(60) NOT NULL UNIQUE,
+    file_hash CHAR(64),
+    created_date DATE NOT NULL,
+    notes TEXT
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+CREATE TABLE sent_resume_versions (
+    job_application_id INT PRIMARY KEY,
+    resume_version_id INT NOT NULL,
+    FOREIGN KEY (job_application_id) REFERENCES job_applications (id) ON DELETE CASCADE,
+    FOREIGN KEY (resume_version_id) REFERENCES resume_versions (id) ON DELETE CASCADE
+);
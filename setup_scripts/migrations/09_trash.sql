@@ -0,0 +1,3 @@
+-- Add the trash to a database created before it was added
+ALTER TABLE job_applications ADD COLUMN deleted_at DATETIME,
+    ADD INDEX job_applications_deleted_at (deleted_at);
@@ -0,0 +1,44 @@
+-- Add the undo journal to a database created before it was added
+CREATE TABLE journal_entries (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    job_application_id INT NOT NULL,
+    operation ENUM('I','U','D','R') NOT NULL,
+    undone BOOLEAN NOT NULL DEFAULT FALSE,
+    FOREIGN KEY (job_application_id) REFERENCES job_applications (id) ON DELETE CASCADE
+);
+CREATE TABLE journal_snapshots (
+    journal_entry_id INT NOT NULL,
+    is_after BOOLEAN NOT NULL,
+    source VARCHAR(60) NOT NULL,
+    company VARCHAR(60) NOT NULL,
+    job_title VARCHAR(255) NOT NULL,
+    application_date DATE NOT NULL,
+    time_investment TIME,
+    human_response ENUM('N','R','I','IR','J') NOT NULL,
+    human_response_date DATE,
+    application_website VARCHAR(255),
+    notes TEXT,
+    salary_min DOUBLE,
+    salary_max DOUBLE,
+    salary_currency VARCHAR(3),
+    pay_period ENUM('H','D','W','M','Y'),
+    equity VARCHAR(255),
+    bonus VARCHAR(255),
+    city VARCHAR(60),
+    region VARCHAR(60),
+    country VARCHAR(60),
+    work_arrangement ENUM('R','H','O'),
+    relocation BOOLEAN,
+    visa_sponsorship BOOLEAN,
+    PRIMARY KEY (journal_entry_id, is_after),
+    FOREIGN KEY (journal_entry_id) REFERENCES journal_entries (id) ON DELETE CASCADE
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+CREATE TABLE journal_custom_field_values (
+    journal_entry_id INT NOT NULL,
+    is_after BOOLEAN NOT NULL,
+    custom_field_id INT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (journal_entry_id, is_after, custom_field_id),
+    FOREIGN KEY (journal_entry_id) REFERENCES journal_entries (id) ON DELETE CASCADE,
+    FOREIGN KEY (custom_field_id) REFERENCES custom_fields (id) ON DELETE CASCADE
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
@@ -0,0 +1,19 @@
+-- Add the audit log to a database created before it was added
+-- Who changed what in each job application, kept after the job application is permanently deleted
+CREATE TABLE audit_log (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    job_application_id INT NOT NULL,
+    operation ENUM('I','U','D','R','P') NOT NULL,
+    changed_at DATETIME NOT NULL,
+    changed_by VARCHAR(255) NOT NULL,
+    INDEX audit_log_job_application_id (job_application_id)
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
+-- The old and new value of each field changed by an audit log entry. NULL means the field was empty.
+CREATE TABLE audit_log_changes (
+    audit_log_id INT NOT NULL,
+    field VARCHAR(255) NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    PRIMARY KEY (audit_log_id, field),
+    FOREIGN KEY (audit_log_id) REFERENCES audit_log (id) ON DELETE CASCADE
+) DEFAULT CHARSET = utf8mb4 COLLATE = utf8mb4_unicode_ci;
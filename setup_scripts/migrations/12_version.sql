@@ -0,0 +1,2 @@
+-- Add the version to a database created before it was added
+ALTER TABLE job_applications ADD COLUMN version INT NOT NULL DEFAULT 1;
@@ -0,0 +1,6 @@
+-- Add when job applications were saved and changed to a database created before they were added
+-- Job applications saved before then use their application date
+ALTER TABLE job_applications ADD COLUMN created_at DATETIME NOT NULL DEFAULT (UTC_TIMESTAMP()),
+    ADD COLUMN updated_at DATETIME NOT NULL DEFAULT (UTC_TIMESTAMP());
+
+UPDATE job_applications SET created_at = application_date, updated_at = application_date;
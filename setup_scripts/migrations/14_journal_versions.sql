@@ -0,0 +1,8 @@
+-- Add the versions of job applications to the undo journal of a database created before they were added
+-- Changes from before then can be undone as long as the job application isn't changed again some other way
+ALTER TABLE journal_entries ADD COLUMN version_before INT NOT NULL DEFAULT 1,
+    ADD COLUMN version_after INT NOT NULL DEFAULT 1;
+
+UPDATE journal_entries
+INNER JOIN job_applications ON job_applications.id = journal_entries.job_application_id
+SET journal_entries.version_before = job_applications.version, journal_entries.version_after = job_applications.version;